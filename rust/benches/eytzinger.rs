@@ -0,0 +1,45 @@
+//! Compares the search times of `Eytzinger` against `search::binary_search`
+//! on sorted arrays of growing size. Run with `cargo bench --bench eytzinger`.
+use algae::search::{self, Eytzinger};
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+/// Shortest of a few runs of `f`, to filter out noise.
+fn best_of<F: FnMut() -> usize>(mut f: F) -> (Duration, usize) {
+    let mut best = Duration::MAX;
+    let mut result = 0;
+    for _ in 0..5 {
+        let start = Instant::now();
+        result = black_box(f());
+        best = best.min(start.elapsed());
+    }
+    (best, result)
+}
+
+fn main() {
+    for n in [1 << 10, 1 << 16, 1 << 20, 1 << 24] {
+        let xs: Vec<u32> = (0..n).map(|x| 2 * x).collect();
+        let tree = Eytzinger::from_sorted(&xs);
+        let queries: Vec<u32> = (0..1_000_000).map(|_| fastrand::u32(..2 * n)).collect();
+
+        let (classic, found) = best_of(|| {
+            queries
+                .iter()
+                .filter(|q| search::binary_search(&xs, q).is_some())
+                .count()
+        });
+        let (eytzinger, found_tree) =
+            best_of(|| queries.iter().filter(|q| tree.contains(q)).count());
+        assert_eq!(found, found_tree);
+
+        println!(
+            "n = {:>8}: binary_search {:>10.2?}, Eytzinger {:>10.2?} ({:.2}x)",
+            n,
+            classic,
+            eytzinger,
+            classic.as_secs_f64() / eytzinger.as_secs_f64()
+        );
+    }
+}
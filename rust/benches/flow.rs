@@ -0,0 +1,66 @@
+//! Compares the running times of `dinic` and `edmonds_karp` on layered
+//! random networks, where Edmonds-Karp finds many augmenting paths of the
+//! same length, each with a search of its own, while Dinic saturates them all
+//! in a single phase. Run with `cargo bench --bench flow`.
+use algae::graph::{dinic, edmonds_karp, FlowNetwork, MaxFlow};
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+/// Shortest of a few runs of `f`, to filter out noise.
+fn best_of<F: FnMut() -> MaxFlow>(mut f: F) -> (Duration, u64) {
+    let mut best = Duration::MAX;
+    let mut value = 0;
+    for _ in 0..5 {
+        let start = Instant::now();
+        value = black_box(f()).value();
+        best = best.min(start.elapsed());
+    }
+    (best, value)
+}
+
+/// Network of `layers` layers of `width` vertices between a source and a
+/// sink, with `degree` random edges from each vertex to the next layer.
+fn layered_network(layers: usize, width: usize, degree: usize) -> FlowNetwork {
+    let n = layers * width + 2;
+    let (s, t) = (n - 2, n - 1);
+    let mut network = FlowNetwork::new(n);
+    for i in 0..width {
+        network.add_edge(s, i, 1_000);
+        network.add_edge((layers - 1) * width + i, t, 1_000);
+    }
+    for layer in 0..layers - 1 {
+        for i in 0..width {
+            for _ in 0..degree {
+                let j = fastrand::usize(..width);
+                network.add_edge(
+                    layer * width + i,
+                    (layer + 1) * width + j,
+                    fastrand::u64(1..100),
+                );
+            }
+        }
+    }
+    network
+}
+
+fn main() {
+    for (layers, width) in [(10, 50), (20, 100), (40, 150)] {
+        let network = layered_network(layers, width, 5);
+        let (s, t) = (network.vertex_count() - 2, network.vertex_count() - 1);
+
+        let (slow, slow_value) = best_of(|| edmonds_karp(&network, s, t));
+        let (fast, fast_value) = best_of(|| dinic(&network, s, t));
+        assert_eq!(slow_value, fast_value);
+
+        println!(
+            "{:>2} layers of {:>3}: edmonds_karp {:>10.2?}, dinic {:>10.2?} ({:.2}x)",
+            layers,
+            width,
+            slow,
+            fast,
+            slow.as_secs_f64() / fast.as_secs_f64()
+        );
+    }
+}
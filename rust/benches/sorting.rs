@@ -0,0 +1,156 @@
+//! Compares the running times of the sorts of `algae::sort` on inputs where
+//! their trade-offs show. Run with `cargo bench --bench sorting`.
+//!
+//! `quick_sort` sorts the subarrays below its cutoff with a sorting network;
+//! it is compared with the same quicksort finishing them by insertion sort,
+//! as it did before, which is rebuilt here from the public insertion sort.
+use algae::sort;
+use std::{
+    hint::black_box,
+    iter,
+    time::{Duration, Instant},
+};
+
+/// Shortest of a few runs of `f` on fresh copies of `xs`, to filter out noise.
+fn best_of<T: Clone, F: FnMut(&mut [T])>(xs: &[T], mut f: F) -> Duration {
+    let mut best = Duration::MAX;
+    for _ in 0..5 {
+        let mut ys = xs.to_vec();
+        let start = Instant::now();
+        f(black_box(&mut ys));
+        best = best.min(start.elapsed());
+    }
+    best
+}
+
+/// `n` random words of at most `max_len` letters from `alphabet`, each behind
+/// a common `prefix`.
+fn words(n: usize, prefix: &str, alphabet: &[u8], max_len: usize) -> Vec<String> {
+    iter::repeat_with(|| {
+        let suffix: String = iter::repeat_with(|| alphabet[fastrand::usize(..alphabet.len())])
+            .take(fastrand::usize(..=max_len))
+            .map(char::from)
+            .collect();
+        format!("{}{}", prefix, suffix)
+    })
+    .take(n)
+    .collect()
+}
+
+fn string_sorts() {
+    let n = 1 << 18;
+    let prefix = "https://example.com/".repeat(4);
+    for (name, words) in [
+        (
+            "random words",
+            words(n, "", b"abcdefghijklmnopqrstuvwxyz", 12),
+        ),
+        ("shared prefix", words(n, &prefix, b"ab", 24)),
+    ] {
+        let xs: Vec<&str> = words.iter().map(|w| w.as_str()).collect();
+        let quick = best_of(&xs, sort::quick_sort);
+        let string = best_of(&xs, sort::quick_string_sort);
+        println!(
+            "{:>13}: quick_sort {:>10.2?}, quick_string_sort {:>10.2?} ({:.2}x)",
+            name,
+            quick,
+            string,
+            quick.as_secs_f64() / string.as_secs_f64()
+        );
+    }
+}
+
+/// Subarrays of at most this many elements are left to the base case, as in
+/// `quick_sort`.
+const CUTOFF: usize = 10;
+
+/// `quick_sort` with insertion sort as the base case.
+fn quick_sort_insertion<T: PartialOrd>(xs: &mut [T]) {
+    fastrand::shuffle(xs);
+    quick_sort_insertion_rec(xs);
+}
+
+fn quick_sort_insertion_rec<T: PartialOrd>(xs: &mut [T]) {
+    if xs.len() <= CUTOFF {
+        sort::insertion_sort(xs);
+        return;
+    }
+    let pivot = partition(xs);
+    let (left, right) = xs.split_at_mut(pivot);
+    quick_sort_insertion_rec(left);
+    quick_sort_insertion_rec(&mut right[1..]);
+}
+
+/// Partition of `quick_sort` around the first element of `xs`, returning
+/// where the pivot ends up.
+fn partition<T: PartialOrd>(xs: &mut [T]) -> usize {
+    let high = xs.len() - 1;
+    let (mut lscan, mut rscan) = (1, high);
+    loop {
+        while xs[lscan] < xs[0] && lscan != high {
+            lscan += 1;
+        }
+        while xs[0] < xs[rscan] && rscan != 0 {
+            rscan -= 1;
+        }
+        if rscan <= lscan {
+            break;
+        }
+        xs.swap(lscan, rscan);
+        lscan += 1;
+        rscan -= 1;
+    }
+    xs.swap(0, rscan);
+    rscan
+}
+
+fn base_cases() {
+    for n in [1 << 10, 1 << 16, 1 << 20] {
+        let xs: Vec<u64> = iter::repeat_with(|| fastrand::u64(..)).take(n).collect();
+        let insertion = best_of(&xs, quick_sort_insertion);
+        let network = best_of(&xs, sort::quick_sort);
+        println!(
+            "n = {:>7}: insertion base case {:>10.2?}, network base case {:>10.2?} ({:.2}x)",
+            n,
+            insertion,
+            network,
+            insertion.as_secs_f64() / network.as_secs_f64()
+        );
+    }
+}
+
+/// The base cases alone, on many tiny slices of random lengths up to the
+/// cutoff, packed one after the other.
+fn tiny_slices() {
+    let lens: Vec<usize> = iter::repeat_with(|| fastrand::usize(..=CUTOFF))
+        .take(1 << 17)
+        .collect();
+    let xs: Vec<u64> = iter::repeat_with(|| fastrand::u64(..))
+        .take(lens.iter().sum())
+        .collect();
+    let sort_each = |f: fn(&mut [u64])| {
+        let lens = &lens;
+        move |mut xs: &mut [u64]| {
+            for &len in lens {
+                let (head, tail) = xs.split_at_mut(len);
+                f(head);
+                xs = tail;
+            }
+        }
+    };
+    let insertion = best_of(&xs, sort_each(sort::insertion_sort));
+    let network = best_of(&xs, sort_each(sort::network::sort_small));
+    println!(
+        "{:>9} tiny slices: insertion_sort {:>10.2?}, sort_small {:>10.2?} ({:.2}x)",
+        lens.len(),
+        insertion,
+        network,
+        insertion.as_secs_f64() / network.as_secs_f64()
+    );
+}
+
+fn main() {
+    string_sorts();
+    base_cases();
+    tiny_slices();
+}
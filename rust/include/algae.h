@@ -0,0 +1,43 @@
+/*
+ * C interface to the algae crate, built with the `ffi` feature:
+ *
+ *     cargo rustc --release --features ffi --crate-type staticlib
+ *
+ * Keep in sync with `src/ffi.rs`: the `header_matches_exports` test compiles
+ * this header against the prototypes derived from the Rust signatures.
+ */
+#ifndef ALGAE_H
+#define ALGAE_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Sorts in place the `len` integers starting at `ptr` using introsort. */
+void algae_sort_i64(int64_t *ptr, size_t len);
+
+/*
+ * Binary searches for `x` among the `len` sorted integers starting at `ptr`.
+ * Returns the index of a matching element, or -1 if there is none.
+ */
+ptrdiff_t algae_binary_search_i64(const int64_t *ptr, size_t len, int64_t x);
+
+/*
+ * Shortest path distances from `source` by Dijkstra's algorithm, in a digraph
+ * with `n` vertices in compressed sparse row form: the edges leaving `v` are
+ * those of index `offsets[v]` to `offsets[v + 1] - 1`, going to `targets[e]`
+ * with weight `weights[e]`. Writes the distance to each vertex to `dist`, as
+ * infinity for unreachable vertices. Returns 0 on success, -1 if the
+ * arguments are invalid and -2 if a weight is negative.
+ */
+int32_t algae_dijkstra_csr(size_t n, const size_t *offsets, const size_t *targets,
+                           const double *weights, size_t source, double *dist);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* ALGAE_H */
@@ -0,0 +1,128 @@
+//! Dynamic programming algorithms.
+use crate::math::{ntt_multiply, NTT_MAX_LEN, NTT_MODULUS};
+
+/// Inputs for which `xs.len() * (max + 1)` does not exceed this threshold are
+/// handled by the quadratic dynamic programming, which is faster than the NTT
+/// for small instances.
+const NAIVE_THRESHOLD: usize = 1 << 16;
+
+/// Subset-sum counting: for each `s` in `0..=max`, computes the number of
+/// subsets of `xs` (as a multiset, that is, equal values at distinct
+/// positions are distinct elements) whose sum is exactly `s`. Since the counts
+/// grow exponentially, they are given modulo `NTT_MODULUS`.
+///
+/// The answer is the coefficient vector of the product of the polynomials
+/// `1 + x^a` for each `a` in `xs`, truncated to degree `max`. Large inputs
+/// compute this product by divide and conquer with NTT-based multiplication,
+/// while small inputs fall back to the classical O(n * max) table. So do inputs
+/// with `max >= NTT_MAX_LEN / 2`, whose products could exceed the longest
+/// transform available.
+///
+/// Example:
+/// ```
+/// use algae::dp;
+///
+/// // The sum 3 is reached by both {3} and {1, 2}.
+/// assert_eq!(dp::subset_sums_count(&[1, 2, 3], 7), vec![1, 1, 1, 2, 1, 1, 1, 0]);
+/// ```
+pub fn subset_sums_count(xs: &[usize], max: usize) -> Vec<u64> {
+    // Truncated factors have at most `max + 1` coefficients, so their products
+    // have at most `2 * max + 1`.
+    if xs.len().saturating_mul(max + 1) <= NAIVE_THRESHOLD || max >= NTT_MAX_LEN / 2 {
+        subset_sums_count_naive(xs, max)
+    } else {
+        subset_sums_count_ntt(xs, max)
+    }
+}
+
+/// Classical subset-sum table, where each element `a` updates the number of
+/// ways to reach `s` by the number of ways to reach `s - a`. Runs in
+/// O(n * max).
+fn subset_sums_count_naive(xs: &[usize], max: usize) -> Vec<u64> {
+    let mut ways = vec![0; max + 1];
+    ways[0] = 1;
+    for &a in xs.iter().filter(|&&a| a <= max) {
+        // Iterate downwards so that each element is used at most once.
+        for s in (a..=max).rev() {
+            ways[s] = (ways[s] + ways[s - a]) % NTT_MODULUS;
+        }
+    }
+    ways
+}
+
+/// Divide and conquer product of the polynomials `1 + x^a`, truncated to
+/// degree `max` after each multiplication.
+fn subset_sums_count_ntt(xs: &[usize], max: usize) -> Vec<u64> {
+    let mut ways = subset_sums_product(xs, max);
+    ways.resize(max + 1, 0);
+    ways
+}
+
+fn subset_sums_product(xs: &[usize], max: usize) -> Vec<u64> {
+    match xs {
+        [] => vec![1],
+        &[a] if a > max => vec![1],
+        &[0] => vec![2],
+        &[a] => {
+            let mut p = vec![0; a + 1];
+            p[0] = 1;
+            p[a] = 1;
+            p
+        }
+        _ => {
+            let (left, right) = xs.split_at(xs.len() / 2);
+            let mut p = ntt_multiply(
+                &subset_sums_product(left, max),
+                &subset_sums_product(right, max),
+            );
+            p.truncate(max + 1);
+            p
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn small_cases() {
+        assert_eq!(subset_sums_count(&[], 3), vec![1, 0, 0, 0]);
+        assert_eq!(subset_sums_count(&[0, 0], 1), vec![4, 0]);
+        assert_eq!(subset_sums_count(&[2, 2], 4), vec![1, 0, 2, 0, 1]);
+        assert_eq!(subset_sums_count(&[10], 3), vec![1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn ntt_agrees_with_naive() {
+        for _ in 0..20 {
+            let xs: Vec<usize> = (0..fastrand::usize(0..60))
+                .map(|_| fastrand::usize(0..40))
+                .collect();
+            let max = fastrand::usize(0..300);
+            assert_eq!(
+                subset_sums_count_ntt(&xs, max),
+                subset_sums_count_naive(&xs, max)
+            );
+        }
+    }
+
+    #[test]
+    fn large_input() {
+        // Subsets of `n` ones: the number of ways to reach `s` is the binomial
+        // coefficient C(n, s), large enough to go through the NTT path.
+        let xs = vec![1; 2_000];
+        let ways = subset_sums_count(&xs, 100);
+        assert_eq!(ways, subset_sums_count_naive(&xs, 100));
+        assert_eq!(ways[1], 2_000);
+        assert_eq!(ways[2], 2_000 * 1_999 / 2);
+    }
+
+    #[test]
+    fn sums_beyond_the_longest_transform() {
+        let ways = subset_sums_count(&[4_500_000, 4_500_000], 5_000_000);
+        assert_eq!(ways.len(), 5_000_001);
+        assert_eq!((ways[0], ways[4_500_000]), (1, 2));
+        assert_eq!(ways.iter().sum::<u64>(), 3);
+    }
+}
@@ -0,0 +1,242 @@
+/// Number of buckets tracked by each word of the occupancy bitmap.
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Bucket queue: a priority queue for integer priorities in a small range
+/// `0..=max_priority`, as used by Dial's variant of Dijkstra's algorithm for
+/// graphs whose edge weights are small integers.
+///
+/// The queue is organized in two levels: an array with one bucket per
+/// priority, holding the items with that priority, and a bitmap marking the
+/// non-empty buckets. Pushing is O(1), while popping finds the first non-empty
+/// bucket by scanning the bitmap 64 buckets at a time, starting from a cursor
+/// below which every bucket is known to be empty. When priorities are popped
+/// in non-decreasing order (as in Dijkstra's algorithm), the cursor only moves
+/// forward and popping is amortized O(1 + max_priority / (64 * n)).
+///
+/// Items with equal priorities are popped in last-in first-out order.
+///
+/// With `pop_cyclic`, the buckets can also be used circularly, for priorities
+/// that are only known modulo the number of buckets.
+///
+/// Example:
+/// ```
+/// use algae::ds::BucketQueue;
+///
+/// let mut queue = BucketQueue::new(10);
+/// queue.push(7, "seven");
+/// queue.push(2, "two");
+/// queue.push(9, "nine");
+/// assert_eq!(queue.pop_min(), Some((2, "two")));
+/// assert_eq!(queue.pop_min(), Some((7, "seven")));
+/// assert_eq!(queue.pop_min(), Some((9, "nine")));
+/// assert_eq!(queue.pop_min(), None);
+/// ```
+#[derive(Debug)]
+pub struct BucketQueue<T> {
+    buckets: Vec<Vec<T>>,
+    /// Bit `r % 64` of the word `r / 64` is set if and only if the bucket of
+    /// the priority `r` is non-empty.
+    occupied: Vec<u64>,
+    /// Index of a word such that all words before it are zero.
+    cursor: usize,
+    len: usize,
+}
+
+impl<T> BucketQueue<T> {
+    /// Creates an empty queue accepting priorities in `0..=max_priority`.
+    /// Panics if `max_priority` is `usize::MAX`, as there would be more
+    /// buckets than can be indexed.
+    pub fn new(max_priority: usize) -> Self {
+        let n_buckets = max_priority
+            .checked_add(1)
+            .expect("The maximum priority of a bucket queue must be below usize::MAX");
+        Self {
+            buckets: (0..n_buckets).map(|_| Vec::new()).collect(),
+            occupied: vec![0; n_buckets.div_ceil(WORD_BITS)],
+            cursor: 0,
+            len: 0,
+        }
+    }
+
+    /// The largest priority accepted by the queue.
+    pub fn max_priority(&self) -> usize {
+        self.buckets.len() - 1
+    }
+
+    /// Number of items in the queue.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the queue has no items.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts `item` with the given `priority`. Panics if the priority is
+    /// greater than `max_priority`.
+    pub fn push(&mut self, priority: usize, item: T) {
+        assert!(
+            priority < self.buckets.len(),
+            "Priority {} exceeds the maximum priority {} of the queue.",
+            priority,
+            self.max_priority()
+        );
+        self.buckets[priority].push(item);
+        let word = priority / WORD_BITS;
+        self.occupied[word] |= 1 << (priority % WORD_BITS);
+        self.cursor = self.cursor.min(word);
+        self.len += 1;
+    }
+
+    /// Returns the smallest priority of the queue, if non-empty.
+    pub fn min_priority(&self) -> Option<usize> {
+        self.occupied[self.cursor..]
+            .iter()
+            .position(|&w| w != 0)
+            .map(|offset| {
+                let word = self.cursor + offset;
+                word * WORD_BITS + self.occupied[word].trailing_zeros() as usize
+            })
+    }
+
+    /// Removes an item with the smallest priority, returning it together with
+    /// its priority, or `None` if the queue is empty.
+    pub fn pop_min(&mut self) -> Option<(usize, T)> {
+        let priority = self.min_priority()?;
+        self.cursor = priority / WORD_BITS;
+        self.take(priority)
+    }
+
+    /// Removes an item with the smallest priority of at least `from`, or, if
+    /// there is none, with the smallest priority overall, wrapping around the
+    /// buckets. Returns it together with its priority, or `None` if the queue
+    /// is empty.
+    ///
+    /// When all priorities of the queue lie within `max_priority + 1` of each
+    /// other, keying the items by their priority modulo `max_priority + 1`
+    /// and popping cyclically from the key of the last popped item gives them
+    /// in order of their true priority.
+    ///
+    /// Example:
+    /// ```
+    /// use algae::ds::BucketQueue;
+    ///
+    /// // The true priorities 8, 10 and 12, modulo 5.
+    /// let mut queue = BucketQueue::new(4);
+    /// for p in [12, 8, 10] {
+    ///     queue.push(p % 5, p);
+    /// }
+    /// assert_eq!(queue.pop_cyclic(8 % 5), Some((3, 8)));
+    /// assert_eq!(queue.pop_cyclic(3), Some((0, 10)));
+    /// assert_eq!(queue.pop_cyclic(0), Some((2, 12)));
+    /// ```
+    pub fn pop_cyclic(&mut self, from: usize) -> Option<(usize, T)> {
+        let priority = self.next_occupied(from).or_else(|| self.min_priority())?;
+        self.take(priority)
+    }
+
+    /// Smallest priority of the queue of at least `from`, if any.
+    fn next_occupied(&self, from: usize) -> Option<usize> {
+        let word = from / WORD_BITS;
+        let first = self.occupied.get(word)? & (!0 << (from % WORD_BITS));
+        if first != 0 {
+            return Some(word * WORD_BITS + first.trailing_zeros() as usize);
+        }
+        self.occupied[word + 1..]
+            .iter()
+            .position(|&w| w != 0)
+            .map(|offset| {
+                let word = word + 1 + offset;
+                word * WORD_BITS + self.occupied[word].trailing_zeros() as usize
+            })
+    }
+
+    /// Removes an item from the non-empty bucket of `priority`.
+    fn take(&mut self, priority: usize) -> Option<(usize, T)> {
+        let word = priority / WORD_BITS;
+        let bucket = &mut self.buckets[priority];
+        let item = bucket.pop()?;
+        if bucket.is_empty() {
+            self.occupied[word] &= !(1 << (priority % WORD_BITS));
+        }
+        self.len -= 1;
+        Some((priority, item))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn push_n_pop() {
+        let mut queue = BucketQueue::new(200);
+        assert!(queue.is_empty());
+        assert_eq!(queue.pop_min(), None);
+        assert_eq!(queue.min_priority(), None);
+
+        for p in [150, 3, 64, 63, 200, 0, 64] {
+            queue.push(p, p * 10);
+        }
+        assert_eq!(queue.len(), 7);
+        assert_eq!(queue.min_priority(), Some(0));
+
+        let popped: Vec<usize> = std::iter::from_fn(|| queue.pop_min().map(|(p, _)| p)).collect();
+        assert_eq!(popped, vec![0, 3, 63, 64, 64, 150, 200]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn cyclic_pops() {
+        // Priorities within a window of 11, stored modulo 11, as in Dial's
+        // algorithm.
+        let mut queue = BucketQueue::new(10);
+        let mut expected = Vec::new();
+        let mut last = 0;
+        for _ in 0..2_000 {
+            if fastrand::u8(..3) > 0 {
+                let p = last + fastrand::usize(..=10);
+                queue.push(p % 11, p);
+                expected.push(p);
+            } else {
+                expected.sort_unstable_by(|a, b| b.cmp(a));
+                let popped = queue.pop_cyclic(last % 11).map(|(_, p)| p);
+                assert_eq!(popped, expected.pop());
+                last = popped.unwrap_or(last);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "below usize::MAX")]
+    fn too_many_buckets() {
+        BucketQueue::<()>::new(usize::MAX);
+    }
+
+    #[test]
+    fn agrees_with_sorting() {
+        let mut queue = BucketQueue::new(1_000);
+        let mut expected = Vec::new();
+        for _ in 0..2_000 {
+            // Interleave pushes of arbitrary priorities, possibly below the
+            // current minimum, with pops.
+            if fastrand::u8(..3) > 0 {
+                let p = fastrand::usize(..=1_000);
+                queue.push(p, ());
+                expected.push(p);
+            } else {
+                expected.sort_unstable_by(|a, b| b.cmp(a));
+                assert_eq!(queue.pop_min().map(|(p, _)| p), expected.pop());
+            }
+            assert_eq!(queue.len(), expected.len());
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn priority_out_of_range() {
+        let mut queue = BucketQueue::new(5);
+        queue.push(6, ());
+    }
+}
@@ -1,104 +1,141 @@
-use std::{
-    cell::{Ref, RefCell},
-    rc::Rc,
-};
-
-// Each node should have exactly two pointers to it. Each node in the middle of
-// the list is pointed at by its predecessor and successor, while the nodes on
-// the ends are pointed to by the list itself.
+use std::{marker::PhantomData, ptr};
+
+// Each node is owned by the list and linked to its neighbours by raw pointers,
+// as in the queue. Nodes in the middle of the list are pointed at by their
+// predecessor and successor, while the nodes on the ends are also pointed to
+// by the list itself. Since no `RefCell` guards the keys, peeking hands out
+// plain references tied to the borrow of the list.
 //
-// `head`: has an empty prev and some next;
-// `tail`: has some next and an empty prev;
+// `head`: has a null prev and possibly some next;
+// `tail`: has a null next and possibly some prev;
 pub struct DoubleLinked<T> {
     head: List<T>,
     tail: List<T>,
+    // The list owns its nodes, which matters for the drop check.
+    _owns: PhantomData<Box<DoubleNode<T>>>,
 }
 
 impl<T> DoubleLinked<T> {
     pub fn new() -> Self {
         Self {
-            head: None,
-            tail: None,
+            head: ptr::null_mut(),
+            tail: ptr::null_mut(),
+            _owns: PhantomData,
         }
     }
 
     pub fn push_front(&mut self, key: T) {
         let new_head = DoubleNode::new(key);
-        match self.head.take() {
-            Some(last_head) => {
-                // Cloning an Rc just creates a new pointer to the same object
-                last_head.borrow_mut().prev = Some(new_head.clone());
-                new_head.borrow_mut().next = Some(last_head);
-                self.head = Some(new_head);
-            }
-            None => {
-                self.head = Some(new_head.clone());
-                self.tail = Some(new_head);
+        unsafe {
+            if self.head.is_null() {
+                self.tail = new_head;
+            } else {
+                (*self.head).prev = new_head;
+                (*new_head).next = self.head;
             }
         }
+        self.head = new_head;
     }
 
     pub fn pop_front(&mut self) -> Option<T> {
-        // Pop only when the list is nonempty
-        self.head.take().map(|last_head| {
-            // Check for the node head points to (next), the next node will
-            // become the new head of the list
-            match last_head.borrow_mut().next.take() {
-                Some(new_head) => {
-                    // Empty the previous node of the new head (which is the
-                    // last head) and update the head
-                    new_head.borrow_mut().prev.take();
-                    self.head = Some(new_head);
-                }
-                None => {
-                    // head was the only node, remove the last pointer to it
-                    self.tail.take();
-                }
+        if self.head.is_null() {
+            return None;
+        }
+        unsafe {
+            // We create a box in order to correctly deallocate the last head
+            let last_head = Box::from_raw(self.head);
+            self.head = last_head.next;
+            if self.head.is_null() {
+                // head was the only node
+                self.tail = ptr::null_mut();
+            } else {
+                (*self.head).prev = ptr::null_mut();
             }
-            Rc::try_unwrap(last_head).ok().unwrap().into_inner().key
-        })
+            Some(last_head.key)
+        }
     }
 
     pub fn push_back(&mut self, key: T) {
         let new_tail = DoubleNode::new(key);
-        match self.tail.take() {
-            Some(last_tail) => {
-                last_tail.borrow_mut().next = Some(new_tail.clone());
-                new_tail.borrow_mut().prev = Some(last_tail);
-                self.tail = Some(new_tail);
-            }
-            None => {
-                self.tail = Some(new_tail.clone());
-                self.head = Some(new_tail);
+        unsafe {
+            if self.tail.is_null() {
+                self.head = new_tail;
+            } else {
+                (*self.tail).next = new_tail;
+                (*new_tail).prev = self.tail;
             }
         }
+        self.tail = new_tail;
     }
 
     pub fn pop_back(&mut self) -> Option<T> {
-        self.tail.take().map(|last_tail| {
-            match last_tail.borrow_mut().prev.take() {
-                Some(new_tail) => {
-                    new_tail.borrow_mut().next = None;
-                    self.tail = Some(new_tail);
-                }
-                None => {
-                    self.head.take();
-                }
+        if self.tail.is_null() {
+            return None;
+        }
+        unsafe {
+            let last_tail = Box::from_raw(self.tail);
+            self.tail = last_tail.prev;
+            if self.tail.is_null() {
+                self.head = ptr::null_mut();
+            } else {
+                (*self.tail).next = ptr::null_mut();
             }
-            Rc::try_unwrap(last_tail).ok().unwrap().into_inner().key
-        })
+            Some(last_tail.key)
+        }
     }
 
     pub fn is_empty(&self) -> bool {
-        self.head.is_none()
+        self.head.is_null()
+    }
+
+    pub fn peek_front(&self) -> Option<&T> {
+        unsafe { self.head.as_ref().map(|node| &node.key) }
+    }
+
+    pub fn peek_back(&self) -> Option<&T> {
+        unsafe { self.tail.as_ref().map(|node| &node.key) }
+    }
+
+    pub fn peek_front_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.head.as_mut().map(|node| &mut node.key) }
+    }
+
+    pub fn peek_back_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.tail.as_mut().map(|node| &mut node.key) }
+    }
+
+    /// Keeps only the keys for which `pred` returns `true`, preserving their
+    /// order.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut pred: F) {
+        self.drain_filter(|key| !pred(key));
     }
 
-    // This is not a good peek because we return an option to a Ref<T>, which
-    // holds the key, but there is no turn around for that
-    pub fn peek_front(&self) -> Option<Ref<T>> {
-        self.head
-            .as_ref()
-            .map(|node| Ref::map(node.borrow(), |node| &node.key))
+    /// Removes the keys for which `pred` returns `true`, returning them in
+    /// their order from front to back. Matching nodes are unlinked in place.
+    pub fn drain_filter<F: FnMut(&T) -> bool>(&mut self, mut pred: F) -> Vec<T> {
+        let mut removed = Vec::new();
+        let mut current = self.head;
+        unsafe {
+            while !current.is_null() {
+                let next = (*current).next;
+                if pred(&(*current).key) {
+                    let node = Box::from_raw(current);
+                    if node.prev.is_null() {
+                        self.head = node.next;
+                    } else {
+                        (*node.prev).next = node.next;
+                    }
+                    if node.next.is_null() {
+                        self.tail = node.prev;
+                    } else {
+                        (*node.next).prev = node.prev;
+                    }
+                    removed.push(node.key);
+                }
+                current = next;
+            }
+        }
+        removed
     }
 }
 
@@ -108,7 +145,13 @@ impl<T> Default for DoubleLinked<T> {
     }
 }
 
-type List<T> = Option<Rc<RefCell<DoubleNode<T>>>>;
+impl<T> Drop for DoubleLinked<T> {
+    fn drop(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
+
+type List<T> = *mut DoubleNode<T>;
 
 struct DoubleNode<T> {
     key: T,
@@ -117,11 +160,11 @@ struct DoubleNode<T> {
 }
 
 impl<T> DoubleNode<T> {
-    fn new(key: T) -> Rc<RefCell<Self>> {
-        Rc::new(RefCell::new(Self {
+    fn new(key: T) -> *mut Self {
+        Box::into_raw(Box::new(Self {
             key,
-            next: None,
-            prev: None,
+            next: ptr::null_mut(),
+            prev: ptr::null_mut(),
         }))
     }
 }
@@ -174,23 +217,59 @@ mod test {
     #[test]
     fn peek_front() {
         let mut list = DoubleLinked::new();
-
-        // We cant test the Option<Ref<'_, T>> for equality directly
-        let check_empty = |node: Option<Ref<'_, i32>>| {
-            if node.is_some() {
-                panic!("Node should be None.");
-            }
-        };
-        check_empty(list.peek_front());
+        assert_eq!(list.peek_front(), None);
 
         for x in 0..3 {
             list.push_front(x);
         }
         for x in (0..3).rev() {
-            assert_eq!(&*list.peek_front().unwrap(), &x);
+            assert_eq!(list.peek_front(), Some(&x));
             list.pop_front();
         }
+        assert_eq!(list.peek_front(), None);
+    }
+
+    #[test]
+    fn peek_back() {
+        let mut list = DoubleLinked::new();
+        assert_eq!(list.peek_back(), None);
+
+        list.push_front(1);
+        list.push_back(2);
+        // Peeks are plain references, usable in ordinary expressions.
+        assert_eq!(list.peek_front().unwrap() + list.peek_back().unwrap(), 3);
+
+        *list.peek_back_mut().unwrap() += 5;
+        *list.peek_front_mut().unwrap() -= 1;
+        assert_eq!(list.pop_back(), Some(7));
+        assert_eq!(list.peek_back(), Some(&0));
+        assert_eq!(list.pop_back(), Some(0));
+        assert_eq!(list.peek_back(), None);
+    }
+
+    #[test]
+    fn retain_n_drain_filter() {
+        let mut list = DoubleLinked::new();
+        for x in 0..10 {
+            list.push_back(x);
+        }
+
+        assert_eq!(list.drain_filter(|x| x % 3 == 0), vec![0, 3, 6, 9]);
+        list.retain(|&x| x > 2);
+        assert_eq!(list.peek_front(), Some(&4));
+        assert_eq!(list.peek_back(), Some(&8));
+        for x in [4, 5, 7, 8] {
+            assert_eq!(list.pop_front(), Some(x));
+        }
+        assert!(list.is_empty());
 
-        check_empty(list.peek_front());
+        // Removing the ends must relink the head and tail.
+        for x in 0..4 {
+            list.push_back(x);
+        }
+        list.retain(|&x| x == 1 || x == 2);
+        assert_eq!(list.pop_back(), Some(2));
+        assert_eq!(list.pop_back(), Some(1));
+        assert_eq!(list.pop_back(), None);
     }
 }
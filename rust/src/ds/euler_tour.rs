@@ -0,0 +1,422 @@
+use std::collections::HashMap;
+
+/// A node of the treap holding an Euler tour, standing either for a vertex or
+/// for a directed edge of the forest.
+#[derive(Debug, Clone)]
+struct Node<T> {
+    children: [Option<usize>; 2],
+    parent: Option<usize>,
+    /// Random heap priority keeping the treap balanced in expectation.
+    priority: u64,
+    /// Number of nodes in the treap subtree.
+    size: usize,
+    /// Number of vertex nodes in the treap subtree.
+    vertices: usize,
+    /// Value of a vertex node, `None` for edge nodes.
+    value: Option<T>,
+    /// Aggregate of the values of the vertex nodes of the treap subtree.
+    aggregate: Option<T>,
+}
+
+/// Euler tour tree: a dynamic forest on the vertices `0..n`, each holding a
+/// value, supporting the addition and removal of edges, connectivity queries
+/// and the aggregation of the values of whole trees and of subtrees, all in
+/// O(log n) expected time.
+///
+/// Each tree is represented by an Euler tour visiting its vertices and both
+/// directions of its edges, stored as a sequence in a treap (a randomized
+/// balanced binary search tree keyed by position). Rerooting a tree rotates
+/// its tour, and linking or cutting an edge splices tours together or apart.
+/// Unlike `LinkCutTree`, aggregates are over sets of vertices rather than
+/// paths, so the operation `op` should be both associative and commutative.
+///
+/// Example:
+/// ```
+/// use algae::ds::EulerTourTree;
+///
+/// let mut forest = EulerTourTree::new(vec![1, 2, 3, 4], |a, b| a + b);
+/// assert!(forest.link(0, 1));
+/// assert!(forest.link(1, 2));
+/// assert!(forest.link(1, 3));
+/// assert_eq!(forest.tree_aggregate(0), 10);
+/// // The subtree of 1 when hanging from 0.
+/// assert_eq!(forest.subtree_aggregate(1, 0), Some(9));
+///
+/// assert!(forest.cut(1, 3));
+/// assert!(!forest.connected(0, 3));
+/// assert_eq!(forest.tree_aggregate(0), 6);
+/// ```
+#[derive(Debug, Clone)]
+pub struct EulerTourTree<T> {
+    /// Vertex `v` is the node `v`, edges use the nodes from `n` onwards.
+    nodes: Vec<Node<T>>,
+    /// Node of each directed edge `(u, v)` of the forest.
+    edges: HashMap<(usize, usize), usize>,
+    /// Edge nodes freed by cuts, to be reused by later links.
+    free: Vec<usize>,
+    op: fn(&T, &T) -> T,
+}
+
+impl<T: Clone> EulerTourTree<T> {
+    /// Creates a forest of isolated vertices, where vertex `i` holds
+    /// `values[i]`, whose values are aggregated with the associative and
+    /// commutative `op`.
+    pub fn new(values: Vec<T>, op: fn(&T, &T) -> T) -> Self {
+        let nodes = values
+            .into_iter()
+            .map(|value| Node {
+                children: [None, None],
+                parent: None,
+                priority: fastrand::u64(..),
+                size: 1,
+                vertices: 1,
+                aggregate: Some(value.clone()),
+                value: Some(value),
+            })
+            .collect();
+        Self {
+            nodes,
+            edges: HashMap::new(),
+            free: Vec::new(),
+            op,
+        }
+    }
+
+    /// Number of vertices of the forest.
+    pub fn len(&self) -> usize {
+        self.nodes.len() - self.edges.len() - self.free.len()
+    }
+
+    /// Whether the forest has no vertices.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The value held by the vertex `u`.
+    pub fn value(&self, u: usize) -> &T {
+        self.nodes[u]
+            .value
+            .as_ref()
+            .expect("Vertex nodes should hold a value")
+    }
+
+    /// Replaces the value held by the vertex `u`.
+    pub fn set_value(&mut self, u: usize, value: T) {
+        self.nodes[u].value = Some(value);
+        let mut node = Some(u);
+        while let Some(x) = node {
+            self.pull(x);
+            node = self.nodes[x].parent;
+        }
+    }
+
+    /// Whether the edge between `u` and `v` is in the forest.
+    pub fn has_edge(&self, u: usize, v: usize) -> bool {
+        self.edges.contains_key(&(u, v))
+    }
+
+    /// Whether `u` and `v` belong to the same tree of the forest.
+    pub fn connected(&self, u: usize, v: usize) -> bool {
+        self.treap_root(u) == self.treap_root(v)
+    }
+
+    /// Number of vertices of the tree containing `u`.
+    pub fn tree_size(&self, u: usize) -> usize {
+        self.nodes[self.treap_root(u)].vertices
+    }
+
+    /// Aggregate of the values of all vertices of the tree containing `u`.
+    pub fn tree_aggregate(&self, u: usize) -> T {
+        self.nodes[self.treap_root(u)]
+            .aggregate
+            .clone()
+            .expect("Trees should contain at least one vertex")
+    }
+
+    /// Aggregate of the values of the subtree of `v` when its tree is rooted
+    /// at its neighbour `parent`, or `None` if they aren't adjacent.
+    pub fn subtree_aggregate(&mut self, v: usize, parent: usize) -> Option<T> {
+        let down = *self.edges.get(&(parent, v))?;
+        let up = self.edges[&(v, parent)];
+        self.reroot(parent);
+
+        // The tour now starts at `parent`, and visits the subtree of `v` right
+        // after going down the edge to `v`, before coming back up.
+        let (i, j) = (self.index(down), self.index(up));
+        let root = self.treap_root(v);
+        let (before, rest) = self.split(Some(root), i + 1);
+        let (subtree, after) = self.split(rest, j - i - 1);
+        let aggregate = subtree.and_then(|s| self.nodes[s].aggregate.clone());
+
+        let rest = self.merge(subtree, after);
+        self.merge(before, rest);
+        aggregate
+    }
+
+    /// Adds the edge between `u` and `v`, provided that they lie in different
+    /// trees. Returns whether the edge was added.
+    pub fn link(&mut self, u: usize, v: usize) -> bool {
+        if self.connected(u, v) {
+            return false;
+        }
+        self.reroot(u);
+        self.reroot(v);
+        let down = self.new_edge(u, v);
+        let up = self.new_edge(v, u);
+
+        // Tour of `u`, then down to `v`, the tour of `v`, and back up to `u`.
+        let (tour_u, tour_v) = (self.treap_root(u), self.treap_root(v));
+        let left = self.merge(Some(tour_u), Some(down));
+        let right = self.merge(Some(tour_v), Some(up));
+        self.merge(left, right);
+        true
+    }
+
+    /// Removes the edge between `u` and `v`, if present. Returns whether the
+    /// edge was removed.
+    pub fn cut(&mut self, u: usize, v: usize) -> bool {
+        let (Some(down), Some(up)) = (self.edges.remove(&(u, v)), self.edges.remove(&(v, u)))
+        else {
+            return false;
+        };
+        let (i, j) = (self.index(down), self.index(up));
+        let (first, second) = if i < j { (i, j) } else { (j, i) };
+
+        // The tour is `before, first edge, inside, second edge, after`, where
+        // `inside` is the tour of the tree that gets separated.
+        let root = self.treap_root(u);
+        let (before, rest) = self.split(Some(root), first);
+        let (_, rest) = self.split(rest, 1);
+        let (_, rest) = self.split(rest, second - first - 1);
+        let (_, after) = self.split(rest, 1);
+        self.merge(before, after);
+
+        self.free.push(down);
+        self.free.push(up);
+        true
+    }
+
+    /// Rotates the tour of the tree containing `u` so that it starts at `u`.
+    fn reroot(&mut self, u: usize) {
+        let root = self.treap_root(u);
+        let (before, after) = self.split(Some(root), self.index(u));
+        self.merge(after, before);
+    }
+
+    /// Allocates an isolated node for the directed edge `(u, v)`.
+    fn new_edge(&mut self, u: usize, v: usize) -> usize {
+        let node = Node {
+            children: [None, None],
+            parent: None,
+            priority: fastrand::u64(..),
+            size: 1,
+            vertices: 0,
+            value: None,
+            aggregate: None,
+        };
+        let e = match self.free.pop() {
+            Some(e) => {
+                self.nodes[e] = node;
+                e
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        };
+        self.edges.insert((u, v), e);
+        e
+    }
+
+    /// Root of the treap containing the node `x`.
+    fn treap_root(&self, mut x: usize) -> usize {
+        while let Some(p) = self.nodes[x].parent {
+            x = p;
+        }
+        x
+    }
+
+    /// Position of the node `x` in the tour stored by its treap.
+    fn index(&self, mut x: usize) -> usize {
+        let mut idx = self.size(self.nodes[x].children[0]);
+        while let Some(p) = self.nodes[x].parent {
+            if self.nodes[p].children[1] == Some(x) {
+                idx += self.size(self.nodes[p].children[0]) + 1;
+            }
+            x = p;
+        }
+        idx
+    }
+
+    fn size(&self, t: Option<usize>) -> usize {
+        t.map_or(0, |x| self.nodes[x].size)
+    }
+
+    /// Splits the treap `t` into the treaps of its first `k` nodes and of the
+    /// remaining ones.
+    fn split(&mut self, t: Option<usize>, k: usize) -> (Option<usize>, Option<usize>) {
+        let Some(x) = t else {
+            return (None, None);
+        };
+        self.nodes[x].parent = None;
+        let [left, right] = self.nodes[x].children;
+        let left_size = self.size(left);
+        if k <= left_size {
+            let (l, r) = self.split(left, k);
+            self.set_child(x, 0, r);
+            (l, Some(x))
+        } else {
+            let (l, r) = self.split(right, k - left_size - 1);
+            self.set_child(x, 1, l);
+            (Some(x), r)
+        }
+    }
+
+    /// Concatenates the treaps `a` and `b`, returning the resulting root.
+    fn merge(&mut self, a: Option<usize>, b: Option<usize>) -> Option<usize> {
+        let (x, y) = match (a, b) {
+            (None, t) | (t, None) => return t,
+            (Some(x), Some(y)) => (x, y),
+        };
+        if self.nodes[x].priority > self.nodes[y].priority {
+            let right = self.nodes[x].children[1];
+            let merged = self.merge(right, Some(y));
+            self.set_child(x, 1, merged);
+            Some(x)
+        } else {
+            let left = self.nodes[y].children[0];
+            let merged = self.merge(Some(x), left);
+            self.set_child(y, 0, merged);
+            Some(y)
+        }
+    }
+
+    /// Makes `child` the child of `x` on the given side, updating `x`.
+    fn set_child(&mut self, x: usize, side: usize, child: Option<usize>) {
+        self.nodes[x].children[side] = child;
+        if let Some(c) = child {
+            self.nodes[c].parent = Some(x);
+        }
+        self.pull(x);
+    }
+
+    /// Recomputes the size and aggregates of `x` from those of its children.
+    fn pull(&mut self, x: usize) {
+        let op = self.op;
+        let node = &self.nodes[x];
+        let (mut size, mut vertices) = (1, usize::from(node.value.is_some()));
+        let mut aggregate = node.value.clone();
+        for c in node.children.into_iter().flatten() {
+            let child = &self.nodes[c];
+            size += child.size;
+            vertices += child.vertices;
+            aggregate = match (aggregate, &child.aggregate) {
+                (Some(a), Some(b)) => Some(op(&a, b)),
+                (a, b) => a.or_else(|| b.clone()),
+            };
+        }
+        let node = &mut self.nodes[x];
+        node.size = size;
+        node.vertices = vertices;
+        node.aggregate = aggregate;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Vertices reachable from `u` without going through `blocked`, in the
+    /// forest given by its set of undirected `edges`.
+    fn naive_component(
+        n: usize,
+        edges: &HashSet<(usize, usize)>,
+        u: usize,
+        blocked: Option<usize>,
+    ) -> Vec<usize> {
+        let mut seen = vec![false; n];
+        let mut stack = vec![u];
+        seen[u] = true;
+        if let Some(b) = blocked {
+            seen[b] = true;
+        }
+        let mut component = Vec::new();
+        while let Some(w) = stack.pop() {
+            component.push(w);
+            for (x, seen_x) in seen.iter_mut().enumerate() {
+                if !*seen_x && (edges.contains(&(w, x)) || edges.contains(&(x, w))) {
+                    *seen_x = true;
+                    stack.push(x);
+                }
+            }
+        }
+        component
+    }
+
+    #[test]
+    fn link_and_cut() {
+        let mut forest = EulerTourTree::new(vec![(); 5], |_, _| ());
+        assert!(forest.link(0, 1));
+        assert!(forest.link(2, 1));
+        assert!(!forest.link(0, 2));
+        assert!(forest.has_edge(1, 2) && forest.has_edge(2, 1));
+        assert_eq!(forest.tree_size(2), 3);
+        assert!(!forest.connected(0, 3));
+
+        assert!(!forest.cut(0, 2));
+        assert!(forest.cut(2, 1));
+        assert!(!forest.cut(2, 1));
+        assert!(!forest.connected(0, 2));
+        assert_eq!(forest.tree_size(0), 2);
+        assert_eq!(forest.subtree_aggregate(2, 1), None);
+
+        // Edge nodes are reused after cuts.
+        assert!(forest.link(3, 4));
+        assert_eq!(forest.len(), 5);
+    }
+
+    #[test]
+    fn agrees_with_naive_forest() {
+        let n = 30;
+        let mut values: Vec<u64> = (0..n).map(|_| fastrand::u64(..100)).collect();
+        let mut forest = EulerTourTree::new(values.clone(), |a, b| a + b);
+        let mut edges = HashSet::new();
+        let sum = |vs: &[usize], values: &[u64]| vs.iter().map(|&w| values[w]).sum::<u64>();
+
+        for _ in 0..3_000 {
+            let (u, v) = (fastrand::usize(..n), fastrand::usize(..n));
+            let component = naive_component(n, &edges, u, None);
+            match fastrand::u8(..5) {
+                0 => {
+                    let linked = !component.contains(&v);
+                    assert_eq!(forest.link(u, v), linked);
+                    if linked {
+                        edges.insert((u, v));
+                    }
+                }
+                1 => {
+                    let present = edges.remove(&(u, v)) || edges.remove(&(v, u));
+                    assert_eq!(forest.cut(u, v), present);
+                }
+                2 => {
+                    values[u] = fastrand::u64(..100);
+                    forest.set_value(u, values[u]);
+                }
+                3 => {
+                    let adjacent = edges.contains(&(u, v)) || edges.contains(&(v, u));
+                    let expected = adjacent.then(|| {
+                        let subtree = naive_component(n, &edges, v, Some(u));
+                        sum(&subtree, &values)
+                    });
+                    assert_eq!(forest.subtree_aggregate(v, u), expected);
+                }
+                _ => {
+                    assert_eq!(forest.connected(u, v), component.contains(&v));
+                    assert_eq!(forest.tree_size(u), component.len());
+                    assert_eq!(forest.tree_aggregate(u), sum(&component, &values));
+                }
+            }
+        }
+    }
+}
@@ -224,6 +224,58 @@ impl<T: PartialOrd + Clone> MaxHeap<T> {
         self.data
     }
 
+    /// Whether the heap has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Returns a reference to the maximum element of the heap, if any.
+    pub fn peek(&self) -> Option<&T> {
+        if self.size == 0 {
+            None
+        } else {
+            Some(&self.data[0])
+        }
+    }
+
+    /// Removes the maximum element of the heap and returns it, or `None` if the
+    /// heap is empty. The last node of the heap takes the place of the root,
+    /// which is then sifted down. Runs in O(log n).
+    pub fn pop(&mut self) -> Option<T> {
+        if self.size == 0 {
+            return None;
+        }
+        self.size -= 1;
+        self.data.swap(0, self.size);
+        let max = self.data.remove(self.size);
+        self.heapify_top(0);
+        Some(max)
+    }
+
+    /// Keeps only the elements for which `pred` returns `true`.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut pred: F) {
+        self.drain_filter(|x| !pred(x));
+    }
+
+    /// Removes the elements for which `pred` returns `true` and returns them
+    /// in no particular order. The heap is rebuilt from the remaining
+    /// elements, which takes O(n).
+    pub fn drain_filter<F: FnMut(&T) -> bool>(&mut self, mut pred: F) -> Vec<T> {
+        // Elements past `size` are not part of the heap and are left untouched.
+        let outside = self.data.split_off(self.size);
+        let (removed, kept): (Vec<T>, Vec<T>) = std::mem::take(&mut self.data)
+            .into_iter()
+            .partition(|x| pred(x));
+
+        self.size = kept.len();
+        self.data = kept;
+        self.data.extend(outside);
+        for idx in (0..self.size / 2).rev() {
+            self.heapify_top(idx);
+        }
+        removed
+    }
+
     /// Heapsort algorithm. Runs in O(n * log n).
     pub fn heapsort(data: Vec<T>) -> Vec<T> {
         let mut h = MaxHeap::from_vec(data);
@@ -263,6 +315,38 @@ mod test {
         assert_eq!(heap.into_vec(), vec![16, 14, 10, 7, 8, 1, 9, 2, 4, 3]);
     }
 
+    #[test]
+    fn pop() {
+        let mut heap = MaxHeap::from_vec(vec![9, 3, 1, 2, 4, 16, 10, 7, 8, 14]);
+        assert_eq!(heap.peek(), Some(&16));
+
+        let mut popped = Vec::new();
+        while let Some(x) = heap.pop() {
+            popped.push(x);
+        }
+        assert_eq!(popped, vec![16, 14, 10, 9, 8, 7, 4, 3, 2, 1]);
+        assert!(heap.is_empty());
+        assert_eq!(heap.peek(), None);
+
+        heap.push(5);
+        heap.push(11);
+        assert_eq!(heap.pop(), Some(11));
+        assert_eq!(heap.pop(), Some(5));
+        assert_eq!(heap.pop(), None);
+    }
+
+    #[test]
+    fn retain_n_drain_filter() {
+        let mut heap = MaxHeap::from_vec((0..20).collect());
+        let mut removed = heap.drain_filter(|x| x % 2 == 1);
+        removed.sort();
+        assert_eq!(removed, (1..20).step_by(2).collect::<Vec<_>>());
+
+        heap.retain(|&x| x < 10);
+        let popped: Vec<i32> = std::iter::from_fn(|| heap.pop()).collect();
+        assert_eq!(popped, vec![8, 6, 4, 2, 0]);
+    }
+
     #[test]
     fn heapsort() {
         let v = sort::rand_vec(1000);
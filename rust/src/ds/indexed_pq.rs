@@ -0,0 +1,196 @@
+use std::cmp::Ordering;
+
+/// Indexed priority queue: a binary min-heap of keys associated with the
+/// indices `0..capacity`, where the key of an index in the queue can be
+/// looked up and decreased. This is the priority queue needed by Dijkstra's
+/// and Prim's algorithms, which keep one entry per vertex and lower it as
+/// shorter paths are found.
+///
+/// The heap holds indices, and the position of each index in the heap is
+/// kept up to date, so that the entry of an index is found in O(1) and
+/// sifted in O(log n) when its key changes.
+///
+/// Example:
+/// ```
+/// use algae::ds::IndexedPriorityQueue;
+///
+/// let mut pq = IndexedPriorityQueue::new(4);
+/// pq.push(0, 5.0);
+/// pq.push(2, 3.0);
+/// pq.push(3, 4.0);
+/// pq.decrease_key(0, 1.0);
+/// assert_eq!(pq.key_of(0), Some(&1.0));
+/// assert_eq!(pq.pop(), Some((0, 1.0)));
+/// assert_eq!(pq.pop(), Some((2, 3.0)));
+/// assert!(!pq.contains(2));
+/// assert_eq!(pq.len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct IndexedPriorityQueue<K> {
+    /// Binary heap of indices, ordered by their keys.
+    heap: Vec<usize>,
+    /// Position of each index in `heap`, or `usize::MAX` if absent.
+    pos: Vec<usize>,
+    keys: Vec<Option<K>>,
+}
+
+impl<K: PartialOrd> IndexedPriorityQueue<K> {
+    /// Empty queue for the indices `0..capacity`.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            heap: Vec::with_capacity(capacity),
+            pos: vec![usize::MAX; capacity],
+            keys: (0..capacity).map(|_| None).collect(),
+        }
+    }
+
+    /// Number of indices in the queue.
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Whether the queue has no indices.
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Whether `i` is in the queue.
+    pub fn contains(&self, i: usize) -> bool {
+        self.pos[i] != usize::MAX
+    }
+
+    /// Key of `i`, if it is in the queue.
+    pub fn key_of(&self, i: usize) -> Option<&K> {
+        self.keys[i].as_ref()
+    }
+
+    /// Inserts `i` with the given key. Panics if `i` is already in the queue.
+    pub fn push(&mut self, i: usize, key: K) {
+        assert!(!self.contains(i), "Index {} is already in the queue.", i);
+        self.pos[i] = self.heap.len();
+        self.heap.push(i);
+        self.keys[i] = Some(key);
+        self.sift_up(self.heap.len() - 1);
+    }
+
+    /// Lowers the key of `i` to `key`. Panics if `i` is not in the queue or
+    /// if `key` is greater than its current key.
+    pub fn decrease_key(&mut self, i: usize, key: K) {
+        assert!(self.contains(i), "Index {} is not in the queue.", i);
+        assert!(
+            self.keys[i]
+                .as_ref()
+                .is_some_and(|k| key.partial_cmp(k) != Some(Ordering::Greater)),
+            "The new key of index {} is greater than the current one.",
+            i
+        );
+        self.keys[i] = Some(key);
+        self.sift_up(self.pos[i]);
+    }
+
+    /// Index with the smallest key and its key, if non-empty.
+    pub fn peek(&self) -> Option<(usize, &K)> {
+        let &i = self.heap.first()?;
+        self.keys[i].as_ref().map(|k| (i, k))
+    }
+
+    /// Removes the index with the smallest key and returns it together with
+    /// its key, or `None` if the queue is empty. Runs in O(log n).
+    pub fn pop(&mut self) -> Option<(usize, K)> {
+        let last = self.heap.len().checked_sub(1)?;
+        self.swap(0, last);
+        let i = self.heap.pop().expect("Heap should be non-empty");
+        self.pos[i] = usize::MAX;
+        self.sift_down(0);
+        self.keys[i].take().map(|k| (i, k))
+    }
+
+    fn less(&self, a: usize, b: usize) -> bool {
+        self.keys[self.heap[a]] < self.keys[self.heap[b]]
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.pos[self.heap[a]] = a;
+        self.pos[self.heap[b]] = b;
+    }
+
+    fn sift_up(&mut self, mut node: usize) {
+        while node > 0 {
+            let parent = (node - 1) / 2;
+            if !self.less(node, parent) {
+                break;
+            }
+            self.swap(node, parent);
+            node = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut node: usize) {
+        loop {
+            let (left, right) = (2 * node + 1, 2 * node + 2);
+            let mut smallest = node;
+            if left < self.heap.len() && self.less(left, smallest) {
+                smallest = left;
+            }
+            if right < self.heap.len() && self.less(right, smallest) {
+                smallest = right;
+            }
+            if smallest == node {
+                break;
+            }
+            self.swap(node, smallest);
+            node = smallest;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pops_in_order() {
+        let n = 200;
+        let mut pq = IndexedPriorityQueue::new(n);
+        let mut keys: Vec<u32> = (0..n).map(|_| fastrand::u32(..1000)).collect();
+        for (i, &k) in keys.iter().enumerate() {
+            pq.push(i, k);
+        }
+        for _ in 0..100 {
+            let i = fastrand::usize(..n);
+            keys[i] = keys[i].saturating_sub(fastrand::u32(..500));
+            pq.decrease_key(i, keys[i]);
+        }
+        assert_eq!(pq.peek().map(|(_, &k)| k), keys.iter().min().copied());
+
+        let mut popped = Vec::new();
+        while let Some((i, k)) = pq.pop() {
+            assert_eq!(keys[i], k);
+            popped.push(k);
+        }
+        let mut sorted = keys.clone();
+        sorted.sort();
+        assert_eq!(popped, sorted);
+        assert!(pq.is_empty() && (0..n).all(|i| !pq.contains(i)));
+    }
+
+    #[test]
+    fn reinsertion() {
+        let mut pq = IndexedPriorityQueue::new(2);
+        pq.push(1, 'b');
+        assert_eq!(pq.pop(), Some((1, 'b')));
+        pq.push(1, 'a');
+        pq.push(0, 'c');
+        assert_eq!(pq.pop(), Some((1, 'a')));
+        assert_eq!(pq.key_of(1), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn increasing_key() {
+        let mut pq = IndexedPriorityQueue::new(1);
+        pq.push(0, 1);
+        pq.decrease_key(0, 2);
+    }
+}
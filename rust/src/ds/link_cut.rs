@@ -0,0 +1,393 @@
+/// A node of the link-cut tree, which is also a node of the splay tree of its
+/// preferred path, ordered from the root of the represented tree downwards.
+#[derive(Debug, Clone)]
+struct Node<T> {
+    /// Left and right children in the splay tree.
+    children: [Option<usize>; 2],
+    /// Splay tree parent or, for the root of a splay tree, the path-parent
+    /// pointer to the node right above the top of the path.
+    parent: Option<usize>,
+    /// Whether the splay subtree should be reversed before being looked into.
+    reversed: bool,
+    value: T,
+    /// Aggregate of the values of the splay subtree, in path order.
+    aggregate: T,
+    /// Aggregate of the values of the splay subtree, in reverse path order.
+    rev_aggregate: T,
+}
+
+/// Link-cut tree: a dynamic forest on the nodes `0..n`, each holding a value,
+/// supporting the addition and removal of edges, connectivity queries, and
+/// the aggregation of the values along the path between two nodes, all in
+/// O(log n) amortized time.
+///
+/// Each tree of the forest is decomposed into vertex-disjoint *preferred
+/// paths*, each stored in a splay tree keyed by depth. The `access` of a node
+/// makes the path from its root to itself preferred, after which any query
+/// about that path is answered by the root of a single splay tree. Rerooting
+/// a tree at a node reverses the path to it, which is done lazily.
+///
+/// The values are aggregated with the associative operation `op`, which need
+/// not be commutative: the aggregate of the path from `u` to `v` is the
+/// combination of the values of its nodes in order from `u` to `v`.
+///
+/// Example:
+/// ```
+/// use algae::ds::LinkCutTree;
+///
+/// // Path sums over the nodes 0, 1, 2, 3 valued 1, 2, 3, 4.
+/// let mut forest = LinkCutTree::new(vec![1, 2, 3, 4], |a, b| a + b);
+/// assert!(forest.link(0, 1));
+/// assert!(forest.link(1, 2));
+/// assert!(forest.link(3, 1));
+/// assert_eq!(forest.path_aggregate(0, 2), Some(6));
+/// assert_eq!(forest.path_aggregate(3, 2), Some(9));
+///
+/// assert!(forest.cut(1, 2));
+/// assert!(!forest.connected(0, 2));
+/// assert_eq!(forest.path_aggregate(0, 2), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LinkCutTree<T> {
+    nodes: Vec<Node<T>>,
+    op: fn(&T, &T) -> T,
+}
+
+impl<T: Clone> LinkCutTree<T> {
+    /// Creates a forest of isolated nodes, where node `i` holds `values[i]`,
+    /// whose values are aggregated along paths with the associative `op`.
+    pub fn new(values: Vec<T>, op: fn(&T, &T) -> T) -> Self {
+        let nodes = values
+            .into_iter()
+            .map(|value| Node {
+                children: [None, None],
+                parent: None,
+                reversed: false,
+                aggregate: value.clone(),
+                rev_aggregate: value.clone(),
+                value,
+            })
+            .collect();
+        Self { nodes, op }
+    }
+
+    /// Number of nodes of the forest.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether the forest has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// The value held by the node `u`.
+    pub fn value(&self, u: usize) -> &T {
+        &self.nodes[u].value
+    }
+
+    /// Replaces the value held by the node `u`.
+    pub fn set_value(&mut self, u: usize, value: T) {
+        self.access(u);
+        self.nodes[u].value = value;
+        self.pull(u);
+    }
+
+    /// Adds the edge between `u` and `v`, provided that they lie in different
+    /// trees. Returns whether the edge was added.
+    pub fn link(&mut self, u: usize, v: usize) -> bool {
+        self.make_root(u);
+        if self.find_root(v) == u {
+            return false;
+        }
+        // `u` is now the root of both its represented tree and its splay tree,
+        // so it can hang from `v` by a path-parent pointer.
+        self.nodes[u].parent = Some(v);
+        true
+    }
+
+    /// Removes the edge between `u` and `v`, if present. Returns whether the
+    /// edge was removed.
+    pub fn cut(&mut self, u: usize, v: usize) -> bool {
+        self.make_root(u);
+        self.access(v);
+        // After the access, the path from `u` to `v` is held by the splay tree
+        // rooted at `v`, and the nodes are adjacent if and only if `u` comes
+        // right before `v`, with nothing in between.
+        if self.nodes[v].children[0] != Some(u) {
+            return false;
+        }
+        self.push(u);
+        if self.nodes[u].children[1].is_some() {
+            return false;
+        }
+        self.nodes[v].children[0] = None;
+        self.nodes[u].parent = None;
+        self.pull(v);
+        true
+    }
+
+    /// Whether `u` and `v` belong to the same tree of the forest.
+    pub fn connected(&mut self, u: usize, v: usize) -> bool {
+        u == v || self.find_root(u) == self.find_root(v)
+    }
+
+    /// Aggregate of the values on the path from `u` to `v`, both included, or
+    /// `None` if they belong to different trees.
+    pub fn path_aggregate(&mut self, u: usize, v: usize) -> Option<T> {
+        if !self.connected(u, v) {
+            return None;
+        }
+        self.make_root(u);
+        self.access(v);
+        Some(self.nodes[v].aggregate.clone())
+    }
+
+    /// Makes `u` the root of its tree.
+    pub fn make_root(&mut self, u: usize) {
+        self.access(u);
+        self.toggle(u);
+    }
+
+    /// Root of the tree containing `u`.
+    pub fn find_root(&mut self, u: usize) -> usize {
+        self.access(u);
+        // The root is the shallowest node of the path, the leftmost one.
+        let mut root = u;
+        loop {
+            self.push(root);
+            match self.nodes[root].children[0] {
+                Some(left) => root = left,
+                None => break,
+            }
+        }
+        self.splay(root);
+        root
+    }
+
+    /// Makes the path from the root of the tree of `u` to `u` preferred, and
+    /// splays `u` to the root of its splay tree, so that it aggregates the
+    /// whole path.
+    fn access(&mut self, u: usize) {
+        self.splay(u);
+        // Nodes below `u` are no longer on its preferred path.
+        self.nodes[u].children[1] = None;
+        self.pull(u);
+        while let Some(w) = self.nodes[u].parent {
+            // Switch the preferred child of the path-parent `w` to `u`.
+            self.splay(w);
+            self.nodes[w].children[1] = Some(u);
+            self.pull(w);
+            self.splay(u);
+        }
+    }
+
+    /// Whether `u` is the root of its splay tree.
+    fn is_splay_root(&self, u: usize) -> bool {
+        match self.nodes[u].parent {
+            Some(p) => !self.nodes[p].children.contains(&Some(u)),
+            None => true,
+        }
+    }
+
+    /// Moves `u` to the root of its splay tree by rotations.
+    fn splay(&mut self, u: usize) {
+        // Push the pending reversals from the splay root down to `u`, so that
+        // the rotations see the actual children.
+        let mut ancestors = vec![u];
+        let mut top = u;
+        while !self.is_splay_root(top) {
+            top = self.nodes[top]
+                .parent
+                .expect("Non-root node should have a parent");
+            ancestors.push(top);
+        }
+        for &a in ancestors.iter().rev() {
+            self.push(a);
+        }
+
+        while !self.is_splay_root(u) {
+            let p = self.nodes[u]
+                .parent
+                .expect("Non-root node should have a parent");
+            if !self.is_splay_root(p) {
+                let g = self.nodes[p]
+                    .parent
+                    .expect("Non-root node should have a parent");
+                let zig_zig = (self.nodes[g].children[0] == Some(p))
+                    == (self.nodes[p].children[0] == Some(u));
+                self.rotate(if zig_zig { p } else { u });
+            }
+            self.rotate(u);
+        }
+    }
+
+    /// Rotates `u` above its splay tree parent.
+    fn rotate(&mut self, u: usize) {
+        let p = self.nodes[u]
+            .parent
+            .expect("Rotated node should have a parent");
+        let g = self.nodes[p].parent;
+        let side = usize::from(self.nodes[p].children[1] == Some(u));
+        trace_event!(node = u, parent = p, "rotation performed");
+
+        // Attach the inner child of `u` to `p`.
+        let inner = self.nodes[u].children[1 - side];
+        self.nodes[p].children[side] = inner;
+        if let Some(c) = inner {
+            self.nodes[c].parent = Some(p);
+        }
+
+        // Replace `p` by `u` below `g`, keeping path-parent pointers as is.
+        if !self.is_splay_root(p) {
+            let g = g.expect("Non-root node should have a parent");
+            let p_side = usize::from(self.nodes[g].children[1] == Some(p));
+            self.nodes[g].children[p_side] = Some(u);
+        }
+        self.nodes[u].parent = g;
+
+        self.nodes[u].children[1 - side] = Some(p);
+        self.nodes[p].parent = Some(u);
+        self.pull(p);
+        self.pull(u);
+    }
+
+    /// Reverses the splay subtree of `u`, deferring the reversal of its
+    /// descendants.
+    fn toggle(&mut self, u: usize) {
+        let node = &mut self.nodes[u];
+        node.children.swap(0, 1);
+        std::mem::swap(&mut node.aggregate, &mut node.rev_aggregate);
+        node.reversed ^= true;
+    }
+
+    /// Propagates a pending reversal of `u` to its children.
+    fn push(&mut self, u: usize) {
+        if self.nodes[u].reversed {
+            self.nodes[u].reversed = false;
+            for c in self.nodes[u].children.into_iter().flatten() {
+                self.toggle(c);
+            }
+        }
+    }
+
+    /// Recomputes the aggregates of `u` from those of its children.
+    fn pull(&mut self, u: usize) {
+        let op = self.op;
+        let [left, right] = self.nodes[u].children;
+        let node = &self.nodes[u];
+
+        let mut aggregate = node.value.clone();
+        let mut rev_aggregate = node.value.clone();
+        if let Some(l) = left {
+            aggregate = op(&self.nodes[l].aggregate, &aggregate);
+            rev_aggregate = op(&rev_aggregate, &self.nodes[l].rev_aggregate);
+        }
+        if let Some(r) = right {
+            aggregate = op(&aggregate, &self.nodes[r].aggregate);
+            rev_aggregate = op(&self.nodes[r].rev_aggregate, &rev_aggregate);
+        }
+        self.nodes[u].aggregate = aggregate;
+        self.nodes[u].rev_aggregate = rev_aggregate;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Path from `u` to `v` in the forest given by its set of `edges`, if any.
+    fn naive_path(
+        n: usize,
+        edges: &HashSet<(usize, usize)>,
+        u: usize,
+        v: usize,
+    ) -> Option<Vec<usize>> {
+        let mut prev = vec![None; n];
+        let mut stack = vec![u];
+        prev[u] = Some(u);
+        while let Some(w) = stack.pop() {
+            for (x, prev_x) in prev.iter_mut().enumerate() {
+                if prev_x.is_none() && (edges.contains(&(w, x)) || edges.contains(&(x, w))) {
+                    *prev_x = Some(w);
+                    stack.push(x);
+                }
+            }
+        }
+        prev[v]?;
+        let mut path = vec![v];
+        while path[path.len() - 1] != u {
+            path.push(prev[path[path.len() - 1]].unwrap());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    #[test]
+    fn link_and_cut() {
+        let mut forest = LinkCutTree::new(vec![0; 5], |a, b| a + b);
+        assert!(forest.link(0, 1));
+        assert!(forest.link(2, 1));
+        assert!(!forest.link(0, 2));
+        assert!(forest.connected(0, 2));
+        assert!(!forest.connected(0, 3));
+        assert_eq!(forest.find_root(2), forest.find_root(0));
+
+        assert!(!forest.cut(0, 2));
+        assert!(forest.cut(2, 1));
+        assert!(!forest.cut(2, 1));
+        assert!(!forest.connected(0, 2));
+        assert!(forest.connected(4, 4));
+    }
+
+    #[test]
+    fn non_commutative_aggregate() {
+        // Concatenation records the order of the nodes along the path.
+        let labels = ["a", "b", "c", "d", "e"].map(String::from).to_vec();
+        let mut forest = LinkCutTree::new(labels, |a, b| format!("{}{}", a, b));
+        for (u, v) in [(0, 1), (1, 2), (3, 2), (2, 4)] {
+            assert!(forest.link(u, v));
+        }
+        assert_eq!(forest.path_aggregate(0, 4).as_deref(), Some("abce"));
+        assert_eq!(forest.path_aggregate(4, 0).as_deref(), Some("ecba"));
+        assert_eq!(forest.path_aggregate(3, 0).as_deref(), Some("dcba"));
+        forest.set_value(2, String::from("C"));
+        assert_eq!(forest.path_aggregate(0, 3).as_deref(), Some("abCd"));
+        assert_eq!(forest.value(2), "C");
+    }
+
+    #[test]
+    fn agrees_with_naive_forest() {
+        let n = 30;
+        let mut values: Vec<u64> = (0..n).map(|_| fastrand::u64(..100)).collect();
+        let mut forest = LinkCutTree::new(values.clone(), |a, b| a.max(b).to_owned());
+        let mut edges = HashSet::new();
+
+        for _ in 0..3_000 {
+            let (u, v) = (fastrand::usize(..n), fastrand::usize(..n));
+            let path = naive_path(n, &edges, u, v);
+            match fastrand::u8(..4) {
+                0 => {
+                    assert_eq!(forest.link(u, v), path.is_none());
+                    if path.is_none() {
+                        edges.insert((u, v));
+                    }
+                }
+                1 => {
+                    let present = edges.remove(&(u, v)) || edges.remove(&(v, u));
+                    assert_eq!(forest.cut(u, v), present);
+                }
+                2 => {
+                    values[u] = fastrand::u64(..100);
+                    forest.set_value(u, values[u]);
+                }
+                _ => {
+                    let expected = path.map(|p| p.iter().map(|&w| values[w]).max().unwrap());
+                    assert_eq!(forest.connected(u, v), expected.is_some());
+                    assert_eq!(forest.path_aggregate(u, v), expected);
+                }
+            }
+        }
+    }
+}
@@ -12,3 +12,18 @@ pub use queue::*;
 
 mod heap;
 pub use heap::*;
+
+mod bucket;
+pub use bucket::*;
+
+mod radix_heap;
+pub use radix_heap::*;
+
+mod link_cut;
+pub use link_cut::*;
+
+mod euler_tour;
+pub use euler_tour::*;
+
+mod indexed_pq;
+pub use indexed_pq::*;
@@ -38,7 +38,7 @@ impl<T> PersistentLinkedList<T> {
         self.head.as_ref().map(|node| &node.key)
     }
 
-    pub fn iter(&self) -> PersistentLinkedListIter<T> {
+    pub fn iter(&self) -> PersistentLinkedListIter<'_, T> {
         PersistentLinkedListIter {
             next: self.head.as_deref(),
         }
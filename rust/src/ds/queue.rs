@@ -15,12 +15,11 @@ impl<T> Node<T> {
 
 // Push to the end of the queue
 // Pop from the head of the queue
-struct Queue<T> {
+pub struct Queue<T> {
     head: List<T>,
     tail: *mut Node<T>,
 }
 
-#[allow(dead_code)]
 impl<T> Queue<T> {
     pub fn new() -> Self {
         Self {
@@ -70,8 +69,30 @@ impl<T> Queue<T> {
         unsafe { self.head.as_mut().map(|head| &mut head.key) }
     }
 
-    pub fn into_iter(self) -> QueueIntoIter<T> {
-        QueueIntoIter(self)
+    pub fn is_empty(&self) -> bool {
+        self.head.is_null()
+    }
+
+    /// Keeps only the keys for which `pred` returns `true`, preserving their
+    /// order.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut pred: F) {
+        self.drain_filter(|key| !pred(key));
+    }
+
+    /// Removes the keys for which `pred` returns `true`, returning them in
+    /// their order in the queue.
+    pub fn drain_filter<F: FnMut(&T) -> bool>(&mut self, mut pred: F) -> Vec<T> {
+        let mut kept = Self::new();
+        let mut removed = Vec::new();
+        while let Some(key) = self.pop() {
+            if pred(&key) {
+                removed.push(key);
+            } else {
+                kept.push(key);
+            }
+        }
+        *self = kept;
+        removed
     }
 
     pub fn iter(&self) -> QueueIter<'_, T> {
@@ -91,6 +112,21 @@ impl<T> Queue<T> {
     }
 }
 
+impl<T> Default for Queue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> IntoIterator for Queue<T> {
+    type Item = T;
+    type IntoIter = QueueIntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        QueueIntoIter(self)
+    }
+}
+
 pub struct QueueIntoIter<T>(Queue<T>);
 
 impl<T> Iterator for QueueIntoIter<T> {
@@ -259,4 +295,16 @@ mod test {
         assert_eq!(queue.pop(), Some(6));
         assert!(queue.peek().is_none());
     }
+
+    #[test]
+    fn retain_n_drain_filter() {
+        let mut queue = Queue::new();
+        for x in 0..10 {
+            queue.push(x);
+        }
+
+        assert_eq!(queue.drain_filter(|x| x % 3 == 0), vec![0, 3, 6, 9]);
+        queue.retain(|&x| x > 2);
+        assert_eq!(queue.into_iter().collect::<Vec<_>>(), vec![4, 5, 7, 8]);
+    }
 }
@@ -0,0 +1,166 @@
+/// Number of buckets of a radix heap: one for the keys equal to the last
+/// popped key, plus one for each bit position where a key may first differ
+/// from it.
+const N_BUCKETS: usize = u64::BITS as usize + 1;
+
+/// Radix heap: a monotone priority queue with `u64` keys, where the popped keys
+/// never decrease and every pushed key is at least the last popped one. These
+/// are exactly the conditions met by Dijkstra's algorithm, where the radix heap
+/// outperforms a binary heap.
+///
+/// Keys are kept in buckets according to the position of the highest bit in
+/// which they differ from the last popped key `last`: the bucket `0` holds the
+/// keys equal to `last`, and the bucket `i > 0` holds the keys `k` such that
+/// `k ^ last` has exactly `i` significant bits. When the bucket `0` runs out,
+/// the first non-empty bucket is redistributed around its minimum key, and each
+/// key can only move to lower buckets, so that every key is moved at most 64
+/// times over its lifetime in the heap.
+///
+/// Pushing a key smaller than the last popped key breaks the invariants of the
+/// heap and is detected by a debug assertion.
+///
+/// Example:
+/// ```
+/// use algae::ds::RadixHeap;
+///
+/// let mut heap = RadixHeap::new();
+/// heap.push(5, 'a');
+/// heap.push(1, 'b');
+/// assert_eq!(heap.pop(), Some((1, 'b')));
+/// heap.push(3, 'c');
+/// assert_eq!(heap.pop(), Some((3, 'c')));
+/// assert_eq!(heap.pop(), Some((5, 'a')));
+/// assert_eq!(heap.pop(), None);
+/// ```
+#[derive(Debug)]
+pub struct RadixHeap<V> {
+    buckets: [Vec<(u64, V)>; N_BUCKETS],
+    last: u64,
+    len: usize,
+}
+
+impl<V> RadixHeap<V> {
+    pub fn new() -> Self {
+        Self {
+            buckets: std::array::from_fn(|_| Vec::new()),
+            last: 0,
+            len: 0,
+        }
+    }
+
+    /// Number of items in the heap.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the heap has no items.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Bucket of `key` relative to the last popped key.
+    #[inline]
+    fn bucket_of(&self, key: u64) -> usize {
+        (u64::BITS - (key ^ self.last).leading_zeros()) as usize
+    }
+
+    /// Inserts `value` with the given `key`, which must not be smaller than
+    /// the last popped key.
+    pub fn push(&mut self, key: u64, value: V) {
+        debug_assert!(
+            key >= self.last,
+            "Non-monotone push: key {} is smaller than the last popped key {}.",
+            key,
+            self.last
+        );
+        let bucket = self.bucket_of(key);
+        self.buckets[bucket].push((key, value));
+        self.len += 1;
+    }
+
+    /// Removes an item with the smallest key, returning it together with its
+    /// key, or `None` if the heap is empty.
+    pub fn pop(&mut self) -> Option<(u64, V)> {
+        if self.buckets[0].is_empty() {
+            let i = self.buckets.iter().position(|b| !b.is_empty())?;
+            let items = std::mem::take(&mut self.buckets[i]);
+            self.last = items
+                .iter()
+                .map(|&(key, _)| key)
+                .min()
+                .expect("Bucket should be non-empty");
+            for (key, value) in items {
+                let bucket = self.bucket_of(key);
+                debug_assert!(bucket < i, "Keys should move to lower buckets.");
+                self.buckets[bucket].push((key, value));
+            }
+        }
+        self.len -= 1;
+        self.buckets[0].pop()
+    }
+
+    /// Returns the smallest key of the heap, if non-empty.
+    pub fn peek_key(&self) -> Option<u64> {
+        if !self.buckets[0].is_empty() {
+            return Some(self.last);
+        }
+        self.buckets
+            .iter()
+            .find(|b| !b.is_empty())
+            .and_then(|b| b.iter().map(|&(key, _)| key).min())
+    }
+}
+
+impl<V> Default for RadixHeap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn monotone_usage() {
+        let mut heap = RadixHeap::new();
+        let mut expected = Vec::new();
+        let mut last = 0;
+        for _ in 0..5_000 {
+            if fastrand::bool() || expected.is_empty() {
+                // Keys far apart exercise the higher buckets.
+                let key = last + fastrand::u64(..1 << fastrand::u32(..40));
+                heap.push(key, ());
+                expected.push(key);
+            } else {
+                expected.sort_unstable_by(|a, b| b.cmp(a));
+                let popped = heap.pop().map(|(key, _)| key);
+                assert_eq!(popped, expected.pop());
+                last = popped.unwrap();
+            }
+            assert_eq!(heap.len(), expected.len());
+            assert_eq!(heap.peek_key(), expected.iter().min().copied());
+        }
+    }
+
+    #[test]
+    fn extreme_keys() {
+        let mut heap = RadixHeap::new();
+        for key in [u64::MAX, 0, u64::MAX, 1 << 63] {
+            heap.push(key, key);
+        }
+        let keys: Vec<u64> = std::iter::from_fn(|| heap.pop().map(|(key, _)| key)).collect();
+        assert_eq!(keys, [0, 1 << 63, u64::MAX, u64::MAX]);
+        assert!(heap.is_empty());
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "Non-monotone push")]
+    fn detects_non_monotone_push() {
+        let mut heap = RadixHeap::new();
+        heap.push(10, ());
+        heap.pop();
+        heap.push(9, ());
+    }
+}
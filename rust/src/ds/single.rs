@@ -40,13 +40,56 @@ impl<T> SingleLinkedList<T> {
         self.head.as_mut().map(|node| &mut node.key)
     }
 
-    pub fn iter(&self) -> SingleLinkedListIter<T> {
+    /// Keeps only the keys for which `pred` returns `true`, preserving their
+    /// order.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut pred: F) {
+        self.drain_filter(|key| !pred(key));
+    }
+
+    /// Removes the keys for which `pred` returns `true`, returning them in
+    /// their order in the list. The remaining nodes are relinked in place.
+    pub fn drain_filter<F: FnMut(&T) -> bool>(&mut self, mut pred: F) -> Vec<T> {
+        let mut removed = Vec::new();
+        let mut cursor = &mut self.head;
+        while let Some(mut node) = cursor.take() {
+            if pred(&node.key) {
+                *cursor = node.next.take();
+                removed.push(node.key);
+            } else {
+                cursor = &mut cursor.insert(node).next;
+            }
+        }
+        removed
+    }
+
+    /// Moves the first key for which `pred` returns `true` to the front of the
+    /// list, returning its former position, if any.
+    pub fn move_to_front<F: FnMut(&T) -> bool>(&mut self, mut pred: F) -> Option<usize> {
+        let mut found = None;
+        let mut index = 0;
+        let mut cursor = &mut self.head;
+        while let Some(mut node) = cursor.take() {
+            if pred(&node.key) {
+                *cursor = node.next.take();
+                found = Some(node);
+                break;
+            }
+            cursor = &mut cursor.insert(node).next;
+            index += 1;
+        }
+        let mut node = found?;
+        node.next = self.head.take();
+        self.head = Some(node);
+        Some(index)
+    }
+
+    pub fn iter(&self) -> SingleLinkedListIter<'_, T> {
         SingleLinkedListIter {
             next: self.head.as_deref(),
         }
     }
 
-    pub fn iter_mut(&mut self) -> SingleLinkedListIterMut<T> {
+    pub fn iter_mut(&mut self) -> SingleLinkedListIterMut<'_, T> {
         SingleLinkedListIterMut {
             next: self.head.as_deref_mut(),
         }
@@ -148,9 +191,9 @@ mod test {
         list.push(1);
         assert_eq!(list.peek(), Some(&1));
 
-        list.peek_mut().map(|key| {
+        if let Some(key) = list.peek_mut() {
             *key += 2;
-        });
+        }
         assert_eq!(list.peek_mut(), Some(&mut 3));
         assert_eq!(list.peek(), Some(&3));
     }
@@ -193,4 +236,37 @@ mod test {
         }
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn retain_n_drain_filter() {
+        let mut list = SingleLinkedList::new();
+        for x in 0..10 {
+            list.push(x);
+        }
+
+        assert_eq!(list.drain_filter(|x| x % 3 == 0), vec![9, 6, 3, 0]);
+        list.retain(|&x| x > 2);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![8, 7, 5, 4]);
+
+        list.retain(|_| false);
+        assert_eq!(list.peek(), None);
+    }
+
+    #[test]
+    fn move_to_front() {
+        let mut list = SingleLinkedList::new();
+        assert_eq!(list.move_to_front(|_| true), None);
+        for x in 0..5 {
+            list.push(x);
+        }
+
+        assert_eq!(list.move_to_front(|&x| x == 1), Some(3));
+        assert_eq!(list.move_to_front(|&x| x == 1), Some(0));
+        assert_eq!(list.move_to_front(|&x| x == 0), Some(4));
+        assert_eq!(list.move_to_front(|&x| x == 7), None);
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![0, 1, 4, 3, 2]
+        );
+    }
 }
@@ -0,0 +1,317 @@
+//! C interface to some of the crate's algorithms (enabled by the `ffi`
+//! feature), declared in `include/algae.h`. A static library can be built
+//! with
+//!
+//! ```sh
+//! cargo rustc --release --features ffi --crate-type staticlib
+//! ```
+//!
+//! and linked against C or C++ programs, for instance to benchmark the
+//! implementations against other libraries.
+use crate::{
+    graph::{self, EdgeWeightedDigraph},
+    search, sort,
+};
+use std::{mem, slice};
+
+/// Sorts in place the `len` integers starting at `ptr` using introsort.
+///
+/// # Safety
+///
+/// `ptr` must be valid for reads and writes of `len` consecutive `i64` values.
+#[no_mangle]
+pub unsafe extern "C" fn algae_sort_i64(ptr: *mut i64, len: usize) {
+    if ptr.is_null() || len == 0 {
+        return;
+    }
+    sort::intro_sort(slice::from_raw_parts_mut(ptr, len));
+}
+
+/// Binary searches for `x` among the `len` sorted integers starting at `ptr`.
+/// Returns the index of a matching element, or `-1` if there is none.
+///
+/// # Safety
+///
+/// `ptr` must be valid for reads of `len` consecutive `i64` values.
+#[no_mangle]
+pub unsafe extern "C" fn algae_binary_search_i64(ptr: *const i64, len: usize, x: i64) -> isize {
+    if ptr.is_null() || len == 0 {
+        return -1;
+    }
+    search::binary_search(slice::from_raw_parts(ptr, len), &x).map_or(-1, |idx| idx as isize)
+}
+
+/// Shortest path distances from `source` by Dijkstra's algorithm, in a
+/// digraph with `n` vertices given in compressed sparse row (CSR) form: the
+/// edges leaving `v` are those of index `offsets[v]..offsets[v + 1]`, going to
+/// `targets[e]` with weight `weights[e]`. The distance to each vertex is
+/// written to `dist`, as infinity for unreachable vertices.
+///
+/// Returns `0` on success, `-1` if the arguments are invalid (null pointers,
+/// too many vertices, decreasing offsets, or vertices out of range) and `-2`
+/// if a weight is negative, in which cases `dist` is left untouched.
+///
+/// # Safety
+///
+/// `offsets` and `dist` must be valid for `n + 1` and `n` consecutive values,
+/// respectively, and `targets` and `weights` for `offsets[n]` values.
+#[no_mangle]
+pub unsafe extern "C" fn algae_dijkstra_csr(
+    n: usize,
+    offsets: *const usize,
+    targets: *const usize,
+    weights: *const f64,
+    source: usize,
+    dist: *mut f64,
+) -> i32 {
+    if offsets.is_null() || dist.is_null() || source >= n {
+        return -1;
+    }
+    // Slices may span at most `isize::MAX` bytes.
+    let Some(len) = n
+        .checked_add(1)
+        .filter(|&len| len <= isize::MAX as usize / mem::size_of::<usize>())
+    else {
+        return -1;
+    };
+    let offsets = slice::from_raw_parts(offsets, len);
+    let m = offsets[n];
+    if offsets[0] != 0 || offsets.windows(2).any(|o| o[0] > o[1]) {
+        return -1;
+    }
+    if m > 0 && (targets.is_null() || weights.is_null()) {
+        return -1;
+    }
+    let (targets, weights) = if m == 0 {
+        (&[][..], &[][..])
+    } else {
+        (
+            slice::from_raw_parts(targets, m),
+            slice::from_raw_parts(weights, m),
+        )
+    };
+    if targets.iter().any(|&w| w >= n) {
+        return -1;
+    }
+
+    let mut graph = EdgeWeightedDigraph::new(n);
+    for v in 0..n {
+        for e in offsets[v]..offsets[v + 1] {
+            graph.add_edge(v, targets[e], weights[e]);
+        }
+    }
+    let Ok(paths) = graph::dijkstra(&graph, source) else {
+        return -2;
+    };
+    let dist = slice::from_raw_parts_mut(dist, n);
+    for (v, d) in dist.iter_mut().enumerate() {
+        *d = paths.dist_to(v).unwrap_or(f64::INFINITY);
+    }
+    0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::{
+        env, fs, io,
+        process::{self, Command},
+        ptr,
+    };
+
+    #[test]
+    fn sort_and_search() {
+        let mut xs = [42, -3, 17, 0, 8, 8, 1 << 40];
+        unsafe {
+            algae_sort_i64(xs.as_mut_ptr(), xs.len());
+            assert_eq!(xs, [-3, 0, 8, 8, 17, 42, 1 << 40]);
+
+            assert_eq!(algae_binary_search_i64(xs.as_ptr(), xs.len(), 17), 4);
+            assert_eq!(algae_binary_search_i64(xs.as_ptr(), xs.len(), 5), -1);
+
+            algae_sort_i64(ptr::null_mut(), 3);
+            assert_eq!(algae_binary_search_i64(ptr::null(), 0, 1), -1);
+        }
+    }
+
+    #[test]
+    fn dijkstra_on_csr() {
+        // 0 -> 1 (4), 0 -> 2 (1), 2 -> 1 (2), 1 -> 3 (1), and 4 unreachable.
+        let offsets = [0, 2, 3, 4, 4, 4];
+        let targets = [1, 2, 3, 1];
+        let weights = [4.0, 1.0, 1.0, 2.0];
+        let mut dist = [0.0; 5];
+        let csr = |source, dist: &mut [f64]| unsafe {
+            algae_dijkstra_csr(
+                5,
+                offsets.as_ptr(),
+                targets.as_ptr(),
+                weights.as_ptr(),
+                source,
+                dist.as_mut_ptr(),
+            )
+        };
+        assert_eq!(csr(0, &mut dist), 0);
+        assert_eq!(dist, [0.0, 3.0, 1.0, 4.0, f64::INFINITY]);
+        assert_eq!(csr(5, &mut dist), -1);
+
+        let negative = [4.0, 1.0, -1.0, 2.0];
+        let out_of_range = [1, 2, 5, 1];
+        unsafe {
+            let mut dist = [0.0; 5];
+            let run = |targets: &[usize], weights: &[f64], dist: &mut [f64]| {
+                algae_dijkstra_csr(
+                    5,
+                    offsets.as_ptr(),
+                    targets.as_ptr(),
+                    weights.as_ptr(),
+                    0,
+                    dist.as_mut_ptr(),
+                )
+            };
+            assert_eq!(run(&targets, &negative, &mut dist), -2);
+            assert_eq!(run(&out_of_range, &weights, &mut dist), -1);
+            assert_eq!(dist, [0.0; 5]);
+
+            // A single vertex without edges needs no edge arrays.
+            let mut dist = [1.0];
+            let offsets = [0, 0];
+            let status = algae_dijkstra_csr(
+                1,
+                offsets.as_ptr(),
+                ptr::null(),
+                ptr::null(),
+                0,
+                dist.as_mut_ptr(),
+            );
+            assert_eq!((status, dist), (0, [0.0]));
+
+            // The vertex count is rejected before `offsets` is read.
+            let status = algae_dijkstra_csr(
+                usize::MAX,
+                offsets.as_ptr(),
+                ptr::null(),
+                ptr::null(),
+                0,
+                dist.as_mut_ptr(),
+            );
+            assert_eq!(status, -1);
+        }
+    }
+
+    #[test]
+    fn header_declares_exports() {
+        let header = include_str!("../include/algae.h");
+        for symbol in [
+            "algae_sort_i64",
+            "algae_binary_search_i64",
+            "algae_dijkstra_csr",
+        ] {
+            assert!(header.contains(symbol), "{} missing from header", symbol);
+        }
+    }
+
+    /// Rust types with a C counterpart.
+    trait CType {
+        fn c_type() -> String;
+    }
+
+    macro_rules! impl_c_type {
+        ($($t:ty => $c:expr),*) => {$(
+            impl CType for $t {
+                fn c_type() -> String {
+                    $c.to_string()
+                }
+            }
+        )*};
+    }
+
+    impl_c_type!(
+        () => "void", i32 => "int32_t", i64 => "int64_t", isize => "ptrdiff_t",
+        usize => "size_t", f64 => "double"
+    );
+
+    impl<T: CType> CType for *const T {
+        fn c_type() -> String {
+            format!("const {} *", T::c_type())
+        }
+    }
+
+    impl<T: CType> CType for *mut T {
+        fn c_type() -> String {
+            format!("{} *", T::c_type())
+        }
+    }
+
+    /// C prototype of a function pointer type.
+    trait CFn {
+        fn c_decl(name: &str) -> String;
+    }
+
+    macro_rules! impl_c_fn {
+        ($($arg:ident),*) => {
+            impl<R: CType, $($arg: CType),*> CFn for unsafe extern "C" fn($($arg),*) -> R {
+                fn c_decl(name: &str) -> String {
+                    let params: Vec<String> = vec![$($arg::c_type()),*];
+                    format!("{} {}({});", R::c_type(), name, params.join(", "))
+                }
+            }
+        };
+    }
+
+    impl_c_fn!(A, B);
+    impl_c_fn!(A, B, C);
+    impl_c_fn!(A, B, C, D, E, F);
+
+    fn c_decl<F: CFn>(name: &str, _: F) -> String {
+        F::c_decl(name)
+    }
+
+    #[test]
+    fn header_matches_exports() {
+        // Redeclares each export with the prototype derived from its Rust
+        // signature after the header, so that the C compiler rejects any
+        // declaration of the header that drifted.
+        let decls = [
+            c_decl(
+                "algae_sort_i64",
+                algae_sort_i64 as unsafe extern "C" fn(_, _),
+            ),
+            c_decl(
+                "algae_binary_search_i64",
+                algae_binary_search_i64 as unsafe extern "C" fn(_, _, _) -> _,
+            ),
+            c_decl(
+                "algae_dijkstra_csr",
+                algae_dijkstra_csr as unsafe extern "C" fn(_, _, _, _, _, _) -> _,
+            ),
+        ];
+        let dir = env::temp_dir().join(format!("algae-header-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("check.c");
+        fs::write(
+            &source,
+            format!("#include \"algae.h\"\n{}\n", decls.join("\n")),
+        )
+        .unwrap();
+
+        let status = Command::new("cc")
+            .args(["-std=c99", "-Wall", "-Werror", "-fsyntax-only", "-I"])
+            .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/include"))
+            .arg(&source)
+            .status();
+        fs::remove_dir_all(&dir).unwrap();
+        match status {
+            Ok(status) => assert!(
+                status.success(),
+                "header disagrees with:\n{}",
+                decls.join("\n")
+            ),
+            // Nothing to check the header with.
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                eprintln!("no C compiler found, skipping the header check")
+            }
+            Err(e) => panic!("failed to run the C compiler: {}", e),
+        }
+    }
+}
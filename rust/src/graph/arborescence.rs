@@ -0,0 +1,236 @@
+use crate::graph::{DirectedEdge, EdgeWeightedDigraph};
+
+/// Spanning arborescence of a digraph: a tree of edges directed away from its
+/// root, through which the root reaches every vertex, each other vertex
+/// having exactly one edge into it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arborescence {
+    pub edges: Vec<DirectedEdge>,
+    pub weight: f64,
+}
+
+/// Contracted digraph of one round of `min_arborescence`.
+struct Round {
+    /// Head of each edge.
+    heads: Vec<usize>,
+    /// Lightest edge into each vertex, or `usize::MAX` for the root.
+    cheapest: Vec<usize>,
+    /// Edge of this round that each edge of the next round comes from.
+    origin: Vec<usize>,
+}
+
+/// Chu-Liu/Edmonds algorithm: a spanning arborescence of least total weight
+/// rooted at `root`, or `None` if some vertex is unreachable from `root`.
+/// Weights may be negative. Takes O(V * E) time.
+///
+/// Each vertex other than the root picks its lightest incoming edge. If the
+/// picked edges have no cycle, they form the arborescence. Otherwise, each
+/// cycle is contracted into a single vertex, where the weight of every edge
+/// entering a vertex `v` of the cycle is lowered by the weight of the edge
+/// picked by `v`: entering the cycle at `v` costs the difference, replacing
+/// the edge of the cycle into `v`. The contracted digraph is solved in the
+/// same way, and its arborescence expanded by adding the edges of each cycle
+/// but the one into the vertex where the cycle is entered. Contractions are
+/// done in rounds kept on a stack, expanded once no cycle remains, so that
+/// there is no recursion.
+///
+/// Example:
+/// ```
+/// use algae::graph::{min_arborescence, EdgeWeightedDigraph};
+///
+/// // The cheap cycle 1->2->1 has to be entered from the root.
+/// let g = EdgeWeightedDigraph::from_edges(
+///     4,
+///     [(0, 1, 10.0), (0, 2, 8.0), (1, 2, 1.0), (2, 1, 1.0), (2, 3, 4.0), (1, 3, 2.0)],
+/// );
+/// let tree = min_arborescence(&g, 0).unwrap();
+/// assert_eq!(tree.weight, 11.0);
+/// let mut edges: Vec<(usize, usize)> = tree.edges.iter().map(|e| (e.from, e.to)).collect();
+/// edges.sort();
+/// assert_eq!(edges, [(0, 2), (1, 3), (2, 1)]);
+///
+/// assert_eq!(min_arborescence(&g, 3), None);
+/// ```
+pub fn min_arborescence(graph: &EdgeWeightedDigraph, root: usize) -> Option<Arborescence> {
+    let original: Vec<DirectedEdge> = graph.edges().copied().collect();
+    let mut edges: Vec<(usize, usize, f64)> =
+        original.iter().map(|e| (e.from, e.to, e.weight)).collect();
+    let (mut n, mut root_now) = (graph.vertex_count(), root);
+    let mut rounds: Vec<Round> = Vec::new();
+
+    let chosen = loop {
+        let mut cheapest = vec![usize::MAX; n];
+        for (e, &(from, to, weight)) in edges.iter().enumerate() {
+            if from != to
+                && to != root_now
+                && (cheapest[to] == usize::MAX || weight < edges[cheapest[to]].2)
+            {
+                cheapest[to] = e;
+            }
+        }
+        if (0..n).any(|v| v != root_now && cheapest[v] == usize::MAX) {
+            return None;
+        }
+
+        // Label the vertices of each cycle of picked edges, found by walking
+        // the picked edges backwards from each vertex, with a new vertex.
+        let mut id = vec![usize::MAX; n];
+        let mut walk = vec![usize::MAX; n];
+        let mut count = 0;
+        for start in 0..n {
+            let mut v = start;
+            while walk[v] == usize::MAX && v != root_now {
+                walk[v] = start;
+                v = edges[cheapest[v]].0;
+            }
+            if v != root_now && walk[v] == start && id[v] == usize::MAX {
+                let mut u = v;
+                loop {
+                    id[u] = count;
+                    u = edges[cheapest[u]].0;
+                    if u == v {
+                        break;
+                    }
+                }
+                count += 1;
+            }
+        }
+        if count == 0 {
+            break (0..n)
+                .filter(|&v| v != root_now)
+                .map(|v| cheapest[v])
+                .collect::<Vec<_>>();
+        }
+        for label in id.iter_mut().filter(|label| **label == usize::MAX) {
+            *label = count;
+            count += 1;
+        }
+
+        let mut contracted = Vec::new();
+        let mut origin = Vec::new();
+        for (e, &(from, to, weight)) in edges.iter().enumerate() {
+            if id[from] != id[to] {
+                let lowered = weight - edges.get(cheapest[to]).map_or(0.0, |c| c.2);
+                contracted.push((id[from], id[to], lowered));
+                origin.push(e);
+            }
+        }
+        rounds.push(Round {
+            heads: edges.iter().map(|e| e.1).collect(),
+            cheapest,
+            origin,
+        });
+        (n, root_now) = (count, id[root_now]);
+        edges = contracted;
+    };
+
+    // Each round keeps its picked edges, except into the vertices entered by
+    // an edge chosen in the contracted digraph.
+    let mut chosen = chosen;
+    while let Some(round) = rounds.pop() {
+        let mut into = round.cheapest;
+        for e in chosen {
+            let e = round.origin[e];
+            into[round.heads[e]] = e;
+        }
+        chosen = into.into_iter().filter(|&e| e != usize::MAX).collect();
+    }
+    let edges: Vec<DirectedEdge> = chosen.into_iter().map(|e| original[e]).collect();
+    let weight = edges.iter().map(|e| e.weight).sum();
+    Some(Arborescence { edges, weight })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Least weight of an arborescence rooted at `root`, by trying every
+    /// choice of an incoming edge for each other vertex.
+    fn brute_force(g: &EdgeWeightedDigraph, root: usize) -> Option<f64> {
+        let n = g.vertex_count();
+        let incoming: Vec<Vec<DirectedEdge>> = g
+            .vertices()
+            .map(|v| {
+                g.edges()
+                    .filter(|e| e.to == v && e.from != v && v != root)
+                    .copied()
+                    .collect()
+            })
+            .collect();
+        if (0..n).any(|v| v != root && incoming[v].is_empty()) {
+            return None;
+        }
+        let mut best: Option<f64> = None;
+        let mut choice = vec![0; n];
+        loop {
+            // Every vertex reaches the root within n steps up the tree.
+            let tree = (0..n).all(|v| {
+                let mut u = v;
+                for _ in 0..n {
+                    if u == root {
+                        break;
+                    }
+                    u = incoming[u][choice[u]].from;
+                }
+                u == root
+            });
+            if tree {
+                let weight: f64 = (0..n)
+                    .filter(|&v| v != root)
+                    .map(|v| incoming[v][choice[v]].weight)
+                    .sum();
+                best = Some(best.map_or(weight, |b| b.min(weight)));
+            }
+            // Next choice, as a mixed radix counter.
+            let Some(v) = (0..n).find(|&v| v != root && choice[v] + 1 < incoming[v].len()) else {
+                return best;
+            };
+            choice[v] += 1;
+            for c in &mut choice[..v] {
+                *c = 0;
+            }
+        }
+    }
+
+    #[test]
+    fn agrees_with_brute_force() {
+        for _ in 0..300 {
+            let n = fastrand::usize(1..6);
+            let g = EdgeWeightedDigraph::from_edges(
+                n,
+                (0..fastrand::usize(..4 * n)).map(|_| {
+                    (
+                        fastrand::usize(..n),
+                        fastrand::usize(..n),
+                        fastrand::i8(-5..10) as f64,
+                    )
+                }),
+            );
+            let root = fastrand::usize(..n);
+            let tree = min_arborescence(&g, root);
+            assert_eq!(tree.as_ref().map(|t| t.weight), brute_force(&g, root));
+            if let Some(tree) = tree {
+                assert_eq!(tree.edges.len(), n - 1);
+                let mut parent = vec![None; n];
+                for e in &tree.edges {
+                    assert!(g.adj(e.from).contains(e));
+                    assert!(parent[e.to].replace(e.from).is_none() && e.to != root);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn deep_contractions() {
+        // Nested cycles force a contraction in every round.
+        let n = 2_000;
+        let mut g = EdgeWeightedDigraph::new(n);
+        for v in 1..n - 1 {
+            g.add_edge(v + 1, v, 1.0);
+            g.add_edge(v, v + 1, 2.0);
+        }
+        g.add_edge(0, n - 1, 100.0);
+        let tree = min_arborescence(&g, 0).unwrap();
+        assert_eq!(tree.weight, 100.0 + (n - 2) as f64);
+    }
+}
@@ -0,0 +1,166 @@
+use crate::graph::{Adjacency, BfsIter, EdgeKind, Event, GraphVisitor};
+
+/// Result of a breadth-first search: the vertices reached from the sources,
+/// with their distance in number of edges and the edge through which each was
+/// first reached, forming a tree of shortest paths.
+#[derive(Debug, Clone)]
+pub struct BfsResult {
+    dist: Vec<Option<usize>>,
+    parent: Vec<Option<usize>>,
+}
+
+impl BfsResult {
+    /// Whether `v` is reachable from a source.
+    pub fn visited(&self, v: usize) -> bool {
+        self.dist[v].is_some()
+    }
+
+    /// Number of edges of a shortest path from a source to `v`, or `None` if
+    /// `v` is unreachable.
+    pub fn dist_to(&self, v: usize) -> Option<usize> {
+        self.dist[v]
+    }
+
+    /// Vertices of a shortest path from a source to `v`, starting at the
+    /// source and ending at `v`, or `None` if `v` is unreachable.
+    pub fn path_to(&self, v: usize) -> Option<Vec<usize>> {
+        self.dist[v]?;
+        let mut path = vec![v];
+        let mut u = v;
+        while let Some(p) = self.parent[u] {
+            path.push(p);
+            u = p;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Breadth-first search from `source`, which visits the vertices in order of
+/// their distance from `source`, in O(V + E) time.
+///
+/// Example:
+/// ```
+/// use algae::graph::{bfs, Graph};
+///
+/// let g = Graph::from_edges(6, [(0, 1), (1, 2), (2, 3), (0, 4), (4, 3)]);
+/// let result = bfs(&g, 0);
+/// assert_eq!(result.dist_to(3), Some(2));
+/// assert_eq!(result.path_to(3), Some(vec![0, 4, 3]));
+/// assert!(!result.visited(5));
+/// assert_eq!(result.path_to(5), None);
+/// ```
+pub fn bfs<G: Adjacency>(graph: &G, source: usize) -> BfsResult {
+    bfs_multi(graph, [source])
+}
+
+/// Breadth-first search from several sources at once, giving the distance of
+/// each vertex to its nearest source. Paths start at that source.
+///
+/// Example:
+/// ```
+/// use algae::graph::{bfs_multi, Digraph};
+///
+/// let g = Digraph::from_edges(5, [(0, 1), (1, 2), (2, 3), (4, 3)]);
+/// let result = bfs_multi(&g, [0, 4]);
+/// assert_eq!(result.dist_to(3), Some(1));
+/// assert_eq!(result.path_to(3), Some(vec![4, 3]));
+/// assert_eq!(result.dist_to(2), Some(2));
+/// ```
+pub fn bfs_multi<G, I>(graph: &G, sources: I) -> BfsResult
+where
+    G: Adjacency,
+    I: IntoIterator<Item = usize>,
+{
+    bfs_visit(graph, sources, &mut ())
+}
+
+/// Breadth-first search from several sources, as `bfs_multi`, passing each
+/// event of the search to `visitor` as in `Adjacency::bfs_iter`.
+pub fn bfs_visit<G, I, V>(graph: &G, sources: I, visitor: &mut V) -> BfsResult
+where
+    G: Adjacency,
+    I: IntoIterator<Item = usize>,
+    V: GraphVisitor,
+{
+    let n = graph.vertex_count();
+    trace_span!("bfs", vertices = n);
+    let mut dist = vec![None; n];
+    let mut parent = vec![None; n];
+    for event in BfsIter::new(graph, sources) {
+        match event {
+            Event::Discover(v) => {
+                // Only sources are discovered without a tree edge to them.
+                dist[v].get_or_insert(0);
+                trace_event!(vertex = v, dist = dist[v], "vertex visited");
+            }
+            Event::Edge(v, w, EdgeKind::Tree) => {
+                dist[w] = dist[v].map(|d: usize| d + 1);
+                parent[w] = Some(v);
+            }
+            _ => {}
+        }
+        visitor.visit(event);
+    }
+    BfsResult { dist, parent }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::{Digraph, Graph};
+
+    /// Distances from `source` by relaxing every edge until nothing changes.
+    fn distances(g: &Digraph, source: usize) -> Vec<Option<usize>> {
+        let mut dist = vec![None; g.vertex_count()];
+        dist[source] = Some(0);
+        loop {
+            let mut changed = false;
+            for (v, w) in g.edges() {
+                if let Some(d) = dist[v] {
+                    if dist[w].is_none_or(|dw| d + 1 < dw) {
+                        dist[w] = Some(d + 1);
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                return dist;
+            }
+        }
+    }
+
+    #[test]
+    fn shortest_paths() {
+        for _ in 0..50 {
+            let n = fastrand::usize(1..20);
+            let g = Digraph::from_edges(
+                n,
+                (0..fastrand::usize(..40)).map(|_| (fastrand::usize(..n), fastrand::usize(..n))),
+            );
+            let result = bfs(&g, 0);
+            for (v, d) in distances(&g, 0).into_iter().enumerate() {
+                assert_eq!(result.dist_to(v), d);
+                assert_eq!(result.visited(v), d.is_some());
+                if let Some(path) = result.path_to(v) {
+                    assert_eq!(path.len(), d.unwrap() + 1);
+                    assert_eq!((path[0], path[path.len() - 1]), (0, v));
+                    assert!(path.windows(2).all(|e| g.has_edge(e[0], e[1])));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn multiple_sources() {
+        let g = Graph::from_edges(7, [(0, 1), (1, 2), (2, 3), (3, 4), (4, 5)]);
+        let result = bfs_multi(&g, [0, 5, 0]);
+        let dist: Vec<_> = g.vertices().map(|v| result.dist_to(v)).collect();
+        assert_eq!(
+            dist,
+            [Some(0), Some(1), Some(2), Some(2), Some(1), Some(0), None]
+        );
+        assert_eq!(result.path_to(5), Some(vec![5]));
+        assert!(bfs_multi(&g, []).path_to(0).is_none());
+    }
+}
@@ -0,0 +1,252 @@
+use crate::graph::Graph;
+
+/// Articulation points, bridges and biconnected components of a graph, found
+/// by a single depth-first search.
+struct LowLink {
+    articulation: Vec<bool>,
+    bridges: Vec<(usize, usize)>,
+    components: Vec<Vec<(usize, usize)>>,
+}
+
+impl LowLink {
+    /// Runs an iterative depth-first search computing, for each vertex `v`,
+    /// its preorder number `pre[v]` and the lowest preorder number `low[v]`
+    /// reachable from the subtree of `v` by tree edges followed by one back
+    /// edge. A tree edge `p-w` then separates the subtree of `w` from the
+    /// vertices above `p` unless `low[w] < pre[p]`, and from `p` as well
+    /// unless `low[w] <= pre[p]`. Self-loops are ignored, while parallel
+    /// edges are back edges like any other.
+    fn new(graph: &Graph) -> Self {
+        let n = graph.vertex_count();
+        let mut pre = vec![usize::MAX; n];
+        let mut low = vec![0; n];
+        let mut result = Self {
+            articulation: vec![false; n],
+            bridges: Vec::new(),
+            components: Vec::new(),
+        };
+        // Edges of the components not yet completed.
+        let mut edges = Vec::new();
+        let mut visited = 0;
+
+        for root in graph.vertices() {
+            if pre[root] != usize::MAX {
+                continue;
+            }
+            pre[root] = visited;
+            low[root] = visited;
+            visited += 1;
+            let mut root_children = 0;
+            // Vertex, index of its next edge, its parent, and whether the
+            // tree edge from the parent was already skipped.
+            let mut stack = vec![(root, 0, usize::MAX, false)];
+            while let Some((v, i, parent, skipped)) = stack.last_mut() {
+                let (v, p) = (*v, *parent);
+                if let Some(&w) = graph.adj(v).get(*i) {
+                    *i += 1;
+                    if w == v {
+                        continue;
+                    }
+                    if w == p && !*skipped {
+                        *skipped = true;
+                        continue;
+                    }
+                    if pre[w] == usize::MAX {
+                        pre[w] = visited;
+                        low[w] = visited;
+                        visited += 1;
+                        edges.push((v, w));
+                        stack.push((w, 0, v, false));
+                    } else if pre[w] < pre[v] {
+                        edges.push((v, w));
+                        low[v] = low[v].min(pre[w]);
+                    }
+                    continue;
+                }
+
+                stack.pop();
+                if p == usize::MAX {
+                    continue;
+                }
+                low[p] = low[p].min(low[v]);
+                if low[v] >= pre[p] {
+                    if p == root {
+                        root_children += 1;
+                    } else {
+                        result.articulation[p] = true;
+                    }
+                    let mut component = Vec::new();
+                    while let Some(e) = edges.pop() {
+                        component.push((e.0.min(e.1), e.0.max(e.1)));
+                        if e == (p, v) {
+                            break;
+                        }
+                    }
+                    component.sort_unstable();
+                    result.components.push(component);
+                }
+                if low[v] > pre[p] {
+                    result.bridges.push((p.min(v), p.max(v)));
+                }
+            }
+            result.articulation[root] = root_children > 1;
+        }
+        result.bridges.sort_unstable();
+        result
+    }
+}
+
+/// Articulation points of `graph`, in increasing order: the vertices whose
+/// removal increases the number of connected components. Takes O(V + E)
+/// time.
+///
+/// Example:
+/// ```
+/// use algae::graph::{articulation_points, Graph};
+///
+/// // Two triangles sharing the vertex 2, with a pendant vertex 5 on 4.
+/// let g = Graph::from_edges(6, [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 2), (4, 5)]);
+/// assert_eq!(articulation_points(&g), [2, 4]);
+/// ```
+pub fn articulation_points(graph: &Graph) -> Vec<usize> {
+    let articulation = LowLink::new(graph).articulation;
+    graph.vertices().filter(|&v| articulation[v]).collect()
+}
+
+/// Bridges of `graph`, as `(v, w)` with `v < w` in increasing order: the
+/// edges whose removal increases the number of connected components. An
+/// edge with a parallel copy is never a bridge. Takes O(V + E) time.
+///
+/// Example:
+/// ```
+/// use algae::graph::{bridges, Graph};
+///
+/// let g = Graph::from_edges(6, [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 5), (5, 3)]);
+/// assert_eq!(bridges(&g), [(2, 3)]);
+/// ```
+pub fn bridges(graph: &Graph) -> Vec<(usize, usize)> {
+    LowLink::new(graph).bridges
+}
+
+/// Biconnected components of `graph`: the maximal sets of edges in which any
+/// two edges lie on a common simple cycle, or single bridges. Components
+/// share at most an articulation point. Each component is given by its edges
+/// `(v, w)` with `v <= w`, in increasing order, and self-loops belong to no
+/// component. Takes O(V + E) time.
+///
+/// Example:
+/// ```
+/// use algae::graph::{biconnected_components, Graph};
+///
+/// let g = Graph::from_edges(5, [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4)]);
+/// let mut components = biconnected_components(&g);
+/// components.sort();
+/// assert_eq!(components, [vec![(0, 1), (0, 2), (1, 2)], vec![(2, 3)], vec![(3, 4)]]);
+/// ```
+pub fn biconnected_components(graph: &Graph) -> Vec<Vec<(usize, usize)>> {
+    LowLink::new(graph).components
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn random_graph(n: usize, m: usize) -> Graph {
+        Graph::from_edges(
+            n,
+            (0..m).map(|_| (fastrand::usize(..n), fastrand::usize(..n))),
+        )
+    }
+
+    /// Number of connected components of the graph without the vertex `skip`
+    /// and the edge of index `skip_edge` in `g.edges()`.
+    fn components_without(g: &Graph, skip: Option<usize>, skip_edge: Option<usize>) -> usize {
+        let edges = g
+            .edges()
+            .enumerate()
+            .filter(|&(i, (v, w))| Some(i) != skip_edge && Some(v) != skip && Some(w) != skip)
+            .map(|(_, e)| e);
+        let mut component: Vec<usize> = g.vertices().collect();
+        fn find(c: &mut [usize], v: usize) -> usize {
+            if c[v] != v {
+                c[v] = find(c, c[v]);
+            }
+            c[v]
+        }
+        let mut count = g.vertex_count() - skip.is_some() as usize;
+        for (v, w) in edges {
+            let (a, b) = (find(&mut component, v), find(&mut component, w));
+            if a != b {
+                component[a] = b;
+                count -= 1;
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn brute_force() {
+        for _ in 0..200 {
+            let n = fastrand::usize(1..12);
+            let g = random_graph(n, fastrand::usize(..2 * n));
+            let base = components_without(&g, None, None);
+
+            let expected: Vec<usize> = g
+                .vertices()
+                .filter(|&v| components_without(&g, Some(v), None) > base)
+                .collect();
+            assert_eq!(articulation_points(&g), expected, "{:?}", g);
+
+            let mut expected: Vec<(usize, usize)> = g
+                .edges()
+                .enumerate()
+                .filter(|&(i, _)| components_without(&g, None, Some(i)) > base)
+                .map(|(_, e)| e)
+                .collect();
+            expected.sort_unstable();
+            assert_eq!(bridges(&g), expected, "{:?}", g);
+        }
+    }
+
+    #[test]
+    fn components_partition_edges() {
+        for _ in 0..100 {
+            let n = fastrand::usize(1..15);
+            let g = random_graph(n, fastrand::usize(..2 * n));
+            let components = biconnected_components(&g);
+            let mut all: Vec<_> = components.concat();
+            let mut edges: Vec<_> = g.edges().filter(|(v, w)| v != w).collect();
+            all.sort_unstable();
+            edges.sort_unstable();
+            assert_eq!(all, edges);
+
+            // Bridges are exactly the components with a single edge and no
+            // parallel copy.
+            let mut single: Vec<_> = components
+                .iter()
+                .filter(|c| c.len() == 1)
+                .map(|c| c[0])
+                .collect();
+            single.sort_unstable();
+            assert_eq!(single, bridges(&g));
+
+            // A vertex in several components is an articulation point.
+            let points = articulation_points(&g);
+            for v in g.vertices() {
+                let count = components
+                    .iter()
+                    .filter(|c| c.iter().any(|&(a, b)| a == v || b == v))
+                    .count();
+                assert_eq!(count > 1, points.contains(&v));
+            }
+        }
+    }
+
+    #[test]
+    fn parallel_edges() {
+        let g = Graph::from_edges(3, [(0, 1), (0, 1), (1, 2)]);
+        assert_eq!(bridges(&g), [(1, 2)]);
+        assert_eq!(articulation_points(&g), [1]);
+        assert_eq!(biconnected_components(&g).len(), 2);
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{
+    ds::Queue,
+    graph::{Bigraph, Graph},
+};
+use std::fmt;
+
+/// Coloring of the vertices of a graph with two colors, `false` and `true`,
+/// such that every edge joins vertices of different colors. The vertices of
+/// color `false` form the left side and those of color `true` the right side,
+/// each numbered in increasing order of vertex.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TwoColoring {
+    color: Vec<bool>,
+    left: Vec<usize>,
+    right: Vec<usize>,
+}
+
+impl TwoColoring {
+    /// Color of `v`.
+    pub fn color(&self, v: usize) -> bool {
+        self.color[v]
+    }
+
+    /// Vertices of color `false`, in increasing order.
+    pub fn left(&self) -> &[usize] {
+        &self.left
+    }
+
+    /// Vertices of color `true`, in increasing order.
+    pub fn right(&self) -> &[usize] {
+        &self.right
+    }
+
+    /// The bipartite graph of `graph`, which this must be a coloring of, with
+    /// the left vertex `i` standing for `left()[i]` and the right vertex `j`
+    /// for `right()[j]`.
+    ///
+    /// Example:
+    /// ```
+    /// use algae::graph::{hopcroft_karp, is_bipartite, Graph};
+    ///
+    /// let g = Graph::from_edges(4, [(0, 1), (1, 2), (2, 3)]);
+    /// let coloring = is_bipartite(&g).unwrap();
+    /// assert_eq!((coloring.left(), coloring.right()), (&[0, 2][..], &[1, 3][..]));
+    /// let matching = hopcroft_karp(&coloring.bigraph(&g));
+    /// assert_eq!(matching.size(), 2);
+    /// ```
+    pub fn bigraph(&self, graph: &Graph) -> Bigraph {
+        // Position of each vertex within its side.
+        let mut index = vec![0; self.color.len()];
+        for side in [&self.left, &self.right] {
+            for (i, &v) in side.iter().enumerate() {
+                index[v] = i;
+            }
+        }
+        let mut bigraph = Bigraph::new(self.left.len(), self.right.len());
+        for &v in &self.left {
+            for &w in graph.adj(v) {
+                bigraph.add_edge(index[v], index[w]);
+            }
+        }
+        bigraph
+    }
+}
+
+/// Cycle of odd length, which proves that a graph is not bipartite. The
+/// vertices are listed in order along the cycle, which is closed by the edge
+/// from the last vertex to the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OddCycle {
+    pub vertices: Vec<usize>,
+}
+
+impl fmt::Display for OddCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "odd cycle")?;
+        for v in self.vertices.iter().chain(self.vertices.first()) {
+            write!(f, " {}", v)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for OddCycle {}
+
+/// Checks whether `graph` is bipartite, returning a two-coloring if it is,
+/// and an odd cycle otherwise. Each component is colored by a breadth-first
+/// search alternating the colors between layers, so that an edge between
+/// vertices of the same color joins two vertices of the same layer. Their
+/// tree paths up to their closest common ancestor then form an odd cycle
+/// with the edge. Takes O(V + E) time.
+///
+/// Example:
+/// ```
+/// use algae::graph::{is_bipartite, Graph};
+///
+/// let square = Graph::from_edges(4, [(0, 1), (1, 2), (2, 3), (3, 0)]);
+/// assert!(is_bipartite(&square).is_ok());
+///
+/// let g = Graph::from_edges(5, [(0, 1), (1, 2), (2, 3), (3, 4), (4, 0)]);
+/// assert_eq!(is_bipartite(&g).unwrap_err().vertices.len(), 5);
+/// ```
+pub fn is_bipartite(graph: &Graph) -> Result<TwoColoring, OddCycle> {
+    let n = graph.vertex_count();
+    let mut color: Vec<Option<bool>> = vec![None; n];
+    let mut parent = vec![usize::MAX; n];
+    for root in graph.vertices() {
+        if color[root].is_some() {
+            continue;
+        }
+        color[root] = Some(false);
+        let mut queue = Queue::new();
+        queue.push(root);
+        while let Some(v) = queue.pop() {
+            for &w in graph.adj(v) {
+                match color[w] {
+                    None => {
+                        color[w] = color[v].map(|c| !c);
+                        parent[w] = v;
+                        queue.push(w);
+                    }
+                    Some(c) if Some(c) == color[v] => {
+                        return Err(OddCycle {
+                            vertices: odd_cycle(&parent, v, w),
+                        })
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+
+    let color: Vec<bool> = color.into_iter().map(|c| c == Some(true)).collect();
+    let (right, left) = graph.vertices().partition(|&v| color[v]);
+    Ok(TwoColoring { color, left, right })
+}
+
+/// Cycle made of the edge `v-w` between two vertices of the same layer of a
+/// breadth-first search, and their tree paths up to their closest common
+/// ancestor.
+fn odd_cycle(parent: &[usize], v: usize, w: usize) -> Vec<usize> {
+    let (mut up, mut down) = (vec![v], vec![w]);
+    let (mut a, mut b) = (v, w);
+    while a != b {
+        a = parent[a];
+        b = parent[b];
+        up.push(a);
+        down.push(b);
+    }
+    // Both paths end at the common ancestor, which is kept once.
+    down.pop();
+    up.reverse();
+    up.extend(down);
+    up
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn colorings_n_odd_cycles() {
+        for _ in 0..200 {
+            let n = fastrand::usize(1..15);
+            let g = Graph::from_edges(
+                n,
+                (0..fastrand::usize(..n + 3)).map(|_| (fastrand::usize(..n), fastrand::usize(..n))),
+            );
+            match is_bipartite(&g) {
+                Ok(coloring) => {
+                    for (v, w) in g.edges() {
+                        assert_ne!(coloring.color(v), coloring.color(w));
+                    }
+                    assert_eq!(coloring.left().len() + coloring.right().len(), n);
+                    assert_eq!(coloring.bigraph(&g).edge_count(), g.edge_count());
+                }
+                Err(cycle) => {
+                    let vs = &cycle.vertices;
+                    assert_eq!(vs.len() % 2, 1);
+                    for (i, &v) in vs.iter().enumerate() {
+                        assert!(g.has_edge(v, vs[(i + 1) % vs.len()]));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn small_cases() {
+        assert_eq!(
+            is_bipartite(&Graph::from_edges(2, [(1, 1)]))
+                .unwrap_err()
+                .vertices,
+            [1]
+        );
+        let triangle = Graph::from_edges(3, [(0, 1), (1, 2), (2, 0)]);
+        let cycle = is_bipartite(&triangle).unwrap_err();
+        assert_eq!(cycle.to_string(), "odd cycle 0 1 2 0");
+        assert!(is_bipartite(&Graph::new(0)).is_ok());
+    }
+}
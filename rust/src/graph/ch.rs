@@ -0,0 +1,511 @@
+//! Contraction hierarchies: a preprocessing of a static edge-weighted digraph
+//! after which shortest path queries only explore a tiny part of it, which
+//! pays off for the many queries of routing on road networks.
+use crate::{
+    ds::{Heap, MaxHeap},
+    graph::{shortest::check_weights, EdgeWeightedDigraph, NegativeWeight, WeightedPath},
+};
+use std::{cmp::Reverse, collections::HashMap};
+
+/// Most vertices finished by a witness search, which bounds the time spent
+/// looking for a path avoiding a contracted vertex. A search giving up only
+/// adds a shortcut which was not needed.
+const MAX_SETTLED: usize = 200;
+
+/// Edge of the hierarchy towards or from another vertex, which is a shortcut
+/// for the two edges through `middle` when it has one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Arc {
+    vertex: usize,
+    weight: f64,
+    middle: Option<usize>,
+}
+
+/// Keeps the lightest of `arc` and the arc of `arcs` to the same vertex.
+fn insert_arc(arcs: &mut Vec<Arc>, arc: Arc) {
+    match arcs.iter_mut().find(|a| a.vertex == arc.vertex) {
+        Some(a) if arc.weight < a.weight => *a = arc,
+        Some(_) => {}
+        None => arcs.push(arc),
+    }
+}
+
+/// Contraction hierarchy of an edge-weighted digraph with non-negative
+/// weights. The vertices are contracted one at a time: a contracted vertex
+/// is removed from the digraph, and each path `u->v->w` through it is
+/// replaced by a shortcut `u->w` of the same weight, unless a witness search
+/// finds a path from `u` to `w` no longer than it avoiding `v`. The order of
+/// contraction ranks the vertices, and adding the shortcuts to the digraph
+/// keeps the distances, while every shortest path now has one which climbs
+/// the ranks and then descends them.
+///
+/// The next vertex to contract is the one adding the fewest shortcuts with
+/// respect to the edges it removes, plus the number of its neighbours already
+/// contracted, which spreads the contractions uniformly over the digraph.
+/// Priorities are updated for the neighbours of each contracted vertex, and
+/// checked again when a vertex comes out of the queue.
+///
+/// Example:
+/// ```
+/// use algae::graph::{ch::ContractionHierarchy, EdgeWeightedDigraph};
+///
+/// let g = EdgeWeightedDigraph::from_edges(
+///     5,
+///     [(0, 1, 4.0), (0, 2, 1.0), (2, 1, 2.0), (1, 3, 1.0), (2, 3, 5.0)],
+/// );
+/// let ch = ContractionHierarchy::new(&g).unwrap();
+/// let path = ch.query(0, 3).unwrap();
+/// assert_eq!(path.vertices, [0, 2, 1, 3]);
+/// assert_eq!(path.weight, 4.0);
+/// assert_eq!(ch.query(3, 0), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ContractionHierarchy {
+    /// Position of each vertex in the order of contraction.
+    rank: Vec<usize>,
+    /// Arcs leaving each vertex towards vertices of higher rank.
+    up: Vec<Vec<Arc>>,
+    /// Arcs entering each vertex from vertices of higher rank.
+    down: Vec<Vec<Arc>>,
+    shortcuts: usize,
+}
+
+impl ContractionHierarchy {
+    /// Contracts every vertex of `graph`, in the order given by their
+    /// priorities. Self-loops are dropped, and only the lightest of parallel
+    /// edges is kept.
+    ///
+    /// Returns an error if any edge has a negative weight.
+    pub fn new(graph: &EdgeWeightedDigraph) -> Result<Self, NegativeWeight> {
+        check_weights(graph)?;
+        trace_span!("contraction_hierarchy", vertices = graph.vertex_count());
+        let n = graph.vertex_count();
+        let mut contraction = Contraction::new(graph);
+        let mut priority: Vec<i64> = (0..n).map(|v| contraction.priority(v)).collect();
+        let mut queue = MaxHeap::from_vec((0..n).map(|v| Reverse((priority[v], v))).collect());
+        let mut rank = vec![usize::MAX; n];
+        let (mut up, mut down) = (vec![Vec::new(); n], vec![Vec::new(); n]);
+        let mut next_rank = 0;
+        while let Some(Reverse((p, v))) = queue.pop() {
+            if rank[v] != usize::MAX || p != priority[v] {
+                continue;
+            }
+            // The priority may have changed with contractions away from `v`.
+            let p = contraction.priority(v);
+            if queue.peek().is_some_and(|next| p > next.0 .0) {
+                priority[v] = p;
+                queue.push(Reverse((p, v)));
+                continue;
+            }
+            rank[v] = next_rank;
+            next_rank += 1;
+            for w in contraction.contract(v, &mut up[v], &mut down[v]) {
+                priority[w] = contraction.priority(w);
+                queue.push(Reverse((priority[w], w)));
+            }
+        }
+        Ok(Self {
+            rank,
+            up,
+            down,
+            shortcuts: contraction.shortcuts,
+        })
+    }
+
+    /// Number of vertices.
+    pub fn vertex_count(&self) -> usize {
+        self.rank.len()
+    }
+
+    /// Number of shortcuts added by the contractions.
+    pub fn shortcut_count(&self) -> usize {
+        self.shortcuts
+    }
+
+    /// Position of `v` in the order of contraction, from 0 to `V - 1`.
+    pub fn rank(&self, v: usize) -> usize {
+        self.rank[v]
+    }
+
+    /// Shortest path from `source` to `target`, or `None` if `target` is
+    /// unreachable. A search from `source` follows the arcs up the ranks, and
+    /// another from `target` follows the arcs coming down to it backwards,
+    /// each time advancing the one whose next vertex is closer, until both
+    /// have passed the length of the shortest path found where they meet.
+    /// The shortcuts of that path are then expanded into edges of the
+    /// digraph. Both searches only ever climb the ranks, so that they finish
+    /// far fewer vertices than `bidirectional_dijkstra` on road-like digraphs.
+    pub fn query(&self, source: usize, target: usize) -> Option<WeightedPath> {
+        let arcs = [&self.up, &self.down];
+        let mut searches = [UpwardSearch::new(source), UpwardSearch::new(target)];
+        // Length of the shortest path found, and the vertex where it joins.
+        let mut best = (f64::INFINITY, source);
+        loop {
+            let next = [searches[0].next_dist(), searches[1].next_dist()];
+            let side = match next {
+                [Some(f), Some(b)] if f.min(b) < best.0 => usize::from(b < f),
+                [Some(f), _] if f < best.0 => 0,
+                [_, Some(b)] if b < best.0 => 1,
+                _ => break,
+            };
+            let (v, d) = searches[side].step(arcs[side]);
+            if let Some(&(other, _, _)) = searches[1 - side].reached.get(&v) {
+                if d + other < best.0 {
+                    best = (d + other, v);
+                }
+            }
+        }
+
+        let (weight, middle) = best;
+        if weight == f64::INFINITY {
+            return None;
+        }
+        // Arcs of the path, up from `source` to `middle` then down to `target`.
+        let mut path = Vec::new();
+        let mut v = middle;
+        while v != source {
+            let (_, from, shortcut) = searches[0].reached[&v];
+            path.push((from, v, shortcut));
+            v = from;
+        }
+        path.reverse();
+        let mut v = middle;
+        while v != target {
+            let (_, to, shortcut) = searches[1].reached[&v];
+            path.push((v, to, shortcut));
+            v = to;
+        }
+
+        let mut vertices = vec![source];
+        for (from, to, shortcut) in path {
+            self.unpack(from, to, shortcut, &mut vertices);
+        }
+        Some(WeightedPath { vertices, weight })
+    }
+
+    /// Appends to `vertices` the vertices after `from` on the path of edges of
+    /// the digraph for the arc `from->to`, which is a shortcut through
+    /// `middle` when it has one.
+    fn unpack(&self, from: usize, to: usize, middle: Option<usize>, vertices: &mut Vec<usize>) {
+        let mut stack = vec![(from, to, middle)];
+        while let Some((from, to, middle)) = stack.pop() {
+            let Some(v) = middle else {
+                vertices.push(to);
+                continue;
+            };
+            // Both halves were arcs of `v` when it was contracted, below
+            // `from` and `to` in rank.
+            let first = self.down[v].iter().find(|a| a.vertex == from);
+            let second = self.up[v].iter().find(|a| a.vertex == to);
+            let (first, second) = (
+                first.expect("Arc into middle"),
+                second.expect("Arc from middle"),
+            );
+            stack.push((v, to, second.middle));
+            stack.push((from, v, first.middle));
+        }
+    }
+}
+
+/// Dijkstra's algorithm over the arcs of a contraction hierarchy going up
+/// from a vertex, or coming down to it when walked backwards. The vertices
+/// reached are kept in a map, as a query only reaches a few of them.
+struct UpwardSearch {
+    /// Distance to each reached vertex, with the previous vertex and the
+    /// middle vertex of the arc from it.
+    reached: HashMap<usize, (f64, usize, Option<usize>)>,
+    queue: MaxHeap<Reverse<(f64, usize)>>,
+}
+
+impl UpwardSearch {
+    fn new(source: usize) -> Self {
+        let mut queue = MaxHeap::new();
+        queue.push(Reverse((0.0, source)));
+        Self {
+            reached: HashMap::from([(source, (0.0, source, None))]),
+            queue,
+        }
+    }
+
+    /// Distance of the next vertex to finish, if any is left, dropping the
+    /// entries of the queue left behind by shorter distances.
+    fn next_dist(&mut self) -> Option<f64> {
+        while let Some(&Reverse((d, v))) = self.queue.peek() {
+            if d <= self.reached[&v].0 {
+                return Some(d);
+            }
+            self.queue.pop();
+        }
+        None
+    }
+
+    /// Finishes the closest vertex, which must exist, relaxing its `arcs`,
+    /// and returns it with its distance.
+    fn step(&mut self, arcs: &[Vec<Arc>]) -> (usize, f64) {
+        let Reverse((d, v)) = self.queue.pop().expect("Vertex to finish");
+        for arc in &arcs[v] {
+            let through = d + arc.weight;
+            let known = self.reached.get(&arc.vertex).map_or(f64::INFINITY, |r| r.0);
+            if through < known {
+                self.reached.insert(arc.vertex, (through, v, arc.middle));
+                self.queue.push(Reverse((through, arc.vertex)));
+            }
+        }
+        (v, d)
+    }
+}
+
+/// Digraph of the vertices not contracted yet, with the shortcuts added so
+/// far, kept as the arcs leaving and entering each vertex.
+struct Contraction {
+    out: Vec<Vec<Arc>>,
+    into: Vec<Vec<Arc>>,
+    /// Number of contracted neighbours of each vertex.
+    contracted_neighbours: Vec<i64>,
+    shortcuts: usize,
+    witness: WitnessSearch,
+}
+
+impl Contraction {
+    fn new(graph: &EdgeWeightedDigraph) -> Self {
+        let n = graph.vertex_count();
+        let (mut out, mut into) = (vec![Vec::new(); n], vec![Vec::new(); n]);
+        for e in graph.edges().filter(|e| e.from != e.to) {
+            let arc = |vertex| Arc {
+                vertex,
+                weight: e.weight,
+                middle: None,
+            };
+            insert_arc(&mut out[e.from], arc(e.to));
+            insert_arc(&mut into[e.to], arc(e.from));
+        }
+        Self {
+            out,
+            into,
+            contracted_neighbours: vec![0; n],
+            shortcuts: 0,
+            witness: WitnessSearch::new(n),
+        }
+    }
+
+    /// Shortcuts `(u, w, weight)` needed to contract `v`: the paths
+    /// `u->v->w` without a witness, a path from `u` to `w` avoiding `v` and
+    /// no longer.
+    fn shortcuts(&mut self, v: usize) -> Vec<(usize, usize, f64)> {
+        let Self {
+            out, into, witness, ..
+        } = self;
+        let farthest = out[v].iter().map(|a| a.weight).fold(0.0, f64::max);
+        let mut shortcuts = Vec::new();
+        for first in &into[v] {
+            let u = first.vertex;
+            witness.run(out, u, v, first.weight + farthest);
+            for second in out[v].iter().filter(|a| a.vertex != u) {
+                let weight = first.weight + second.weight;
+                if witness.dist[second.vertex] > weight {
+                    shortcuts.push((u, second.vertex, weight));
+                }
+            }
+        }
+        shortcuts
+    }
+
+    /// Priority of `v` for contraction, lower first: the number of shortcuts
+    /// its contraction adds minus the number of arcs it removes, plus the
+    /// number of its contracted neighbours.
+    fn priority(&mut self, v: usize) -> i64 {
+        let removed = self.out[v].len() + self.into[v].len();
+        self.shortcuts(v).len() as i64 - removed as i64 + self.contracted_neighbours[v]
+    }
+
+    /// Contracts `v`, adding the shortcuts it needs, and moves its arcs, all
+    /// towards vertices contracted later, to `up` and `down`. Returns the
+    /// neighbours of `v`.
+    fn contract(&mut self, v: usize, up: &mut Vec<Arc>, down: &mut Vec<Arc>) -> Vec<usize> {
+        for (u, w, weight) in self.shortcuts(v) {
+            let arc = |vertex| Arc {
+                vertex,
+                weight,
+                middle: Some(v),
+            };
+            insert_arc(&mut self.out[u], arc(w));
+            insert_arc(&mut self.into[w], arc(u));
+            self.shortcuts += 1;
+        }
+        *up = std::mem::take(&mut self.out[v]);
+        *down = std::mem::take(&mut self.into[v]);
+        for arc in up.iter() {
+            self.into[arc.vertex].retain(|a| a.vertex != v);
+        }
+        for arc in down.iter() {
+            self.out[arc.vertex].retain(|a| a.vertex != v);
+        }
+        let mut neighbours: Vec<usize> = up.iter().chain(down.iter()).map(|a| a.vertex).collect();
+        neighbours.sort_unstable();
+        neighbours.dedup();
+        for &w in &neighbours {
+            self.contracted_neighbours[w] += 1;
+        }
+        neighbours
+    }
+}
+
+/// Dijkstra's algorithm limited in distance and in finished vertices, over
+/// arrays reset after each search for the vertices it reached only.
+struct WitnessSearch {
+    dist: Vec<f64>,
+    reached: Vec<usize>,
+    queue: MaxHeap<Reverse<(f64, usize)>>,
+}
+
+impl WitnessSearch {
+    fn new(n: usize) -> Self {
+        Self {
+            dist: vec![f64::INFINITY; n],
+            reached: Vec::new(),
+            queue: MaxHeap::new(),
+        }
+    }
+
+    /// Distances from `source` along the arcs `out`, avoiding `skip`, exact
+    /// up to `limit` unless `MAX_SETTLED` vertices were finished first, and
+    /// upper bounds otherwise.
+    fn run(&mut self, out: &[Vec<Arc>], source: usize, skip: usize, limit: f64) {
+        for v in self.reached.drain(..) {
+            self.dist[v] = f64::INFINITY;
+        }
+        self.queue = MaxHeap::new();
+        self.dist[source] = 0.0;
+        self.reached.push(source);
+        self.queue.push(Reverse((0.0, source)));
+        let mut settled = 0;
+        while let Some(Reverse((d, v))) = self.queue.pop() {
+            if d > self.dist[v] {
+                continue;
+            }
+            if d > limit || settled == MAX_SETTLED {
+                break;
+            }
+            settled += 1;
+            for arc in out[v].iter().filter(|a| a.vertex != skip) {
+                let through = d + arc.weight;
+                if through < self.dist[arc.vertex] {
+                    if self.dist[arc.vertex] == f64::INFINITY {
+                        self.reached.push(arc.vertex);
+                    }
+                    self.dist[arc.vertex] = through;
+                    self.queue.push(Reverse((through, arc.vertex)));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::{dijkstra, dijkstra_to};
+
+    fn random_digraph(n: usize, m: usize) -> EdgeWeightedDigraph {
+        EdgeWeightedDigraph::from_edges(
+            n,
+            (0..m).map(|_| {
+                let weight = fastrand::u32(..20) as f64;
+                (fastrand::usize(..n), fastrand::usize(..n), weight)
+            }),
+        )
+    }
+
+    /// Grid of `side * side` vertices with roads both ways between
+    /// neighbours, like a street map.
+    fn grid(side: usize) -> EdgeWeightedDigraph {
+        let mut g = EdgeWeightedDigraph::new(side * side);
+        for r in 0..side {
+            for c in 0..side {
+                let v = r * side + c;
+                for w in [
+                    (c + 1 < side).then_some(v + 1),
+                    (r + 1 < side).then_some(v + side),
+                ]
+                .into_iter()
+                .flatten()
+                {
+                    g.add_edge(v, w, fastrand::u32(1..100) as f64);
+                    g.add_edge(w, v, fastrand::u32(1..100) as f64);
+                }
+            }
+        }
+        g
+    }
+
+    /// Checks that `path` follows edges of `g` from `source` to `target` with
+    /// its weight. Integer weights keep the sums exact.
+    fn assert_path(g: &EdgeWeightedDigraph, path: &WeightedPath, source: usize, target: usize) {
+        assert_eq!(path.vertices.first(), Some(&source));
+        assert_eq!(path.vertices.last(), Some(&target));
+        let weight: f64 = path
+            .vertices
+            .windows(2)
+            .map(|e| {
+                g.adj(e[0])
+                    .iter()
+                    .filter(|edge| edge.to == e[1])
+                    .map(|edge| edge.weight)
+                    .fold(f64::INFINITY, f64::min)
+            })
+            .sum();
+        assert_eq!(weight, path.weight);
+    }
+
+    #[test]
+    fn all_pairs_against_dijkstra() {
+        for _ in 0..50 {
+            let n = fastrand::usize(1..40);
+            let g = random_digraph(n, fastrand::usize(..4 * n));
+            let ch = ContractionHierarchy::new(&g).unwrap();
+            let mut ranks: Vec<usize> = g.vertices().map(|v| ch.rank(v)).collect();
+            ranks.sort_unstable();
+            assert!(ranks.iter().copied().eq(0..n));
+            for s in g.vertices() {
+                let paths = dijkstra(&g, s).unwrap();
+                for t in g.vertices() {
+                    match ch.query(s, t) {
+                        Some(path) => {
+                            assert_eq!(paths.dist_to(t), Some(path.weight));
+                            assert_path(&g, &path, s, t);
+                        }
+                        None => assert!(!paths.has_path_to(t)),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn road_grid() {
+        let g = grid(30);
+        let ch = ContractionHierarchy::new(&g).unwrap();
+        assert!(ch.shortcut_count() < 4 * g.edge_count());
+        for _ in 0..200 {
+            let (s, t) = (fastrand::usize(..900), fastrand::usize(..900));
+            let path = ch.query(s, t).unwrap();
+            let expected = dijkstra_to(&g, s, t).unwrap().unwrap();
+            assert_eq!(path.weight, expected.weight);
+            assert_path(&g, &path, s, t);
+        }
+    }
+
+    #[test]
+    fn small_cases() {
+        let g = EdgeWeightedDigraph::from_edges(3, [(0, 0, 1.0), (0, 1, 3.0), (0, 1, 2.0)]);
+        let ch = ContractionHierarchy::new(&g).unwrap();
+        assert_eq!(ch.vertex_count(), 3);
+        let path = ch.query(0, 1).unwrap();
+        assert_eq!((path.vertices, path.weight), (vec![0, 1], 2.0));
+        let path = ch.query(2, 2).unwrap();
+        assert_eq!((path.vertices, path.weight), (vec![2], 0.0));
+        assert_eq!(ch.query(1, 0), None);
+        let negative = EdgeWeightedDigraph::from_edges(2, [(0, 1, -1.0)]);
+        assert!(ContractionHierarchy::new(&negative).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+use crate::graph::Graph;
+
+/// Order in which `greedy_coloring` colors the vertices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColoringOrder {
+    /// Increasing order of vertex.
+    #[default]
+    Natural,
+    /// Decreasing order of degree, as in the Welsh-Powell algorithm, so that
+    /// the most constrained vertices get the smallest colors.
+    LargestDegreeFirst,
+    /// DSATUR, by Brélaz: the next vertex is the one with the most distinct
+    /// colors among its neighbors, breaking ties by degree. It colors
+    /// bipartite graphs, cycles and wheels optimally.
+    Dsatur,
+}
+
+/// Coloring of the vertices of a graph with the colors `0..count()`, such
+/// that adjacent vertices have different colors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coloring {
+    colors: Vec<usize>,
+    count: usize,
+}
+
+impl Coloring {
+    /// Color of `v`.
+    pub fn color(&self, v: usize) -> usize {
+        self.colors[v]
+    }
+
+    /// Colors of all vertices.
+    pub fn colors(&self) -> &[usize] {
+        &self.colors
+    }
+
+    /// Number of colors used.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+/// Greedy coloring: colors the vertices one at a time, in the given order,
+/// with the smallest color not used by their neighbors. At most `d + 1`
+/// colors are used, for `d` the maximum degree, but the result may be far
+/// from the chromatic number, which is NP-hard to compute. The order makes a
+/// large difference, and DSATUR usually uses the fewest colors. Self-loops
+/// are ignored. Takes O(V + E) time for the fixed orders and O(V^2 + E) for
+/// DSATUR.
+///
+/// Example:
+/// ```
+/// use algae::graph::{greedy_coloring, ColoringOrder, Graph};
+///
+/// // A crown graph: the vertices `2 * i` and `2 * j + 1` are adjacent
+/// // whenever `i != j`, which is bipartite, but the natural order alternates
+/// // between the sides.
+/// let n = 5;
+/// let edges = (0..n).flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (2 * i, 2 * j + 1)));
+/// let crown = Graph::from_edges(2 * n, edges);
+/// assert_eq!(greedy_coloring(&crown, ColoringOrder::Natural).count(), 5);
+/// assert_eq!(greedy_coloring(&crown, ColoringOrder::Dsatur).count(), 2);
+/// ```
+pub fn greedy_coloring(graph: &Graph, order: ColoringOrder) -> Coloring {
+    match order {
+        ColoringOrder::Natural => color_in_order(graph, graph.vertices()),
+        ColoringOrder::LargestDegreeFirst => {
+            let mut vertices: Vec<usize> = graph.vertices().collect();
+            vertices.sort_by_key(|&v| std::cmp::Reverse(graph.degree(v)));
+            color_in_order(graph, vertices)
+        }
+        ColoringOrder::Dsatur => dsatur(graph),
+    }
+}
+
+/// Smallest color not in `used`, which holds the colors of the neighbors of a
+/// vertex and is cleared afterwards.
+fn smallest_free(used: &mut [bool]) -> usize {
+    let color = used.iter().position(|&u| !u).unwrap_or(used.len());
+    used.fill(false);
+    color
+}
+
+fn color_in_order<I: IntoIterator<Item = usize>>(graph: &Graph, order: I) -> Coloring {
+    let n = graph.vertex_count();
+    let mut colors = vec![usize::MAX; n];
+    let mut count = 0;
+    let mut used = Vec::new();
+    for v in order {
+        used.resize(graph.degree(v) + 1, false);
+        for &w in graph.adj(v) {
+            if w != v && colors[w] < used.len() {
+                used[colors[w]] = true;
+            }
+        }
+        colors[v] = smallest_free(&mut used);
+        count = count.max(colors[v] + 1);
+    }
+    Coloring { colors, count }
+}
+
+fn dsatur(graph: &Graph) -> Coloring {
+    let n = graph.vertex_count();
+    let mut colors = vec![usize::MAX; n];
+    let mut count = 0;
+    // Distinct colors among the neighbors of each vertex, as a mask and a
+    // count.
+    let mut neighbor_colors: Vec<Vec<bool>> = vec![Vec::new(); n];
+    let mut saturation = vec![0; n];
+    let mut used = Vec::new();
+    for _ in 0..n {
+        let v = graph
+            .vertices()
+            .filter(|&v| colors[v] == usize::MAX)
+            .max_by_key(|&v| (saturation[v], graph.degree(v), std::cmp::Reverse(v)))
+            .expect("Uncolored vertex left");
+        used.clear();
+        used.extend_from_slice(&neighbor_colors[v]);
+        used.push(false);
+        let color = smallest_free(&mut used);
+        colors[v] = color;
+        count = count.max(color + 1);
+        for &w in graph.adj(v) {
+            let seen = &mut neighbor_colors[w];
+            if seen.len() <= color {
+                seen.resize(color + 1, false);
+            }
+            if !seen[color] {
+                seen[color] = true;
+                saturation[w] += 1;
+            }
+        }
+    }
+    Coloring { colors, count }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ORDERS: [ColoringOrder; 3] = [
+        ColoringOrder::Natural,
+        ColoringOrder::LargestDegreeFirst,
+        ColoringOrder::Dsatur,
+    ];
+
+    fn assert_proper(g: &Graph, coloring: &Coloring) {
+        for (v, w) in g.edges().filter(|(v, w)| v != w) {
+            assert_ne!(coloring.color(v), coloring.color(w));
+        }
+        assert!(coloring.colors().iter().all(|&c| c < coloring.count()));
+    }
+
+    #[test]
+    fn proper_colorings() {
+        for _ in 0..100 {
+            let n = fastrand::usize(..30);
+            let g = Graph::from_edges(
+                n,
+                (0..fastrand::usize(..3 * n + 1))
+                    .map(|_| (fastrand::usize(..n), fastrand::usize(..n))),
+            );
+            let max_degree = g.vertices().map(|v| g.degree(v)).max().unwrap_or(0);
+            for order in ORDERS {
+                let coloring = greedy_coloring(&g, order);
+                assert_proper(&g, &coloring);
+                assert!(coloring.count() <= max_degree + 1);
+            }
+        }
+    }
+
+    #[test]
+    fn known_chromatic_numbers() {
+        let complete = Graph::from_edges(6, (0..6).flat_map(|v| (0..v).map(move |w| (v, w))));
+        let odd_cycle = Graph::from_edges(7, (0..7).map(|v| (v, (v + 1) % 7)));
+        let even_cycle = Graph::from_edges(8, (0..8).map(|v| (v, (v + 1) % 8)));
+        // A wheel: a hub adjacent to every vertex of an odd cycle.
+        let wheel = Graph::from_edges(6, (1..6).flat_map(|v| [(0, v), (v, v % 5 + 1)]));
+        let petersen = Graph::from_edges(
+            10,
+            (0..5).flat_map(|i| [(i, (i + 1) % 5), (i, i + 5), (i + 5, (i + 2) % 5 + 5)]),
+        );
+        for (g, chromatic) in [
+            (complete, 6),
+            (odd_cycle, 3),
+            (even_cycle, 2),
+            (wheel, 4),
+            (petersen, 3),
+        ] {
+            for order in ORDERS {
+                let coloring = greedy_coloring(&g, order);
+                assert_proper(&g, &coloring);
+                assert!(coloring.count() >= chromatic);
+            }
+            assert_eq!(
+                greedy_coloring(&g, ColoringOrder::Dsatur).count(),
+                chromatic
+            );
+        }
+    }
+
+    #[test]
+    fn dsatur_on_bipartite_graphs() {
+        for _ in 0..50 {
+            let (a, b) = (fastrand::usize(1..10), fastrand::usize(1..10));
+            let g = Graph::from_edges(
+                a + b,
+                (0..fastrand::usize(1..30))
+                    .map(|_| (fastrand::usize(..a), a + fastrand::usize(..b))),
+            );
+            assert_eq!(greedy_coloring(&g, ColoringOrder::Dsatur).count(), 2);
+        }
+    }
+}
@@ -0,0 +1,467 @@
+//! Community detection: partitions of the vertices of an undirected graph
+//! into groups with many edges inside and few between them, as measured by
+//! their modularity.
+//!
+//! Parallel edges count as an edge of greater weight. As in the definition of
+//! modularity, a self-loop counts twice in the degree of its vertex.
+use crate::graph::Graph;
+
+/// Most passes over the vertices made by `label_propagation`.
+const MAX_PASSES: usize = 100;
+
+/// Partition of the vertices of a graph into communities, each vertex labelled
+/// by the id of its community, from 0 to `count() - 1`, together with the
+/// modularity of the partition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Communities {
+    id: Vec<usize>,
+    count: usize,
+    modularity: f64,
+}
+
+impl Communities {
+    /// Labels each vertex by its community in `labels`, any integers, which
+    /// are renumbered in increasing order of the smallest vertex of each
+    /// community.
+    fn new(graph: &Graph, labels: &[usize]) -> Self {
+        let mut renamed = vec![usize::MAX; labels.len()];
+        let mut count = 0;
+        let id: Vec<usize> = labels
+            .iter()
+            .map(|&label| {
+                if renamed[label] == usize::MAX {
+                    renamed[label] = count;
+                    count += 1;
+                }
+                renamed[label]
+            })
+            .collect();
+        let modularity = modularity(graph, &id);
+        Self {
+            id,
+            count,
+            modularity,
+        }
+    }
+
+    /// Number of communities.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Id of the community of `v`.
+    pub fn id(&self, v: usize) -> usize {
+        self.id[v]
+    }
+
+    /// Modularity of the partition, as computed by `modularity`.
+    pub fn modularity(&self) -> f64 {
+        self.modularity
+    }
+
+    /// Vertices of each community, indexed by id, in increasing order.
+    pub fn communities(&self) -> Vec<Vec<usize>> {
+        let mut communities = vec![Vec::new(); self.count];
+        for (v, &c) in self.id.iter().enumerate() {
+            communities[c].push(v);
+        }
+        communities
+    }
+}
+
+/// Graph with weighted edges, where a vertex may have a self-loop of weight
+/// `w`, adding `w` to its degree. Each other edge `v-w` appears in the lists
+/// of both `v` and `w`.
+struct Weighted {
+    adj: Vec<Vec<(usize, f64)>>,
+    /// Sum of the weights of the edges of each vertex.
+    degree: Vec<f64>,
+    /// Sum of the degrees, that is, twice the total weight of the edges.
+    total: f64,
+}
+
+impl Weighted {
+    fn from_adj(adj: Vec<Vec<(usize, f64)>>) -> Self {
+        let degree: Vec<f64> = adj
+            .iter()
+            .map(|edges| edges.iter().map(|e| e.1).sum())
+            .collect();
+        let total = degree.iter().sum();
+        Self { adj, degree, total }
+    }
+
+    /// Weighted version of `graph`, where a self-loop, which appears once in
+    /// the list of its vertex, has weight 2.
+    fn from_graph(graph: &Graph) -> Self {
+        let adj = graph
+            .vertices()
+            .map(|v| {
+                let mut edges: Vec<(usize, f64)> = graph
+                    .adj(v)
+                    .iter()
+                    .map(|&w| (w, if w == v { 2.0 } else { 1.0 }))
+                    .collect();
+                merge_parallel(&mut edges);
+                edges
+            })
+            .collect();
+        Self::from_adj(adj)
+    }
+
+    fn vertex_count(&self) -> usize {
+        self.adj.len()
+    }
+
+    /// Graph of the communities of `id`, numbered from 0 to `count - 1`, the
+    /// weight of an edge between two communities being the sum of the
+    /// weights of the edges between them. The edges inside a community,
+    /// seen from both ends, add up to its self-loop.
+    fn aggregate(&self, id: &[usize], count: usize) -> Self {
+        let mut adj = vec![Vec::new(); count];
+        for (v, edges) in self.adj.iter().enumerate() {
+            adj[id[v]].extend(edges.iter().map(|&(w, weight)| (id[w], weight)));
+        }
+        for edges in &mut adj {
+            merge_parallel(edges);
+        }
+        Self::from_adj(adj)
+    }
+}
+
+/// Sorts `edges` by vertex, merging the edges to the same vertex into one
+/// carrying the sum of their weights.
+fn merge_parallel(edges: &mut Vec<(usize, f64)>) {
+    edges.sort_by_key(|e| e.0);
+    edges.dedup_by(|next, kept| {
+        let same = next.0 == kept.0;
+        if same {
+            kept.1 += next.1;
+        }
+        same
+    });
+}
+
+/// Modularity of the partition of the vertices of `graph` given by the
+/// community `id` of each vertex: the fraction of the edges inside the
+/// communities, minus the fraction expected if the edges were placed at
+/// random keeping the degrees of the vertices. For `m` edges, `e_c` edges
+/// inside the community `c`, and `d_c` the sum of the degrees of its
+/// vertices, this is the sum over the communities of
+/// `e_c / m - (d_c / 2m)^2`. The modularity lies between -1/2 and 1, and it
+/// is 0 for a graph without edges.
+///
+/// Example:
+/// ```
+/// use algae::graph::{community, Graph};
+///
+/// // Two triangles joined by the edge 2-3.
+/// let g = Graph::from_edges(6, [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)]);
+/// let q = community::modularity(&g, &[0, 0, 0, 1, 1, 1]);
+/// assert!((q - 5.0 / 14.0).abs() < 1e-12);
+/// assert_eq!(community::modularity(&g, &[0; 6]), 0.0);
+/// ```
+pub fn modularity(graph: &Graph, id: &[usize]) -> f64 {
+    let weighted = Weighted::from_graph(graph);
+    if weighted.total == 0.0 {
+        return 0.0;
+    }
+    let count = id.iter().map(|&c| c + 1).max().unwrap_or(0);
+    let mut inside = vec![0.0; count];
+    let mut degree = vec![0.0; count];
+    for (v, edges) in weighted.adj.iter().enumerate() {
+        degree[id[v]] += weighted.degree[v];
+        for &(w, weight) in edges {
+            if id[v] == id[w] {
+                inside[id[v]] += weight;
+            }
+        }
+    }
+    let m2 = weighted.total;
+    inside
+        .iter()
+        .zip(&degree)
+        .map(|(&e, &d)| e / m2 - (d / m2) * (d / m2))
+        .sum()
+}
+
+/// Label propagation: each vertex starts with a label of its own, then the
+/// vertices, in random order, take the label carried by most of their
+/// neighbours, keeping their own in case of a tie, until every vertex
+/// carries a label of most of its neighbours. The communities are the sets of
+/// vertices with the same label. Each pass takes O(V + E) time, and dense
+/// groups of vertices agree on a label within a few passes, but the result
+/// depends on the random order, and at most `MAX_PASSES` passes are made.
+///
+/// Example:
+/// ```
+/// use algae::graph::{community, Graph};
+///
+/// // Two cliques of 4 vertices.
+/// let mut g = Graph::new(8);
+/// for offset in [0, 4] {
+///     for v in offset..offset + 4 {
+///         for w in v + 1..offset + 4 {
+///             g.add_edge(v, w);
+///         }
+///     }
+/// }
+/// let communities = community::label_propagation(&g);
+/// assert_eq!(communities.communities(), [vec![0, 1, 2, 3], vec![4, 5, 6, 7]]);
+/// assert!((communities.modularity() - 0.5).abs() < 1e-12);
+/// ```
+pub fn label_propagation(graph: &Graph) -> Communities {
+    let weighted = Weighted::from_graph(graph);
+    let n = weighted.vertex_count();
+    let mut label: Vec<usize> = (0..n).collect();
+    let mut order: Vec<usize> = (0..n).collect();
+    // Weight of the neighbours of the current vertex carrying each label.
+    let mut votes = vec![0.0; n];
+    for _ in 0..MAX_PASSES {
+        fastrand::shuffle(&mut order);
+        let mut changed = false;
+        for &v in &order {
+            let neighbours = weighted.adj[v].iter().filter(|e| e.0 != v);
+            for &(w, weight) in neighbours.clone() {
+                votes[label[w]] += weight;
+            }
+            let mut best = label[v];
+            for &(w, _) in neighbours.clone() {
+                if votes[label[w]] > votes[best] {
+                    best = label[w];
+                }
+            }
+            for &(w, _) in neighbours {
+                votes[label[w]] = 0.0;
+            }
+            votes[label[v]] = 0.0;
+            if best != label[v] {
+                label[v] = best;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    Communities::new(graph, &label)
+}
+
+/// Louvain method: a greedy maximization of the modularity, working in levels.
+/// In each level, the vertices start in communities of their own, and each
+/// vertex in turn moves to the community of a neighbour which increases the
+/// modularity the most, until no move increases it. The communities then
+/// become the vertices of the graph of the next level, joined by edges
+/// weighing as much as all the edges between them, so that the next level
+/// merges whole communities. The levels stop when no vertex moves.
+///
+/// Each level takes O(E) time per pass over the vertices, and the graphs
+/// shrink quickly, so that the method scales to large graphs.
+///
+/// Example:
+/// ```
+/// use algae::graph::{community, Graph};
+///
+/// // A ring of 6 cliques of 5 vertices, joined by single edges.
+/// let mut g = Graph::new(30);
+/// for c in 0..6 {
+///     for v in 5 * c..5 * c + 5 {
+///         for w in v + 1..5 * c + 5 {
+///             g.add_edge(v, w);
+///         }
+///     }
+///     g.add_edge(5 * c + 4, (5 * c + 5) % 30);
+/// }
+/// let communities = community::louvain(&g);
+/// assert_eq!(communities.count(), 6);
+/// assert!((0..30).all(|v| communities.id(v) == v / 5));
+/// ```
+pub fn louvain(graph: &Graph) -> Communities {
+    let n = graph.vertex_count();
+    // Community of each vertex of `graph` among the vertices of `level`.
+    let mut id: Vec<usize> = (0..n).collect();
+    let mut level = Weighted::from_graph(graph);
+    loop {
+        let (moved, community) = move_vertices(&level);
+        if !moved {
+            break;
+        }
+        // Renumbers the communities of the level from 0.
+        let mut renamed = vec![usize::MAX; level.vertex_count()];
+        let mut count = 0;
+        for &c in &community {
+            if renamed[c] == usize::MAX {
+                renamed[c] = count;
+                count += 1;
+            }
+        }
+        let community: Vec<usize> = community.iter().map(|&c| renamed[c]).collect();
+        for c in &mut id {
+            *c = community[*c];
+        }
+        level = level.aggregate(&community, count);
+    }
+    Communities::new(graph, &id)
+}
+
+/// Local moving phase of `louvain` on `graph`, where each vertex starts alone
+/// in its community: moves vertices to the community of a neighbour while
+/// this increases the modularity. Returns whether any vertex moved, and the
+/// community of each vertex, named by one of its vertices.
+fn move_vertices(graph: &Weighted) -> (bool, Vec<usize>) {
+    let n = graph.vertex_count();
+    let mut community: Vec<usize> = (0..n).collect();
+    // Sum of the degrees of the vertices of each community.
+    let mut total = graph.degree.clone();
+    // Weight of the edges from the current vertex to each community.
+    let mut links = vec![0.0; n];
+    let m2 = graph.total;
+    let mut moved = false;
+    loop {
+        let mut improved = false;
+        for v in 0..n {
+            let neighbours = graph.adj[v].iter().filter(|e| e.0 != v);
+            for &(w, weight) in neighbours.clone() {
+                links[community[w]] += weight;
+            }
+            let (own, k) = (community[v], graph.degree[v]);
+            total[own] -= k;
+            // Gain of adding `v` to the community `c`, up to a constant
+            // factor, against leaving it alone.
+            let gain = |c: usize| links[c] - total[c] * k / m2;
+            let mut best = own;
+            let mut best_gain = gain(own);
+            for &(w, _) in neighbours.clone() {
+                let c = community[w];
+                if gain(c) > best_gain + 1e-12 * m2 {
+                    best = c;
+                    best_gain = gain(c);
+                }
+            }
+            total[best] += k;
+            for &(w, _) in neighbours {
+                links[community[w]] = 0.0;
+            }
+            links[own] = 0.0;
+            if best != own {
+                community[v] = best;
+                improved = true;
+                moved = true;
+            }
+        }
+        if !improved {
+            return (moved, community);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn random_graph(n: usize, m: usize) -> Graph {
+        Graph::from_edges(
+            n,
+            (0..m).map(|_| (fastrand::usize(..n), fastrand::usize(..n))),
+        )
+    }
+
+    /// Modularity from its definition over all pairs of vertices, `A` being
+    /// the adjacency matrix where a self-loop counts twice.
+    fn modularity_by_pairs(g: &Graph, id: &[usize]) -> f64 {
+        let n = g.vertex_count();
+        let mut a = vec![vec![0.0; n]; n];
+        for (v, w) in g.edges() {
+            a[v][w] += 1.0;
+            a[w][v] += 1.0;
+        }
+        let k: Vec<f64> = a.iter().map(|row| row.iter().sum()).collect();
+        let m2: f64 = k.iter().sum();
+        if m2 == 0.0 {
+            return 0.0;
+        }
+        let mut q = 0.0;
+        for i in 0..n {
+            for j in 0..n {
+                if id[i] == id[j] {
+                    q += a[i][j] - k[i] * k[j] / m2;
+                }
+            }
+        }
+        q / m2
+    }
+
+    fn assert_partition(g: &Graph, communities: &Communities) {
+        let ids: Vec<usize> = g.vertices().map(|v| communities.id(v)).collect();
+        assert!(ids.iter().all(|&c| c < communities.count()));
+        let smallest: Vec<usize> = communities.communities().iter().map(|c| c[0]).collect();
+        assert!(smallest.windows(2).all(|s| s[0] < s[1]));
+        let expected = modularity_by_pairs(g, &ids);
+        assert!((communities.modularity() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn modularity_by_definition() {
+        for _ in 0..100 {
+            let n = fastrand::usize(1..10);
+            let g = random_graph(n, fastrand::usize(..3 * n));
+            let id: Vec<usize> = (0..n).map(|_| fastrand::usize(..3)).collect();
+            let q = modularity(&g, &id);
+            assert!((q - modularity_by_pairs(&g, &id)).abs() < 1e-9);
+            assert!((-0.5..=1.0).contains(&q));
+        }
+        assert_eq!(modularity(&Graph::new(3), &[0, 1, 2]), 0.0);
+    }
+
+    #[test]
+    fn louvain_improves_on_singletons() {
+        for _ in 0..100 {
+            let n = fastrand::usize(1..25);
+            let g = random_graph(n, fastrand::usize(..3 * n));
+            let communities = louvain(&g);
+            assert_partition(&g, &communities);
+            let singletons: Vec<usize> = (0..n).collect();
+            assert!(communities.modularity() >= modularity(&g, &singletons) - 1e-9);
+            assert!(communities.modularity() >= -1e-9);
+        }
+    }
+
+    #[test]
+    fn label_propagation_agrees_with_neighbours() {
+        for _ in 0..100 {
+            let n = fastrand::usize(1..25);
+            let g = random_graph(n, fastrand::usize(..3 * n));
+            let communities = label_propagation(&g);
+            assert_partition(&g, &communities);
+            for v in g.vertices() {
+                let mut votes = vec![0; communities.count()];
+                for &w in g.adj(v).iter().filter(|&&w| w != v) {
+                    votes[communities.id(w)] += 1;
+                }
+                let most = votes.iter().copied().max().unwrap_or(0);
+                assert!(most == 0 || votes[communities.id(v)] == most);
+            }
+        }
+    }
+
+    #[test]
+    fn separate_cliques() {
+        // Cliques of random sizes, without edges between them.
+        let sizes: Vec<usize> = (0..8).map(|_| fastrand::usize(2..7)).collect();
+        let n = sizes.iter().sum();
+        let mut g = Graph::new(n);
+        let mut start = 0;
+        for &size in &sizes {
+            for v in start..start + size {
+                for w in v + 1..start + size {
+                    g.add_edge(v, w);
+                }
+            }
+            start += size;
+        }
+        for communities in [louvain(&g), label_propagation(&g)] {
+            assert_eq!(communities.count(), sizes.len());
+            let found: Vec<usize> = communities.communities().iter().map(Vec::len).collect();
+            assert_eq!(found, sizes);
+        }
+    }
+}
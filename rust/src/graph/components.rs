@@ -0,0 +1,120 @@
+use crate::graph::{dfs_forest_visit, Graph, GraphVisitor};
+
+/// Visitor labelling each vertex by the tree of the search it is in, numbered
+/// in the order the trees are started. A vertex discovered while no other is
+/// being explored is the root of a new tree.
+pub(super) struct TreeLabels {
+    pub(super) id: Vec<usize>,
+    pub(super) count: usize,
+    /// Number of vertices discovered but not yet finished.
+    depth: usize,
+}
+
+impl TreeLabels {
+    pub(super) fn new(n: usize) -> Self {
+        Self {
+            id: vec![usize::MAX; n],
+            count: 0,
+            depth: 0,
+        }
+    }
+}
+
+impl GraphVisitor for TreeLabels {
+    fn discover(&mut self, v: usize) {
+        if self.depth == 0 {
+            self.count += 1;
+        }
+        self.depth += 1;
+        self.id[v] = self.count - 1;
+    }
+
+    fn finish(&mut self, _: usize) {
+        self.depth -= 1;
+    }
+}
+
+/// Connected components of an undirected graph: maximal sets of vertices
+/// joined by paths. Each vertex is labelled by the id of its component, from
+/// 0 to `count() - 1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectedComponents {
+    id: Vec<usize>,
+    count: usize,
+}
+
+impl ConnectedComponents {
+    /// Number of components.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Id of the component of `v`.
+    pub fn id(&self, v: usize) -> usize {
+        self.id[v]
+    }
+
+    /// Whether there is a path between `v` and `w`.
+    pub fn connected(&self, v: usize, w: usize) -> bool {
+        self.id[v] == self.id[w]
+    }
+
+    /// Vertices of each component, indexed by id, in increasing order.
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut components = vec![Vec::new(); self.count];
+        for (v, &c) in self.id.iter().enumerate() {
+            components[c].push(v);
+        }
+        components
+    }
+}
+
+/// Connected components of `graph`, as the trees of a depth-first search of
+/// the whole graph, in O(V + E) time. Components are numbered in increasing
+/// order of their smallest vertex.
+///
+/// Example:
+/// ```
+/// use algae::graph::{connected_components, Graph};
+///
+/// let g = Graph::from_edges(5, [(0, 3), (1, 2), (3, 4)]);
+/// let cc = connected_components(&g);
+/// assert_eq!(cc.count(), 2);
+/// assert!(cc.connected(0, 4));
+/// assert!(!cc.connected(0, 1));
+/// assert_eq!(cc.components(), [vec![0, 3, 4], vec![1, 2]]);
+/// ```
+pub fn connected_components(graph: &Graph) -> ConnectedComponents {
+    let mut labels = TreeLabels::new(graph.vertex_count());
+    dfs_forest_visit(graph, &mut labels);
+    ConnectedComponents {
+        id: labels.id,
+        count: labels.count,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::bfs;
+
+    #[test]
+    fn agrees_with_reachability() {
+        for _ in 0..50 {
+            let n = fastrand::usize(1..25);
+            let g = Graph::from_edges(
+                n,
+                (0..fastrand::usize(..n)).map(|_| (fastrand::usize(..n), fastrand::usize(..n))),
+            );
+            let cc = connected_components(&g);
+            for v in g.vertices() {
+                let reach = bfs(&g, v);
+                for w in g.vertices() {
+                    assert_eq!(cc.connected(v, w), reach.visited(w));
+                }
+            }
+            let smallest: Vec<usize> = cc.components().iter().map(|c| c[0]).collect();
+            assert!(smallest.windows(2).all(|s| s[0] < s[1]));
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use crate::graph::{dinic, Adjacency, FlowNetwork, MaxFlow};
+use std::collections::BTreeMap;
+
+/// Splits a maximum flow of unit capacity edges into paths from `s` to `t`,
+/// as vertex lists, which use disjoint sets of edges of `network`. Flows
+/// through antiparallel edges cancel out first, so that no edge of an
+/// undirected graph, seen as two antiparallel edges, is used twice. Loops
+/// met on the way are dropped, so the paths have no repeated vertices.
+fn flow_paths(network: &FlowNetwork, flow: &MaxFlow, s: usize, t: usize) -> Vec<Vec<usize>> {
+    let mut used: BTreeMap<(usize, usize), Vec<usize>> = BTreeMap::new();
+    for (e, edge) in network.edges().iter().enumerate() {
+        if flow.flow(e) > 0 {
+            used.entry((edge.from, edge.to)).or_default().push(e);
+        }
+    }
+    let mut out = vec![Vec::new(); network.vertex_count()];
+    for (&(v, w), edges) in &used {
+        let opposite = used.get(&(w, v)).map_or(0, Vec::len);
+        // Of two antiparallel flows, only the surplus of one is kept.
+        let kept = edges.len().saturating_sub(opposite);
+        out[v].extend(edges.iter().take(kept).map(|&e| network.edge(e).to));
+    }
+
+    let mut paths = Vec::new();
+    let mut position = vec![None; network.vertex_count()];
+    loop {
+        let mut path = vec![s];
+        position[s] = Some(0);
+        let mut v = s;
+        while v != t {
+            let Some(w) = out[v].pop() else {
+                return paths;
+            };
+            if let Some(p) = position[w] {
+                for u in path.drain(p + 1..) {
+                    position[u] = None;
+                }
+            } else {
+                position[w] = Some(path.len());
+                path.push(w);
+            }
+            v = w;
+        }
+        for &u in &path {
+            position[u] = None;
+        }
+        paths.push(path);
+    }
+}
+
+/// Edge connectivity between `s` and `t`: a largest set of paths from `s` to
+/// `t` no two of which share an edge. By Menger's theorem, the number of
+/// paths is also the least number of edges whose removal leaves no path from
+/// `s` to `t`. Each edge is given a unit capacity, an undirected edge `v-w`
+/// becoming the two edges `v->w` and `w->v`, and the paths are those of a
+/// maximum flow from `s` to `t`, found by `dinic` in O(E * sqrt(E)) time.
+///
+/// Paths are given by their vertices, none repeated. Parallel edges count as
+/// different edges, and self-loops are ignored. Panics if `s == t`.
+///
+/// Example:
+/// ```
+/// use algae::graph::{edge_connectivity, Graph};
+///
+/// // Two triangles sharing the vertex 2.
+/// let g = Graph::from_edges(5, [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 2)]);
+/// assert_eq!(edge_connectivity(&g, 0, 1).len(), 2);
+/// let paths = edge_connectivity(&g, 0, 4);
+/// assert_eq!(paths.len(), 2);
+/// assert!(paths.iter().all(|p| p.first() == Some(&0) && p.last() == Some(&4)));
+/// ```
+pub fn edge_connectivity<G: Adjacency>(graph: &G, s: usize, t: usize) -> Vec<Vec<usize>> {
+    let mut network = FlowNetwork::new(graph.vertex_count());
+    for v in 0..graph.vertex_count() {
+        for &w in graph.adj(v).iter().filter(|&&w| w != v) {
+            network.add_edge(v, w, 1);
+        }
+    }
+    let flow = dinic(&network, s, t);
+    flow_paths(&network, &flow, s, t)
+}
+
+/// Vertex connectivity between `s` and `t`: a largest set of paths from `s`
+/// to `t` no two of which share a vertex other than `s` and `t`. By Menger's
+/// theorem, if there is no edge between `s` and `t`, the number of paths is
+/// also the least number of vertices other than `s` and `t` whose removal
+/// leaves no path from `s` to `t`. An edge between `s` and `t` is a path
+/// without inner vertices, which no removal of vertices breaks.
+///
+/// Each vertex `v` is split into `v_in`, the head of the edges into `v`, and
+/// `v_out`, the tail of the edges out of `v`, joined by the edge
+/// `v_in->v_out` of unit capacity, so that a flow from `s_out` to `t_in`
+/// goes through each vertex at most once. The paths are those of a maximum
+/// flow found by `dinic`.
+///
+/// Paths are given by their vertices, none repeated. Parallel edges count as
+/// a single edge, and self-loops are ignored. Panics if `s == t`.
+///
+/// Example:
+/// ```
+/// use algae::graph::{edge_connectivity, vertex_connectivity, Graph};
+///
+/// // Two triangles sharing the vertex 2, which every path goes through.
+/// let g = Graph::from_edges(5, [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 2)]);
+/// assert_eq!(vertex_connectivity(&g, 0, 4), [vec![0, 2, 4]]);
+/// assert_eq!(edge_connectivity(&g, 0, 4).len(), 2);
+/// ```
+pub fn vertex_connectivity<G: Adjacency>(graph: &G, s: usize, t: usize) -> Vec<Vec<usize>> {
+    assert_ne!(s, t, "Source and sink must be distinct");
+    let n = graph.vertex_count();
+    // `v_in` is `v`, and `v_out` is `v + n`.
+    let mut network = FlowNetwork::new(2 * n);
+    for v in 0..n {
+        network.add_edge(v, v + n, 1);
+        let mut adj: Vec<usize> = graph.adj(v).iter().copied().filter(|&w| w != v).collect();
+        adj.sort_unstable();
+        adj.dedup();
+        for w in adj {
+            network.add_edge(v + n, w, 1);
+        }
+    }
+    let flow = dinic(&network, s + n, t);
+    flow_paths(&network, &flow, s + n, t)
+        .into_iter()
+        .map(|path| {
+            // Keep `v_in` of each vertex, and `s_out` as `s`.
+            path.into_iter()
+                .filter(|&v| v < n || v == s + n)
+                .map(|v| v % n)
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::{bfs, Digraph, Graph};
+
+    /// Whether `t` is reachable from `s` in the graph of `edges` without the
+    /// edges in `cut`, given by their position, or the vertices in `removed`.
+    fn reaches(
+        n: usize,
+        edges: &[(usize, usize)],
+        directed: bool,
+        cut: u32,
+        removed: u32,
+        s: usize,
+        t: usize,
+    ) -> bool {
+        let kept = edges
+            .iter()
+            .enumerate()
+            .filter(|&(i, &(v, w))| {
+                cut & (1 << i) == 0 && removed & (1 << v) == 0 && removed & (1 << w) == 0
+            })
+            .map(|(_, &e)| e);
+        if directed {
+            bfs(&Digraph::from_edges(n, kept), s).visited(t)
+        } else {
+            bfs(&Graph::from_edges(n, kept), s).visited(t)
+        }
+    }
+
+    /// Least number of set bits of the masks below `2^bits`, allowed by
+    /// `allowed`, for which `t` is not reachable, by exhaustive search.
+    fn min_cut<F: Fn(u32) -> bool, R: Fn(u32) -> bool>(bits: usize, allowed: F, reach: R) -> u32 {
+        (0..1_u32 << bits)
+            .filter(|&m| allowed(m) && !reach(m))
+            .map(u32::count_ones)
+            .min()
+            .expect("Removing everything disconnects")
+    }
+
+    fn assert_paths<G: Adjacency>(g: &G, paths: &[Vec<usize>], s: usize, t: usize) {
+        for path in paths {
+            assert_eq!((path[0], path[path.len() - 1]), (s, t));
+            for w in path.windows(2) {
+                assert!(g.adj(w[0]).contains(&w[1]), "{:?} is not a path", path);
+            }
+            let mut distinct = path.clone();
+            distinct.sort();
+            distinct.dedup();
+            assert_eq!(distinct.len(), path.len());
+        }
+    }
+
+    fn random_edges(n: usize, m: usize) -> Vec<(usize, usize)> {
+        (0..m)
+            .map(|_| (fastrand::usize(..n), fastrand::usize(..n)))
+            .collect()
+    }
+
+    #[test]
+    fn edge_connectivity_agrees_with_brute_force() {
+        for _ in 0..100 {
+            let n = fastrand::usize(2..7);
+            let edges = random_edges(n, fastrand::usize(..11));
+            let (s, t) = (0, n - 1);
+            for directed in [false, true] {
+                let paths = if directed {
+                    let g = Digraph::from_edges(n, edges.iter().copied());
+                    let paths = edge_connectivity(&g, s, t);
+                    assert_paths(&g, &paths, s, t);
+                    paths
+                } else {
+                    let g = Graph::from_edges(n, edges.iter().copied());
+                    let paths = edge_connectivity(&g, s, t);
+                    assert_paths(&g, &paths, s, t);
+                    paths
+                };
+                // Each edge is used by at most one path.
+                let mut used: Vec<(usize, usize)> = paths
+                    .iter()
+                    .flat_map(|p| p.windows(2).map(|w| (w[0], w[1])))
+                    .map(|(v, w)| {
+                        if directed {
+                            (v, w)
+                        } else {
+                            (v.min(w), v.max(w))
+                        }
+                    })
+                    .collect();
+                used.sort();
+                for pair in used.chunk_by(|a, b| a == b) {
+                    let (v, w) = pair[0];
+                    let available = edges
+                        .iter()
+                        .filter(|&&e| e == (v, w) || (!directed && e == (w, v)))
+                        .count();
+                    assert!(pair.len() <= available);
+                }
+                let cut = min_cut(
+                    edges.len(),
+                    |_| true,
+                    |m| reaches(n, &edges, directed, m, 0, s, t),
+                );
+                assert_eq!(paths.len(), cut as usize);
+            }
+        }
+    }
+
+    #[test]
+    fn vertex_connectivity_agrees_with_brute_force() {
+        for _ in 0..100 {
+            let n = fastrand::usize(2..8);
+            let (s, t) = (0, n - 1);
+            let edges: Vec<_> = random_edges(n, fastrand::usize(..15))
+                .into_iter()
+                .filter(|&(v, w)| (v, w) != (s, t) && (v, w) != (t, s))
+                .collect();
+            for directed in [false, true] {
+                let paths = if directed {
+                    let g = Digraph::from_edges(n, edges.iter().copied());
+                    let paths = vertex_connectivity(&g, s, t);
+                    assert_paths(&g, &paths, s, t);
+                    paths
+                } else {
+                    let g = Graph::from_edges(n, edges.iter().copied());
+                    let paths = vertex_connectivity(&g, s, t);
+                    assert_paths(&g, &paths, s, t);
+                    paths
+                };
+                let mut inner: Vec<usize> = paths
+                    .iter()
+                    .flat_map(|p| p[1..p.len() - 1].iter().copied())
+                    .collect();
+                let count = inner.len();
+                inner.sort();
+                inner.dedup();
+                assert_eq!(inner.len(), count);
+                let ends = (1 << s) | (1 << t);
+                let cut = min_cut(
+                    n,
+                    |m| m & ends == 0,
+                    |m| reaches(n, &edges, directed, 0, m, s, t),
+                );
+                assert_eq!(paths.len(), cut as usize);
+            }
+        }
+    }
+
+    #[test]
+    fn adjacent_ends() {
+        let g = Graph::from_edges(3, [(0, 2), (0, 2), (0, 1), (1, 2)]);
+        assert_eq!(edge_connectivity(&g, 0, 2).len(), 3);
+        let mut paths = vertex_connectivity(&g, 0, 2);
+        paths.sort();
+        assert_eq!(paths, [vec![0, 1, 2], vec![0, 2]]);
+    }
+}
@@ -0,0 +1,156 @@
+use crate::graph::{Adjacency, Digraph, EdgeKind, Event, Graph};
+
+/// Vertices of the tree path from `from` down to `to`, following `parent`
+/// upwards from `to`.
+pub(super) fn tree_path(parent: &[usize], from: usize, to: usize) -> Vec<usize> {
+    let mut path = vec![to];
+    let mut v = to;
+    while v != from {
+        v = parent[v];
+        path.push(v);
+    }
+    path.reverse();
+    path
+}
+
+/// Cycle closed by the first back edge `v->w` of a depth-first search of the
+/// whole graph: the tree path from `w` down to `v`. The search is lazy, and
+/// stops at that edge.
+fn first_cycle<G: Adjacency>(graph: &G) -> Option<Vec<usize>> {
+    let mut parent = vec![usize::MAX; graph.vertex_count()];
+    graph.dfs_forest_iter().find_map(|event| match event {
+        Event::Edge(v, w, EdgeKind::Tree) => {
+            parent[w] = v;
+            None
+        }
+        Event::Edge(v, w, EdgeKind::Back) => Some(tree_path(&parent, w, v)),
+        _ => None,
+    })
+}
+
+impl Graph {
+    /// Finds a cycle of the graph, if any, as its vertices in order along the
+    /// cycle, which is closed by the edge from the last vertex to the first.
+    /// A self-loop is a cycle of one vertex, and parallel edges form a cycle
+    /// of two. Uses the lazy depth-first search of `dfs_forest_iter`, which
+    /// finds a cycle at the first back edge, leading to a vertex on the
+    /// current path other than through the tree edge to the parent. Takes
+    /// O(V + E) time.
+    ///
+    /// Example:
+    /// ```
+    /// use algae::graph::Graph;
+    ///
+    /// let tree = Graph::from_edges(4, [(0, 1), (1, 2), (1, 3)]);
+    /// assert_eq!(tree.has_cycle(), None);
+    ///
+    /// let g = Graph::from_edges(4, [(0, 1), (1, 2), (2, 3), (3, 1)]);
+    /// assert_eq!(g.has_cycle(), Some(vec![1, 2, 3]));
+    /// ```
+    pub fn has_cycle(&self) -> Option<Vec<usize>> {
+        first_cycle(self)
+    }
+}
+
+impl Digraph {
+    /// Finds a directed cycle of the digraph, if any, as its vertices in
+    /// order along the cycle, which is closed by the edge from the last
+    /// vertex to the first. Uses the lazy depth-first search of
+    /// `dfs_forest_iter`: a back edge, into a vertex still being explored,
+    /// closes a cycle with the tree path from that vertex, while the other
+    /// edges lead to vertices known to reach no cycle or to be explored
+    /// later. Takes O(V + E) time.
+    ///
+    /// Example:
+    /// ```
+    /// use algae::graph::Digraph;
+    ///
+    /// let dag = Digraph::from_edges(3, [(0, 1), (0, 2), (1, 2)]);
+    /// assert_eq!(dag.has_cycle(), None);
+    ///
+    /// let g = Digraph::from_edges(4, [(0, 1), (1, 2), (2, 3), (3, 1)]);
+    /// assert_eq!(g.has_cycle(), Some(vec![1, 2, 3]));
+    /// ```
+    pub fn has_cycle(&self) -> Option<Vec<usize>> {
+        first_cycle(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::topological_sort_kahn;
+
+    /// Whether the graph is a forest, by counting the edges of a spanning
+    /// forest built with union by relabeling.
+    fn is_forest(g: &Graph) -> bool {
+        let mut component: Vec<usize> = g.vertices().collect();
+        for (v, w) in g.edges() {
+            let (cv, cw) = (component[v], component[w]);
+            if cv == cw {
+                return false;
+            }
+            component
+                .iter_mut()
+                .filter(|c| **c == cw)
+                .for_each(|c| *c = cv);
+        }
+        true
+    }
+
+    fn assert_closed<F: Fn(usize, usize) -> bool>(cycle: &[usize], has_edge: F) {
+        assert!(!cycle.is_empty());
+        for (i, &v) in cycle.iter().enumerate() {
+            assert!(has_edge(v, cycle[(i + 1) % cycle.len()]), "{:?}", cycle);
+        }
+        let mut distinct = cycle.to_vec();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), cycle.len());
+    }
+
+    #[test]
+    fn undirected_cycles() {
+        for _ in 0..100 {
+            let n = fastrand::usize(1..15);
+            let g = Graph::from_edges(
+                n,
+                (0..fastrand::usize(..n + 2)).map(|_| (fastrand::usize(..n), fastrand::usize(..n))),
+            );
+            match g.has_cycle() {
+                Some(cycle) => {
+                    assert_closed(&cycle, |v, w| g.has_edge(v, w));
+                    // A cycle of two vertices needs a parallel edge.
+                    if cycle.len() == 2 {
+                        let count = g.adj(cycle[0]).iter().filter(|&&w| w == cycle[1]).count();
+                        assert!(count >= 2);
+                    }
+                }
+                None => assert!(is_forest(&g), "{:?}", g),
+            }
+        }
+        assert_eq!(Graph::from_edges(2, [(1, 1)]).has_cycle(), Some(vec![1]));
+        assert_eq!(
+            Graph::from_edges(2, [(0, 1), (1, 0)]).has_cycle(),
+            Some(vec![0, 1])
+        );
+        assert_eq!(Graph::new(0).has_cycle(), None);
+    }
+
+    #[test]
+    fn directed_cycles() {
+        for _ in 0..100 {
+            let n = fastrand::usize(1..15);
+            let g = Digraph::from_edges(
+                n,
+                (0..fastrand::usize(..2 * n)).map(|_| (fastrand::usize(..n), fastrand::usize(..n))),
+            );
+            match g.has_cycle() {
+                Some(cycle) => assert_closed(&cycle, |v, w| g.has_edge(v, w)),
+                None => assert!(topological_sort_kahn(&g).is_ok()),
+            }
+        }
+        assert_eq!(Digraph::from_edges(1, [(0, 0)]).has_cycle(), Some(vec![0]));
+        assert_eq!(Digraph::from_edges(2, [(0, 1), (0, 1)]).has_cycle(), None);
+    }
+}
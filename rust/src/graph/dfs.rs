@@ -0,0 +1,368 @@
+use crate::graph::{Adjacency, DfsIter, Event, GraphVisitor};
+
+/// Kinds of edges `v->w` found by a depth-first search, according to the
+/// state of `w` when the edge is examined. Other searches only tell tree
+/// edges apart from the rest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    /// `w` was not visited yet, and is visited through this edge.
+    Tree,
+    /// `w` is an ancestor of `v` still being explored, so the edge closes a
+    /// cycle.
+    Back,
+    /// `w` is a descendant of `v` already finished.
+    Forward,
+    /// `w` was finished in another subtree, or another tree of the forest.
+    Cross,
+    /// `w` was already reached by a search other than a depth-first one, such
+    /// as a breadth-first search, which does not classify the edge further.
+    NonTree,
+}
+
+/// Result of a depth-first search: the preorder and postorder of the visited
+/// vertices, the tree of the edges through which they were visited, and the
+/// classification of every examined edge.
+///
+/// In undirected graphs, each edge is examined in both directions, except for
+/// tree edges, which are not examined again from the child back to its
+/// parent. Back edges are then also seen as forward edges from the ancestor,
+/// while cross edges never occur. Parallel edges to the parent are examined,
+/// as back edges closing a cycle of length 2.
+#[derive(Debug, Clone)]
+pub struct DfsResult {
+    pre: Vec<Option<usize>>,
+    post: Vec<Option<usize>>,
+    parent: Vec<Option<usize>>,
+    preorder: Vec<usize>,
+    postorder: Vec<usize>,
+    edges: Vec<(usize, usize, EdgeKind)>,
+}
+
+impl DfsResult {
+    fn new(n: usize) -> Self {
+        Self {
+            pre: vec![None; n],
+            post: vec![None; n],
+            parent: vec![None; n],
+            preorder: Vec::new(),
+            postorder: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    /// Whether `v` was visited.
+    pub fn visited(&self, v: usize) -> bool {
+        self.pre[v].is_some()
+    }
+
+    /// Position of `v` in the preorder, if visited.
+    pub fn pre(&self, v: usize) -> Option<usize> {
+        self.pre[v]
+    }
+
+    /// Position of `v` in the postorder, if visited.
+    pub fn post(&self, v: usize) -> Option<usize> {
+        self.post[v]
+    }
+
+    /// Visited vertices in the order they were first reached.
+    pub fn preorder(&self) -> &[usize] {
+        &self.preorder
+    }
+
+    /// Visited vertices in the order they were finished, that is, after all
+    /// vertices reachable from them.
+    pub fn postorder(&self) -> &[usize] {
+        &self.postorder
+    }
+
+    /// Vertex from which `v` was visited, or `None` for roots and unvisited
+    /// vertices.
+    pub fn parent(&self, v: usize) -> Option<usize> {
+        self.parent[v]
+    }
+
+    /// Examined edges `(v, w, kind)`, in the order they were examined.
+    pub fn edges(&self) -> &[(usize, usize, EdgeKind)] {
+        &self.edges
+    }
+
+    /// Vertices of the tree path from the root of the tree of `v` to `v`, or
+    /// `None` if `v` was not visited.
+    pub fn path_to(&self, v: usize) -> Option<Vec<usize>> {
+        self.pre[v]?;
+        let mut path = vec![v];
+        let mut u = v;
+        while let Some(p) = self.parent[u] {
+            path.push(p);
+            u = p;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Starts the visit of `v` from `parent`.
+    fn enter(&mut self, v: usize, parent: Option<usize>) {
+        trace_event!(vertex = v, pre = self.preorder.len(), "vertex visited");
+        self.pre[v] = Some(self.preorder.len());
+        self.preorder.push(v);
+        self.parent[v] = parent;
+    }
+
+    /// Finishes the visit of `v`.
+    fn leave(&mut self, v: usize) {
+        self.post[v] = Some(self.postorder.len());
+        self.postorder.push(v);
+    }
+
+    /// Classifies and records the edge `v->w`, returning whether `w` is yet
+    /// to be visited.
+    fn examine(&mut self, v: usize, w: usize) -> bool {
+        let kind = match (self.pre[w], self.post[w]) {
+            (None, _) => EdgeKind::Tree,
+            (Some(_), None) => EdgeKind::Back,
+            (Some(pw), Some(_)) if self.pre[v] < Some(pw) => EdgeKind::Forward,
+            _ => EdgeKind::Cross,
+        };
+        self.edges.push((v, w, kind));
+        kind == EdgeKind::Tree
+    }
+
+    fn visit_recursive<G: Adjacency>(&mut self, graph: &G, v: usize, parent: Option<usize>) {
+        self.enter(v, parent);
+        let mut skip = parent.filter(|_| !graph.is_directed());
+        for &w in graph.adj(v) {
+            if skip == Some(w) {
+                skip = None;
+                continue;
+            }
+            if self.examine(v, w) {
+                self.visit_recursive(graph, w, Some(v));
+            }
+        }
+        self.leave(v);
+    }
+
+    /// Records the events of an iterative search, passing them to `visitor`.
+    fn record<I, V>(&mut self, events: I, visitor: &mut V)
+    where
+        I: Iterator<Item = Event>,
+        V: GraphVisitor,
+    {
+        for event in events {
+            visitor.visit(event);
+            match event {
+                Event::Discover(v) => {
+                    trace_event!(vertex = v, pre = self.preorder.len(), "vertex visited");
+                    self.pre[v] = Some(self.preorder.len());
+                    self.preorder.push(v);
+                }
+                Event::Finish(v) => self.leave(v),
+                Event::Edge(v, w, kind) => {
+                    if kind == EdgeKind::Tree {
+                        self.parent[w] = Some(v);
+                    }
+                    self.edges.push((v, w, kind));
+                }
+            }
+        }
+    }
+}
+
+/// Recursive depth-first search from `source`, in O(V + E) time. The
+/// recursion depth is the length of the longest tree path, so deep graphs
+/// should use `dfs_iterative` instead.
+///
+/// Example:
+/// ```
+/// use algae::graph::{dfs, Digraph, EdgeKind};
+///
+/// let g = Digraph::from_edges(4, [(0, 1), (1, 2), (2, 0), (0, 2), (3, 0)]);
+/// let result = dfs(&g, 0);
+/// assert_eq!(result.preorder(), [0, 1, 2]);
+/// assert_eq!(result.postorder(), [2, 1, 0]);
+/// assert_eq!(result.path_to(2), Some(vec![0, 1, 2]));
+/// assert!(!result.visited(3));
+/// assert_eq!(
+///     result.edges(),
+///     [
+///         (0, 1, EdgeKind::Tree),
+///         (1, 2, EdgeKind::Tree),
+///         (2, 0, EdgeKind::Back),
+///         (0, 2, EdgeKind::Forward),
+///     ]
+/// );
+/// ```
+pub fn dfs<G: Adjacency>(graph: &G, source: usize) -> DfsResult {
+    trace_span!("dfs", vertices = graph.vertex_count(), source);
+    let mut result = DfsResult::new(graph.vertex_count());
+    result.visit_recursive(graph, source, None);
+    result
+}
+
+/// Depth-first search from `source` with an explicit stack, giving the same
+/// result as `dfs` without recursion.
+pub fn dfs_iterative<G: Adjacency>(graph: &G, source: usize) -> DfsResult {
+    dfs_visit(graph, source, &mut ())
+}
+
+/// Depth-first search from `source` with an explicit stack, as
+/// `dfs_iterative`, passing each event of the search to `visitor` as in
+/// `Adjacency::dfs_iter`.
+pub fn dfs_visit<G: Adjacency, V: GraphVisitor>(
+    graph: &G,
+    source: usize,
+    visitor: &mut V,
+) -> DfsResult {
+    trace_span!("dfs", vertices = graph.vertex_count(), source);
+    let mut result = DfsResult::new(graph.vertex_count());
+    result.record(DfsIter::new(graph, source), visitor);
+    result
+}
+
+/// Depth-first search of the whole graph, starting a new tree from each
+/// vertex not yet visited, in increasing order. Uses an explicit stack.
+///
+/// Example:
+/// ```
+/// use algae::graph::{dfs_forest, Digraph, EdgeKind};
+///
+/// let g = Digraph::from_edges(3, [(0, 1), (2, 1)]);
+/// let result = dfs_forest(&g);
+/// assert_eq!(result.postorder(), [1, 0, 2]);
+/// assert_eq!(result.edges()[1], (2, 1, EdgeKind::Cross));
+/// ```
+pub fn dfs_forest<G: Adjacency>(graph: &G) -> DfsResult {
+    dfs_forest_visit(graph, &mut ())
+}
+
+/// Depth-first search of the whole graph, as `dfs_forest`, passing each event
+/// of the search to `visitor` as in `Adjacency::dfs_forest_iter`.
+pub fn dfs_forest_visit<G: Adjacency, V: GraphVisitor>(graph: &G, visitor: &mut V) -> DfsResult {
+    trace_span!("dfs_forest", vertices = graph.vertex_count());
+    let mut result = DfsResult::new(graph.vertex_count());
+    result.record(DfsIter::forest(graph), visitor);
+    result
+}
+
+/// Depth-first search starting a new tree from each of `roots` not yet
+/// visited, in the given order, passing each event of the search to
+/// `visitor`. Vertices unreachable from `roots` are not visited. Uses an
+/// explicit stack.
+///
+/// Example:
+/// ```
+/// use algae::graph::{dfs_roots_visit, Digraph};
+///
+/// let g = Digraph::from_edges(4, [(0, 1), (2, 1), (3, 2)]);
+/// let result = dfs_roots_visit(&g, [2, 0], &mut ());
+/// assert_eq!(result.preorder(), [2, 1, 0]);
+/// assert!(!result.visited(3));
+/// ```
+pub fn dfs_roots_visit<G, I, V>(graph: &G, roots: I, visitor: &mut V) -> DfsResult
+where
+    G: Adjacency,
+    I: IntoIterator<Item = usize>,
+    V: GraphVisitor,
+{
+    trace_span!("dfs_forest", vertices = graph.vertex_count());
+    let mut result = DfsResult::new(graph.vertex_count());
+    result.record(DfsIter::from_roots(graph, roots), visitor);
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::{Digraph, Graph};
+
+    fn random_digraph(n: usize, m: usize) -> Digraph {
+        Digraph::from_edges(
+            n,
+            (0..m).map(|_| (fastrand::usize(..n), fastrand::usize(..n))),
+        )
+    }
+
+    fn assert_same_search(r: &DfsResult, i: &DfsResult) {
+        assert_eq!(r.preorder(), i.preorder());
+        assert_eq!(r.postorder(), i.postorder());
+        assert_eq!(r.edges(), i.edges());
+        for v in 0..r.pre.len() {
+            assert_eq!(r.parent(v), i.parent(v));
+            assert_eq!(r.path_to(v), i.path_to(v));
+        }
+    }
+
+    #[test]
+    fn iterative_agrees_with_recursive() {
+        for _ in 0..50 {
+            let g = random_digraph(fastrand::usize(1..20), fastrand::usize(..50));
+            assert_same_search(&dfs(&g, 0), &dfs_iterative(&g, 0));
+
+            let n = fastrand::usize(1..20);
+            let g = Graph::from_edges(
+                n,
+                (0..fastrand::usize(..30)).map(|_| (fastrand::usize(..n), fastrand::usize(..n))),
+            );
+            assert_same_search(&dfs(&g, 0), &dfs_iterative(&g, 0));
+        }
+    }
+
+    #[test]
+    fn edge_classification() {
+        for _ in 0..50 {
+            let g = random_digraph(fastrand::usize(1..20), fastrand::usize(..50));
+            let result = dfs_forest(&g);
+            assert_eq!(result.edges().len(), g.edge_count());
+            let (pre, post) = (|v| result.pre(v).unwrap(), |v| result.post(v).unwrap());
+            // `v` is an ancestor of `w` iff its interval of visit contains
+            // that of `w`.
+            let ancestor = |v, w| pre(v) <= pre(w) && post(w) <= post(v);
+            for &(v, w, kind) in result.edges() {
+                match kind {
+                    EdgeKind::Tree => assert_eq!(result.parent(w), Some(v)),
+                    EdgeKind::Back => assert!(ancestor(w, v)),
+                    EdgeKind::Forward => assert!(ancestor(v, w) && v != w),
+                    EdgeKind::Cross => assert!(!ancestor(v, w) && !ancestor(w, v)),
+                    EdgeKind::NonTree => unreachable!(),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn undirected_kinds() {
+        let g = Graph::from_edges(3, [(0, 1), (1, 2), (2, 0)]);
+        let kinds: Vec<_> = dfs(&g, 0).edges().iter().map(|e| e.2).collect();
+        assert_eq!(
+            kinds,
+            [
+                EdgeKind::Tree,
+                EdgeKind::Tree,
+                EdgeKind::Back,
+                EdgeKind::Forward
+            ]
+        );
+
+        // A tree has no back edges, unless an edge is doubled.
+        let g = Graph::from_edges(4, [(0, 1), (1, 2), (1, 3)]);
+        assert!(dfs(&g, 0).edges().iter().all(|e| e.2 == EdgeKind::Tree));
+        let g = Graph::from_edges(3, [(0, 1), (1, 2), (2, 1)]);
+        let back: Vec<_> = dfs_iterative(&g, 0)
+            .edges()
+            .iter()
+            .filter(|e| e.2 == EdgeKind::Back)
+            .map(|e| (e.0, e.1))
+            .collect();
+        assert_eq!(back, [(2, 1)]);
+    }
+
+    #[test]
+    fn deep_path() {
+        let n = 1_000_000;
+        let g = Digraph::from_edges(n, (1..n).map(|v| (v - 1, v)));
+        let result = dfs_iterative(&g, 0);
+        assert_eq!(result.postorder()[0], n - 1);
+        assert_eq!(result.path_to(n - 1).map(|p| p.len()), Some(n));
+    }
+}
@@ -0,0 +1,167 @@
+use std::ops::Range;
+
+/// Directed graph over the vertices `0..n`, stored as adjacency lists of the
+/// edges leaving each vertex. The in-degree of each vertex is kept up to date,
+/// so that both degrees take O(1) time.
+///
+/// Example:
+/// ```
+/// use algae::graph::Digraph;
+///
+/// let mut g = Digraph::from_edges(3, [(0, 1), (0, 2)]);
+/// g.add_edge(1, 2);
+/// assert_eq!(g.adj(0), [1, 2]);
+/// assert_eq!((g.out_degree(2), g.in_degree(2)), (0, 2));
+///
+/// let r = g.reverse();
+/// assert_eq!(r.adj(2), [0, 1]);
+/// assert_eq!(r.reverse(), g);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Digraph {
+    adj: Vec<Vec<usize>>,
+    indegree: Vec<usize>,
+    edges: usize,
+}
+
+impl Digraph {
+    /// Digraph with `n` vertices and no edges.
+    pub fn new(n: usize) -> Self {
+        Self {
+            adj: vec![Vec::new(); n],
+            indegree: vec![0; n],
+            edges: 0,
+        }
+    }
+
+    /// Digraph with `n` vertices and the given edges `(from, to)`.
+    pub fn from_edges<I: IntoIterator<Item = (usize, usize)>>(n: usize, edges: I) -> Self {
+        let mut g = Self::new(n);
+        for (from, to) in edges {
+            g.add_edge(from, to);
+        }
+        g
+    }
+
+    /// Number of vertices.
+    pub fn vertex_count(&self) -> usize {
+        self.adj.len()
+    }
+
+    /// Number of edges, counting parallel edges and self-loops.
+    pub fn edge_count(&self) -> usize {
+        self.edges
+    }
+
+    /// The vertices of the digraph, `0..n`.
+    pub fn vertices(&self) -> Range<usize> {
+        0..self.adj.len()
+    }
+
+    /// Adds a new vertex without edges and returns it.
+    pub fn add_vertex(&mut self) -> usize {
+        self.adj.push(Vec::new());
+        self.indegree.push(0);
+        self.adj.len() - 1
+    }
+
+    /// Adds the edge `from->to`. Panics if either vertex is out of range.
+    pub fn add_edge(&mut self, from: usize, to: usize) {
+        for v in [from, to] {
+            assert!(
+                v < self.adj.len(),
+                "Vertex {} out of range for a digraph with {} vertices",
+                v,
+                self.adj.len()
+            );
+        }
+        self.adj[from].push(to);
+        self.indegree[to] += 1;
+        self.edges += 1;
+    }
+
+    /// Heads of the edges leaving `v`, in the order the edges were added.
+    pub fn adj(&self, v: usize) -> &[usize] {
+        &self.adj[v]
+    }
+
+    /// Number of edges leaving `v`.
+    pub fn out_degree(&self, v: usize) -> usize {
+        self.adj[v].len()
+    }
+
+    /// Number of edges entering `v`.
+    pub fn in_degree(&self, v: usize) -> usize {
+        self.indegree[v]
+    }
+
+    /// Whether there is an edge `from->to`. Takes O(out_degree(from)) time.
+    pub fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.adj[from].contains(&to)
+    }
+
+    /// All edges as `(from, to)`, ordered by `from`.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.vertices()
+            .flat_map(move |v| self.adj[v].iter().map(move |&w| (v, w)))
+    }
+
+    /// Digraph with every edge reversed, built in O(V + E) time. The
+    /// adjacency lists of the reverse are ordered by the tail of the original
+    /// edges, so reversing twice gives back the original lists only up to the
+    /// order of each list.
+    pub fn reverse(&self) -> Self {
+        let mut adj: Vec<Vec<usize>> = self
+            .indegree
+            .iter()
+            .map(|&d| Vec::with_capacity(d))
+            .collect();
+        for (v, w) in self.edges() {
+            adj[w].push(v);
+        }
+        Self {
+            adj,
+            indegree: self.adj.iter().map(Vec::len).collect(),
+            edges: self.edges,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn degrees() {
+        let g = Digraph::from_edges(4, [(0, 1), (1, 2), (2, 0), (2, 2), (0, 1)]);
+        assert_eq!(g.edge_count(), 5);
+        assert_eq!(g.adj(0), [1, 1]);
+        assert_eq!((g.out_degree(0), g.in_degree(0)), (2, 1));
+        assert_eq!((g.out_degree(2), g.in_degree(2)), (2, 2));
+        assert_eq!((g.out_degree(3), g.in_degree(3)), (0, 0));
+        assert!(g.has_edge(1, 2) && !g.has_edge(2, 1));
+        let in_sum: usize = g.vertices().map(|v| g.in_degree(v)).sum();
+        assert_eq!(in_sum, g.edge_count());
+    }
+
+    #[test]
+    fn reverse() {
+        for _ in 0..20 {
+            let n = fastrand::usize(1..30);
+            let edges: Vec<_> = (0..fastrand::usize(..100))
+                .map(|_| (fastrand::usize(..n), fastrand::usize(..n)))
+                .collect();
+            let g = Digraph::from_edges(n, edges.iter().copied());
+            let r = g.reverse();
+            let mut reversed: Vec<_> = r.edges().map(|(v, w)| (w, v)).collect();
+            let mut original: Vec<_> = g.edges().collect();
+            reversed.sort();
+            original.sort();
+            assert_eq!(reversed, original);
+            for v in g.vertices() {
+                assert_eq!(g.in_degree(v), r.out_degree(v));
+                assert_eq!(g.out_degree(v), r.in_degree(v));
+            }
+        }
+    }
+}
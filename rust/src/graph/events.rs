@@ -0,0 +1,345 @@
+//! Traversals as lazy iterators over the events of the search, so that custom
+//! algorithms can be written by consuming events rather than by reimplementing
+//! the traversal.
+use crate::{
+    ds::Queue,
+    graph::{Adjacency, EdgeKind},
+};
+
+/// Event of a graph traversal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Event {
+    /// `v` is reached for the first time.
+    Discover(usize),
+    /// Every edge leaving `v` was examined.
+    Finish(usize),
+    /// The edge `v->w` is examined. A tree edge comes right before the
+    /// discovery of `w` through it.
+    Edge(usize, usize, EdgeKind),
+}
+
+/// Callbacks for the events of a search, accepted by `bfs_visit`,
+/// `dfs_visit`, `dfs_forest_visit`, `dfs_roots_visit` and `dijkstra_visit`,
+/// so that algorithms can hook into a traversal without reimplementing it, as
+/// `connected_components`, `topological_sort` and the strongly connected
+/// components do. Every method does nothing by default, and `()` is the
+/// visitor ignoring every event.
+///
+/// Example:
+/// ```
+/// use algae::graph::{dfs_forest_visit, Digraph, GraphVisitor};
+///
+/// /// Depth of each vertex in the tree of the search it is in.
+/// struct Depths {
+///     depth: Vec<usize>,
+///     current: usize,
+/// }
+///
+/// impl GraphVisitor for Depths {
+///     fn discover(&mut self, v: usize) {
+///         self.depth[v] = self.current;
+///         self.current += 1;
+///     }
+///
+///     fn finish(&mut self, _: usize) {
+///         self.current -= 1;
+///     }
+/// }
+///
+/// let g = Digraph::from_edges(5, [(0, 1), (1, 2), (0, 3), (4, 3)]);
+/// let mut depths = Depths {
+///     depth: vec![0; 5],
+///     current: 0,
+/// };
+/// dfs_forest_visit(&g, &mut depths);
+/// assert_eq!(depths.depth, [0, 1, 2, 1, 0]);
+/// ```
+pub trait GraphVisitor {
+    /// `v` is reached for the first time.
+    fn discover(&mut self, _v: usize) {}
+
+    /// Every edge leaving `v` was examined.
+    fn finish(&mut self, _v: usize) {}
+
+    /// The edge `v->w` is examined.
+    fn edge(&mut self, _v: usize, _w: usize, _kind: EdgeKind) {}
+
+    /// Passes `event` to the method handling it.
+    fn visit(&mut self, event: Event) {
+        match event {
+            Event::Discover(v) => self.discover(v),
+            Event::Finish(v) => self.finish(v),
+            Event::Edge(v, w, kind) => self.edge(v, w, kind),
+        }
+    }
+}
+
+impl GraphVisitor for () {}
+
+/// Lazy breadth-first search, given by `Adjacency::bfs_iter`. Edges are
+/// either `EdgeKind::Tree` or `EdgeKind::NonTree`, and each vertex is
+/// finished once its adjacency list is exhausted, before the next vertex of
+/// the queue is explored.
+pub struct BfsIter<'a, G> {
+    graph: &'a G,
+    discovered: Vec<bool>,
+    queue: Queue<usize>,
+    /// Vertex whose edges are being examined, with the index of the next one.
+    current: Option<(usize, usize)>,
+    /// Events to report before resuming the search.
+    pending: Queue<Event>,
+}
+
+impl<'a, G: Adjacency> BfsIter<'a, G> {
+    pub(crate) fn new<I: IntoIterator<Item = usize>>(graph: &'a G, sources: I) -> Self {
+        let mut iter = Self {
+            graph,
+            discovered: vec![false; graph.vertex_count()],
+            queue: Queue::new(),
+            current: None,
+            pending: Queue::new(),
+        };
+        for s in sources {
+            if !iter.discovered[s] {
+                iter.discover(s);
+            }
+        }
+        iter
+    }
+
+    fn discover(&mut self, v: usize) {
+        self.discovered[v] = true;
+        self.queue.push(v);
+        self.pending.push(Event::Discover(v));
+    }
+}
+
+impl<G: Adjacency> Iterator for BfsIter<'_, G> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        if let Some(event) = self.pending.pop() {
+            return Some(event);
+        }
+        let (v, i) = match self.current {
+            Some(current) => current,
+            None => (self.queue.pop()?, 0),
+        };
+        match self.graph.adj(v).get(i) {
+            Some(&w) => {
+                self.current = Some((v, i + 1));
+                if self.discovered[w] {
+                    return Some(Event::Edge(v, w, EdgeKind::NonTree));
+                }
+                self.discover(w);
+                Some(Event::Edge(v, w, EdgeKind::Tree))
+            }
+            None => {
+                self.current = None;
+                Some(Event::Finish(v))
+            }
+        }
+    }
+}
+
+/// Lazy depth-first search with an explicit stack, given by
+/// `Adjacency::dfs_iter` and `Adjacency::dfs_forest_iter`. Edges are
+/// classified as in `DfsResult::edges`: in particular, in undirected graphs,
+/// the tree edge leading to a vertex is not reported again as a back edge to
+/// its parent.
+pub struct DfsIter<'a, G> {
+    graph: &'a G,
+    pre: Vec<Option<usize>>,
+    finished: Vec<bool>,
+    discovered: usize,
+    /// Vertices being explored, with the index of the next edge to examine in
+    /// their adjacency list and, in undirected graphs, the parent whose tree
+    /// edge is yet to be skipped.
+    stack: Vec<(usize, usize, Option<usize>)>,
+    /// Discovery following a tree edge, reported next.
+    pending: Option<Event>,
+    /// Vertices from which to start the next trees, in reverse order, those
+    /// already visited being skipped.
+    roots: Vec<usize>,
+}
+
+impl<'a, G: Adjacency> DfsIter<'a, G> {
+    pub(crate) fn new(graph: &'a G, source: usize) -> Self {
+        let mut iter = Self::from_roots(graph, []);
+        iter.pending = Some(iter.discover(source, None));
+        iter
+    }
+
+    pub(crate) fn forest(graph: &'a G) -> Self {
+        Self::from_roots(graph, 0..graph.vertex_count())
+    }
+
+    pub(crate) fn from_roots<I: IntoIterator<Item = usize>>(graph: &'a G, roots: I) -> Self {
+        let n = graph.vertex_count();
+        let mut roots: Vec<usize> = roots.into_iter().collect();
+        roots.reverse();
+        Self {
+            graph,
+            pre: vec![None; n],
+            finished: vec![false; n],
+            discovered: 0,
+            stack: Vec::new(),
+            pending: None,
+            roots,
+        }
+    }
+
+    fn discover(&mut self, v: usize, parent: Option<usize>) -> Event {
+        self.pre[v] = Some(self.discovered);
+        self.discovered += 1;
+        let parent = parent.filter(|_| !self.graph.is_directed());
+        self.stack.push((v, 0, parent));
+        Event::Discover(v)
+    }
+}
+
+impl<G: Adjacency> Iterator for DfsIter<'_, G> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        if let Some(event) = self.pending.take() {
+            return Some(event);
+        }
+        let Some(&mut (v, ref mut i, ref mut parent)) = self.stack.last_mut() else {
+            let root = std::iter::from_fn(|| self.roots.pop()).find(|&r| self.pre[r].is_none())?;
+            return Some(self.discover(root, None));
+        };
+        // Skip one edge back to the parent: parallel edges are reported.
+        if self
+            .graph
+            .adj(v)
+            .get(*i)
+            .is_some_and(|&w| Some(w) == *parent)
+        {
+            *i += 1;
+            *parent = None;
+        }
+        match self.graph.adj(v).get(*i) {
+            Some(&w) => {
+                *i += 1;
+                let kind = match self.pre[w] {
+                    None => {
+                        self.pending = Some(self.discover(w, Some(v)));
+                        EdgeKind::Tree
+                    }
+                    Some(_) if !self.finished[w] => EdgeKind::Back,
+                    Some(pw) if self.pre[v] < Some(pw) => EdgeKind::Forward,
+                    Some(_) => EdgeKind::Cross,
+                };
+                Some(Event::Edge(v, w, kind))
+            }
+            None => {
+                self.stack.pop();
+                self.finished[v] = true;
+                Some(Event::Finish(v))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::{bfs, dfs, dfs_forest, Digraph, Graph};
+
+    fn random_digraph(n: usize, m: usize) -> Digraph {
+        Digraph::from_edges(
+            n,
+            (0..m).map(|_| (fastrand::usize(..n), fastrand::usize(..n))),
+        )
+    }
+
+    #[test]
+    fn dfs_events_agree_with_dfs() {
+        for _ in 0..50 {
+            let g = random_digraph(fastrand::usize(1..20), fastrand::usize(..50));
+            for (events, result) in [
+                (g.dfs_iter(0).collect::<Vec<_>>(), dfs(&g, 0)),
+                (g.dfs_forest_iter().collect(), dfs_forest(&g)),
+            ] {
+                let discovered: Vec<usize> = events
+                    .iter()
+                    .filter_map(|&e| match e {
+                        Event::Discover(v) => Some(v),
+                        _ => None,
+                    })
+                    .collect();
+                let finished: Vec<usize> = events
+                    .iter()
+                    .filter_map(|&e| match e {
+                        Event::Finish(v) => Some(v),
+                        _ => None,
+                    })
+                    .collect();
+                let edges: Vec<_> = events
+                    .iter()
+                    .filter_map(|&e| match e {
+                        Event::Edge(v, w, kind) => Some((v, w, kind)),
+                        _ => None,
+                    })
+                    .collect();
+                assert_eq!(discovered, result.preorder());
+                assert_eq!(finished, result.postorder());
+                assert_eq!(edges, result.edges());
+            }
+        }
+    }
+
+    #[test]
+    fn bfs_events() {
+        for _ in 0..50 {
+            let g = random_digraph(fastrand::usize(1..20), fastrand::usize(..50));
+            let result = bfs(&g, 0);
+            let mut level = vec![None; g.vertex_count()];
+            let mut finished = vec![false; g.vertex_count()];
+            for event in g.bfs_iter(0) {
+                match event {
+                    Event::Discover(v) => {
+                        if v == 0 {
+                            level[v] = Some(0);
+                        }
+                        assert!(level[v].is_some());
+                    }
+                    Event::Edge(v, w, kind) => {
+                        assert!(!finished[v]);
+                        if kind == EdgeKind::Tree {
+                            assert!(level[w].is_none());
+                            level[w] = level[v].map(|d| d + 1);
+                        } else {
+                            assert_eq!(kind, EdgeKind::NonTree);
+                        }
+                    }
+                    Event::Finish(v) => finished[v] = true,
+                }
+            }
+            for v in g.vertices() {
+                assert_eq!(level[v], result.dist_to(v));
+                assert_eq!(finished[v], result.visited(v));
+            }
+        }
+    }
+
+    #[test]
+    fn lazy_events() {
+        // Stops after a few events, without exploring the rest.
+        let n = 1_000_000;
+        let g = Graph::from_edges(n, (1..n).map(|v| (v - 1, v)));
+        let mut events = g.dfs_iter(0);
+        assert_eq!(events.next(), Some(Event::Discover(0)));
+        assert_eq!(events.next(), Some(Event::Edge(0, 1, EdgeKind::Tree)));
+        assert_eq!(events.next(), Some(Event::Discover(1)));
+        assert_eq!(events.next(), Some(Event::Edge(1, 2, EdgeKind::Tree)));
+
+        let mut events = g.bfs_iter(n / 2);
+        assert_eq!(events.next(), Some(Event::Discover(n / 2)));
+        assert_eq!(
+            events.next(),
+            Some(Event::Edge(n / 2, n / 2 - 1, EdgeKind::Tree))
+        );
+    }
+}
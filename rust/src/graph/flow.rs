@@ -0,0 +1,517 @@
+use crate::ds::Queue;
+use std::ops::Range;
+
+/// Edge `from->to` of a `FlowNetwork`, with the capacity bounding the flow
+/// through it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlowEdge {
+    pub from: usize,
+    pub to: usize,
+    pub capacity: u64,
+}
+
+/// Flow network: a directed graph over the vertices `0..n` with a capacity on
+/// each edge. Edges are identified by the index returned by `add_edge`, which
+/// is also the index of their flow in a `MaxFlow`.
+///
+/// Example:
+/// ```
+/// use algae::graph::FlowNetwork;
+///
+/// let mut network = FlowNetwork::new(3);
+/// let e = network.add_edge(0, 1, 5);
+/// network.add_edge(1, 2, 3);
+/// assert_eq!(network.edge(e).capacity, 5);
+/// assert_eq!(network.edge_count(), 2);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FlowNetwork {
+    n: usize,
+    edges: Vec<FlowEdge>,
+}
+
+impl FlowNetwork {
+    /// Network with `n` vertices and no edges.
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            edges: Vec::new(),
+        }
+    }
+
+    /// Network with `n` vertices and the given edges `(from, to, capacity)`,
+    /// identified by their position.
+    pub fn from_edges<I: IntoIterator<Item = (usize, usize, u64)>>(n: usize, edges: I) -> Self {
+        let mut network = Self::new(n);
+        for (from, to, capacity) in edges {
+            network.add_edge(from, to, capacity);
+        }
+        network
+    }
+
+    /// Number of vertices.
+    pub fn vertex_count(&self) -> usize {
+        self.n
+    }
+
+    /// Number of edges.
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// The vertices of the network, `0..n`.
+    pub fn vertices(&self) -> Range<usize> {
+        0..self.n
+    }
+
+    /// Adds the edge `from->to` with the given capacity and returns its
+    /// index. Panics if either vertex is out of range.
+    pub fn add_edge(&mut self, from: usize, to: usize, capacity: u64) -> usize {
+        for v in [from, to] {
+            assert!(
+                v < self.n,
+                "Vertex {} out of range for a network with {} vertices",
+                v,
+                self.n
+            );
+        }
+        self.edges.push(FlowEdge { from, to, capacity });
+        self.edges.len() - 1
+    }
+
+    /// Edge of index `e`.
+    pub fn edge(&self, e: usize) -> &FlowEdge {
+        &self.edges[e]
+    }
+
+    /// All edges, in order of their index.
+    pub fn edges(&self) -> &[FlowEdge] {
+        &self.edges
+    }
+}
+
+/// Maximum flow from a source to a sink of a `FlowNetwork`, together with a
+/// minimum cut: the vertices still reachable from the source in the residual
+/// graph. The edges leaving the cut are saturated, and their capacities add
+/// up to the value of the flow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaxFlow {
+    value: u64,
+    flows: Vec<u64>,
+    cut: Vec<bool>,
+}
+
+impl MaxFlow {
+    /// Total flow leaving the source.
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// Flow through the edge of index `e`.
+    pub fn flow(&self, e: usize) -> u64 {
+        self.flows[e]
+    }
+
+    /// Flows through all edges, in order of their index.
+    pub fn flows(&self) -> &[u64] {
+        &self.flows
+    }
+
+    /// Whether `v` is on the source side of the minimum cut.
+    pub fn in_cut(&self, v: usize) -> bool {
+        self.cut[v]
+    }
+
+    /// Vertices on the source side of the minimum cut, in increasing order.
+    pub fn min_cut(&self) -> Vec<usize> {
+        (0..self.cut.len()).filter(|&v| self.cut[v]).collect()
+    }
+}
+
+/// Residual graph of a flow network. Each edge `e` of the network becomes
+/// the residual edge `2 * e`, whose capacity is what is left of the edge,
+/// and the residual edge `2 * e + 1` in the opposite direction, whose
+/// capacity is the flow that can be sent back.
+struct Residual {
+    adj: Vec<Vec<usize>>,
+    to: Vec<usize>,
+    capacity: Vec<u64>,
+}
+
+impl Residual {
+    fn new(network: &FlowNetwork) -> Self {
+        let mut adj = vec![Vec::new(); network.vertex_count()];
+        let mut to = Vec::with_capacity(2 * network.edge_count());
+        let mut capacity = Vec::with_capacity(2 * network.edge_count());
+        for (e, edge) in network.edges().iter().enumerate() {
+            adj[edge.from].push(2 * e);
+            adj[edge.to].push(2 * e + 1);
+            to.extend([edge.to, edge.from]);
+            capacity.extend([edge.capacity, 0]);
+        }
+        Self { adj, to, capacity }
+    }
+
+    /// Sends `amount` more flow through the residual edge `r`.
+    fn push(&mut self, r: usize, amount: u64) {
+        self.capacity[r] -= amount;
+        self.capacity[r ^ 1] += amount;
+    }
+
+    /// Vertices reachable from `s` through residual edges of positive
+    /// capacity, as a mask.
+    fn reachable(&self, s: usize) -> Vec<bool> {
+        let mut seen = vec![false; self.adj.len()];
+        seen[s] = true;
+        let mut stack = vec![s];
+        while let Some(v) = stack.pop() {
+            for &r in &self.adj[v] {
+                let w = self.to[r];
+                if self.capacity[r] > 0 && !seen[w] {
+                    seen[w] = true;
+                    stack.push(w);
+                }
+            }
+        }
+        seen
+    }
+
+    /// The maximum flow `value` found, with the flows read back from the
+    /// reverse residual edges.
+    fn into_max_flow(self, value: u64, s: usize) -> MaxFlow {
+        let cut = self.reachable(s);
+        let flows = self.capacity.iter().skip(1).step_by(2).copied().collect();
+        MaxFlow { value, flows, cut }
+    }
+}
+
+/// Edmonds-Karp algorithm: maximum flow from `s` to `t`, augmenting the flow
+/// along a shortest path of the residual graph, found by breadth-first
+/// search, until `t` is unreachable. Shortest paths guarantee that there are
+/// at most O(V * E) augmentations, for a total time of O(V * E^2) regardless
+/// of the capacities.
+///
+/// Panics if `s == t`, or if the value of the flow overflows a `u64`.
+///
+/// Example:
+/// ```
+/// use algae::graph::{edmonds_karp, FlowNetwork};
+///
+/// let network = FlowNetwork::from_edges(
+///     4,
+///     [(0, 1, 3), (0, 2, 2), (1, 2, 1), (1, 3, 2), (2, 3, 3)],
+/// );
+/// let flow = edmonds_karp(&network, 0, 3);
+/// assert_eq!(flow.value(), 5);
+/// assert_eq!(flow.flows(), [3, 2, 1, 2, 3]);
+/// assert_eq!(flow.min_cut(), [0]);
+/// ```
+pub fn edmonds_karp(network: &FlowNetwork, s: usize, t: usize) -> MaxFlow {
+    edmonds_karp_searches(network, s, t).0
+}
+
+/// Edmonds-Karp algorithm, also returning the number of breadth-first
+/// searches of the residual graph, one per augmenting path and a last one
+/// finding none.
+fn edmonds_karp_searches(network: &FlowNetwork, s: usize, t: usize) -> (MaxFlow, usize) {
+    assert_ne!(s, t, "Source and sink must be distinct");
+    let mut residual = Residual::new(network);
+    let mut value = 0;
+    let mut searches = 0;
+    // Residual edge through which each vertex was reached by the search.
+    let mut edge_to = vec![usize::MAX; network.vertex_count()];
+    loop {
+        searches += 1;
+        edge_to.fill(usize::MAX);
+        let mut queue = Queue::new();
+        queue.push(s);
+        while let Some(v) = queue.pop() {
+            for &r in &residual.adj[v] {
+                let w = residual.to[r];
+                if residual.capacity[r] > 0 && w != s && edge_to[w] == usize::MAX {
+                    edge_to[w] = r;
+                    queue.push(w);
+                }
+            }
+        }
+        if edge_to[t] == usize::MAX {
+            break;
+        }
+
+        let path = || {
+            std::iter::successors(Some(edge_to[t]), |&r| {
+                let v = residual.to[r ^ 1];
+                (v != s).then(|| edge_to[v])
+            })
+        };
+        let bottleneck = path()
+            .map(|r| residual.capacity[r])
+            .min()
+            .expect("Path to sink");
+        for r in path().collect::<Vec<_>>() {
+            residual.push(r, bottleneck);
+        }
+        value = add_flow(value, bottleneck);
+    }
+    (residual.into_max_flow(value, s), searches)
+}
+
+/// Dinic's algorithm: maximum flow from `s` to `t`, which works in phases.
+/// Each phase labels the vertices by their distance from `s` in the residual
+/// graph, and saturates the level graph of the edges going one level deeper
+/// with a blocking flow, found by depth-first searches that never revisit a
+/// dead-end edge. The distance to `t` increases after every phase, so there
+/// are at most V phases, for a total time of O(V^2 * E), and much less on
+/// unit capacity networks such as those of bipartite matching.
+///
+/// Panics if `s == t`, or if the value of the flow overflows a `u64`.
+///
+/// Example:
+/// ```
+/// use algae::graph::{dinic, edmonds_karp, FlowNetwork};
+///
+/// let network = FlowNetwork::from_edges(
+///     4,
+///     [(0, 1, 3), (0, 2, 2), (1, 2, 1), (1, 3, 2), (2, 3, 3)],
+/// );
+/// let flow = dinic(&network, 0, 3);
+/// assert_eq!(flow.value(), 5);
+/// assert_eq!(flow.value(), edmonds_karp(&network, 0, 3).value());
+/// ```
+pub fn dinic(network: &FlowNetwork, s: usize, t: usize) -> MaxFlow {
+    dinic_searches(network, s, t).0
+}
+
+/// Dinic's algorithm, also returning the number of breadth-first searches of
+/// the residual graph, one per phase and a last one not reaching `t`.
+fn dinic_searches(network: &FlowNetwork, s: usize, t: usize) -> (MaxFlow, usize) {
+    assert_ne!(s, t, "Source and sink must be distinct");
+    let n = network.vertex_count();
+    let mut residual = Residual::new(network);
+    let mut value = 0;
+    let mut searches = 0;
+    let mut level = vec![usize::MAX; n];
+    // Index of the next residual edge to try in the adjacency list of each
+    // vertex during the current phase.
+    let mut next = vec![0; n];
+    loop {
+        searches += 1;
+        level.fill(usize::MAX);
+        level[s] = 0;
+        let mut queue = Queue::new();
+        queue.push(s);
+        while let Some(v) = queue.pop() {
+            for &r in &residual.adj[v] {
+                let w = residual.to[r];
+                if residual.capacity[r] > 0 && level[w] == usize::MAX {
+                    level[w] = level[v] + 1;
+                    queue.push(w);
+                }
+            }
+        }
+        if level[t] == usize::MAX {
+            break;
+        }
+
+        next.fill(0);
+        loop {
+            let pushed = blocking_path(&mut residual, &level, &mut next, s, t);
+            if pushed == 0 {
+                break;
+            }
+            value = add_flow(value, pushed);
+        }
+    }
+    (residual.into_max_flow(value, s), searches)
+}
+
+/// Sends flow from `s` to `t` along a single path of the level graph,
+/// returning the amount sent, or 0 if `t` can no longer be reached. The path
+/// is kept as a stack of residual edges, so that long paths need no
+/// recursion. Edges found to lead nowhere are popped, and skipped for the
+/// rest of the phase by advancing `next`.
+fn blocking_path(
+    residual: &mut Residual,
+    level: &[usize],
+    next: &mut [usize],
+    s: usize,
+    t: usize,
+) -> u64 {
+    let mut path = Vec::new();
+    let mut v = s;
+    while v != t {
+        match residual.adj[v].get(next[v]) {
+            Some(&r) => {
+                let w = residual.to[r];
+                if residual.capacity[r] > 0 && level[w] == level[v] + 1 {
+                    path.push(r);
+                    v = w;
+                } else {
+                    next[v] += 1;
+                }
+            }
+            None => {
+                // Dead end: retreat along the last edge of the path.
+                let Some(r) = path.pop() else {
+                    return 0;
+                };
+                v = residual.to[r ^ 1];
+                next[v] += 1;
+            }
+        }
+    }
+    let pushed = path
+        .iter()
+        .map(|&r| residual.capacity[r])
+        .min()
+        .expect("Path to sink");
+    for r in path {
+        residual.push(r, pushed);
+    }
+    pushed
+}
+
+/// Adds `amount` to the value of a flow, panicking on overflow.
+fn add_flow(value: u64, amount: u64) -> u64 {
+    value
+        .checked_add(amount)
+        .expect("The value of the maximum flow overflows u64")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn random_network(n: usize, m: usize) -> FlowNetwork {
+        FlowNetwork::from_edges(
+            n,
+            (0..m).map(|_| {
+                (
+                    fastrand::usize(..n),
+                    fastrand::usize(..n),
+                    fastrand::u64(..10),
+                )
+            }),
+        )
+    }
+
+    /// Checks capacity constraints, conservation, and that the cut is a
+    /// minimum cut, whose capacity equals the value of the flow.
+    fn assert_max_flow(network: &FlowNetwork, flow: &MaxFlow, s: usize, t: usize) {
+        let mut excess = vec![0_i64; network.vertex_count()];
+        for (e, edge) in network.edges().iter().enumerate() {
+            assert!(flow.flow(e) <= edge.capacity);
+            excess[edge.from] -= flow.flow(e) as i64;
+            excess[edge.to] += flow.flow(e) as i64;
+        }
+        for v in network.vertices().filter(|&v| v != s && v != t) {
+            assert_eq!(excess[v], 0);
+        }
+        assert_eq!(excess[t], flow.value() as i64);
+        assert!(flow.in_cut(s) && !flow.in_cut(t));
+        let cut_capacity: u64 = network
+            .edges()
+            .iter()
+            .filter(|e| flow.in_cut(e.from) && !flow.in_cut(e.to))
+            .map(|e| e.capacity)
+            .sum();
+        assert_eq!(cut_capacity, flow.value());
+    }
+
+    #[test]
+    fn random_networks() {
+        for _ in 0..100 {
+            let n = fastrand::usize(2..15);
+            let network = random_network(n, fastrand::usize(..4 * n));
+            let flow = edmonds_karp(&network, 0, n - 1);
+            assert_max_flow(&network, &flow, 0, n - 1);
+            let other = dinic(&network, 0, n - 1);
+            assert_max_flow(&network, &other, 0, n - 1);
+            assert_eq!(flow.min_cut(), other.min_cut());
+        }
+    }
+
+    #[test]
+    fn antiparallel_edges() {
+        let network = FlowNetwork::from_edges(3, [(0, 1, 4), (1, 0, 2), (1, 2, 3), (2, 1, 5)]);
+        for flow in [edmonds_karp(&network, 0, 2), dinic(&network, 0, 2)] {
+            assert_eq!(flow.value(), 3);
+            assert_eq!(flow.min_cut(), [0, 1]);
+            assert_max_flow(&network, &flow, 0, 2);
+        }
+    }
+
+    #[test]
+    fn long_path() {
+        let n = 1_000_000;
+        let network = FlowNetwork::from_edges(n, (1..n).map(|v| (v - 1, v, v as u64)));
+        assert_eq!(dinic(&network, 0, n - 1).value(), 1);
+        assert_eq!(edmonds_karp(&network, 0, n - 1).value(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows u64")]
+    fn overflowing_value() {
+        let network = FlowNetwork::from_edges(2, [(0, 1, u64::MAX), (0, 1, u64::MAX)]);
+        dinic(&network, 0, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows u64")]
+    fn overflowing_value_edmonds_karp() {
+        let network = FlowNetwork::from_edges(2, [(0, 1, u64::MAX), (0, 1, u64::MAX)]);
+        edmonds_karp(&network, 0, 1);
+    }
+
+    /// Network of `layers` layers of `width` vertices between a source and a
+    /// sink, with random edges between consecutive layers.
+    fn layered_network(layers: usize, width: usize, degree: usize) -> FlowNetwork {
+        let n = layers * width + 2;
+        let (s, t) = (n - 2, n - 1);
+        let mut network = FlowNetwork::new(n);
+        for i in 0..width {
+            network.add_edge(s, i, 1_000);
+            network.add_edge((layers - 1) * width + i, t, 1_000);
+        }
+        for layer in 0..layers - 1 {
+            for i in 0..width {
+                for _ in 0..degree {
+                    let j = fastrand::usize(..width);
+                    network.add_edge(
+                        layer * width + i,
+                        (layer + 1) * width + j,
+                        fastrand::u64(1..100),
+                    );
+                }
+            }
+        }
+        network
+    }
+
+    #[test]
+    fn layered_networks() {
+        for _ in 0..10 {
+            let network = layered_network(5, 10, 3);
+            let (s, t) = (network.vertex_count() - 2, network.vertex_count() - 1);
+            let flow = dinic(&network, s, t);
+            assert_max_flow(&network, &flow, s, t);
+            assert_eq!(flow.value(), edmonds_karp(&network, s, t).value());
+        }
+    }
+
+    /// Each search of the residual graph costs O(E) in both algorithms, but
+    /// Dinic saturates all the shortest augmenting paths of a phase after a
+    /// single one, while Edmonds-Karp searches again after every path.
+    #[test]
+    fn dinic_needs_fewer_searches() {
+        for (layers, width) in [(5, 20), (10, 50)] {
+            let network = layered_network(layers, width, 5);
+            let (s, t) = (network.vertex_count() - 2, network.vertex_count() - 1);
+            let (slow, augmentations) = edmonds_karp_searches(&network, s, t);
+            let (fast, phases) = dinic_searches(&network, s, t);
+            assert_eq!(slow.value(), fast.value());
+            assert!(phases <= network.vertex_count());
+            assert!(10 * phases < augmentations);
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use crate::graph::{bfs, Graph};
+
+/// Hamiltonian path of `graph`, visiting every vertex exactly once, if any.
+/// Finding one is NP-hard, and this backtracking search takes exponential
+/// time in the worst case, although pruning keeps it fast on many graphs:
+///
+/// * Disconnected graphs, and graphs with more than two vertices of degree
+///   one, are rejected upfront. A vertex of degree one must end the path, so
+///   the search starts from it if there is one.
+/// * Neighbors with fewer unvisited neighbors are tried first, as they are
+///   the most likely to be stranded.
+/// * A branch is abandoned as soon as some unvisited vertex has no unvisited
+///   neighbor left, or two of them are reachable only through a single
+///   unvisited neighbor, since both would have to end the path.
+///
+/// Example:
+/// ```
+/// use algae::graph::{hamiltonian_path, Graph};
+///
+/// let g = Graph::from_edges(5, [(0, 1), (0, 2), (1, 2), (2, 3), (3, 4), (1, 4)]);
+/// let path = hamiltonian_path(&g).unwrap();
+/// assert_eq!(path.len(), 5);
+/// assert!(path.windows(2).all(|e| g.has_edge(e[0], e[1])));
+///
+/// // A star has no Hamiltonian path.
+/// let star = Graph::from_edges(4, [(0, 1), (0, 2), (0, 3)]);
+/// assert_eq!(hamiltonian_path(&star), None);
+/// ```
+pub fn hamiltonian_path(graph: &Graph) -> Option<Vec<usize>> {
+    let n = graph.vertex_count();
+    if n == 0 {
+        return Some(Vec::new());
+    }
+    let adj = simple_adjacency(graph);
+    let reached = bfs(graph, 0);
+    if !graph.vertices().all(|v| reached.visited(v)) {
+        return None;
+    }
+    let leaves: Vec<usize> = graph.vertices().filter(|&v| adj[v].len() <= 1).collect();
+    if n > 1 && leaves.len() > 2 {
+        return None;
+    }
+
+    let starts: Vec<usize> = match leaves.first() {
+        Some(&leaf) => vec![leaf],
+        None => graph.vertices().collect(),
+    };
+    for start in starts {
+        let mut search = Backtracking {
+            adj: &adj,
+            visited: vec![false; n],
+            free_degree: adj.iter().map(Vec::len).collect(),
+            path: Vec::with_capacity(n),
+        };
+        search.visit(start);
+        if search.extend() {
+            return Some(search.path);
+        }
+    }
+    None
+}
+
+/// Adjacency lists without self-loops nor parallel edges.
+fn simple_adjacency(graph: &Graph) -> Vec<Vec<usize>> {
+    graph
+        .vertices()
+        .map(|v| {
+            let mut adj: Vec<usize> = graph.adj(v).iter().copied().filter(|&w| w != v).collect();
+            adj.sort_unstable();
+            adj.dedup();
+            adj
+        })
+        .collect()
+}
+
+struct Backtracking<'a> {
+    adj: &'a [Vec<usize>],
+    visited: Vec<bool>,
+    /// Number of unvisited neighbors of each vertex.
+    free_degree: Vec<usize>,
+    path: Vec<usize>,
+}
+
+impl Backtracking<'_> {
+    fn visit(&mut self, v: usize) {
+        self.visited[v] = true;
+        self.path.push(v);
+        for &w in &self.adj[v] {
+            self.free_degree[w] -= 1;
+        }
+    }
+
+    /// Removes the last vertex `v` of the path.
+    fn unvisit(&mut self, v: usize) {
+        self.visited[v] = false;
+        self.path.pop();
+        for &w in &self.adj[v] {
+            self.free_degree[w] += 1;
+        }
+    }
+
+    /// Whether the path can still be completed, as far as the degrees of the
+    /// unvisited vertices tell.
+    fn feasible(&self, current: usize) -> bool {
+        let remaining = self.visited.len() - self.path.len();
+        if remaining <= 1 {
+            return true;
+        }
+        let mut endpoints = 0;
+        for u in (0..self.visited.len()).filter(|&u| !self.visited[u]) {
+            match self.free_degree[u] {
+                // Only reachable from the current vertex, as the last one.
+                0 => return false,
+                1 if !self.adj[current].contains(&u) => endpoints += 1,
+                _ => {}
+            }
+        }
+        endpoints <= 1
+    }
+
+    /// Unvisited neighbors to extend the path with from its last vertex, in
+    /// reverse order of preference, or none if the path cannot be completed.
+    fn candidates(&self) -> Vec<usize> {
+        let current = *self.path.last().expect("Path should be non-empty");
+        if !self.feasible(current) {
+            return Vec::new();
+        }
+        let mut next: Vec<usize> = self.adj[current]
+            .iter()
+            .copied()
+            .filter(|&w| !self.visited[w])
+            .collect();
+        next.sort_by_key(|&w| self.free_degree[w]);
+        next.reverse();
+        next
+    }
+
+    /// Extends the path from its last vertex until it covers every vertex,
+    /// returning whether it succeeded. The search can go as deep as there are
+    /// vertices, so the candidates left at each depth are kept on an explicit
+    /// stack rather than on the call stack.
+    fn extend(&mut self) -> bool {
+        let n = self.visited.len();
+        let mut stack = vec![self.candidates()];
+        while self.path.len() < n {
+            let Some(candidates) = stack.last_mut() else {
+                return false;
+            };
+            match candidates.pop() {
+                Some(w) => {
+                    self.visit(w);
+                    stack.push(self.candidates());
+                }
+                None => {
+                    // Every extension of the last vertex failed. The first
+                    // vertex is left in place, as the caller tried it.
+                    stack.pop();
+                    if !stack.is_empty() {
+                        let v = *self.path.last().expect("Path should be non-empty");
+                        self.unvisit(v);
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Hamiltonian path of `graph` by dynamic programming over the subsets of
+/// vertices, in the manner of Held and Karp: for each set of vertices, the
+/// vertices at which a path covering exactly that set can end are found from
+/// those of the set without the endpoint. Takes O(2^n * n^2) time and O(2^n)
+/// space regardless of the structure of the graph, which is practical up to
+/// about 20 vertices. Unlike `hamiltonian_path`, its running time doesn't
+/// depend on luck.
+///
+/// Panics if the graph has more than 25 vertices.
+///
+/// Example:
+/// ```
+/// use algae::graph::{hamiltonian_path_dp, Graph};
+///
+/// let g = Graph::from_edges(4, [(0, 1), (1, 2), (2, 3), (3, 0)]);
+/// assert_eq!(hamiltonian_path_dp(&g).map(|p| p.len()), Some(4));
+/// ```
+pub fn hamiltonian_path_dp(graph: &Graph) -> Option<Vec<usize>> {
+    let n = graph.vertex_count();
+    assert!(
+        n <= 25,
+        "Held-Karp needs 2^n space, {} vertices is too many",
+        n
+    );
+    if n == 0 {
+        return Some(Vec::new());
+    }
+    let neighbors: Vec<u32> = simple_adjacency(graph)
+        .iter()
+        .map(|adj| adj.iter().fold(0, |mask, &w| mask | 1 << w))
+        .collect();
+
+    // `ends[set]` is the mask of the vertices at which a path covering
+    // exactly `set` can end.
+    let full = (1_usize << n) - 1;
+    let mut ends = vec![0_u32; full + 1];
+    for v in 0..n {
+        ends[1 << v] = 1 << v;
+    }
+    for set in 1..=full {
+        if ends[set] == 0 {
+            continue;
+        }
+        for (w, &adj) in neighbors.iter().enumerate() {
+            if set & (1 << w) == 0 && ends[set] & adj != 0 {
+                ends[set | 1 << w] |= 1 << w;
+            }
+        }
+    }
+
+    // Walk back from the full set, each time to a neighbor which can end the
+    // path over the remaining vertices.
+    let mut v = ends[full].trailing_zeros() as usize;
+    if v >= n {
+        return None;
+    }
+    let mut set = full;
+    let mut path = vec![v];
+    while set != 1 << v {
+        set &= !(1 << v);
+        v = (ends[set] & neighbors[v]).trailing_zeros() as usize;
+        path.push(v);
+    }
+    Some(path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_path(g: &Graph, path: &[usize]) {
+        let mut sorted = path.to_vec();
+        sorted.sort_unstable();
+        assert_eq!(sorted, g.vertices().collect::<Vec<_>>());
+        assert!(path.windows(2).all(|e| g.has_edge(e[0], e[1])));
+    }
+
+    #[test]
+    fn backtracking_agrees_with_dp() {
+        for _ in 0..300 {
+            let n = fastrand::usize(..10);
+            let g = Graph::from_edges(
+                n,
+                (0..fastrand::usize(..2 * n + 1))
+                    .map(|_| (fastrand::usize(..n), fastrand::usize(..n))),
+            );
+            let (backtracking, dp) = (hamiltonian_path(&g), hamiltonian_path_dp(&g));
+            assert_eq!(backtracking.is_some(), dp.is_some(), "{:?}", g);
+            if let (Some(p), Some(q)) = (backtracking, dp) {
+                assert_path(&g, &p);
+                assert_path(&g, &q);
+            }
+        }
+    }
+
+    #[test]
+    fn known_graphs() {
+        // The Petersen graph has a Hamiltonian path but no Hamiltonian cycle.
+        let petersen = Graph::from_edges(
+            10,
+            (0..5).flat_map(|i| [(i, (i + 1) % 5), (i, i + 5), (i + 5, (i + 2) % 5 + 5)]),
+        );
+        assert_path(&petersen, &hamiltonian_path(&petersen).unwrap());
+        assert_path(&petersen, &hamiltonian_path_dp(&petersen).unwrap());
+
+        // A long path with its vertices shuffled.
+        let n = 2_000;
+        let mut order: Vec<usize> = (0..n).collect();
+        fastrand::shuffle(&mut order);
+        let g = Graph::from_edges(n, order.windows(2).map(|e| (e[0], e[1])));
+        assert_path(&g, &hamiltonian_path(&g).unwrap());
+
+        assert_eq!(hamiltonian_path(&Graph::new(1)), Some(vec![0]));
+        assert_eq!(hamiltonian_path(&Graph::new(2)), None);
+        assert_eq!(hamiltonian_path_dp(&Graph::new(2)), None);
+    }
+
+    #[test]
+    fn deep_search_on_small_stack() {
+        // A cycle with chords: the search goes as deep as there are vertices,
+        // which a recursive search could not afford on such a stack.
+        let n = 3_000;
+        let g = Graph::from_edges(
+            n,
+            (0..n).flat_map(|v| [(v, (v + 1) % n), (v, (v + n / 2) % n)]),
+        );
+        let path = std::thread::Builder::new()
+            .stack_size(64 * 1024)
+            .spawn(move || hamiltonian_path(&g).map(|p| (g, p)))
+            .unwrap()
+            .join()
+            .unwrap();
+        let (g, path) = path.unwrap();
+        assert_path(&g, &path);
+    }
+}
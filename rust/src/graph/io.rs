@@ -0,0 +1,371 @@
+//! Reading graphs from edge lists, and writing them in the DOT language of
+//! Graphviz for visualization, for instance with `dot -Tsvg graph.dot`.
+use crate::graph::{Digraph, EdgeWeightedDigraph, FlowNetwork, Graph};
+use std::{
+    fmt::{self, Write as _},
+    io::{self, BufRead},
+};
+
+/// Graphs which can be written in the DOT language: their vertices `0..n` and
+/// their edges, with an optional label such as a weight.
+pub trait ToDot {
+    /// Whether the edges are directed.
+    fn is_directed(&self) -> bool;
+
+    /// Number of vertices.
+    fn vertex_count(&self) -> usize;
+
+    /// Edges `(v, w, label)`, each given once.
+    fn dot_edges(&self) -> Vec<(usize, usize, Option<String>)>;
+}
+
+impl ToDot for Graph {
+    fn is_directed(&self) -> bool {
+        false
+    }
+
+    fn vertex_count(&self) -> usize {
+        Graph::vertex_count(self)
+    }
+
+    fn dot_edges(&self) -> Vec<(usize, usize, Option<String>)> {
+        self.edges().map(|(v, w)| (v, w, None)).collect()
+    }
+}
+
+impl ToDot for Digraph {
+    fn is_directed(&self) -> bool {
+        true
+    }
+
+    fn vertex_count(&self) -> usize {
+        Digraph::vertex_count(self)
+    }
+
+    fn dot_edges(&self) -> Vec<(usize, usize, Option<String>)> {
+        self.edges().map(|(v, w)| (v, w, None)).collect()
+    }
+}
+
+impl ToDot for EdgeWeightedDigraph {
+    fn is_directed(&self) -> bool {
+        true
+    }
+
+    fn vertex_count(&self) -> usize {
+        EdgeWeightedDigraph::vertex_count(self)
+    }
+
+    fn dot_edges(&self) -> Vec<(usize, usize, Option<String>)> {
+        self.edges()
+            .map(|e| (e.from, e.to, Some(e.weight.to_string())))
+            .collect()
+    }
+}
+
+impl ToDot for FlowNetwork {
+    fn is_directed(&self) -> bool {
+        true
+    }
+
+    fn vertex_count(&self) -> usize {
+        FlowNetwork::vertex_count(self)
+    }
+
+    fn dot_edges(&self) -> Vec<(usize, usize, Option<String>)> {
+        self.edges()
+            .iter()
+            .map(|e| (e.from, e.to, Some(e.capacity.to_string())))
+            .collect()
+    }
+}
+
+/// Writes `graph` in the DOT language, with the vertices named by their
+/// index. Every vertex is declared, so that isolated vertices are drawn, and
+/// weights and capacities become edge labels.
+///
+/// Example:
+/// ```
+/// use algae::graph::{io::to_dot, EdgeWeightedDigraph, Graph};
+///
+/// let g = Graph::from_edges(3, [(0, 1), (1, 2)]);
+/// assert_eq!(to_dot(&g), "graph {\n    0;\n    1;\n    2;\n    0 -- 1;\n    1 -- 2;\n}\n");
+///
+/// let g = EdgeWeightedDigraph::from_edges(2, [(0, 1, 2.5)]);
+/// assert!(to_dot(&g).contains("    0 -> 1 [label=\"2.5\"];\n"));
+/// ```
+pub fn to_dot<G: ToDot>(graph: &G) -> String {
+    write_dot(graph, |_| None)
+}
+
+/// Writes `graph` in the DOT language, labelling the vertex `v` by
+/// `labels[v]`. Panics if there are fewer labels than vertices.
+///
+/// Example:
+/// ```
+/// use algae::graph::{io::to_dot_labeled, Digraph};
+///
+/// let g = Digraph::from_edges(2, [(0, 1)]);
+/// let dot = to_dot_labeled(&g, &["shirt", "\"tie\""]);
+/// assert!(dot.starts_with("digraph {\n    0 [label=\"shirt\"];\n    1 [label=\"\\\"tie\\\"\"];\n"));
+/// ```
+pub fn to_dot_labeled<G: ToDot, L: fmt::Display>(graph: &G, labels: &[L]) -> String {
+    assert!(
+        labels.len() >= graph.vertex_count(),
+        "{} labels for {} vertices",
+        labels.len(),
+        graph.vertex_count()
+    );
+    write_dot(graph, |v| Some(labels[v].to_string()))
+}
+
+fn write_dot<G: ToDot, F: Fn(usize) -> Option<String>>(graph: &G, label: F) -> String {
+    let (keyword, arrow) = if graph.is_directed() {
+        ("digraph", "->")
+    } else {
+        ("graph", "--")
+    };
+    // Writing to a `String` never fails.
+    let mut dot = format!("{} {{\n", keyword);
+    for v in 0..graph.vertex_count() {
+        match label(v) {
+            Some(l) => writeln!(dot, "    {} [label=\"{}\"];", v, escape(&l)),
+            None => writeln!(dot, "    {};", v),
+        }
+        .unwrap();
+    }
+    for (v, w, l) in graph.dot_edges() {
+        match l {
+            Some(l) => writeln!(dot, "    {} {} {} [label=\"{}\"];", v, arrow, w, escape(&l)),
+            None => writeln!(dot, "    {} {} {};", v, arrow, w),
+        }
+        .unwrap();
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Escapes the quotes and backslashes of a quoted DOT string.
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Error reading an edge list.
+#[derive(Debug)]
+pub enum ReadError {
+    /// The reader failed.
+    Io(io::Error),
+    /// A line, numbered from 1, isn't a valid edge.
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(err) => write!(f, "{}", err),
+            ReadError::Parse { line, message } => write!(f, "{} at line {}", message, line),
+        }
+    }
+}
+
+impl std::error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadError::Io(err) => Some(err),
+            ReadError::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(err: io::Error) -> Self {
+        ReadError::Io(err)
+    }
+}
+
+/// Edges read by `from_edge_list`, as `(from, to, weight)`, which can be
+/// turned into any of the graph types.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EdgeList {
+    pub edges: Vec<(usize, usize, Option<f64>)>,
+}
+
+impl EdgeList {
+    /// Number of vertices needed for the edges: one more than the largest
+    /// vertex.
+    pub fn vertex_count(&self) -> usize {
+        self.edges
+            .iter()
+            .map(|&(v, w, _)| v.max(w) + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Undirected graph of the edges, ignoring weights.
+    pub fn to_graph(&self) -> Graph {
+        Graph::from_edges(
+            self.vertex_count(),
+            self.edges.iter().map(|&(v, w, _)| (v, w)),
+        )
+    }
+
+    /// Directed graph of the edges, ignoring weights.
+    pub fn to_digraph(&self) -> Digraph {
+        Digraph::from_edges(
+            self.vertex_count(),
+            self.edges.iter().map(|&(v, w, _)| (v, w)),
+        )
+    }
+
+    /// Edge-weighted digraph of the edges, where edges without a weight have
+    /// weight 1.
+    pub fn to_weighted_digraph(&self) -> EdgeWeightedDigraph {
+        EdgeWeightedDigraph::from_edges(
+            self.vertex_count(),
+            self.edges
+                .iter()
+                .map(|&(v, w, weight)| (v, w, weight.unwrap_or(1.0))),
+        )
+    }
+}
+
+/// Reads an edge list with one edge per line, given by its two vertices and
+/// an optional weight, separated by whitespace or commas as in CSV files.
+/// Blank lines and lines starting with `#` are skipped, as is a header line
+/// first in the file, recognized by its first field not being a number.
+///
+/// Vertices must be less than `max_vertices`, so that a malformed or hostile
+/// input cannot make the graphs built from the list exhaust the memory.
+///
+/// Example:
+/// ```
+/// use algae::graph::io::from_edge_list;
+///
+/// let csv = "from,to,weight\n0,1,2.5\n1,2,-1\n";
+/// let edges = from_edge_list(csv.as_bytes(), 100).unwrap();
+/// assert_eq!(edges.edges, [(0, 1, Some(2.5)), (1, 2, Some(-1.0))]);
+///
+/// let text = "# A triangle\n0 1\n1 2\n2 0\n";
+/// let g = from_edge_list(text.as_bytes(), 100).unwrap().to_graph();
+/// assert_eq!((g.vertex_count(), g.edge_count()), (3, 3));
+///
+/// let err = from_edge_list("0 1\n1 x\n".as_bytes(), 100).unwrap_err();
+/// assert_eq!(err.to_string(), "invalid vertex `x` at line 2");
+/// let err = from_edge_list("0 100\n".as_bytes(), 100).unwrap_err();
+/// assert_eq!(err.to_string(), "vertex 100 out of range 0..100 at line 1");
+/// ```
+pub fn from_edge_list<R: BufRead>(reader: R, max_vertices: usize) -> Result<EdgeList, ReadError> {
+    let mut list = EdgeList::default();
+    let mut first = true;
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .map(str::trim)
+            .filter(|f| !f.is_empty())
+            .collect();
+        let is_header = first && fields.first().is_some_and(|f| f.parse::<f64>().is_err());
+        first = false;
+        if is_header {
+            continue;
+        }
+
+        let parse_error = |message: String| ReadError::Parse {
+            line: i + 1,
+            message,
+        };
+        if !(2..=3).contains(&fields.len()) {
+            return Err(parse_error(format!(
+                "expected 2 or 3 fields, found {}",
+                fields.len()
+            )));
+        }
+        let vertex = |f: &str| match f.parse::<usize>() {
+            Ok(v) if v < max_vertices => Ok(v),
+            Ok(v) => Err(parse_error(format!(
+                "vertex {} out of range 0..{}",
+                v, max_vertices
+            ))),
+            Err(_) => Err(parse_error(format!("invalid vertex `{}`", f))),
+        };
+        let (v, w) = (vertex(fields[0])?, vertex(fields[1])?);
+        let weight = match fields.get(2) {
+            Some(f) => Some(
+                f.parse::<f64>()
+                    .map_err(|_| parse_error(format!("invalid weight `{}`", f)))?,
+            ),
+            None => None,
+        };
+        list.edges.push((v, w, weight));
+    }
+    Ok(list)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::FlowNetwork;
+
+    #[test]
+    fn dot_output() {
+        let g = Digraph::from_edges(3, [(0, 1), (0, 1), (2, 2)]);
+        assert_eq!(
+            to_dot(&g),
+            "digraph {\n    0;\n    1;\n    2;\n    0 -> 1;\n    0 -> 1;\n    2 -> 2;\n}\n"
+        );
+        let network = FlowNetwork::from_edges(2, [(0, 1, 7)]);
+        assert!(to_dot(&network).contains("0 -> 1 [label=\"7\"];"));
+        assert_eq!(to_dot(&Graph::new(0)), "graph {\n}\n");
+    }
+
+    #[test]
+    fn round_trip() {
+        let n = 20;
+        let g = Digraph::from_edges(
+            n,
+            (0..50).map(|_| (fastrand::usize(..n), fastrand::usize(..n))),
+        );
+        let text: String = g.edges().map(|(v, w)| format!("{}\t{}\n", v, w)).collect();
+        let read = from_edge_list(text.as_bytes(), n).unwrap().to_digraph();
+        assert_eq!(
+            read.edges().collect::<Vec<_>>(),
+            g.edges().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let err = |text: &str| {
+            from_edge_list(text.as_bytes(), 1000)
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            err("0 1\n\n0 1 2 3\n"),
+            "expected 2 or 3 fields, found 4 at line 3"
+        );
+        assert_eq!(err("0, 1, heavy\n"), "invalid weight `heavy` at line 1");
+        // Only the first line may be a header.
+        assert_eq!(err("0 1\nfrom to\n"), "invalid vertex `from` at line 2");
+        assert_eq!(err("-1 2\n"), "invalid vertex `-1` at line 1");
+        assert_eq!(
+            err("0 1\n1000000000000 1\n"),
+            "vertex 1000000000000 out of range 0..1000 at line 2"
+        );
+        // Even without a meaningful limit, counting the vertices cannot overflow.
+        let max = usize::MAX;
+        let err = from_edge_list(format!("{} 0\n", max).as_bytes(), max).unwrap_err();
+        assert!(matches!(err, ReadError::Parse { line: 1, .. }));
+        let list = from_edge_list(format!("{} 0\n", max - 1).as_bytes(), max).unwrap();
+        assert_eq!(list.vertex_count(), max);
+        let weighted = from_edge_list("0 1\n1 2 0.5\n".as_bytes(), 3)
+            .unwrap()
+            .to_weighted_digraph();
+        let weights: Vec<f64> = weighted.edges().map(|e| e.weight).collect();
+        assert_eq!(weights, [1.0, 0.5]);
+    }
+}
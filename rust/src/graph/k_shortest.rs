@@ -0,0 +1,187 @@
+use crate::graph::{dijkstra_to, EdgeWeightedDigraph, NegativeWeight, WeightedPath};
+
+/// Weight of the path through `vertices`, going through the lightest of any
+/// parallel edges.
+fn path_weight(graph: &EdgeWeightedDigraph, vertices: &[usize]) -> f64 {
+    vertices
+        .windows(2)
+        .map(|w| {
+            graph
+                .adj(w[0])
+                .iter()
+                .filter(|e| e.to == w[1])
+                .map(|e| e.weight)
+                .fold(f64::INFINITY, f64::min)
+        })
+        .sum()
+}
+
+/// Yen's algorithm: the `k` shortest loopless paths from `source` to `target`,
+/// in increasing order of weight, or fewer if there aren't that many. Paths
+/// are told apart by their vertices, so that parallel edges don't give
+/// different paths.
+///
+/// The first path is found by `dijkstra_to`. Each next one deviates from a
+/// path already found at some spur vertex: it follows that path up to the
+/// spur, and then the shortest path from the spur to `target` avoiding the
+/// vertices before the spur, which keeps it loopless, and the edges leaving
+/// the spur along the paths found with the same beginning, which makes it
+/// new. The deviations of the last path found join a set of candidates, and
+/// the lightest candidate is the next path. This takes O(k * V) runs of
+/// Dijkstra's algorithm, each on a copy of the digraph without the avoided
+/// vertices and edges.
+///
+/// Returns an error if any edge has a negative weight.
+///
+/// Example:
+/// ```
+/// use algae::graph::{k_shortest_paths, EdgeWeightedDigraph};
+///
+/// let g = EdgeWeightedDigraph::from_edges(
+///     4,
+///     [(0, 1, 1.0), (1, 3, 1.0), (0, 2, 2.0), (2, 3, 1.0), (1, 2, 0.5), (0, 3, 5.0)],
+/// );
+/// let paths = k_shortest_paths(&g, 0, 3, 10).unwrap();
+/// let vertices: Vec<&[usize]> = paths.iter().map(|p| p.vertices.as_slice()).collect();
+/// assert_eq!(vertices, [&[0, 1, 3][..], &[0, 1, 2, 3], &[0, 2, 3], &[0, 3]]);
+/// let weights: Vec<f64> = paths.iter().map(|p| p.weight).collect();
+/// assert_eq!(weights, [2.0, 2.5, 3.0, 5.0]);
+/// ```
+pub fn k_shortest_paths(
+    graph: &EdgeWeightedDigraph,
+    source: usize,
+    target: usize,
+    k: usize,
+) -> Result<Vec<WeightedPath>, NegativeWeight> {
+    let mut found: Vec<WeightedPath> = Vec::with_capacity(k);
+    if k == 0 {
+        return Ok(found);
+    }
+    match dijkstra_to(graph, source, target)? {
+        Some(path) => found.push(path),
+        None => return Ok(found),
+    }
+    let mut candidates: Vec<WeightedPath> = Vec::new();
+    let n = graph.vertex_count();
+    while found.len() < k {
+        let last = found.last().expect("Path found").vertices.clone();
+        for i in 0..last.len() - 1 {
+            let (root, spur) = (&last[..=i], last[i]);
+            let mut avoided = vec![false; n];
+            for &v in &root[..i] {
+                avoided[v] = true;
+            }
+            // Edges leaving the spur along the paths found with this root.
+            let cut: Vec<usize> = found
+                .iter()
+                .filter(|p| p.vertices.len() > i + 1 && p.vertices[..=i] == *root)
+                .map(|p| p.vertices[i + 1])
+                .collect();
+            let spur_graph = EdgeWeightedDigraph::from_edges(
+                n,
+                graph
+                    .edges()
+                    .filter(|e| !avoided[e.from] && !avoided[e.to])
+                    .filter(|e| e.from != spur || !cut.contains(&e.to))
+                    .map(|e| (e.from, e.to, e.weight)),
+            );
+            let Some(spur_path) = dijkstra_to(&spur_graph, spur, target)? else {
+                continue;
+            };
+            let mut vertices = root[..i].to_vec();
+            vertices.extend(spur_path.vertices);
+            if candidates.iter().any(|p| p.vertices == vertices) {
+                continue;
+            }
+            let weight = path_weight(graph, &vertices);
+            candidates.push(WeightedPath { vertices, weight });
+        }
+
+        // Lightest candidate, preferring fewer vertices, then the smallest.
+        let Some(best) = (0..candidates.len()).min_by(|&a, &b| {
+            let (a, b) = (&candidates[a], &candidates[b]);
+            a.weight
+                .total_cmp(&b.weight)
+                .then(a.vertices.len().cmp(&b.vertices.len()))
+                .then(a.vertices.cmp(&b.vertices))
+        }) else {
+            break;
+        };
+        found.push(candidates.swap_remove(best));
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// All loopless paths from the last vertex of `path` to `target`,
+    /// extending `path`, by exhaustive depth-first search.
+    fn all_paths(
+        g: &EdgeWeightedDigraph,
+        path: &mut Vec<usize>,
+        target: usize,
+        paths: &mut Vec<Vec<usize>>,
+    ) {
+        let v = *path.last().unwrap();
+        if v == target {
+            paths.push(path.clone());
+            return;
+        }
+        let mut next: Vec<usize> = g.adj(v).iter().map(|e| e.to).collect();
+        next.sort();
+        next.dedup();
+        for w in next {
+            if !path.contains(&w) {
+                path.push(w);
+                all_paths(g, path, target, paths);
+                path.pop();
+            }
+        }
+    }
+
+    #[test]
+    fn agrees_with_exhaustive_search() {
+        for _ in 0..100 {
+            let n = fastrand::usize(1..8);
+            let g = EdgeWeightedDigraph::from_edges(
+                n,
+                (0..fastrand::usize(..3 * n)).map(|_| {
+                    (
+                        fastrand::usize(..n),
+                        fastrand::usize(..n),
+                        fastrand::u8(..10) as f64,
+                    )
+                }),
+            );
+            let (source, target) = (fastrand::usize(..n), fastrand::usize(..n));
+            let mut paths = Vec::new();
+            all_paths(&g, &mut vec![source], target, &mut paths);
+            let mut expected: Vec<f64> = paths.iter().map(|p| path_weight(&g, p)).collect();
+            expected.sort_by(f64::total_cmp);
+
+            let k = fastrand::usize(..paths.len() + 3);
+            let found = k_shortest_paths(&g, source, target, k).unwrap();
+            assert_eq!(found.len(), k.min(paths.len()));
+            let weights: Vec<f64> = found.iter().map(|p| p.weight).collect();
+            assert_eq!(weights, expected[..found.len()]);
+            for (i, p) in found.iter().enumerate() {
+                assert!(paths.contains(&p.vertices), "{:?} is not a path", p);
+                assert!(found[..i].iter().all(|q| q.vertices != p.vertices));
+            }
+        }
+    }
+
+    #[test]
+    fn parallel_edges_and_no_path() {
+        let g = EdgeWeightedDigraph::from_edges(3, [(0, 1, 2.0), (0, 1, 1.0), (1, 2, 1.0)]);
+        let paths = k_shortest_paths(&g, 0, 2, 5).unwrap();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].weight, 2.0);
+        assert!(k_shortest_paths(&g, 2, 0, 5).unwrap().is_empty());
+        assert_eq!(k_shortest_paths(&g, 1, 1, 5).unwrap().len(), 1);
+        let negative = EdgeWeightedDigraph::from_edges(2, [(0, 1, -1.0)]);
+        assert!(k_shortest_paths(&negative, 0, 1, 2).is_err());
+    }
+}
@@ -0,0 +1,351 @@
+use crate::ds::Queue;
+
+/// Bipartite graph with `left` vertices `0..left` on one side and `right`
+/// vertices `0..right` on the other, with edges only between the sides. Each
+/// side has its own numbering, and edges are stored in the adjacency lists of
+/// their left endpoint.
+///
+/// Example:
+/// ```
+/// use algae::graph::Bigraph;
+///
+/// let mut g = Bigraph::new(2, 3);
+/// g.add_edge(0, 2);
+/// g.add_edge(1, 2);
+/// assert_eq!(g.adj(1), [2]);
+/// assert_eq!((g.left_count(), g.right_count(), g.edge_count()), (2, 3, 2));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bigraph {
+    adj: Vec<Vec<usize>>,
+    right: usize,
+    edges: usize,
+}
+
+impl Bigraph {
+    /// Bipartite graph with the given numbers of vertices on each side and no
+    /// edges.
+    pub fn new(left: usize, right: usize) -> Self {
+        Self {
+            adj: vec![Vec::new(); left],
+            right,
+            edges: 0,
+        }
+    }
+
+    /// Bipartite graph with the given edges `(u, v)` from the left vertex `u`
+    /// to the right vertex `v`.
+    pub fn from_edges<I: IntoIterator<Item = (usize, usize)>>(
+        left: usize,
+        right: usize,
+        edges: I,
+    ) -> Self {
+        let mut g = Self::new(left, right);
+        for (u, v) in edges {
+            g.add_edge(u, v);
+        }
+        g
+    }
+
+    /// Number of left vertices.
+    pub fn left_count(&self) -> usize {
+        self.adj.len()
+    }
+
+    /// Number of right vertices.
+    pub fn right_count(&self) -> usize {
+        self.right
+    }
+
+    /// Number of edges.
+    pub fn edge_count(&self) -> usize {
+        self.edges
+    }
+
+    /// Adds the edge between the left vertex `u` and the right vertex `v`.
+    /// Panics if either is out of range.
+    pub fn add_edge(&mut self, u: usize, v: usize) {
+        assert!(
+            u < self.adj.len() && v < self.right,
+            "Edge {}-{} out of range for a bigraph with {} and {} vertices",
+            u,
+            v,
+            self.adj.len(),
+            self.right
+        );
+        self.adj[u].push(v);
+        self.edges += 1;
+    }
+
+    /// Right vertices adjacent to the left vertex `u`.
+    pub fn adj(&self, u: usize) -> &[usize] {
+        &self.adj[u]
+    }
+}
+
+/// Matching of a bipartite graph: a set of edges without common endpoints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matching {
+    mate_left: Vec<Option<usize>>,
+    mate_right: Vec<Option<usize>>,
+    size: usize,
+}
+
+impl Matching {
+    /// Number of matched edges.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Right vertex matched to the left vertex `u`, if any.
+    pub fn mate_of_left(&self, u: usize) -> Option<usize> {
+        self.mate_left[u]
+    }
+
+    /// Left vertex matched to the right vertex `v`, if any.
+    pub fn mate_of_right(&self, v: usize) -> Option<usize> {
+        self.mate_right[v]
+    }
+
+    /// Matched edges `(u, v)`, in increasing order of the left vertex.
+    pub fn matched_pairs(&self) -> Vec<(usize, usize)> {
+        self.mate_left
+            .iter()
+            .enumerate()
+            .filter_map(|(u, v)| v.map(|v| (u, v)))
+            .collect()
+    }
+
+    /// Minimum vertex cover of `graph`, of which this must be a maximum
+    /// matching, as its left and right vertices. By König's theorem, its size
+    /// equals that of the matching: with `Z` the vertices reachable from the
+    /// unmatched left vertices by alternating paths, the cover is made of the
+    /// left vertices not in `Z` and the right vertices in `Z`.
+    ///
+    /// Example:
+    /// ```
+    /// use algae::graph::{hopcroft_karp, Bigraph};
+    ///
+    /// // Stars centered at the left vertex 0 and at the right vertex 0.
+    /// let g = Bigraph::from_edges(3, 3, [(0, 0), (0, 1), (0, 2), (1, 0), (2, 0)]);
+    /// let matching = hopcroft_karp(&g);
+    /// assert_eq!(matching.size(), 2);
+    /// assert_eq!(matching.min_vertex_cover(&g), (vec![0], vec![0]));
+    /// ```
+    pub fn min_vertex_cover(&self, graph: &Bigraph) -> (Vec<usize>, Vec<usize>) {
+        let mut left_seen = vec![false; graph.left_count()];
+        let mut right_seen = vec![false; graph.right_count()];
+        let mut stack: Vec<usize> = (0..graph.left_count())
+            .filter(|&u| self.mate_left[u].is_none())
+            .collect();
+        for &u in &stack {
+            left_seen[u] = true;
+        }
+        // Alternating paths leave left vertices by any edge, and right
+        // vertices by their matched edge.
+        while let Some(u) = stack.pop() {
+            for &v in graph.adj(u) {
+                if !right_seen[v] {
+                    right_seen[v] = true;
+                    if let Some(w) = self.mate_right[v] {
+                        if !left_seen[w] {
+                            left_seen[w] = true;
+                            stack.push(w);
+                        }
+                    }
+                }
+            }
+        }
+        let left = (0..graph.left_count()).filter(|&u| !left_seen[u]).collect();
+        let right = (0..graph.right_count())
+            .filter(|&v| right_seen[v])
+            .collect();
+        (left, right)
+    }
+}
+
+/// Hopcroft-Karp algorithm: maximum matching of a bipartite graph. Each phase
+/// finds, by breadth-first search from the unmatched left vertices, the
+/// length of the shortest augmenting paths, and then augments along a maximal
+/// set of disjoint shortest paths by depth-first search. There are O(sqrt(V))
+/// phases, for a total time of O(E * sqrt(V)).
+///
+/// Example:
+/// ```
+/// use algae::graph::{hopcroft_karp, Bigraph};
+///
+/// // Workers and the tasks they can do.
+/// let g = Bigraph::from_edges(3, 3, [(0, 0), (0, 1), (1, 0), (2, 1), (2, 2)]);
+/// let matching = hopcroft_karp(&g);
+/// assert_eq!(matching.size(), 3);
+/// assert_eq!(matching.matched_pairs(), [(0, 1), (1, 0), (2, 2)]);
+/// ```
+pub fn hopcroft_karp(graph: &Bigraph) -> Matching {
+    let (left, right) = (graph.left_count(), graph.right_count());
+    let mut matching = Matching {
+        mate_left: vec![None; left],
+        mate_right: vec![None; right],
+        size: 0,
+    };
+    let mut dist = vec![usize::MAX; left];
+    let mut next = vec![0; left];
+    loop {
+        // Layers of the left vertices by alternating distance from the
+        // unmatched ones, up to the first layer reaching an unmatched right
+        // vertex.
+        dist.fill(usize::MAX);
+        let mut queue = Queue::new();
+        for u in (0..left).filter(|&u| matching.mate_left[u].is_none()) {
+            dist[u] = 0;
+            queue.push(u);
+        }
+        let mut shortest = usize::MAX;
+        while let Some(u) = queue.pop() {
+            if dist[u] >= shortest {
+                continue;
+            }
+            for &v in graph.adj(u) {
+                match matching.mate_right[v] {
+                    None => shortest = shortest.min(dist[u] + 1),
+                    Some(w) if dist[w] == usize::MAX => {
+                        dist[w] = dist[u] + 1;
+                        queue.push(w);
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        if shortest == usize::MAX {
+            return matching;
+        }
+
+        next.fill(0);
+        for u in 0..left {
+            if matching.mate_left[u].is_none()
+                && augment(graph, &mut matching, &dist, shortest, &mut next, u)
+            {
+                matching.size += 1;
+            }
+        }
+    }
+}
+
+/// Looks for a shortest augmenting path from the unmatched left vertex
+/// `root`, going one layer deeper at each step and ending at an unmatched
+/// right vertex in layer `shortest`, and flips it if found. The left vertices
+/// of the path are kept on an explicit stack, so that long paths need no
+/// recursion. Edges which led nowhere are skipped for the rest of the phase.
+fn augment(
+    graph: &Bigraph,
+    matching: &mut Matching,
+    dist: &[usize],
+    shortest: usize,
+    next: &mut [usize],
+    root: usize,
+) -> bool {
+    let mut path = vec![root];
+    while let Some(&u) = path.last() {
+        let Some(&v) = graph.adj(u).get(next[u]) else {
+            path.pop();
+            continue;
+        };
+        next[u] += 1;
+        match matching.mate_right[v] {
+            None if dist[u] + 1 == shortest => {
+                // Each left vertex of the path takes the right vertex it
+                // tried last.
+                for &u in &path {
+                    let v = graph.adj(u)[next[u] - 1];
+                    matching.mate_left[u] = Some(v);
+                    matching.mate_right[v] = Some(u);
+                }
+                return true;
+            }
+            Some(w) if dist[w] == dist[u] + 1 && dist[w] < shortest => path.push(w),
+            _ => {}
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::{dinic, FlowNetwork};
+
+    fn random_bigraph() -> Bigraph {
+        let (left, right) = (fastrand::usize(1..15), fastrand::usize(1..15));
+        Bigraph::from_edges(
+            left,
+            right,
+            (0..fastrand::usize(..3 * left))
+                .map(|_| (fastrand::usize(..left), fastrand::usize(..right))),
+        )
+    }
+
+    /// Size of a maximum matching, as a maximum flow with unit capacities.
+    fn max_flow_matching(g: &Bigraph) -> usize {
+        let (left, right) = (g.left_count(), g.right_count());
+        let (s, t) = (left + right, left + right + 1);
+        let mut network = FlowNetwork::new(left + right + 2);
+        for u in 0..left {
+            network.add_edge(s, u, 1);
+            for &v in g.adj(u) {
+                network.add_edge(u, left + v, 1);
+            }
+        }
+        for v in 0..right {
+            network.add_edge(left + v, t, 1);
+        }
+        dinic(&network, s, t).value() as usize
+    }
+
+    #[test]
+    fn maximum_matchings() {
+        for _ in 0..200 {
+            let g = random_bigraph();
+            let matching = hopcroft_karp(&g);
+            let pairs = matching.matched_pairs();
+            assert_eq!(pairs.len(), matching.size());
+            assert_eq!(matching.size(), max_flow_matching(&g));
+            for &(u, v) in &pairs {
+                assert!(g.adj(u).contains(&v));
+                assert_eq!(matching.mate_of_right(v), Some(u));
+            }
+
+            let (left, right) = matching.min_vertex_cover(&g);
+            assert_eq!(left.len() + right.len(), matching.size());
+            for u in 0..g.left_count() {
+                for &v in g.adj(u) {
+                    assert!(left.contains(&u) || right.contains(&v));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn perfect_matching() {
+        // Each left vertex `u` is adjacent to `u` and `u + 1`, so the greedy
+        // choice of the first edge must be undone along a long path.
+        let n = 1_000;
+        let g = Bigraph::from_edges(n, n, (0..n).flat_map(|u| [(u, (u + 1) % n), (u, u)]));
+        assert_eq!(hopcroft_karp(&g).size(), n);
+        assert_eq!(hopcroft_karp(&Bigraph::new(3, 0)).size(), 0);
+    }
+
+    #[test]
+    fn long_augmenting_path() {
+        // The first phase matches each `u` to `u + 1`, leaving the last left
+        // vertex and the right vertex 0 free, joined by an augmenting path
+        // through every vertex.
+        let n = 200_000;
+        let g = Bigraph::from_edges(
+            n,
+            n,
+            (0..n).flat_map(|u| [(u, u + 1), (u, u)].into_iter().filter(|&(_, v)| v < n)),
+        );
+        let matching = hopcroft_karp(&g);
+        assert_eq!(matching.size(), n);
+        assert!((0..n).all(|u| matching.mate_of_left(u) == Some(u)));
+    }
+}
@@ -0,0 +1,417 @@
+//! Descriptive statistics of graphs: the distances between their vertices,
+//! found by breadth-first search, and the distribution of their degrees and
+//! triangles.
+use crate::graph::{bfs, Adjacency, Graph};
+
+/// Eccentricity of each vertex: the greatest distance, in number of edges,
+/// from the vertex to any other, or `None` if some vertex is unreachable from
+/// it. Runs a breadth-first search from every vertex, which takes
+/// O(V * (V + E)) time. In a digraph, distances follow the edges.
+///
+/// Example:
+/// ```
+/// use algae::graph::{metrics, Graph};
+///
+/// // A path 0-1-2-3 with a pendant vertex 4 on 1.
+/// let g = Graph::from_edges(5, [(0, 1), (1, 2), (2, 3), (1, 4)]);
+/// let ecc = metrics::eccentricities(&g);
+/// assert_eq!(ecc, [Some(3), Some(2), Some(2), Some(3), Some(3)]);
+///
+/// let disconnected = Graph::from_edges(3, [(0, 1)]);
+/// assert_eq!(metrics::eccentricities(&disconnected), [None, None, None]);
+/// ```
+pub fn eccentricities<G: Adjacency>(graph: &G) -> Vec<Option<usize>> {
+    (0..graph.vertex_count())
+        .map(|v| {
+            let result = bfs(graph, v);
+            (0..graph.vertex_count())
+                .map(|w| result.dist_to(w))
+                .try_fold(0, |ecc, d| Some(ecc.max(d?)))
+        })
+        .collect()
+}
+
+/// Diameter of the graph: the greatest eccentricity, or `None` if the graph
+/// has no vertices or some vertex is unreachable from another. Takes
+/// O(V * (V + E)) time, as `eccentricities`.
+///
+/// Example:
+/// ```
+/// use algae::graph::{metrics, Graph};
+///
+/// let cycle = Graph::from_edges(6, (0..6).map(|v| (v, (v + 1) % 6)));
+/// assert_eq!(metrics::diameter(&cycle), Some(3));
+/// assert_eq!(metrics::diameter(&Graph::new(2)), None);
+/// ```
+pub fn diameter<G: Adjacency>(graph: &G) -> Option<usize> {
+    let ecc: Option<Vec<usize>> = eccentricities(graph).into_iter().collect();
+    ecc?.into_iter().max()
+}
+
+/// Radius of the graph: the least eccentricity, or `None` if the graph has no
+/// vertices or some vertex is unreachable from another. Takes
+/// O(V * (V + E)) time, as `eccentricities`.
+///
+/// Example:
+/// ```
+/// use algae::graph::{metrics, Graph};
+///
+/// let star = Graph::from_edges(5, (1..5).map(|v| (0, v)));
+/// assert_eq!(metrics::radius(&star), Some(1));
+/// assert_eq!(metrics::diameter(&star), Some(2));
+/// ```
+pub fn radius<G: Adjacency>(graph: &G) -> Option<usize> {
+    let ecc: Option<Vec<usize>> = eccentricities(graph).into_iter().collect();
+    ecc?.into_iter().min()
+}
+
+/// Double sweep from `start`: a breadth-first search from `start` finds a
+/// farthest vertex `a`, and a second one from `a` finds a farthest vertex
+/// `b`. Returns `(a, b, d)`, where `d` is the distance between `a` and `b`.
+/// This is a lower bound of the diameter of the component of `start`, found
+/// in O(V + E) time, which is exact on trees and often on sparse graphs.
+///
+/// Example:
+/// ```
+/// use algae::graph::{metrics, Graph};
+///
+/// let path = Graph::from_edges(5, [(2, 1), (1, 0), (2, 3), (3, 4)]);
+/// assert_eq!(metrics::double_sweep(&path, 2), (0, 4, 4));
+/// ```
+pub fn double_sweep(graph: &Graph, start: usize) -> (usize, usize, usize) {
+    let (a, _) = farthest(graph, start);
+    let (b, d) = farthest(graph, a);
+    (a, b, d)
+}
+
+/// Vertex reachable from `source` at the greatest distance, with that
+/// distance, preferring the smallest such vertex.
+fn farthest(graph: &Graph, source: usize) -> (usize, usize) {
+    let result = bfs(graph, source);
+    graph
+        .vertices()
+        .filter_map(|v| Some((v, result.dist_to(v)?)))
+        .fold(
+            (source, 0),
+            |best, (v, d)| if d > best.1 { (v, d) } else { best },
+        )
+}
+
+/// Approximate eccentricities of the vertices of the component of `start`,
+/// from the two ends `a` and `b` of the double sweep from `start`: the
+/// eccentricity of `v` is estimated by the greater of its distances to `a`
+/// and to `b`. This takes O(V + E) time, gives a lower bound of each
+/// eccentricity, and is exact on trees, where `a` and `b` are the ends of a
+/// longest path. The vertices of other components are `None`.
+///
+/// Example:
+/// ```
+/// use algae::graph::{metrics, Graph};
+///
+/// // A tree and an isolated vertex 5.
+/// let g = Graph::from_edges(6, [(0, 1), (1, 2), (2, 3), (1, 4)]);
+/// let approx = metrics::approximate_eccentricities(&g, 4);
+/// assert_eq!(approx, [Some(3), Some(2), Some(2), Some(3), Some(3), None]);
+/// ```
+pub fn approximate_eccentricities(graph: &Graph, start: usize) -> Vec<Option<usize>> {
+    let (a, b, _) = double_sweep(graph, start);
+    let (from_a, from_b) = (bfs(graph, a), bfs(graph, b));
+    graph
+        .vertices()
+        .map(|v| Some(from_a.dist_to(v)?.max(from_b.dist_to(v)?)))
+        .collect()
+}
+
+/// Degree distribution: the number of vertices of each degree, indexed by
+/// degree, up to the maximum degree. Degrees are those of `Graph::degree`,
+/// where a self-loop counts once.
+///
+/// Example:
+/// ```
+/// use algae::graph::{metrics, Graph};
+///
+/// let star = Graph::from_edges(5, (1..5).map(|v| (0, v)));
+/// assert_eq!(metrics::degree_distribution(&star), [0, 4, 0, 0, 1]);
+/// assert!(metrics::degree_distribution(&Graph::new(0)).is_empty());
+/// ```
+pub fn degree_distribution(graph: &Graph) -> Vec<usize> {
+    let max = graph.vertices().map(|v| graph.degree(v)).max();
+    let mut counts = vec![0; max.map_or(0, |d| d + 1)];
+    for v in graph.vertices() {
+        counts[graph.degree(v)] += 1;
+    }
+    counts
+}
+
+/// Neighbours of each vertex, sorted and without repetitions, leaving out
+/// the vertex itself.
+fn simple_neighbours(graph: &Graph) -> Vec<Vec<usize>> {
+    graph
+        .vertices()
+        .map(|v| {
+            let mut adj: Vec<usize> = graph.adj(v).iter().copied().filter(|&w| w != v).collect();
+            adj.sort_unstable();
+            adj.dedup();
+            adj
+        })
+        .collect()
+}
+
+/// Number of edges between the neighbours of each vertex, and the number of
+/// pairs of its neighbours.
+fn neighbour_links(graph: &Graph) -> Vec<(usize, usize)> {
+    let adj = simple_neighbours(graph);
+    let mut marked = vec![false; graph.vertex_count()];
+    graph
+        .vertices()
+        .map(|v| {
+            for &u in &adj[v] {
+                marked[u] = true;
+            }
+            // Each edge between two neighbours is seen from both of them.
+            let seen: usize = adj[v]
+                .iter()
+                .map(|&u| adj[u].iter().filter(|&&w| marked[w]).count())
+                .sum();
+            for &u in &adj[v] {
+                marked[u] = false;
+            }
+            let k = adj[v].len();
+            (seen / 2, k * k.saturating_sub(1) / 2)
+        })
+        .collect()
+}
+
+/// Local clustering coefficient of each vertex: the fraction of the pairs of
+/// its neighbours which are adjacent, that is, how close its neighbourhood is
+/// to a clique. It is 0 for vertices with fewer than two neighbours.
+/// Parallel edges and self-loops are ignored. Takes O(V + sum of d(v)^2)
+/// time, for the degrees d(v).
+///
+/// Example:
+/// ```
+/// use algae::graph::{metrics, Graph};
+///
+/// // A triangle 0-1-2 with a pendant vertex 3 on 0.
+/// let g = Graph::from_edges(4, [(0, 1), (1, 2), (2, 0), (0, 3)]);
+/// let c = metrics::clustering_coefficients(&g);
+/// assert_eq!(c, [1.0 / 3.0, 1.0, 1.0, 0.0]);
+/// assert!((metrics::average_clustering(&g) - 7.0 / 12.0).abs() < 1e-12);
+/// ```
+pub fn clustering_coefficients(graph: &Graph) -> Vec<f64> {
+    neighbour_links(graph)
+        .into_iter()
+        .map(|(links, pairs)| {
+            if pairs == 0 {
+                0.0
+            } else {
+                links as f64 / pairs as f64
+            }
+        })
+        .collect()
+}
+
+/// Average of the local clustering coefficients, or 0 for a graph without
+/// vertices.
+pub fn average_clustering(graph: &Graph) -> f64 {
+    let c = clustering_coefficients(graph);
+    if c.is_empty() {
+        0.0
+    } else {
+        c.iter().sum::<f64>() / c.len() as f64
+    }
+}
+
+/// Global clustering coefficient, or transitivity: the fraction of the paths
+/// of two edges `u-v-w` which are closed by an edge `u-w`, that is, three
+/// times the number of triangles over the number of such paths. It is 0 for
+/// a graph without such paths. Parallel edges and self-loops are ignored.
+///
+/// Example:
+/// ```
+/// use algae::graph::{metrics, Graph};
+///
+/// // A triangle 0-1-2 with a pendant vertex 3 on 0: one triangle and five
+/// // paths of two edges.
+/// let g = Graph::from_edges(4, [(0, 1), (1, 2), (2, 0), (0, 3)]);
+/// assert_eq!(metrics::transitivity(&g), 3.0 / 5.0);
+/// ```
+pub fn transitivity(graph: &Graph) -> f64 {
+    let (closed, pairs) = neighbour_links(graph)
+        .into_iter()
+        .fold((0, 0), |(c, p), (links, pairs)| (c + links, p + pairs));
+    if pairs == 0 {
+        0.0
+    } else {
+        closed as f64 / pairs as f64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::Digraph;
+
+    fn random_graph(n: usize, m: usize) -> Graph {
+        Graph::from_edges(
+            n,
+            (0..m).map(|_| (fastrand::usize(..n), fastrand::usize(..n))),
+        )
+    }
+
+    /// Random tree, each vertex joining one of the vertices before it.
+    fn random_tree(n: usize) -> Graph {
+        Graph::from_edges(n, (1..n).map(|v| (fastrand::usize(..v), v)))
+    }
+
+    /// Distances between all pairs of vertices by Floyd-Warshall.
+    fn all_distances<G: Adjacency>(g: &G) -> Vec<Vec<Option<usize>>> {
+        let n = g.vertex_count();
+        let mut dist = vec![vec![None; n]; n];
+        for (v, row) in dist.iter_mut().enumerate() {
+            for &w in g.adj(v) {
+                row[w] = Some(1);
+            }
+            row[v] = Some(0);
+        }
+        for k in 0..n {
+            for i in 0..n {
+                for j in 0..n {
+                    if let (Some(a), Some(b)) = (dist[i][k], dist[k][j]) {
+                        if dist[i][j].is_none_or(|d| a + b < d) {
+                            dist[i][j] = Some(a + b);
+                        }
+                    }
+                }
+            }
+        }
+        dist
+    }
+
+    /// Checks the eccentricities, diameter and radius of `g` against the
+    /// distances between all pairs of vertices.
+    fn assert_distances<G: Adjacency>(g: &G) {
+        let expected: Vec<Option<usize>> = all_distances(g)
+            .iter()
+            .map(|row| row.iter().try_fold(0, |e, &d| Some(e.max(d?))))
+            .collect();
+        assert_eq!(eccentricities(g), expected);
+        let connected: Option<Vec<usize>> = expected.into_iter().collect();
+        let connected = connected.filter(|ecc| !ecc.is_empty());
+        assert_eq!(
+            diameter(g),
+            connected.as_ref().map(|e| *e.iter().max().unwrap())
+        );
+        assert_eq!(
+            radius(g),
+            connected.as_ref().map(|e| *e.iter().min().unwrap())
+        );
+    }
+
+    #[test]
+    fn exact_distances() {
+        for _ in 0..100 {
+            let n = fastrand::usize(1..12);
+            let m = fastrand::usize(..3 * n);
+            assert_distances(&random_graph(n, m));
+            assert_distances(&random_tree(n));
+            assert_distances(&Digraph::from_edges(
+                n,
+                (0..m).map(|_| (fastrand::usize(..n), fastrand::usize(..n))),
+            ));
+        }
+        assert_eq!(diameter(&Graph::new(0)), None);
+        assert_eq!(radius(&Graph::new(1)), Some(0));
+    }
+
+    #[test]
+    fn double_sweeps() {
+        for _ in 0..100 {
+            let n = fastrand::usize(1..30);
+            let start = fastrand::usize(..n);
+            let tree = random_tree(n);
+            let (a, b, d) = double_sweep(&tree, start);
+            assert_eq!(bfs(&tree, a).dist_to(b), Some(d));
+            assert_eq!(Some(d), diameter(&tree));
+            assert_eq!(
+                approximate_eccentricities(&tree, start),
+                eccentricities(&tree)
+            );
+
+            // Elsewhere, lower bounds of the distances within the component
+            // of `start`.
+            let g = random_graph(n, fastrand::usize(..2 * n));
+            let (a, b, d) = double_sweep(&g, start);
+            assert_eq!(bfs(&g, a).dist_to(b), Some(d));
+            let dist = all_distances(&g);
+            let component: Vec<usize> =
+                g.vertices().filter(|&v| dist[start][v].is_some()).collect();
+            let within = |v: usize| component.iter().filter_map(|&w| dist[v][w]).max();
+            assert_eq!(Some(d), within(a));
+            assert!(d <= component.iter().filter_map(|&v| within(v)).max().unwrap());
+            for (v, approx) in approximate_eccentricities(&g, start)
+                .into_iter()
+                .enumerate()
+            {
+                match approx {
+                    Some(approx) => assert!(approx <= within(v).unwrap()),
+                    None => assert!(!component.contains(&v)),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn degrees() {
+        for _ in 0..50 {
+            let n = fastrand::usize(..20);
+            let g = random_graph(n.max(1), fastrand::usize(..3 * n + 1));
+            let distribution = degree_distribution(&g);
+            assert_eq!(distribution.iter().sum::<usize>(), g.vertex_count());
+            for (d, &count) in distribution.iter().enumerate() {
+                assert_eq!(count, g.vertices().filter(|&v| g.degree(v) == d).count());
+            }
+            assert_ne!(distribution.last(), Some(&0));
+        }
+    }
+
+    #[test]
+    fn clustering_by_triples() {
+        for _ in 0..100 {
+            let n = fastrand::usize(1..12);
+            let g = random_graph(n, fastrand::usize(..4 * n));
+            let adjacent = |v: usize, w: usize| v != w && g.has_edge(v, w);
+            let (mut closed, mut paths) = (0, 0);
+            let local = clustering_coefficients(&g);
+            for v in g.vertices() {
+                let (mut links, mut pairs) = (0, 0);
+                for u in 0..n {
+                    for w in u + 1..n {
+                        if adjacent(v, u) && adjacent(v, w) {
+                            pairs += 1;
+                            links += usize::from(adjacent(u, w));
+                        }
+                    }
+                }
+                let expected = if pairs == 0 {
+                    0.0
+                } else {
+                    links as f64 / pairs as f64
+                };
+                assert!((local[v] - expected).abs() < 1e-12);
+                closed += links;
+                paths += pairs;
+            }
+            let expected = if paths == 0 {
+                0.0
+            } else {
+                closed as f64 / paths as f64
+            };
+            assert!((transitivity(&g) - expected).abs() < 1e-12);
+            let average = local.iter().sum::<f64>() / n as f64;
+            assert!((average_clustering(&g) - average).abs() < 1e-12);
+        }
+        assert_eq!(average_clustering(&Graph::new(0)), 0.0);
+        assert_eq!(transitivity(&Graph::new(3)), 0.0);
+    }
+}
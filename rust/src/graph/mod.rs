@@ -0,0 +1,169 @@
+//! Graphs and graph algorithms. Vertices are the integers `0..n` of a graph
+//! with `n` vertices, so that per-vertex data is kept in plain vectors
+//! indexed by vertex, and edges are stored in adjacency lists.
+
+mod undirected;
+pub use undirected::*;
+
+mod directed;
+pub use directed::*;
+
+mod weighted;
+pub use weighted::*;
+
+mod adj_matrix;
+pub use adj_matrix::*;
+
+mod bfs;
+pub use bfs::*;
+
+mod dfs;
+pub use dfs::*;
+
+mod events;
+pub use events::*;
+
+mod components;
+pub use components::*;
+
+mod topological;
+pub use topological::*;
+
+mod cycle;
+
+mod scc;
+pub use scc::*;
+
+mod shortest;
+pub use shortest::*;
+
+mod k_shortest;
+pub use k_shortest::*;
+
+mod arborescence;
+pub use arborescence::*;
+
+mod flow;
+pub use flow::*;
+
+mod connectivity;
+pub use connectivity::*;
+
+mod matching;
+pub use matching::*;
+
+mod bipartite;
+pub use bipartite::*;
+
+mod biconnected;
+pub use biconnected::*;
+
+mod hamiltonian;
+pub use hamiltonian::*;
+
+mod coloring;
+pub use coloring::*;
+
+mod planar;
+pub use planar::*;
+
+pub mod ch;
+pub mod community;
+pub mod io;
+pub mod metrics;
+pub mod tree;
+
+/// Graphs given by adjacency lists over the vertices `0..n`, which the
+/// traversals work on regardless of whether edges are directed. An
+/// undirected edge `v-w` is seen as the two directed edges `v->w` and `w->v`.
+pub trait Adjacency {
+    /// Number of vertices.
+    fn vertex_count(&self) -> usize;
+
+    /// Vertices reached by the edges leaving `v`.
+    fn adj(&self, v: usize) -> &[usize];
+
+    /// Whether edges are directed. Depth-first searches of undirected graphs
+    /// do not examine the edge through which a vertex was discovered again
+    /// on the way back to its parent.
+    fn is_directed(&self) -> bool;
+
+    /// Lazy breadth-first search from `source`.
+    ///
+    /// Example:
+    /// ```
+    /// use algae::graph::{Adjacency, EdgeKind, Event, Graph};
+    ///
+    /// let g = Graph::from_edges(3, [(0, 1), (0, 2), (1, 2)]);
+    /// let non_tree = g
+    ///     .bfs_iter(0)
+    ///     .filter(|e| matches!(e, Event::Edge(_, _, EdgeKind::NonTree)))
+    ///     .count();
+    /// // Both directions of the edges seen from the other side, and 1-2.
+    /// assert_eq!(non_tree, 4);
+    /// ```
+    fn bfs_iter(&self, source: usize) -> BfsIter<'_, Self>
+    where
+        Self: Sized,
+    {
+        BfsIter::new(self, [source])
+    }
+
+    /// Lazy depth-first search from `source`, using an explicit stack.
+    ///
+    /// Example:
+    /// ```
+    /// use algae::graph::{Adjacency, Digraph, EdgeKind, Event};
+    ///
+    /// // Finds a back edge, closing a cycle, without searching any further.
+    /// let g = Digraph::from_edges(4, [(0, 1), (1, 2), (2, 0), (2, 3)]);
+    /// let back = g.dfs_iter(0).find_map(|e| match e {
+    ///     Event::Edge(v, w, EdgeKind::Back) => Some((v, w)),
+    ///     _ => None,
+    /// });
+    /// assert_eq!(back, Some((2, 0)));
+    /// ```
+    fn dfs_iter(&self, source: usize) -> DfsIter<'_, Self>
+    where
+        Self: Sized,
+    {
+        DfsIter::new(self, source)
+    }
+
+    /// Lazy depth-first search of the whole graph, starting a new tree from
+    /// each vertex not yet visited, in increasing order.
+    fn dfs_forest_iter(&self) -> DfsIter<'_, Self>
+    where
+        Self: Sized,
+    {
+        DfsIter::forest(self)
+    }
+}
+
+impl Adjacency for Graph {
+    fn vertex_count(&self) -> usize {
+        Graph::vertex_count(self)
+    }
+
+    fn adj(&self, v: usize) -> &[usize] {
+        Graph::adj(self, v)
+    }
+
+    fn is_directed(&self) -> bool {
+        false
+    }
+}
+
+impl Adjacency for Digraph {
+    fn vertex_count(&self) -> usize {
+        Digraph::vertex_count(self)
+    }
+
+    fn adj(&self, v: usize) -> &[usize] {
+        Digraph::adj(self, v)
+    }
+
+    fn is_directed(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,652 @@
+use crate::graph::{biconnected_components, Graph};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+/// Planar embedding of a graph, given by its rotation system: the cyclic
+/// order of the neighbours around each vertex in a drawing of the graph in
+/// the plane without crossings. Self-loops and parallel edges, which never
+/// affect planarity, are left out, so that each neighbour appears once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Embedding {
+    rotation: Vec<Vec<usize>>,
+}
+
+impl Embedding {
+    /// Neighbours of `v` in cyclic order around it.
+    pub fn rotation(&self, v: usize) -> &[usize] {
+        &self.rotation[v]
+    }
+
+    /// Faces of the embedding, each given by the vertices along its
+    /// boundary. A face is walked by leaving each vertex `w`, reached from
+    /// `v`, towards the neighbour following `v` in the rotation of `w`, so
+    /// that every edge is walked once in each direction over all faces. A
+    /// vertex whose removal disconnects the graph appears several times on
+    /// the boundary of a face around it.
+    ///
+    /// Example:
+    /// ```
+    /// use algae::graph::{is_planar, Graph};
+    ///
+    /// let triangle = Graph::from_edges(3, [(0, 1), (1, 2), (2, 0)]);
+    /// let faces = is_planar(&triangle).unwrap().faces();
+    /// assert_eq!(faces.len(), 2);
+    /// assert!(faces.iter().all(|face| face.len() == 3));
+    /// ```
+    pub fn faces(&self) -> Vec<Vec<usize>> {
+        // Position of each neighbour in the rotation of each vertex.
+        let mut position = HashMap::new();
+        for (v, around) in self.rotation.iter().enumerate() {
+            for (i, &w) in around.iter().enumerate() {
+                position.insert((v, w), i);
+            }
+        }
+        let mut walked = HashSet::new();
+        let mut faces = Vec::new();
+        for (v, around) in self.rotation.iter().enumerate() {
+            for &w in around {
+                let mut face = Vec::new();
+                let (mut a, mut b) = (v, w);
+                while walked.insert((a, b)) {
+                    face.push(a);
+                    let around_b = &self.rotation[b];
+                    let next = around_b[(position[&(b, a)] + 1) % around_b.len()];
+                    (a, b) = (b, next);
+                }
+                if !face.is_empty() {
+                    faces.push(face);
+                }
+            }
+        }
+        faces
+    }
+}
+
+/// Subgraph which proves that a graph is not planar, by Kuratowski's
+/// theorem: a subdivision of the complete graph `K5` or of the complete
+/// bipartite graph `K3,3`, whose edges became paths through new vertices of
+/// degree 2. The edges `(v, w)` have `v < w`, in increasing order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KuratowskiSubgraph {
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl KuratowskiSubgraph {
+    /// Vertices of the subdivided graph, in increasing order: the 5 vertices
+    /// of degree 4 of a `K5`, or the 6 vertices of degree 3 of a `K3,3`.
+    pub fn branch_vertices(&self) -> Vec<usize> {
+        let mut degree: HashMap<usize, usize> = HashMap::new();
+        for &(v, w) in &self.edges {
+            *degree.entry(v).or_default() += 1;
+            *degree.entry(w).or_default() += 1;
+        }
+        let mut branch: Vec<usize> = degree
+            .into_iter()
+            .filter(|&(_, d)| d > 2)
+            .map(|(v, _)| v)
+            .collect();
+        branch.sort_unstable();
+        branch
+    }
+}
+
+impl fmt::Display for KuratowskiSubgraph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.branch_vertices().len() {
+            5 => "K5",
+            _ => "K3,3",
+        };
+        write!(f, "subdivision of {}", kind)?;
+        for (v, w) in &self.edges {
+            write!(f, " {}-{}", v, w)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for KuratowskiSubgraph {}
+
+/// Checks whether `graph` is planar, returning a planar embedding if it is,
+/// and a Kuratowski subgraph otherwise.
+///
+/// The graph is planar exactly when each of its biconnected components is,
+/// and their embeddings join at the articulation points. Each component is
+/// embedded by the path addition algorithm of Demoucron, Malgrange and
+/// Pertuiset: starting from a cycle, drawn with an inner and an outer face,
+/// it repeatedly draws a path of the rest of the graph inside a face holding
+/// all the vertices the path's fragment attaches to, choosing first the
+/// fragments with a single such face. A fragment without any proves the
+/// graph nonplanar. This is simpler than the linear-time left-right or
+/// Boyer-Myrvold algorithms, at O(V^3) time, since a component with more
+/// than `3V - 6` edges is rejected at once.
+///
+/// The Kuratowski subgraph is found by removing, one at a time, each edge of
+/// a nonplanar component whose removal leaves it nonplanar, which repeats
+/// the test once per edge of the component.
+///
+/// Example:
+/// ```
+/// use algae::graph::{is_planar, Graph};
+///
+/// let cube = Graph::from_edges(
+///     8,
+///     [(0, 1), (1, 2), (2, 3), (3, 0), (4, 5), (5, 6), (6, 7), (7, 4), (0, 4), (1, 5), (2, 6), (3, 7)],
+/// );
+/// let embedding = is_planar(&cube).unwrap();
+/// assert_eq!(embedding.faces().len(), 6);
+///
+/// let k33 = Graph::from_edges(6, (0..3).flat_map(|v| (3..6).map(move |w| (v, w))));
+/// let witness = is_planar(&k33).unwrap_err();
+/// assert_eq!(witness.to_string(), "subdivision of K3,3 0-3 0-4 0-5 1-3 1-4 1-5 2-3 2-4 2-5");
+/// ```
+pub fn is_planar(graph: &Graph) -> Result<Embedding, KuratowskiSubgraph> {
+    let n = graph.vertex_count();
+    let mut edges: Vec<(usize, usize)> = graph
+        .edges()
+        .filter(|&(v, w)| v != w)
+        .map(|(v, w)| (v.min(w), v.max(w)))
+        .collect();
+    edges.sort_unstable();
+    edges.dedup();
+    match rotation_system(n, &edges) {
+        Some(rotation) => Ok(Embedding { rotation }),
+        None => Err(KuratowskiSubgraph {
+            edges: kuratowski_edges(n, &edges),
+        }),
+    }
+}
+
+/// Rotation system of a planar embedding of the simple graph on `n` vertices
+/// with the given edges, or `None` if it is not planar.
+fn rotation_system(n: usize, edges: &[(usize, usize)]) -> Option<Vec<Vec<usize>>> {
+    let graph = Graph::from_edges(n, edges.iter().copied());
+    let mut rotation = vec![Vec::new(); n];
+    for component in biconnected_components(&graph) {
+        embed_component(&component, &mut rotation)?;
+    }
+    Some(rotation)
+}
+
+/// Edges of a minimal nonplanar subgraph of the nonplanar simple graph on `n`
+/// vertices with the given edges, which is a Kuratowski subgraph.
+fn kuratowski_edges(n: usize, edges: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let graph = Graph::from_edges(n, edges.iter().copied());
+    let mut witness = biconnected_components(&graph)
+        .into_iter()
+        .find(|component| rotation_system(n, component).is_none())
+        .expect("a nonplanar graph has a nonplanar biconnected component");
+    let mut i = 0;
+    while i < witness.len() {
+        let edge = witness.remove(i);
+        if rotation_system(n, &witness).is_some() {
+            witness.insert(i, edge);
+            i += 1;
+        }
+    }
+    witness
+}
+
+/// Appends to `rotation` the cyclic order of the neighbours of each vertex
+/// in a planar embedding of the biconnected component with the given edges,
+/// or returns `None` if it is not planar.
+fn embed_component(edges: &[(usize, usize)], rotation: &mut [Vec<usize>]) -> Option<()> {
+    if let [(v, w)] = *edges {
+        rotation[v].push(w);
+        rotation[w].push(v);
+        return Some(());
+    }
+    let mut vertices: Vec<usize> = edges.iter().flat_map(|&(v, w)| [v, w]).collect();
+    vertices.sort_unstable();
+    vertices.dedup();
+    let local = |v: usize| vertices.binary_search(&v).unwrap();
+    let mut adj = vec![Vec::new(); vertices.len()];
+    for &(v, w) in edges {
+        adj[local(v)].push(local(w));
+        adj[local(w)].push(local(v));
+    }
+
+    // Neighbour following each neighbour `u` around each vertex `v`, which
+    // comes after `u, v` along a face.
+    let mut next = HashMap::new();
+    for face in embed_biconnected(&adj)? {
+        for (i, &v) in face.iter().enumerate() {
+            let u = face[(i + face.len() - 1) % face.len()];
+            next.insert((v, u), face[(i + 1) % face.len()]);
+        }
+    }
+    for (v, around) in adj.iter().enumerate() {
+        let mut u = around[0];
+        loop {
+            rotation[vertices[v]].push(vertices[u]);
+            u = next[&(v, u)];
+            if u == around[0] {
+                break;
+            }
+        }
+    }
+    Some(())
+}
+
+/// Part of the graph not drawn yet, attached to the drawn vertices: either an
+/// edge between two drawn vertices, or a connected component of the vertices
+/// not drawn together with its edges to the drawn ones.
+struct Fragment {
+    /// Drawn vertices of the fragment.
+    attachments: Vec<usize>,
+    /// Component of the vertices not drawn, if not a single edge.
+    component: Option<usize>,
+}
+
+/// Faces of a planar embedding of the biconnected simple graph `adj`, with
+/// at least three vertices, by path addition, or `None` if it is not planar.
+/// Each face lists the vertices along its boundary, which is a cycle, such
+/// that each edge is walked once in each direction over all faces.
+fn embed_biconnected(adj: &[Vec<usize>]) -> Option<Vec<Vec<usize>>> {
+    let n = adj.len();
+    let m = adj.iter().map(Vec::len).sum::<usize>() / 2;
+    if m > 3 * n - 6 {
+        return None;
+    }
+
+    // A first cycle through the vertex 0, closed by a path between two of its
+    // neighbours avoiding it.
+    let (a, b) = (adj[0][0], adj[0][1]);
+    let mut cycle = vec![0, a];
+    if adj[a].contains(&b) {
+        cycle.push(b);
+    } else {
+        cycle.extend(inner_path(adj, a, |w| w != 0, |w| w == b)?);
+    }
+    let mut drawn = vec![false; n];
+    let mut drawn_edges = HashSet::new();
+    draw(&cycle, &mut drawn, &mut drawn_edges);
+    drawn_edges.insert((cycle[0].min(b), cycle[0].max(b)));
+    let mut faces = vec![cycle.clone(), cycle.into_iter().rev().collect()];
+
+    // Last face whose vertices were marked, counting marks over all rounds.
+    let mut mark = vec![0; n];
+    let mut marks = 0;
+    while drawn_edges.len() < m {
+        let (fragments, component) = fragments(adj, &drawn, &drawn_edges);
+        // Number of faces holding all the attachments of each fragment, and
+        // the first of them.
+        let mut fits = vec![(0, 0); fragments.len()];
+        for (f, face) in faces.iter().enumerate() {
+            marks += 1;
+            for &v in face {
+                mark[v] = marks;
+            }
+            for (fit, fragment) in fits.iter_mut().zip(&fragments) {
+                if fragment.attachments.iter().all(|&v| mark[v] == marks) {
+                    if fit.0 == 0 {
+                        fit.1 = f;
+                    }
+                    fit.0 += 1;
+                }
+            }
+        }
+        if fits.iter().any(|fit| fit.0 == 0) {
+            return None;
+        }
+        let i = fits.iter().position(|fit| fit.0 == 1).unwrap_or(0);
+        let fragment = &fragments[i];
+        let path = match fragment.component {
+            None => fragment.attachments.clone(),
+            Some(c) => {
+                let from = fragment.attachments[0];
+                inner_path(adj, from, |w| component[w] == c, |w| drawn[w])
+                    .map(|path| [vec![from], path].concat())
+                    .expect("a fragment of a biconnected graph has two attachments")
+            }
+        };
+
+        // The path splits the face in two, each closed by the path walked in
+        // one direction.
+        let face = faces.swap_remove(fits[i].1);
+        let (first, last) = (path[0], path[path.len() - 1]);
+        let inner = &path[1..path.len() - 1];
+        let mut one = boundary(&face, first, last);
+        one.extend(inner.iter().rev());
+        let mut other = boundary(&face, last, first);
+        other.extend(inner);
+        faces.push(one);
+        faces.push(other);
+        draw(&path, &mut drawn, &mut drawn_edges);
+    }
+    Some(faces)
+}
+
+/// Marks the vertices and edges of `path` as drawn.
+fn draw(path: &[usize], drawn: &mut [bool], drawn_edges: &mut HashSet<(usize, usize)>) {
+    for &v in path {
+        drawn[v] = true;
+    }
+    for pair in path.windows(2) {
+        drawn_edges.insert((pair[0].min(pair[1]), pair[0].max(pair[1])));
+    }
+}
+
+/// Vertices along `face` from `from` to `to`, both included.
+fn boundary(face: &[usize], from: usize, to: usize) -> Vec<usize> {
+    let len = face.len();
+    let i = face.iter().position(|&v| v == from).unwrap();
+    let j = face.iter().position(|&v| v == to).unwrap();
+    (0..=(j + len - i) % len)
+        .map(|k| face[(i + k) % len])
+        .collect()
+}
+
+/// Shortest path, without `from`, from a neighbour of `from` to a vertex with
+/// `target`, whose other vertices all satisfy `inner`. The path has at least
+/// one inner vertex, as the neighbours of `from` are never targets.
+fn inner_path<I, T>(adj: &[Vec<usize>], from: usize, inner: I, target: T) -> Option<Vec<usize>>
+where
+    I: Fn(usize) -> bool,
+    T: Fn(usize) -> bool,
+{
+    let mut parent = vec![usize::MAX; adj.len()];
+    parent[from] = from;
+    let mut queue = vec![from];
+    let mut next = 0;
+    while next < queue.len() {
+        let v = queue[next];
+        next += 1;
+        for &w in &adj[v] {
+            if parent[w] != usize::MAX {
+                continue;
+            }
+            if v != from && target(w) {
+                let mut path = vec![w];
+                let mut x = v;
+                while x != from {
+                    path.push(x);
+                    x = parent[x];
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if inner(w) {
+                parent[w] = v;
+                queue.push(w);
+            }
+        }
+    }
+    None
+}
+
+/// Fragments of the graph `adj` relative to the drawn vertices and edges,
+/// with the component of each vertex not drawn.
+fn fragments(
+    adj: &[Vec<usize>],
+    drawn: &[bool],
+    drawn_edges: &HashSet<(usize, usize)>,
+) -> (Vec<Fragment>, Vec<usize>) {
+    let n = adj.len();
+    let mut fragments = Vec::new();
+    for v in (0..n).filter(|&v| drawn[v]) {
+        for &w in &adj[v] {
+            if drawn[w] && v < w && !drawn_edges.contains(&(v, w)) {
+                fragments.push(Fragment {
+                    attachments: vec![v, w],
+                    component: None,
+                });
+            }
+        }
+    }
+
+    let mut component = vec![usize::MAX; n];
+    // Last component found attached to each drawn vertex.
+    let mut attached = vec![usize::MAX; n];
+    let mut count = 0;
+    for root in 0..n {
+        if drawn[root] || component[root] != usize::MAX {
+            continue;
+        }
+        let c = count;
+        count += 1;
+        component[root] = c;
+        let mut attachments = Vec::new();
+        let mut stack = vec![root];
+        while let Some(v) = stack.pop() {
+            for &w in &adj[v] {
+                if drawn[w] {
+                    if attached[w] != c {
+                        attached[w] = c;
+                        attachments.push(w);
+                    }
+                } else if component[w] == usize::MAX {
+                    component[w] = c;
+                    stack.push(w);
+                }
+            }
+        }
+        fragments.push(Fragment {
+            attachments,
+            component: Some(c),
+        });
+    }
+    (fragments, component)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Simple edges `(v, w)` with `v < w` of the graph, in increasing order.
+    fn simple_edges(g: &Graph) -> Vec<(usize, usize)> {
+        let mut edges: Vec<(usize, usize)> = g
+            .edges()
+            .filter(|&(v, w)| v != w)
+            .map(|(v, w)| (v.min(w), v.max(w)))
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+        edges
+    }
+
+    /// Checks that the rotation system lists the neighbours of each vertex,
+    /// and that it draws each connected component on a sphere, where
+    /// `V - E + F = 2` by Euler's formula.
+    fn assert_embedding(g: &Graph, embedding: &Embedding) {
+        let edges = simple_edges(g);
+        let mut neighbours = vec![Vec::new(); g.vertex_count()];
+        let mut component: Vec<usize> = g.vertices().collect();
+        for &(v, w) in &edges {
+            neighbours[v].push(w);
+            neighbours[w].push(v);
+            let (cv, cw) = (component[v], component[w]);
+            component
+                .iter_mut()
+                .filter(|c| **c == cw)
+                .for_each(|c| *c = cv);
+        }
+        for v in g.vertices() {
+            let mut around = embedding.rotation(v).to_vec();
+            around.sort_unstable();
+            neighbours[v].sort_unstable();
+            assert_eq!(around, neighbours[v]);
+        }
+        let mut drawn: Vec<usize> = edges.iter().map(|&(v, _)| component[v]).collect();
+        drawn.sort_unstable();
+        drawn.dedup();
+        let touched = g.vertices().filter(|&v| !neighbours[v].is_empty()).count();
+        let faces = embedding.faces();
+        assert_eq!(faces.iter().map(Vec::len).sum::<usize>(), 2 * edges.len());
+        assert_eq!(touched + faces.len(), edges.len() + 2 * drawn.len());
+    }
+
+    /// Checks that the witness is made of edges of the graph, and that
+    /// contracting its vertices of degree 2 leaves a `K5` or a `K3,3`.
+    fn assert_kuratowski(g: &Graph, witness: &KuratowskiSubgraph) {
+        let edges = simple_edges(g);
+        assert!(witness.edges.windows(2).all(|e| e[0] < e[1]));
+        assert!(witness.edges.iter().all(|e| edges.binary_search(e).is_ok()));
+        let mut adj = vec![Vec::new(); g.vertex_count()];
+        for &(v, w) in &witness.edges {
+            adj[v].push(w);
+            adj[w].push(v);
+        }
+        let branch = witness.branch_vertices();
+        assert!(adj.iter().all(|a| a.len() != 1));
+        // Branch vertices joined by each path through vertices of degree 2.
+        let mut joined = Vec::new();
+        let mut walked = 0;
+        for &b in &branch {
+            for &first in &adj[b] {
+                let (mut prev, mut v) = (b, first);
+                walked += 1;
+                while adj[v].len() == 2 {
+                    let next = if adj[v][0] == prev {
+                        adj[v][1]
+                    } else {
+                        adj[v][0]
+                    };
+                    (prev, v) = (v, next);
+                    walked += 1;
+                }
+                joined.push((b.min(v), b.max(v)));
+            }
+        }
+        // Every edge lies on a path between branch vertices, walked from both
+        // ends.
+        assert_eq!(walked, 2 * witness.edges.len());
+        joined.sort_unstable();
+        let pairs: Vec<(usize, usize)> = joined.chunks(2).map(|p| p[0]).collect();
+        assert!(joined.chunks(2).all(|p| p[0] == p[1]));
+        let mut distinct = pairs.clone();
+        distinct.dedup();
+        assert_eq!(distinct, pairs);
+        match branch.len() {
+            5 => assert_eq!(pairs.len(), 10),
+            6 => {
+                assert_eq!(pairs.len(), 9);
+                let side: Vec<usize> = pairs
+                    .iter()
+                    .filter(|p| p.0 == branch[0])
+                    .map(|p| p.1)
+                    .collect();
+                assert_eq!(side.len(), 3);
+                for &(v, w) in &pairs {
+                    assert_ne!(side.contains(&v), side.contains(&w));
+                }
+            }
+            k => panic!("{} branch vertices", k),
+        }
+    }
+
+    fn assert_planarity(g: &Graph) -> bool {
+        match is_planar(g) {
+            Ok(embedding) => {
+                assert_embedding(g, &embedding);
+                true
+            }
+            Err(witness) => {
+                assert_kuratowski(g, &witness);
+                false
+            }
+        }
+    }
+
+    /// Maximal planar graph on `n >= 3` vertices, each new vertex being put
+    /// inside a random triangular face and joined to its corners.
+    fn random_triangulation(n: usize) -> Graph {
+        let mut g = Graph::from_edges(n, [(0, 1), (1, 2), (2, 0)]);
+        let mut faces = vec![[0, 1, 2], [0, 2, 1]];
+        for x in 3..n {
+            let [a, b, c] = faces.swap_remove(fastrand::usize(..faces.len()));
+            faces.extend([[a, b, x], [b, c, x], [c, a, x]]);
+            g.add_edge(a, x);
+            g.add_edge(b, x);
+            g.add_edge(c, x);
+        }
+        g
+    }
+
+    #[test]
+    fn random_graphs() {
+        for _ in 0..300 {
+            let n = fastrand::usize(1..12);
+            let g = Graph::from_edges(
+                n,
+                (0..fastrand::usize(..3 * n)).map(|_| (fastrand::usize(..n), fastrand::usize(..n))),
+            );
+            assert_planarity(&g);
+        }
+    }
+
+    #[test]
+    fn triangulations() {
+        for _ in 0..30 {
+            let n = fastrand::usize(3..60);
+            let mut g = random_triangulation(n);
+            assert!(assert_planarity(&g));
+            // Any other edge makes a maximal planar graph nonplanar.
+            let (v, w) = (fastrand::usize(..n), fastrand::usize(..n));
+            if v != w && !g.has_edge(v, w) {
+                g.add_edge(v, w);
+                assert!(!assert_planarity(&g));
+            }
+        }
+    }
+
+    #[test]
+    fn subdivided_kuratowski_graphs() {
+        let k5: Vec<(usize, usize)> = (0..5)
+            .flat_map(|v| (v + 1..5).map(move |w| (v, w)))
+            .collect();
+        let k33: Vec<(usize, usize)> = (0..3).flat_map(|v| (3..6).map(move |w| (v, w))).collect();
+        for (edges, branch) in [(k5, 5), (k33, 6)] {
+            // Subdivides edges at random, and adds a random planar part.
+            let mut n = branch;
+            let mut subdivided = Vec::new();
+            for (v, w) in edges {
+                let mut prev = v;
+                for _ in 0..fastrand::usize(..3) {
+                    subdivided.push((prev, n));
+                    prev = n;
+                    n += 1;
+                }
+                subdivided.push((prev, w));
+            }
+            let mut g = Graph::from_edges(n + 3, subdivided);
+            g.add_edge(n, n + 1);
+            g.add_edge(n + 1, n + 2);
+            g.add_edge(0, n);
+            let witness = is_planar(&g).unwrap_err();
+            assert_kuratowski(&g, &witness);
+            assert_eq!(witness.branch_vertices(), (0..branch).collect::<Vec<_>>());
+        }
+        let petersen = Graph::from_edges(
+            10,
+            (0..5).flat_map(|v| [(v, (v + 1) % 5), (v, v + 5), (v + 5, (v + 2) % 5 + 5)]),
+        );
+        assert_eq!(is_planar(&petersen).unwrap_err().branch_vertices().len(), 6);
+    }
+
+    #[test]
+    fn small_cases() {
+        assert!(is_planar(&Graph::new(0)).unwrap().faces().is_empty());
+        let looped = Graph::from_edges(2, [(0, 0), (0, 1), (1, 0)]);
+        let embedding = is_planar(&looped).unwrap();
+        assert_eq!(embedding.rotation(0), [1]);
+        assert_eq!(embedding.faces(), [vec![0, 1]]);
+        // Two squares sharing the vertex 0.
+        let bowtie = Graph::from_edges(
+            7,
+            [
+                (0, 1),
+                (1, 2),
+                (2, 3),
+                (3, 0),
+                (0, 4),
+                (4, 5),
+                (5, 6),
+                (6, 0),
+            ],
+        );
+        assert!(assert_planarity(&bowtie));
+        assert_eq!(is_planar(&bowtie).unwrap().faces().len(), 3);
+    }
+}
@@ -0,0 +1,244 @@
+use crate::graph::{
+    components::TreeLabels, dfs_forest, dfs_forest_visit, dfs_roots_visit, Digraph, EdgeKind,
+    GraphVisitor,
+};
+
+/// Strongly connected components of a digraph: maximal sets of vertices which
+/// can all reach each other. Each vertex is labelled by the id of its
+/// component, from 0 to `count() - 1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StronglyConnected {
+    id: Vec<usize>,
+    count: usize,
+}
+
+impl StronglyConnected {
+    /// Number of components.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Id of the component of `v`.
+    pub fn id(&self, v: usize) -> usize {
+        self.id[v]
+    }
+
+    /// Whether `v` and `w` reach each other.
+    pub fn strongly_connected(&self, v: usize, w: usize) -> bool {
+        self.id[v] == self.id[w]
+    }
+
+    /// Vertices of each component, indexed by id, in increasing order.
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut components = vec![Vec::new(); self.count];
+        for (v, &c) in self.id.iter().enumerate() {
+            components[c].push(v);
+        }
+        components
+    }
+
+    /// Condensation of `graph`, whose components these are: the digraph over
+    /// the component ids with an edge `a->b` whenever some edge of `graph`
+    /// goes from component `a` to a different component `b`. Each such edge
+    /// is added once, and the result is acyclic.
+    ///
+    /// Example:
+    /// ```
+    /// use algae::graph::{tarjan_scc, Digraph};
+    ///
+    /// let g = Digraph::from_edges(5, [(0, 1), (1, 0), (1, 2), (2, 3), (3, 2), (4, 3)]);
+    /// let scc = tarjan_scc(&g);
+    /// let dag = scc.condense(&g);
+    /// assert_eq!(dag.vertex_count(), 3);
+    /// assert_eq!(dag.edge_count(), 2);
+    /// assert!(dag.has_edge(scc.id(0), scc.id(2)));
+    /// assert!(dag.has_cycle().is_none());
+    /// ```
+    pub fn condense(&self, graph: &Digraph) -> Digraph {
+        let mut edges: Vec<(usize, usize)> = graph
+            .edges()
+            .map(|(v, w)| (self.id[v], self.id[w]))
+            .filter(|(a, b)| a != b)
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+        Digraph::from_edges(self.count, edges)
+    }
+}
+
+/// Strongly connected components by Tarjan's algorithm, in a single
+/// depth-first search taking O(V + E) time. Vertices are kept on a stack in
+/// the order they are visited, and the lowest visit index reachable from each
+/// subtree through the vertices still on the stack finds the roots of the
+/// components, which are popped when their root is finished. The search uses
+/// an explicit stack, so deep digraphs are fine.
+///
+/// Components are found in reverse topological order of the condensation, so
+/// that every edge between components goes from a larger id to a smaller one.
+///
+/// Example:
+/// ```
+/// use algae::graph::{tarjan_scc, Digraph};
+///
+/// let g = Digraph::from_edges(5, [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4)]);
+/// let scc = tarjan_scc(&g);
+/// assert_eq!(scc.count(), 3);
+/// assert!(scc.strongly_connected(0, 2));
+/// assert!(!scc.strongly_connected(2, 3));
+/// assert_eq!(scc.components(), [vec![4], vec![3], vec![0, 1, 2]]);
+/// ```
+pub fn tarjan_scc(graph: &Digraph) -> StronglyConnected {
+    let n = graph.vertex_count();
+    let mut tarjan = Tarjan {
+        index: vec![usize::MAX; n],
+        low: vec![0; n],
+        parent: vec![None; n],
+        on_stack: vec![false; n],
+        stack: Vec::new(),
+        id: vec![0; n],
+        visited: 0,
+        count: 0,
+    };
+    dfs_forest_visit(graph, &mut tarjan);
+    StronglyConnected {
+        id: tarjan.id,
+        count: tarjan.count,
+    }
+}
+
+/// Visitor computing the strongly connected components of Tarjan's algorithm
+/// during a depth-first search of the whole digraph.
+struct Tarjan {
+    index: Vec<usize>,
+    low: Vec<usize>,
+    parent: Vec<Option<usize>>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    id: Vec<usize>,
+    visited: usize,
+    count: usize,
+}
+
+impl GraphVisitor for Tarjan {
+    fn discover(&mut self, v: usize) {
+        self.index[v] = self.visited;
+        self.low[v] = self.visited;
+        self.visited += 1;
+        self.stack.push(v);
+        self.on_stack[v] = true;
+    }
+
+    fn edge(&mut self, v: usize, w: usize, kind: EdgeKind) {
+        if kind == EdgeKind::Tree {
+            self.parent[w] = Some(v);
+        } else if self.on_stack[w] {
+            self.low[v] = self.low[v].min(self.index[w]);
+        }
+    }
+
+    fn finish(&mut self, v: usize) {
+        if let Some(p) = self.parent[v] {
+            self.low[p] = self.low[p].min(self.low[v]);
+        }
+        if self.low[v] == self.index[v] {
+            while let Some(w) = self.stack.pop() {
+                self.on_stack[w] = false;
+                self.id[w] = self.count;
+                if w == v {
+                    break;
+                }
+            }
+            self.count += 1;
+        }
+    }
+}
+
+/// Strongly connected components by the Kosaraju-Sharir algorithm, in two
+/// depth-first searches taking O(V + E) time. The first computes the
+/// postorder of the reverse digraph, and the second explores the digraph from
+/// the vertices in reverse of that order: each tree of the second search then
+/// spans exactly one component.
+///
+/// As with `tarjan_scc`, components are found in reverse topological order
+/// of the condensation, although their ids may differ.
+///
+/// Example:
+/// ```
+/// use algae::graph::{kosaraju_scc, Digraph};
+///
+/// let g = Digraph::from_edges(5, [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4)]);
+/// let scc = kosaraju_scc(&g);
+/// assert_eq!(scc.components(), [vec![4], vec![3], vec![0, 1, 2]]);
+/// ```
+pub fn kosaraju_scc(graph: &Digraph) -> StronglyConnected {
+    let order = dfs_forest(&graph.reverse());
+    let mut labels = TreeLabels::new(graph.vertex_count());
+    dfs_roots_visit(graph, order.postorder().iter().rev().copied(), &mut labels);
+    StronglyConnected {
+        id: labels.id,
+        count: labels.count,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::bfs;
+
+    fn random_digraph(n: usize, m: usize) -> Digraph {
+        Digraph::from_edges(
+            n,
+            (0..m).map(|_| (fastrand::usize(..n), fastrand::usize(..n))),
+        )
+    }
+
+    #[test]
+    fn mutual_reachability() {
+        for _ in 0..50 {
+            let n = fastrand::usize(1..25);
+            let g = random_digraph(n, fastrand::usize(..2 * n));
+            let scc = tarjan_scc(&g);
+            let reach: Vec<_> = g.vertices().map(|v| bfs(&g, v)).collect();
+            for v in g.vertices() {
+                for w in g.vertices() {
+                    let mutual = reach[v].visited(w) && reach[w].visited(v);
+                    assert_eq!(scc.strongly_connected(v, w), mutual);
+                }
+            }
+            // Edges between components go to smaller ids.
+            for (v, w) in g.edges() {
+                assert!(scc.id(v) >= scc.id(w));
+            }
+            assert!(scc.condense(&g).has_cycle().is_none());
+        }
+    }
+
+    #[test]
+    fn kosaraju_agrees_with_tarjan() {
+        for _ in 0..100 {
+            let n = fastrand::usize(1..40);
+            let g = random_digraph(n, fastrand::usize(..3 * n));
+            let (tarjan, kosaraju) = (tarjan_scc(&g), kosaraju_scc(&g));
+            assert_eq!(tarjan.count(), kosaraju.count());
+            let mut a = tarjan.components();
+            let mut b = kosaraju.components();
+            a.sort();
+            b.sort();
+            assert_eq!(a, b);
+            for (v, w) in g.edges() {
+                assert!(kosaraju.id(v) >= kosaraju.id(w));
+            }
+        }
+    }
+
+    #[test]
+    fn long_cycle() {
+        let n = 500_000;
+        let g = Digraph::from_edges(n, (0..n).map(|v| (v, (v + 1) % n)));
+        assert_eq!(tarjan_scc(&g).count(), 1);
+        assert_eq!(kosaraju_scc(&g).count(), 1);
+        let path = Digraph::from_edges(n, (1..n).map(|v| (v - 1, v)));
+        assert_eq!(tarjan_scc(&path).count(), n);
+        assert_eq!(kosaraju_scc(&path).count(), n);
+    }
+}
@@ -0,0 +1,911 @@
+use crate::{
+    ds::{BucketQueue, IndexedPriorityQueue, Queue, RadixHeap},
+    graph::{DirectedEdge, EdgeKind, EdgeWeightedDigraph, GraphVisitor},
+};
+use std::fmt;
+
+/// Shortest paths from a source of an edge-weighted digraph: the distance to
+/// each vertex and the last edge of a shortest path to it, forming a tree of
+/// shortest paths.
+#[derive(Debug, Clone)]
+pub struct ShortestPaths {
+    dist: Vec<f64>,
+    edge_to: Vec<Option<DirectedEdge>>,
+}
+
+impl ShortestPaths {
+    /// Paths from `source` before any edge is relaxed.
+    fn new(n: usize, source: usize) -> Self {
+        let mut dist = vec![f64::INFINITY; n];
+        dist[source] = 0.0;
+        Self {
+            dist,
+            edge_to: vec![None; n],
+        }
+    }
+
+    /// Lowers the distance to the head of `e` if going through `e` is
+    /// shorter, returning whether it did.
+    fn relax(&mut self, e: &DirectedEdge) -> bool {
+        let through = self.dist[e.from] + e.weight;
+        if through < self.dist[e.to] {
+            self.dist[e.to] = through;
+            self.edge_to[e.to] = Some(*e);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Finds a cycle among the edges `edge_to`, which can only appear once a
+    /// negative cycle has been relaxed. Since each vertex has at most one such
+    /// edge, walking them backwards from each vertex in turn either ends at a
+    /// vertex without one, or at a vertex seen before in the same walk, which
+    /// is on a cycle. Takes O(V) time.
+    fn find_cycle(&self) -> Option<Vec<DirectedEdge>> {
+        let n = self.dist.len();
+        // Walk in which each vertex was first seen.
+        let mut walk = vec![usize::MAX; n];
+        for start in 0..n {
+            let mut v = start;
+            while walk[v] == usize::MAX {
+                walk[v] = start;
+                match self.edge_to[v] {
+                    Some(e) => v = e.from,
+                    None => break,
+                }
+            }
+            if walk[v] == start && self.edge_to[v].is_some() {
+                // Back at `v` within this walk: collect the cycle through it.
+                let mut cycle = Vec::new();
+                let mut u = v;
+                loop {
+                    let e = self.edge_to[u].expect("Vertex on cycle");
+                    cycle.push(e);
+                    u = e.from;
+                    if u == v {
+                        break;
+                    }
+                }
+                cycle.reverse();
+                return Some(cycle);
+            }
+        }
+        None
+    }
+
+    /// Whether `v` is reachable from the source.
+    pub fn has_path_to(&self, v: usize) -> bool {
+        self.dist[v] < f64::INFINITY
+    }
+
+    /// Total weight of a shortest path from the source to `v`, or `None` if
+    /// `v` is unreachable.
+    pub fn dist_to(&self, v: usize) -> Option<f64> {
+        self.has_path_to(v).then_some(self.dist[v])
+    }
+
+    /// Edge through which a shortest path reaches `v`, or `None` for the
+    /// source and unreachable vertices.
+    pub fn edge_to(&self, v: usize) -> Option<&DirectedEdge> {
+        self.edge_to[v].as_ref()
+    }
+
+    /// Vertices of a shortest path from the source to `v`, or `None` if `v`
+    /// is unreachable.
+    pub fn path_to(&self, v: usize) -> Option<Vec<usize>> {
+        if !self.has_path_to(v) {
+            return None;
+        }
+        let mut path = vec![v];
+        let mut u = v;
+        while let Some(e) = self.edge_to[u] {
+            path.push(e.from);
+            u = e.from;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Error of Dijkstra's algorithm on a digraph with an edge of negative (or
+/// NaN) weight, for which it may give wrong distances.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NegativeWeight {
+    pub edge: DirectedEdge,
+}
+
+impl fmt::Display for NegativeWeight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "edge {}->{} has negative weight {}",
+            self.edge.from, self.edge.to, self.edge.weight
+        )
+    }
+}
+
+impl std::error::Error for NegativeWeight {}
+
+/// Dijkstra's algorithm: shortest paths from `source` in a digraph with
+/// non-negative weights. Vertices are finished in increasing order of
+/// distance, each relaxing the edges leaving it, with the tentative distances
+/// kept in an `IndexedPriorityQueue` and lowered by `decrease_key`. Takes
+/// O(E * log V) time. For small integer weights, `dijkstra_small_int` is
+/// faster.
+///
+/// Returns an error if any edge has a negative weight.
+///
+/// Example:
+/// ```
+/// use algae::graph::{dijkstra, EdgeWeightedDigraph};
+///
+/// let g = EdgeWeightedDigraph::from_edges(
+///     5,
+///     [(0, 1, 4.0), (0, 2, 1.0), (2, 1, 2.0), (1, 3, 1.0), (2, 3, 5.0)],
+/// );
+/// let paths = dijkstra(&g, 0).unwrap();
+/// assert_eq!(paths.dist_to(3), Some(4.0));
+/// assert_eq!(paths.path_to(3), Some(vec![0, 2, 1, 3]));
+/// assert_eq!(paths.dist_to(4), None);
+///
+/// let negative = EdgeWeightedDigraph::from_edges(2, [(0, 1, -1.0)]);
+/// assert!(dijkstra(&negative, 0).is_err());
+/// ```
+pub fn dijkstra(
+    graph: &EdgeWeightedDigraph,
+    source: usize,
+) -> Result<ShortestPaths, NegativeWeight> {
+    dijkstra_visit(graph, source, &mut ())
+}
+
+/// Dijkstra's algorithm, as `dijkstra`, passing the events of the search to
+/// `visitor`. A vertex is discovered when it first gets a tentative distance
+/// and finished once its distance is final and the edges leaving it relaxed.
+/// Each relaxed edge is a tree edge if it lowered the distance to its head,
+/// so that the last tree edge into a vertex is on its shortest path, and a
+/// non-tree edge otherwise.
+///
+/// Example:
+/// ```
+/// use algae::graph::{dijkstra_visit, EdgeWeightedDigraph, GraphVisitor};
+///
+/// struct Settled(Vec<usize>);
+///
+/// impl GraphVisitor for Settled {
+///     fn finish(&mut self, v: usize) {
+///         self.0.push(v);
+///     }
+/// }
+///
+/// let g = EdgeWeightedDigraph::from_edges(3, [(0, 1, 5.0), (0, 2, 1.0), (2, 1, 1.0)]);
+/// let mut settled = Settled(Vec::new());
+/// dijkstra_visit(&g, 0, &mut settled).unwrap();
+/// assert_eq!(settled.0, [0, 2, 1]);
+/// ```
+pub fn dijkstra_visit<V: GraphVisitor>(
+    graph: &EdgeWeightedDigraph,
+    source: usize,
+    visitor: &mut V,
+) -> Result<ShortestPaths, NegativeWeight> {
+    check_weights(graph)?;
+    trace_span!("dijkstra", vertices = graph.vertex_count(), source);
+    let mut search = DijkstraSearch::new(graph, source, visitor);
+    while search.step(graph, visitor).is_some() {}
+    Ok(search.paths)
+}
+
+/// Returns an error for the first edge of negative (or NaN) weight.
+pub(crate) fn check_weights(graph: &EdgeWeightedDigraph) -> Result<(), NegativeWeight> {
+    match graph.edges().find(|e| e.weight < 0.0 || e.weight.is_nan()) {
+        Some(&edge) => Err(NegativeWeight { edge }),
+        None => Ok(()),
+    }
+}
+
+/// Dijkstra's algorithm in progress, finishing one vertex at a time, so that
+/// a search can stop once it has finished the vertices it needs, or run side
+/// by side with another search.
+struct DijkstraSearch {
+    paths: ShortestPaths,
+    pq: IndexedPriorityQueue<f64>,
+}
+
+impl DijkstraSearch {
+    fn new<V: GraphVisitor>(graph: &EdgeWeightedDigraph, source: usize, visitor: &mut V) -> Self {
+        let mut pq = IndexedPriorityQueue::new(graph.vertex_count());
+        pq.push(source, 0.0);
+        visitor.discover(source);
+        Self {
+            paths: ShortestPaths::new(graph.vertex_count(), source),
+            pq,
+        }
+    }
+
+    /// Distance of the next vertex to finish, if any is left.
+    fn next_dist(&self) -> Option<f64> {
+        self.pq.peek().map(|(_, &d)| d)
+    }
+
+    /// Finishes the closest vertex not yet finished, relaxing the edges
+    /// leaving it, and returns it, or `None` if every reachable vertex is
+    /// finished.
+    fn step<V: GraphVisitor>(
+        &mut self,
+        graph: &EdgeWeightedDigraph,
+        visitor: &mut V,
+    ) -> Option<usize> {
+        let (v, _) = self.pq.pop()?;
+        trace_event!(vertex = v, dist = self.paths.dist[v], "vertex visited");
+        for e in graph.adj(v) {
+            let reached = self.paths.has_path_to(e.to);
+            if !self.paths.relax(e) {
+                visitor.edge(v, e.to, EdgeKind::NonTree);
+                continue;
+            }
+            visitor.edge(v, e.to, EdgeKind::Tree);
+            let d = self.paths.dist[e.to];
+            if self.pq.contains(e.to) {
+                self.pq.decrease_key(e.to, d);
+            } else {
+                self.pq.push(e.to, d);
+            }
+            if !reached {
+                visitor.discover(e.to);
+            }
+        }
+        visitor.finish(v);
+        Some(v)
+    }
+}
+
+/// Path of an edge-weighted digraph, given by its vertices, with its total
+/// weight.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightedPath {
+    pub vertices: Vec<usize>,
+    pub weight: f64,
+}
+
+/// Dijkstra's algorithm from `source`, stopping as soon as the distance to
+/// `target` is final, which saves finishing the vertices farther from
+/// `source` than `target`. Returns a shortest path from `source` to `target`,
+/// or `None` if `target` is unreachable.
+///
+/// Returns an error if any edge has a negative weight.
+///
+/// Example:
+/// ```
+/// use algae::graph::{dijkstra_to, EdgeWeightedDigraph};
+///
+/// let g = EdgeWeightedDigraph::from_edges(
+///     5,
+///     [(0, 1, 4.0), (0, 2, 1.0), (2, 1, 2.0), (1, 3, 1.0), (2, 3, 5.0)],
+/// );
+/// let path = dijkstra_to(&g, 0, 3).unwrap().unwrap();
+/// assert_eq!(path.vertices, [0, 2, 1, 3]);
+/// assert_eq!(path.weight, 4.0);
+/// assert_eq!(dijkstra_to(&g, 0, 4).unwrap(), None);
+/// ```
+pub fn dijkstra_to(
+    graph: &EdgeWeightedDigraph,
+    source: usize,
+    target: usize,
+) -> Result<Option<WeightedPath>, NegativeWeight> {
+    dijkstra_to_finished(graph, source, target).map(|(path, _)| path)
+}
+
+/// Dijkstra's algorithm stopping at `target`, as `dijkstra_to`, also
+/// returning the number of finished vertices.
+fn dijkstra_to_finished(
+    graph: &EdgeWeightedDigraph,
+    source: usize,
+    target: usize,
+) -> Result<(Option<WeightedPath>, usize), NegativeWeight> {
+    check_weights(graph)?;
+    trace_span!(
+        "dijkstra_to",
+        vertices = graph.vertex_count(),
+        source,
+        target
+    );
+    let mut search = DijkstraSearch::new(graph, source, &mut ());
+    let mut finished = 0;
+    while let Some(v) = search.step(graph, &mut ()) {
+        finished += 1;
+        if v == target {
+            break;
+        }
+    }
+    let path = search.paths.path_to(target).map(|vertices| WeightedPath {
+        vertices,
+        weight: search.paths.dist[target],
+    });
+    Ok((path, finished))
+}
+
+/// Bidirectional Dijkstra: a shortest path from `source` to `target`, found
+/// by a search forwards from `source` and another backwards from `target`,
+/// in the reverse digraph, each time advancing the one whose next vertex is
+/// closer. Whenever an edge relaxed by one search reaches a vertex already
+/// reached by the other, the two paths to it join into a path from `source`
+/// to `target`, and the shortest one is kept. The searches stop once the
+/// next distances of both add up to at least the length of that path, since
+/// any shorter path would have to go through vertices closer to either end.
+/// Each search then covers a ball of about half the radius of that of
+/// `dijkstra_to`, which finishes far fewer vertices on large road-like
+/// digraphs.
+///
+/// Returns `None` if `target` is unreachable, and an error if any edge has a
+/// negative weight.
+///
+/// Example:
+/// ```
+/// use algae::graph::{bidirectional_dijkstra, EdgeWeightedDigraph};
+///
+/// let g = EdgeWeightedDigraph::from_edges(
+///     5,
+///     [(0, 1, 4.0), (0, 2, 1.0), (2, 1, 2.0), (1, 3, 1.0), (2, 3, 5.0)],
+/// );
+/// let path = bidirectional_dijkstra(&g, 0, 3).unwrap().unwrap();
+/// assert_eq!(path.vertices, [0, 2, 1, 3]);
+/// assert_eq!(path.weight, 4.0);
+/// assert_eq!(bidirectional_dijkstra(&g, 3, 0).unwrap(), None);
+/// ```
+pub fn bidirectional_dijkstra(
+    graph: &EdgeWeightedDigraph,
+    source: usize,
+    target: usize,
+) -> Result<Option<WeightedPath>, NegativeWeight> {
+    bidirectional_dijkstra_finished(graph, source, target).map(|(path, _)| path)
+}
+
+/// Visitor collecting the vertices whose distance was lowered.
+struct Lowered(Vec<usize>);
+
+impl GraphVisitor for Lowered {
+    fn edge(&mut self, _: usize, w: usize, kind: EdgeKind) {
+        if kind == EdgeKind::Tree {
+            self.0.push(w);
+        }
+    }
+}
+
+/// Bidirectional Dijkstra, as `bidirectional_dijkstra`, also returning the
+/// number of vertices finished by both searches.
+fn bidirectional_dijkstra_finished(
+    graph: &EdgeWeightedDigraph,
+    source: usize,
+    target: usize,
+) -> Result<(Option<WeightedPath>, usize), NegativeWeight> {
+    check_weights(graph)?;
+    trace_span!(
+        "bidirectional_dijkstra",
+        vertices = graph.vertex_count(),
+        source,
+        target
+    );
+    let reverse = graph.reverse();
+    let graphs = [graph, &reverse];
+    let mut searches = [
+        DijkstraSearch::new(graph, source, &mut ()),
+        DijkstraSearch::new(&reverse, target, &mut ()),
+    ];
+    // Length of the shortest path found, and the vertex where it joins.
+    let mut best = (f64::INFINITY, source);
+    if source == target {
+        best = (0.0, source);
+    }
+    let mut lowered = Lowered(Vec::new());
+    let mut finished = 0;
+    while let (Some(f), Some(b)) = (searches[0].next_dist(), searches[1].next_dist()) {
+        if f + b >= best.0 {
+            break;
+        }
+        let side = if f <= b { 0 } else { 1 };
+        searches[side].step(graphs[side], &mut lowered);
+        finished += 1;
+        for v in lowered.0.drain(..) {
+            let through = searches[0].paths.dist[v] + searches[1].paths.dist[v];
+            if through < best.0 {
+                best = (through, v);
+            }
+        }
+    }
+
+    let (weight, middle) = best;
+    if weight == f64::INFINITY {
+        return Ok((None, finished));
+    }
+    let mut vertices = searches[0].paths.path_to(middle).expect("Path to middle");
+    let mut back = searches[1].paths.path_to(middle).expect("Path from middle");
+    back.pop();
+    vertices.extend(back.into_iter().rev());
+    Ok((Some(WeightedPath { vertices, weight }), finished))
+}
+
+/// Dial's algorithm: Dijkstra's algorithm for digraphs whose weights are
+/// small non-negative integers, with the tentative distances kept in a
+/// `BucketQueue` instead of a binary heap. The distances waiting in the queue
+/// always lie within `C` of the last finished one, where `C` is the largest
+/// weight, so `C + 1` buckets used cyclically suffice. Takes O(E + V * C / 64)
+/// time, which beats `dijkstra` when `C` is small.
+///
+/// Returns an error if any edge has a negative weight. Panics if a weight is
+/// not an integer.
+///
+/// Example:
+/// ```
+/// use algae::graph::{dijkstra_small_int, EdgeWeightedDigraph};
+///
+/// let g = EdgeWeightedDigraph::from_edges(
+///     5,
+///     [(0, 1, 4.0), (0, 2, 1.0), (2, 1, 2.0), (1, 3, 1.0), (2, 3, 5.0)],
+/// );
+/// let paths = dijkstra_small_int(&g, 0).unwrap();
+/// assert_eq!(paths.dist_to(3), Some(4.0));
+/// assert_eq!(paths.path_to(3), Some(vec![0, 2, 1, 3]));
+/// ```
+pub fn dijkstra_small_int(
+    graph: &EdgeWeightedDigraph,
+    source: usize,
+) -> Result<ShortestPaths, NegativeWeight> {
+    check_weights(graph)?;
+    if let Some(e) = graph.edges().find(|e| e.weight.fract() != 0.0) {
+        panic!(
+            "Weight {} of the edge {}->{} is not an integer",
+            e.weight, e.from, e.to
+        );
+    }
+    let max_weight = graph.edges().map(|e| e.weight as usize).max().unwrap_or(0);
+    let buckets = max_weight + 1;
+
+    let mut paths = ShortestPaths::new(graph.vertex_count(), source);
+    let mut finished = vec![false; graph.vertex_count()];
+    let mut queue = BucketQueue::new(max_weight);
+    queue.push(0, source);
+    let mut last = 0;
+    while let Some((bucket, v)) = queue.pop_cyclic(last) {
+        last = bucket;
+        // Vertices whose distance was lowered after being pushed are popped
+        // again later, from the bucket of their former distance.
+        if finished[v] {
+            continue;
+        }
+        finished[v] = true;
+        for e in graph.adj(v) {
+            if paths.relax(e) {
+                queue.push(paths.dist[e.to] as usize % buckets, e.to);
+            }
+        }
+    }
+    Ok(paths)
+}
+
+/// Dijkstra's algorithm with the tentative distances kept in a `RadixHeap`
+/// rather than an `IndexedPriorityQueue`. The bits of non-negative floats
+/// order them as their values do, so the distances serve as the `u64` keys
+/// of the heap, and they are popped in increasing order, which is the
+/// monotone use the radix heap relies on. Instead of decreasing keys, a
+/// vertex is pushed again each time its distance is lowered, and its stale
+/// entries are skipped once it is finished. Each of the O(E) entries moves
+/// between buckets at most 64 times, and usually far fewer, so that this
+/// tends to beat the binary heap on large digraphs.
+///
+/// Returns an error if any edge has a negative weight.
+///
+/// Example:
+/// ```
+/// use algae::graph::{dijkstra_radix, EdgeWeightedDigraph};
+///
+/// let g = EdgeWeightedDigraph::from_edges(
+///     5,
+///     [(0, 1, 4.0), (0, 2, 1.0), (2, 1, 2.0), (1, 3, 1.0), (2, 3, 5.0)],
+/// );
+/// let paths = dijkstra_radix(&g, 0).unwrap();
+/// assert_eq!(paths.dist_to(3), Some(4.0));
+/// assert_eq!(paths.path_to(3), Some(vec![0, 2, 1, 3]));
+/// ```
+pub fn dijkstra_radix(
+    graph: &EdgeWeightedDigraph,
+    source: usize,
+) -> Result<ShortestPaths, NegativeWeight> {
+    check_weights(graph)?;
+    let mut paths = ShortestPaths::new(graph.vertex_count(), source);
+    let mut finished = vec![false; graph.vertex_count()];
+    let mut heap = RadixHeap::new();
+    heap.push(0.0_f64.to_bits(), source);
+    while let Some((_, v)) = heap.pop() {
+        if finished[v] {
+            continue;
+        }
+        finished[v] = true;
+        for e in graph.adj(v) {
+            if paths.relax(e) {
+                // Distances are never `-0.0`, whose bits would order it after
+                // every other distance, since `0.0 + -0.0` is `0.0`.
+                heap.push(paths.dist[e.to].to_bits(), e.to);
+            }
+        }
+    }
+    Ok(paths)
+}
+
+/// Cycle of negative total weight, along which paths can be made arbitrarily
+/// short, so that shortest paths through it are undefined. The edges are
+/// listed in order along the cycle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NegativeCycle {
+    pub edges: Vec<DirectedEdge>,
+}
+
+impl NegativeCycle {
+    /// Total weight of the cycle, which is negative.
+    pub fn weight(&self) -> f64 {
+        self.edges.iter().map(|e| e.weight).sum()
+    }
+}
+
+impl fmt::Display for NegativeCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "negative cycle")?;
+        for e in &self.edges {
+            write!(f, " {}", e.from)?;
+        }
+        if let Some(e) = self.edges.first() {
+            write!(f, " {}", e.from)?;
+        }
+        write!(f, " of weight {}", self.weight())
+    }
+}
+
+impl std::error::Error for NegativeCycle {}
+
+/// Bellman-Ford algorithm: shortest paths from `source` in a digraph whose
+/// weights may be negative. Uses the queue-based variant, which only relaxes
+/// the edges leaving vertices whose distance changed in the previous pass,
+/// making it much faster than relaxing every edge V times on most digraphs,
+/// while keeping the O(V * E) worst case.
+///
+/// If a cycle of negative weight is reachable from `source`, the distances
+/// keep decreasing, and the cycle is returned instead of the paths. Such a
+/// cycle appears among the last edges of the paths, which are checked for
+/// cycles after every V relaxations.
+///
+/// Example:
+/// ```
+/// use algae::graph::{bellman_ford, EdgeWeightedDigraph};
+///
+/// let mut g = EdgeWeightedDigraph::from_edges(4, [(0, 1, 4.0), (0, 2, 5.0), (2, 1, -3.0), (1, 3, 2.0)]);
+/// let paths = bellman_ford(&g, 0).unwrap();
+/// assert_eq!(paths.dist_to(3), Some(4.0));
+/// assert_eq!(paths.path_to(3), Some(vec![0, 2, 1, 3]));
+///
+/// g.add_edge(3, 2, -1.0);
+/// let cycle = bellman_ford(&g, 0).unwrap_err();
+/// assert_eq!(cycle.weight(), -2.0);
+/// assert_eq!(cycle.edges.len(), 3);
+/// ```
+pub fn bellman_ford(
+    graph: &EdgeWeightedDigraph,
+    source: usize,
+) -> Result<ShortestPaths, NegativeCycle> {
+    let n = graph.vertex_count();
+    let mut paths = ShortestPaths::new(n, source);
+    let mut on_queue = vec![false; n];
+    let mut queue = Queue::new();
+    queue.push(source);
+    on_queue[source] = true;
+    let mut relaxations = 0;
+    while let Some(v) = queue.pop() {
+        on_queue[v] = false;
+        for e in graph.adj(v) {
+            if paths.relax(e) && !on_queue[e.to] {
+                queue.push(e.to);
+                on_queue[e.to] = true;
+            }
+            relaxations += 1;
+            if relaxations % n == 0 {
+                if let Some(edges) = paths.find_cycle() {
+                    return Err(NegativeCycle { edges });
+                }
+            }
+        }
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn random_graph(n: usize, m: usize) -> EdgeWeightedDigraph {
+        EdgeWeightedDigraph::from_edges(
+            n,
+            (0..m).map(|_| {
+                (
+                    fastrand::usize(..n),
+                    fastrand::usize(..n),
+                    fastrand::u8(..20) as f64,
+                )
+            }),
+        )
+    }
+
+    /// Distances by relaxing every edge `n` times.
+    fn relax_all(g: &EdgeWeightedDigraph, source: usize) -> Vec<f64> {
+        let mut paths = ShortestPaths::new(g.vertex_count(), source);
+        for _ in g.vertices() {
+            for e in g.edges() {
+                paths.relax(e);
+            }
+        }
+        paths.dist
+    }
+
+    #[test]
+    fn agrees_with_relaxation() {
+        for _ in 0..50 {
+            let n = fastrand::usize(1..30);
+            let g = random_graph(n, fastrand::usize(..4 * n));
+            let source = fastrand::usize(..n);
+            let paths = dijkstra(&g, source).unwrap();
+            for (v, d) in relax_all(&g, source).into_iter().enumerate() {
+                assert_eq!(paths.dist_to(v), d.is_finite().then_some(d));
+                if let Some(path) = paths.path_to(v) {
+                    assert_eq!(path[0], source);
+                    let length: f64 = path
+                        .windows(2)
+                        .map(|w| {
+                            paths
+                                .edge_to(w[1])
+                                .filter(|e| e.from == w[0])
+                                .unwrap()
+                                .weight
+                        })
+                        .sum();
+                    assert_eq!(length, d);
+                }
+            }
+        }
+    }
+
+    /// Checks that `path` goes from `source` to `target` along edges of `g`
+    /// adding up to its weight, which is the distance found by `dijkstra`.
+    fn assert_shortest(g: &EdgeWeightedDigraph, path: &WeightedPath, source: usize, target: usize) {
+        let dist = dijkstra(g, source).unwrap().dist_to(target);
+        assert_eq!(Some(path.weight), dist);
+        assert_eq!(path.vertices.first(), Some(&source));
+        assert_eq!(path.vertices.last(), Some(&target));
+        let length: f64 = path
+            .vertices
+            .windows(2)
+            .map(|w| {
+                g.adj(w[0])
+                    .iter()
+                    .filter(|e| e.to == w[1])
+                    .map(|e| e.weight)
+                    .fold(f64::INFINITY, f64::min)
+            })
+            .sum();
+        assert_eq!(length, path.weight);
+    }
+
+    #[test]
+    fn single_target_agrees_with_dijkstra() {
+        for _ in 0..200 {
+            let n = fastrand::usize(1..30);
+            let g = random_graph(n, fastrand::usize(..3 * n));
+            let (source, target) = (fastrand::usize(..n), fastrand::usize(..n));
+            let reachable = dijkstra(&g, source).unwrap().has_path_to(target);
+            for path in [
+                dijkstra_to(&g, source, target).unwrap(),
+                bidirectional_dijkstra(&g, source, target).unwrap(),
+            ] {
+                assert_eq!(path.is_some(), reachable);
+                if let Some(path) = path {
+                    assert_shortest(&g, &path, source, target);
+                }
+            }
+        }
+        let g = EdgeWeightedDigraph::from_edges(3, [(0, 1, 1.0), (2, 1, -1.0)]);
+        assert!(dijkstra_to(&g, 0, 1).is_err());
+        assert!(bidirectional_dijkstra(&g, 0, 1).is_err());
+    }
+
+    #[test]
+    fn single_target_stops_early() {
+        let n = 1_000;
+        let path = EdgeWeightedDigraph::from_edges(n, (1..n).map(|v| (v - 1, v, 1.0)));
+        let (found, finished) = dijkstra_to_finished(&path, 0, 1).unwrap();
+        assert_eq!(found.unwrap().vertices, [0, 1]);
+        assert_eq!(finished, 2);
+
+        // Both ends have many edges leading away from the other end, which
+        // a search from either end alone would explore before reaching it.
+        let mut g = EdgeWeightedDigraph::from_edges(n, [(0, 1, 1.0), (1, 2, 1.0)]);
+        for v in 3..n {
+            if v % 2 == 0 {
+                g.add_edge(0, v, 1.5);
+            } else {
+                g.add_edge(v, 2, 1.5);
+            }
+        }
+        let (found, finished) = bidirectional_dijkstra_finished(&g, 0, 2).unwrap();
+        assert_eq!(found.unwrap().vertices, [0, 1, 2]);
+        assert!(finished <= 4);
+        let (_, finished) = dijkstra_to_finished(&g, 0, 2).unwrap();
+        assert!(finished > n / 2);
+    }
+
+    #[test]
+    fn dial_agrees_with_dijkstra() {
+        for max_weight in [0, 1, 5, 100] {
+            for _ in 0..20 {
+                let n = fastrand::usize(1..30);
+                let g = EdgeWeightedDigraph::from_edges(
+                    n,
+                    (0..fastrand::usize(..80)).map(|_| {
+                        (
+                            fastrand::usize(..n),
+                            fastrand::usize(..n),
+                            fastrand::u32(..=max_weight) as f64,
+                        )
+                    }),
+                );
+                let (dial, heap) = (dijkstra_small_int(&g, 0).unwrap(), dijkstra(&g, 0).unwrap());
+                for v in 0..n {
+                    assert_eq!(dial.dist_to(v), heap.dist_to(v));
+                }
+            }
+        }
+        let g = EdgeWeightedDigraph::from_edges(2, [(0, 1, -1.0)]);
+        assert!(dijkstra_small_int(&g, 0).is_err());
+    }
+
+    #[test]
+    fn radix_agrees_with_dijkstra() {
+        for _ in 0..100 {
+            let n = fastrand::usize(1..40);
+            // Both tiny and huge weights, whose distances differ in the high
+            // bits as well as the low ones, and zero weights.
+            let g = EdgeWeightedDigraph::from_edges(
+                n,
+                (0..fastrand::usize(..120)).map(|_| {
+                    let weight = match fastrand::u8(..3) {
+                        0 => 0.0,
+                        1 => fastrand::f64(),
+                        _ => fastrand::f64() * 1e12,
+                    };
+                    (fastrand::usize(..n), fastrand::usize(..n), weight)
+                }),
+            );
+            let source = fastrand::usize(..n);
+            let (radix, heap) = (
+                dijkstra_radix(&g, source).unwrap(),
+                dijkstra(&g, source).unwrap(),
+            );
+            for v in 0..n {
+                assert_eq!(radix.dist_to(v), heap.dist_to(v));
+            }
+        }
+        let g = EdgeWeightedDigraph::from_edges(2, [(0, 1, -1.0)]);
+        assert!(dijkstra_radix(&g, 0).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "not an integer")]
+    fn dial_rejects_fractional_weights() {
+        let g = EdgeWeightedDigraph::from_edges(2, [(0, 1, 0.5)]);
+        let _ = dijkstra_small_int(&g, 0);
+    }
+
+    #[test]
+    fn dijkstra_events() {
+        #[derive(Default)]
+        struct Events {
+            discovered: Vec<usize>,
+            finished: Vec<usize>,
+            last_tree_edge: Vec<Option<usize>>,
+        }
+
+        impl GraphVisitor for Events {
+            fn discover(&mut self, v: usize) {
+                self.discovered.push(v);
+            }
+
+            fn finish(&mut self, v: usize) {
+                self.finished.push(v);
+            }
+
+            fn edge(&mut self, v: usize, w: usize, kind: EdgeKind) {
+                if kind == EdgeKind::Tree {
+                    self.last_tree_edge[w] = Some(v);
+                }
+            }
+        }
+
+        for _ in 0..50 {
+            let n = fastrand::usize(1..30);
+            let g = random_graph(n, fastrand::usize(..80));
+            let mut events = Events {
+                last_tree_edge: vec![None; n],
+                ..Default::default()
+            };
+            let paths = dijkstra_visit(&g, 0, &mut events).unwrap();
+            let reached: Vec<usize> = (0..n).filter(|&v| paths.has_path_to(v)).collect();
+            let mut discovered = events.discovered.clone();
+            discovered.sort();
+            assert_eq!(discovered, reached);
+            // Vertices are finished once each, by increasing distance.
+            let mut finished = events.finished.clone();
+            finished.sort();
+            assert_eq!(finished, reached);
+            let dist: Vec<f64> = events
+                .finished
+                .iter()
+                .map(|&v| paths.dist_to(v).unwrap())
+                .collect();
+            assert!(dist.windows(2).all(|d| d[0] <= d[1]));
+            for v in 0..n {
+                assert_eq!(events.last_tree_edge[v], paths.edge_to(v).map(|e| e.from));
+            }
+        }
+    }
+
+    #[test]
+    fn negative_weights() {
+        for _ in 0..100 {
+            let n = fastrand::usize(1..20);
+            let g = EdgeWeightedDigraph::from_edges(
+                n,
+                (0..fastrand::usize(..2 * n)).map(|_| {
+                    let weight = fastrand::i8(-3..20) as f64;
+                    (fastrand::usize(..n), fastrand::usize(..n), weight)
+                }),
+            );
+            let dist = relax_all(&g, 0);
+            // With no negative cycle, one more pass changes nothing.
+            let mut check = ShortestPaths::new(n, 0);
+            check.dist = dist.clone();
+            let stable = g.edges().all(|e| !check.relax(e));
+            match bellman_ford(&g, 0) {
+                Ok(paths) => {
+                    assert!(stable);
+                    for (v, &d) in dist.iter().enumerate() {
+                        assert_eq!(paths.dist_to(v), d.is_finite().then_some(d));
+                    }
+                }
+                Err(cycle) => {
+                    assert!(!stable);
+                    assert!(cycle.weight() < 0.0);
+                    let k = cycle.edges.len();
+                    for (i, e) in cycle.edges.iter().enumerate() {
+                        assert_eq!(e.to, cycle.edges[(i + 1) % k].from);
+                        assert!(g.adj(e.from).contains(e));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn negative_self_loop() {
+        let g = EdgeWeightedDigraph::from_edges(2, [(0, 1, 1.0), (1, 1, -1.0)]);
+        let cycle = bellman_ford(&g, 0).unwrap_err();
+        assert_eq!(cycle.to_string(), "negative cycle 1 1 of weight -1");
+        // Unreachable negative cycles don't matter.
+        let g = EdgeWeightedDigraph::from_edges(2, [(1, 1, -1.0)]);
+        assert_eq!(bellman_ford(&g, 0).unwrap().dist_to(1), None);
+    }
+
+    #[test]
+    fn rejects_negative_weights() {
+        let g = EdgeWeightedDigraph::from_edges(3, [(0, 1, 1.0), (2, 1, -0.5)]);
+        let err = dijkstra(&g, 0).unwrap_err();
+        assert_eq!(err.edge.from, 2);
+        assert_eq!(err.to_string(), "edge 2->1 has negative weight -0.5");
+        let nan = EdgeWeightedDigraph::from_edges(2, [(0, 1, f64::NAN)]);
+        assert!(dijkstra(&nan, 0).is_err());
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{
+    ds::Queue,
+    graph::{cycle::tree_path, dfs_forest_visit, Digraph, EdgeKind, GraphVisitor},
+};
+use std::fmt;
+
+/// Directed cycle found in a digraph which was expected to be acyclic. The
+/// vertices are listed in order along the cycle, which is closed by the edge
+/// from the last vertex to the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    pub vertices: Vec<usize>,
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cycle")?;
+        for v in self.vertices.iter().chain(self.vertices.first()) {
+            write!(f, " {}", v)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Cycle {}
+
+/// Topological order of `graph`: every edge `v->w` has `v` before `w`. Uses
+/// the reverse postorder of a depth-first search, in O(V + E) time. If the
+/// digraph has a cycle, there is no such order, and the cycle closed by the
+/// first back edge of the search is returned instead.
+///
+/// Example:
+/// ```
+/// use algae::graph::{topological_sort, Digraph};
+///
+/// // Tasks and their dependents.
+/// let g = Digraph::from_edges(4, [(0, 1), (0, 2), (2, 1), (1, 3)]);
+/// assert_eq!(topological_sort(&g), Ok(vec![0, 2, 1, 3]));
+///
+/// let g = Digraph::from_edges(3, [(0, 1), (1, 2), (2, 1)]);
+/// assert_eq!(topological_sort(&g).unwrap_err().vertices, [1, 2]);
+/// ```
+pub fn topological_sort(graph: &Digraph) -> Result<Vec<usize>, Cycle> {
+    let mut visitor = ReversePostorder {
+        parent: vec![usize::MAX; graph.vertex_count()],
+        order: Vec::with_capacity(graph.vertex_count()),
+        cycle: None,
+    };
+    dfs_forest_visit(graph, &mut visitor);
+    match visitor.cycle {
+        Some(vertices) => Err(Cycle { vertices }),
+        None => {
+            visitor.order.reverse();
+            Ok(visitor.order)
+        }
+    }
+}
+
+/// Visitor collecting the postorder of a depth-first search, along with the
+/// cycle closed by the first back edge, if any.
+struct ReversePostorder {
+    parent: Vec<usize>,
+    order: Vec<usize>,
+    cycle: Option<Vec<usize>>,
+}
+
+impl GraphVisitor for ReversePostorder {
+    fn finish(&mut self, v: usize) {
+        self.order.push(v);
+    }
+
+    fn edge(&mut self, v: usize, w: usize, kind: EdgeKind) {
+        match kind {
+            EdgeKind::Tree => self.parent[w] = v,
+            EdgeKind::Back if self.cycle.is_none() => {
+                self.cycle = Some(tree_path(&self.parent, w, v));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Topological order of `graph` by Kahn's algorithm, which repeatedly removes
+/// a vertex without incoming edges, in O(V + E) time. If vertices remain when
+/// none is free of incoming edges, they contain a cycle, which is returned.
+///
+/// Example:
+/// ```
+/// use algae::graph::{topological_sort_kahn, Digraph};
+///
+/// let g = Digraph::from_edges(4, [(0, 1), (0, 2), (2, 1), (1, 3)]);
+/// assert_eq!(topological_sort_kahn(&g), Ok(vec![0, 2, 1, 3]));
+/// ```
+pub fn topological_sort_kahn(graph: &Digraph) -> Result<Vec<usize>, Cycle> {
+    let mut indegree: Vec<usize> = graph.vertices().map(|v| graph.in_degree(v)).collect();
+    let mut queue = Queue::new();
+    for v in graph.vertices().filter(|&v| indegree[v] == 0) {
+        queue.push(v);
+    }
+    let mut order = Vec::with_capacity(graph.vertex_count());
+    while let Some(v) = queue.pop() {
+        order.push(v);
+        for &w in graph.adj(v) {
+            indegree[w] -= 1;
+            if indegree[w] == 0 {
+                queue.push(w);
+            }
+        }
+    }
+    if order.len() == graph.vertex_count() {
+        return Ok(order);
+    }
+
+    // Every remaining vertex has an incoming edge from another remaining
+    // vertex, so walking backwards along those edges must repeat a vertex.
+    let reverse = graph.reverse();
+    let mut step = vec![None; graph.vertex_count()];
+    let mut v = graph
+        .vertices()
+        .find(|&v| indegree[v] > 0)
+        .expect("Vertex left");
+    let mut walk = Vec::new();
+    while step[v].is_none() {
+        step[v] = Some(walk.len());
+        walk.push(v);
+        v = *reverse
+            .adj(v)
+            .iter()
+            .find(|&&u| indegree[u] > 0)
+            .expect("Incoming edge");
+    }
+    let mut vertices = walk.split_off(step[v].unwrap());
+    vertices.reverse();
+    Err(Cycle { vertices })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn random_digraph(n: usize, m: usize) -> Digraph {
+        Digraph::from_edges(
+            n,
+            (0..m).map(|_| (fastrand::usize(..n), fastrand::usize(..n))),
+        )
+    }
+
+    fn assert_order(g: &Digraph, order: &[usize]) {
+        let mut position = vec![usize::MAX; g.vertex_count()];
+        for (i, &v) in order.iter().enumerate() {
+            position[v] = i;
+        }
+        assert!(position.iter().all(|&p| p < order.len()));
+        assert!(g.edges().all(|(v, w)| position[v] < position[w]));
+    }
+
+    fn assert_cycle(g: &Digraph, cycle: &Cycle) {
+        let vs = &cycle.vertices;
+        assert!(!vs.is_empty());
+        for (i, &v) in vs.iter().enumerate() {
+            assert!(g.has_edge(v, vs[(i + 1) % vs.len()]), "{} in {:?}", v, vs);
+        }
+    }
+
+    #[test]
+    fn acyclic_orders() {
+        for _ in 0..50 {
+            let n = fastrand::usize(2..30);
+            // Edges from smaller to larger vertices can't form cycles.
+            let edges = random_digraph(n, fastrand::usize(..60))
+                .edges()
+                .collect::<Vec<_>>();
+            let g = Digraph::from_edges(n, edges.into_iter().filter(|(v, w)| v < w));
+            assert_order(&g, &topological_sort(&g).unwrap());
+            assert_order(&g, &topological_sort_kahn(&g).unwrap());
+        }
+    }
+
+    #[test]
+    fn cycle_witnesses() {
+        for _ in 0..100 {
+            let n = fastrand::usize(1..20);
+            let g = random_digraph(n, fastrand::usize(..40));
+            match (topological_sort(&g), topological_sort_kahn(&g)) {
+                (Ok(order), Ok(other)) => {
+                    assert_order(&g, &order);
+                    assert_order(&g, &other);
+                }
+                (Err(cycle), Err(other)) => {
+                    assert_cycle(&g, &cycle);
+                    assert_cycle(&g, &other);
+                }
+                _ => panic!("Algorithms disagree on {:?}", g),
+            }
+        }
+    }
+
+    #[test]
+    fn self_loop() {
+        let g = Digraph::from_edges(2, [(0, 1), (1, 1)]);
+        assert_eq!(topological_sort(&g).unwrap_err().vertices, [1]);
+        assert_eq!(topological_sort_kahn(&g).unwrap_err().vertices, [1]);
+        assert_eq!(
+            topological_sort_kahn(&g).unwrap_err().to_string(),
+            "cycle 1 1"
+        );
+    }
+}
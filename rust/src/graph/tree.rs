@@ -0,0 +1,316 @@
+//! Isomorphism of free trees, by the Aho-Hopcroft-Ullman (AHU) encoding of
+//! the tree rooted at its centroids.
+//!
+//! Two trees are isomorphic when a bijection between their vertices maps the
+//! edges of one onto the edges of the other. Rooting a tree at a centroid,
+//! which any isomorphism maps to a centroid, reduces the problem to rooted
+//! trees, which the AHU encoding decides by sorting subtrees bottom-up.
+use crate::{graph::Graph, strings::RollingHash};
+
+/// Fixed base of `canonical_hash`, so that hashes are reproducible.
+const HASH_BASE: u64 = 0x5851_f42d_4c95_7f2d;
+
+/// Whether the graph is a tree: it has at least one vertex, is connected, and
+/// has one edge fewer than vertices, which rules out cycles, self-loops and
+/// parallel edges.
+///
+/// Example:
+/// ```
+/// use algae::graph::{tree, Graph};
+///
+/// assert!(tree::is_tree(&Graph::from_edges(3, [(0, 1), (1, 2)])));
+/// assert!(!tree::is_tree(&Graph::from_edges(3, [(0, 1), (1, 0)])));
+/// assert!(!tree::is_tree(&Graph::new(0)));
+/// ```
+pub fn is_tree(graph: &Graph) -> bool {
+    let n = graph.vertex_count();
+    n > 0 && graph.edge_count() == n - 1 && bfs_order(graph, 0).0.len() == n
+}
+
+/// Vertices in breadth-first order from `root`, with the parent of each
+/// reached vertex, the root being its own parent.
+fn bfs_order(graph: &Graph, root: usize) -> (Vec<usize>, Vec<usize>) {
+    let mut parent = vec![usize::MAX; graph.vertex_count()];
+    parent[root] = root;
+    let mut order = vec![root];
+    let mut next = 0;
+    while next < order.len() {
+        let v = order[next];
+        next += 1;
+        for &w in graph.adj(v) {
+            if parent[w] == usize::MAX {
+                parent[w] = v;
+                order.push(w);
+            }
+        }
+    }
+    (order, parent)
+}
+
+/// Centroids of the tree, in increasing order, or `None` if the graph is not
+/// a tree: the vertices whose removal leaves no component with more than half
+/// of the vertices. A tree has one centroid, or two adjacent ones. Takes
+/// O(V) time.
+///
+/// Example:
+/// ```
+/// use algae::graph::{tree, Graph};
+///
+/// let path = Graph::from_edges(4, [(0, 1), (1, 2), (2, 3)]);
+/// assert_eq!(tree::centroids(&path), Some(vec![1, 2]));
+/// let star = Graph::from_edges(4, [(0, 1), (0, 2), (0, 3)]);
+/// assert_eq!(tree::centroids(&star), Some(vec![0]));
+/// ```
+pub fn centroids(tree: &Graph) -> Option<Vec<usize>> {
+    if !is_tree(tree) {
+        return None;
+    }
+    let n = tree.vertex_count();
+    let (order, parent) = bfs_order(tree, 0);
+    let mut size = vec![1; n];
+    // Size of the largest component left by removing each vertex.
+    let mut largest = vec![0; n];
+    for &v in order.iter().rev() {
+        largest[v] = largest[v].max(n - size[v]);
+        if v != 0 {
+            size[parent[v]] += size[v];
+            largest[parent[v]] = largest[parent[v]].max(size[v]);
+        }
+    }
+    Some(tree.vertices().filter(|&v| 2 * largest[v] <= n).collect())
+}
+
+/// AHU encoding of the tree rooted at `root`: each vertex is written as `(`,
+/// the encodings of its children, then `)`, the children being ordered by a
+/// rank which only depends on the shape of their subtrees.
+///
+/// The ranks are assigned level by level from the deepest: a vertex is keyed
+/// by the sorted ranks of its children, and the vertices of a level are
+/// ranked by sorting their keys. Sorting the keys takes O(V log V) time over
+/// all levels.
+fn rooted_encoding(tree: &Graph, root: usize) -> String {
+    let n = tree.vertex_count();
+    let (order, parent) = bfs_order(tree, root);
+    let mut depth = vec![0; n];
+    for &v in &order[1..] {
+        depth[v] = depth[parent[v]] + 1;
+    }
+    let mut rank = vec![0; n];
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); n];
+    // Breadth-first order lists the levels one after another.
+    let mut end = n;
+    while end > 0 {
+        let start = order[..end].partition_point(|&v| depth[v] < depth[order[end - 1]]);
+        let level = &order[start..end];
+        for &v in level {
+            children[v] = tree
+                .adj(v)
+                .iter()
+                .copied()
+                .filter(|&w| w != parent[v])
+                .collect();
+            children[v].sort_by_key(|&w| rank[w]);
+        }
+        let keys: Vec<Vec<usize>> = level
+            .iter()
+            .map(|&v| children[v].iter().map(|&w| rank[w]).collect())
+            .collect();
+        let mut by_key: Vec<usize> = (0..level.len()).collect();
+        by_key.sort_by(|&i, &j| keys[i].cmp(&keys[j]));
+        let mut r = 0;
+        for (k, &i) in by_key.iter().enumerate() {
+            if k > 0 && keys[i] != keys[by_key[k - 1]] {
+                r += 1;
+            }
+            rank[level[i]] = r;
+        }
+        end = start;
+    }
+
+    let mut encoding = String::with_capacity(2 * n);
+    // Vertices being written, with the number of their children written.
+    let mut stack = vec![(root, 0)];
+    encoding.push('(');
+    while let Some((v, written)) = stack.last_mut() {
+        match children[*v].get(*written) {
+            Some(&w) => {
+                *written += 1;
+                encoding.push('(');
+                stack.push((w, 0));
+            }
+            None => {
+                encoding.push(')');
+                stack.pop();
+            }
+        }
+    }
+    encoding
+}
+
+/// Canonical form of the tree, or `None` if the graph is not a tree: the
+/// least AHU encoding of the tree rooted at one of its centroids, a string of
+/// `2 * V` balanced parentheses. Two trees are isomorphic exactly when their
+/// canonical forms are equal. Takes O(V log V) time.
+///
+/// Example:
+/// ```
+/// use algae::graph::{tree, Graph};
+///
+/// let path = Graph::from_edges(3, [(0, 1), (1, 2)]);
+/// assert_eq!(tree::canonical_form(&path).as_deref(), Some("(()())"));
+/// let star = Graph::from_edges(4, [(3, 0), (3, 1), (3, 2)]);
+/// assert_eq!(tree::canonical_form(&star).as_deref(), Some("(()()())"));
+/// ```
+pub fn canonical_form(tree: &Graph) -> Option<String> {
+    centroids(tree)?
+        .into_iter()
+        .map(|root| rooted_encoding(tree, root))
+        .min()
+}
+
+/// Hash of the canonical form of the tree, or `None` if the graph is not a
+/// tree, so that isomorphic trees hash alike. The hash is polynomial with a
+/// fixed base, as `RollingHash::with_base`: distinct trees rarely collide,
+/// but `are_isomorphic` should confirm equal hashes when exactness matters.
+///
+/// Example:
+/// ```
+/// use algae::graph::{tree, Graph};
+///
+/// let a = Graph::from_edges(5, [(0, 1), (1, 2), (2, 3), (1, 4)]);
+/// let b = Graph::from_edges(5, [(4, 3), (3, 2), (2, 1), (2, 0)]);
+/// let c = Graph::from_edges(5, [(0, 1), (1, 2), (2, 3), (3, 4)]);
+/// assert_eq!(tree::canonical_hash(&a), tree::canonical_hash(&b));
+/// assert_ne!(tree::canonical_hash(&a), tree::canonical_hash(&c));
+/// ```
+pub fn canonical_hash(tree: &Graph) -> Option<u64> {
+    let form = canonical_form(tree)?;
+    Some(RollingHash::with_base(form.len(), HASH_BASE).hash(form.as_bytes()))
+}
+
+/// Whether both graphs are trees and isomorphic, by comparing their canonical
+/// forms. Takes O(V log V) time.
+///
+/// Example:
+/// ```
+/// use algae::graph::{tree, Graph};
+///
+/// // Spiders with legs of lengths 2, 1, 1 around different centres.
+/// let a = Graph::from_edges(5, [(0, 1), (1, 2), (0, 3), (0, 4)]);
+/// let b = Graph::from_edges(5, [(4, 3), (3, 2), (4, 1), (4, 0)]);
+/// assert!(tree::are_isomorphic(&a, &b));
+/// let path = Graph::from_edges(5, [(0, 1), (1, 2), (2, 3), (3, 4)]);
+/// assert!(!tree::are_isomorphic(&a, &path));
+/// ```
+pub fn are_isomorphic(t1: &Graph, t2: &Graph) -> bool {
+    if t1.vertex_count() != t2.vertex_count() {
+        return false;
+    }
+    match (canonical_form(t1), canonical_form(t2)) {
+        (Some(f1), Some(f2)) => f1 == f2,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use itertools::Itertools;
+
+    /// Tree where each vertex but the first hangs from a random earlier one,
+    /// under a random relabelling of the vertices.
+    fn random_tree(n: usize) -> Graph {
+        let mut label: Vec<usize> = (0..n).collect();
+        fastrand::shuffle(&mut label);
+        Graph::from_edges(n, (1..n).map(|v| (label[v], label[fastrand::usize(..v)])))
+    }
+
+    fn relabelled(g: &Graph) -> Graph {
+        let mut label: Vec<usize> = g.vertices().collect();
+        fastrand::shuffle(&mut label);
+        let mut edges: Vec<(usize, usize)> = g.edges().map(|(v, w)| (label[w], label[v])).collect();
+        fastrand::shuffle(&mut edges);
+        Graph::from_edges(g.vertex_count(), edges)
+    }
+
+    /// Isomorphism by trying every bijection between the vertices.
+    fn isomorphic_by_permutations(t1: &Graph, t2: &Graph) -> bool {
+        let n = t1.vertex_count();
+        n == t2.vertex_count()
+            && t1.edge_count() == t2.edge_count()
+            && (0..n)
+                .permutations(n)
+                .any(|p| t1.edges().all(|(v, w)| t2.has_edge(p[v], p[w])))
+    }
+
+    #[test]
+    fn against_permutations() {
+        for _ in 0..300 {
+            let n = fastrand::usize(1..7);
+            let (t1, t2) = (random_tree(n), random_tree(n));
+            let expected = isomorphic_by_permutations(&t1, &t2);
+            assert_eq!(are_isomorphic(&t1, &t2), expected);
+            if expected {
+                assert_eq!(canonical_hash(&t1), canonical_hash(&t2));
+            }
+        }
+    }
+
+    #[test]
+    fn invariant_under_relabelling() {
+        for _ in 0..100 {
+            let t = random_tree(fastrand::usize(1..200));
+            let u = relabelled(&t);
+            assert!(are_isomorphic(&t, &u));
+            let form = canonical_form(&t).unwrap();
+            assert_eq!(form.len(), 2 * t.vertex_count());
+            assert_eq!(canonical_form(&u), Some(form));
+            assert_eq!(canonical_hash(&t), canonical_hash(&u));
+        }
+    }
+
+    #[test]
+    fn centroids_by_removal() {
+        for _ in 0..100 {
+            let n = fastrand::usize(1..30);
+            let t = random_tree(n);
+            let expected: Vec<usize> = t
+                .vertices()
+                .filter(|&c| {
+                    // Components of the tree without `c`, by union by
+                    // relabeling.
+                    let mut component: Vec<usize> = t.vertices().collect();
+                    for (v, w) in t.edges().filter(|&(v, w)| v != c && w != c) {
+                        let (cv, cw) = (component[v], component[w]);
+                        component
+                            .iter_mut()
+                            .filter(|x| **x == cw)
+                            .for_each(|x| *x = cv);
+                    }
+                    t.vertices()
+                        .filter(|&v| v != c)
+                        .all(|v| 2 * component.iter().filter(|&&x| x == component[v]).count() <= n)
+                })
+                .collect();
+            let found = centroids(&t).unwrap();
+            assert_eq!(found, expected);
+            assert!(found.len() == 1 || t.has_edge(found[0], found[1]));
+        }
+    }
+
+    #[test]
+    fn not_trees() {
+        let cycle = Graph::from_edges(3, [(0, 1), (1, 2), (2, 0)]);
+        let forest = Graph::from_edges(4, [(0, 1), (2, 3)]);
+        let looped = Graph::from_edges(3, [(0, 1), (1, 1)]);
+        for g in [&cycle, &forest, &looped, &Graph::new(0)] {
+            assert!(!is_tree(g));
+            assert_eq!(centroids(g), None);
+            assert_eq!(canonical_hash(g), None);
+            assert!(!are_isomorphic(g, g));
+        }
+        let path = Graph::from_edges(3, [(0, 1), (1, 2)]);
+        assert!(!are_isomorphic(&path, &looped));
+        assert!(!are_isomorphic(&path, &random_tree(4)));
+    }
+}
@@ -0,0 +1,145 @@
+use std::ops::Range;
+
+/// Undirected graph over the vertices `0..n`, stored as adjacency lists.
+/// Parallel edges and self-loops are allowed: each edge `v-w` appears in the
+/// lists of both `v` and `w`, while a self-loop `v-v` appears once in the
+/// list of `v`.
+///
+/// Example:
+/// ```
+/// use algae::graph::Graph;
+///
+/// let mut g = Graph::from_edges(4, [(0, 1), (0, 2), (1, 2)]);
+/// g.add_edge(2, 3);
+/// assert_eq!(g.adj(2), [0, 1, 3]);
+/// assert_eq!(g.degree(2), 3);
+/// assert_eq!(g.edge_count(), 4);
+/// assert_eq!(g.vertices().filter(|&v| g.degree(v) == 2).count(), 2);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Graph {
+    adj: Vec<Vec<usize>>,
+    edges: usize,
+}
+
+impl Graph {
+    /// Graph with `n` vertices and no edges.
+    pub fn new(n: usize) -> Self {
+        Self {
+            adj: vec![Vec::new(); n],
+            edges: 0,
+        }
+    }
+
+    /// Graph with `n` vertices and the given edges.
+    pub fn from_edges<I: IntoIterator<Item = (usize, usize)>>(n: usize, edges: I) -> Self {
+        let mut g = Self::new(n);
+        for (v, w) in edges {
+            g.add_edge(v, w);
+        }
+        g
+    }
+
+    /// Number of vertices.
+    pub fn vertex_count(&self) -> usize {
+        self.adj.len()
+    }
+
+    /// Number of edges, counting parallel edges and self-loops.
+    pub fn edge_count(&self) -> usize {
+        self.edges
+    }
+
+    /// The vertices of the graph, `0..n`.
+    pub fn vertices(&self) -> Range<usize> {
+        0..self.adj.len()
+    }
+
+    /// Adds a new vertex without edges and returns it.
+    pub fn add_vertex(&mut self) -> usize {
+        self.adj.push(Vec::new());
+        self.adj.len() - 1
+    }
+
+    /// Adds the edge `v-w`. Panics if either vertex is out of range.
+    pub fn add_edge(&mut self, v: usize, w: usize) {
+        self.check_vertex(v);
+        self.check_vertex(w);
+        self.adj[v].push(w);
+        if v != w {
+            self.adj[w].push(v);
+        }
+        self.edges += 1;
+    }
+
+    /// Vertices adjacent to `v`, in the order the edges were added.
+    pub fn adj(&self, v: usize) -> &[usize] {
+        &self.adj[v]
+    }
+
+    /// Number of edges incident to `v`.
+    pub fn degree(&self, v: usize) -> usize {
+        self.adj[v].len()
+    }
+
+    /// Whether there is an edge `v-w`. Takes O(degree(v)) time.
+    pub fn has_edge(&self, v: usize, w: usize) -> bool {
+        self.adj[v].contains(&w)
+    }
+
+    /// Each edge once, as `(v, w)` with `v <= w`, ordered by `v`.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.vertices().flat_map(move |v| {
+            self.adj[v]
+                .iter()
+                .filter(move |&&w| v <= w)
+                .map(move |&w| (v, w))
+        })
+    }
+
+    fn check_vertex(&self, v: usize) {
+        assert!(
+            v < self.adj.len(),
+            "Vertex {} out of range for a graph with {} vertices",
+            v,
+            self.adj.len()
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn edges_n_degrees() {
+        let g = Graph::from_edges(5, [(0, 1), (1, 2), (2, 0), (3, 3), (0, 1)]);
+        assert_eq!(g.vertex_count(), 5);
+        assert_eq!(g.edge_count(), 5);
+        assert_eq!(g.adj(0), [1, 2, 1]);
+        assert_eq!(g.adj(3), [3]);
+        assert_eq!(g.degree(4), 0);
+        assert!(g.has_edge(2, 1) && g.has_edge(1, 2));
+        assert!(!g.has_edge(0, 4));
+
+        let edges: Vec<_> = g.edges().collect();
+        assert_eq!(edges, [(0, 1), (0, 2), (0, 1), (1, 2), (3, 3)]);
+        let degree_sum: usize = g.vertices().map(|v| g.degree(v)).sum();
+        assert_eq!(degree_sum, 2 * g.edge_count() - 1);
+    }
+
+    #[test]
+    fn growing() {
+        let mut g = Graph::default();
+        assert_eq!(g.vertex_count(), 0);
+        let (a, b) = (g.add_vertex(), g.add_vertex());
+        g.add_edge(a, b);
+        assert_eq!(g, Graph::from_edges(2, [(0, 1)]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn vertex_out_of_range() {
+        Graph::new(2).add_edge(0, 2);
+    }
+}
@@ -0,0 +1,97 @@
+use std::ops::Range;
+
+/// Weighted edge `from->to` of an `EdgeWeightedDigraph`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DirectedEdge {
+    pub from: usize,
+    pub to: usize,
+    pub weight: f64,
+}
+
+/// Directed graph over the vertices `0..n` with a weight on each edge, stored
+/// as adjacency lists of the edges leaving each vertex. The weights are
+/// usually lengths or costs for shortest path algorithms.
+///
+/// Example:
+/// ```
+/// use algae::graph::EdgeWeightedDigraph;
+///
+/// let g = EdgeWeightedDigraph::from_edges(3, [(0, 1, 2.5), (1, 2, 1.0), (0, 2, 4.0)]);
+/// let weights: Vec<f64> = g.adj(0).iter().map(|e| e.weight).collect();
+/// assert_eq!(weights, [2.5, 4.0]);
+/// assert_eq!(g.edge_count(), 3);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EdgeWeightedDigraph {
+    adj: Vec<Vec<DirectedEdge>>,
+    edges: usize,
+}
+
+impl EdgeWeightedDigraph {
+    /// Digraph with `n` vertices and no edges.
+    pub fn new(n: usize) -> Self {
+        Self {
+            adj: vec![Vec::new(); n],
+            edges: 0,
+        }
+    }
+
+    /// Digraph with `n` vertices and the given edges `(from, to, weight)`.
+    pub fn from_edges<I: IntoIterator<Item = (usize, usize, f64)>>(n: usize, edges: I) -> Self {
+        let mut g = Self::new(n);
+        for (from, to, weight) in edges {
+            g.add_edge(from, to, weight);
+        }
+        g
+    }
+
+    /// Number of vertices.
+    pub fn vertex_count(&self) -> usize {
+        self.adj.len()
+    }
+
+    /// Number of edges, counting parallel edges and self-loops.
+    pub fn edge_count(&self) -> usize {
+        self.edges
+    }
+
+    /// The vertices of the digraph, `0..n`.
+    pub fn vertices(&self) -> Range<usize> {
+        0..self.adj.len()
+    }
+
+    /// Adds the edge `from->to` with the given weight. Panics if either
+    /// vertex is out of range.
+    pub fn add_edge(&mut self, from: usize, to: usize, weight: f64) {
+        for v in [from, to] {
+            assert!(
+                v < self.adj.len(),
+                "Vertex {} out of range for a digraph with {} vertices",
+                v,
+                self.adj.len()
+            );
+        }
+        self.adj[from].push(DirectedEdge { from, to, weight });
+        self.edges += 1;
+    }
+
+    /// Edges leaving `v`, in the order they were added.
+    pub fn adj(&self, v: usize) -> &[DirectedEdge] {
+        &self.adj[v]
+    }
+
+    /// All edges, ordered by their tail.
+    pub fn edges(&self) -> impl Iterator<Item = &DirectedEdge> + '_ {
+        self.adj.iter().flatten()
+    }
+
+    /// Digraph with the same vertices and every edge reversed, keeping its
+    /// weight.
+    pub fn reverse(&self) -> Self {
+        let mut r = Self::new(self.vertex_count());
+        for e in self.edges() {
+            r.add_edge(e.to, e.from, e.weight);
+        }
+        r
+    }
+}
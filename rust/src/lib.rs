@@ -1,4 +1,51 @@
+/// Emits a `tracing` event at the trace level when the `tracing` feature is
+/// enabled, otherwise expands to nothing.
+macro_rules! trace_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::trace!($($arg)*);
+    };
+}
+
+/// Enters a `tracing` span at the trace level when the `tracing` feature is
+/// enabled. The span is exited at the end of the enclosing scope.
+macro_rules! trace_span {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!($($arg)*).entered();
+    };
+}
+
+/// Records a swap of two elements in the statistics of `sort::stats` when the
+/// `stats` feature is enabled, otherwise expands to nothing.
+macro_rules! stats_swap {
+    () => {
+        #[cfg(feature = "stats")]
+        crate::sort::stats::record_swap();
+    };
+}
+
+/// Records one more level of recursion in the statistics of `sort::stats`
+/// when the `stats` feature is enabled. The level is left at the end of the
+/// enclosing scope.
+macro_rules! stats_depth {
+    () => {
+        #[cfg(feature = "stats")]
+        let _depth = crate::sort::stats::DepthGuard::enter();
+    };
+}
+
+pub mod dp;
 pub mod ds;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod graph;
 pub mod math;
+pub mod resume;
 pub mod search;
+#[cfg(feature = "simd")]
+pub mod simd;
 pub mod sort;
+pub mod strings;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -0,0 +1,137 @@
+//! Read-only views of two-dimensional arrays, shared by the algorithms
+//! working on matrices regardless of how their entries are stored.
+
+/// A matrix with `n_rows()` rows and `n_cols()` columns, whose entries can be
+/// read by their row and column indices.
+pub trait Matrix<T> {
+    /// Number of rows.
+    fn n_rows(&self) -> usize;
+
+    /// Number of columns.
+    fn n_cols(&self) -> usize;
+
+    /// Entry at row `i` and column `j`. Panics if out of bounds.
+    fn get(&self, i: usize, j: usize) -> &T;
+
+    /// Whether the matrix has no entries.
+    fn is_empty(&self) -> bool {
+        self.n_rows() == 0 || self.n_cols() == 0
+    }
+}
+
+/// Rows stored as separate vectors, which are assumed to be of the same
+/// length.
+impl<T> Matrix<T> for [Vec<T>] {
+    fn n_rows(&self) -> usize {
+        self.len()
+    }
+
+    fn n_cols(&self) -> usize {
+        self.first().map_or(0, |row| row.len())
+    }
+
+    fn get(&self, i: usize, j: usize) -> &T {
+        &self[i][j]
+    }
+}
+
+impl<T> Matrix<T> for Vec<Vec<T>> {
+    fn n_rows(&self) -> usize {
+        self.as_slice().n_rows()
+    }
+
+    fn n_cols(&self) -> usize {
+        self.as_slice().n_cols()
+    }
+
+    fn get(&self, i: usize, j: usize) -> &T {
+        &self[i][j]
+    }
+}
+
+/// Matrix over a slice holding its entries in row-major order.
+///
+/// Example:
+/// ```
+/// use algae::math::{Matrix, MatrixView};
+///
+/// let entries = [1, 2, 3, 4, 5, 6];
+/// let m = MatrixView::new(&entries, 2, 3);
+/// assert_eq!(*m.get(1, 0), 4);
+/// assert_eq!(m.row(1), [4, 5, 6]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct MatrixView<'a, T> {
+    entries: &'a [T],
+    n_rows: usize,
+    n_cols: usize,
+}
+
+impl<'a, T> MatrixView<'a, T> {
+    /// Views `entries` as a matrix with `n_rows` rows and `n_cols` columns.
+    /// Panics if the number of entries doesn't match.
+    pub fn new(entries: &'a [T], n_rows: usize, n_cols: usize) -> Self {
+        assert_eq!(
+            Some(entries.len()),
+            n_rows.checked_mul(n_cols),
+            "A {}x{} matrix can't have {} entries",
+            n_rows,
+            n_cols,
+            entries.len()
+        );
+        Self {
+            entries,
+            n_rows,
+            n_cols,
+        }
+    }
+
+    /// The entries of the row `i`.
+    pub fn row(&self, i: usize) -> &'a [T] {
+        &self.entries[i * self.n_cols..(i + 1) * self.n_cols]
+    }
+}
+
+impl<T> Matrix<T> for MatrixView<'_, T> {
+    fn n_rows(&self) -> usize {
+        self.n_rows
+    }
+
+    fn n_cols(&self) -> usize {
+        self.n_cols
+    }
+
+    fn get(&self, i: usize, j: usize) -> &T {
+        assert!(j < self.n_cols, "Column {} out of bounds", j);
+        &self.entries[i * self.n_cols + j]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn views_agree() {
+        let rows = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        let entries = rows.concat();
+        let view = MatrixView::new(&entries, 2, 3);
+        assert_eq!(
+            (rows.n_rows(), rows.n_cols()),
+            (view.n_rows(), view.n_cols())
+        );
+        for i in 0..2 {
+            for j in 0..3 {
+                assert_eq!(rows.get(i, j), view.get(i, j));
+            }
+        }
+        assert!(Vec::<Vec<i32>>::new().is_empty());
+        assert!(Matrix::is_empty(&MatrixView::<i32>::new(&[], 3, 0)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn wrong_shape() {
+        MatrixView::new(&[1, 2, 3], 2, 2);
+    }
+}
@@ -10,6 +10,14 @@ pub use poly::*;
 mod fft;
 pub use fft::*;
 
+mod ntt;
+pub use ntt::*;
+
+mod matrix;
+pub use matrix::*;
+
+pub mod safe;
+
 /// Given a number `n`, computes and returns the smallest power of 2 greater
 /// than or equal to `n`. Assumes 0 as a power of 2.
 pub fn next_power_of_2(n: usize) -> usize {
@@ -19,7 +27,8 @@ pub fn next_power_of_2(n: usize) -> usize {
     } else if (n & (n - 1)) == 0 {
         n
     } else {
-        0x8000000000000000 >> (n.leading_zeros() - 1)
+        // The bit length of `n` is independent of the width of `usize`.
+        1 << (usize::BITS - n.leading_zeros())
     }
 }
 
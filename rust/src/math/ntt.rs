@@ -0,0 +1,204 @@
+//! Number theoretic transform (NTT): the analogue of the FFT over the finite
+//! field `Z/pZ`, which allows for exact polynomial multiplication of integer
+//! coefficients (modulo `p`) without floating point errors.
+use super::next_power_of_2;
+
+/// Prime modulus `p = 119 * 2^23 + 1` used by the NTT. Since `2^23` divides
+/// `p - 1`, the field has roots of unity of every power of 2 order up to
+/// `2^23`, which bounds the length of the transforms.
+pub const NTT_MODULUS: u64 = 998_244_353;
+
+// The butterflies multiply residues as `u64`, which cannot overflow as long as
+// they fit in 32 bits.
+const _: () = assert!(NTT_MODULUS < 1 << 32);
+
+/// Maximum length of a transform: the largest power of 2 dividing
+/// `NTT_MODULUS - 1`.
+pub const NTT_MAX_LEN: usize = 1 << 23;
+
+/// Generator of the multiplicative group of `Z/pZ` for `p = NTT_MODULUS`.
+const PRIMITIVE_ROOT: u64 = 3;
+
+/// Computes `base^exp mod modulus` by repeated squaring. Runs in O(log exp).
+/// Products are computed in `u128`, so any nonzero `modulus` is allowed.
+pub fn mod_pow(mut base: u64, mut exp: u64, modulus: u64) -> u64 {
+    let mul_mod = |a: u64, b: u64| (a as u128 * b as u128 % modulus as u128) as u64;
+    let mut result = 1 % modulus;
+    base %= modulus;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base);
+        }
+        base = mul_mod(base, base);
+        exp >>= 1;
+    }
+    result
+}
+
+/// In-place iterative NTT of `a`, whose length should be a power of 2 of at
+/// most `NTT_MAX_LEN`. All entries are assumed to be reduced modulo
+/// `NTT_MODULUS`. If `invert` is `true`, computes the inverse transform
+/// instead.
+pub fn ntt(a: &mut [u64], invert: bool) {
+    let n = a.len();
+    if n <= 1 {
+        return;
+    }
+    assert!(n.is_power_of_two(), "NTT length {} is not a power of 2", n);
+    assert!(
+        n <= NTT_MAX_LEN,
+        "NTT length {} exceeds the maximum of {}",
+        n,
+        NTT_MAX_LEN
+    );
+
+    // Bit-reversal permutation, so that the butterflies can be done in place.
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j ^= bit;
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        // Primitive `len`-th root of unity (or its inverse).
+        let mut root = mod_pow(PRIMITIVE_ROOT, (NTT_MODULUS - 1) / len as u64, NTT_MODULUS);
+        if invert {
+            root = mod_pow(root, NTT_MODULUS - 2, NTT_MODULUS);
+        }
+
+        for chunk in a.chunks_mut(len) {
+            let (even, odd) = chunk.split_at_mut(len / 2);
+            let mut omega = 1;
+            for (u, v) in even.iter_mut().zip(odd.iter_mut()) {
+                let t = *v * omega % NTT_MODULUS;
+                *v = (*u + NTT_MODULUS - t) % NTT_MODULUS;
+                *u = (*u + t) % NTT_MODULUS;
+                omega = omega * root % NTT_MODULUS;
+            }
+        }
+        len <<= 1;
+    }
+
+    if invert {
+        let n_inv = mod_pow(n as u64, NTT_MODULUS - 2, NTT_MODULUS);
+        a.iter_mut().for_each(|x| *x = *x * n_inv % NTT_MODULUS);
+    }
+}
+
+/// Multiplies the polynomials with coefficients `a` and `b` modulo
+/// `NTT_MODULUS`, returning the coefficients of the product. Runs in
+/// O(n log n) where `n = a.len() + b.len()`, which must be at most
+/// `NTT_MAX_LEN + 1`.
+///
+/// Example:
+/// ```
+/// use algae::math::ntt_multiply;
+///
+/// // (1 + 2x) * (3 + 4x) = 3 + 10x + 8x^2
+/// assert_eq!(ntt_multiply(&[1, 2], &[3, 4]), vec![3, 10, 8]);
+/// ```
+pub fn ntt_multiply(a: &[u64], b: &[u64]) -> Vec<u64> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    let len = a.len() + b.len() - 1;
+    assert!(
+        len <= NTT_MAX_LEN,
+        "Product of length {} exceeds the maximum NTT length of {}",
+        len,
+        NTT_MAX_LEN
+    );
+    let n = next_power_of_2(len);
+
+    let mut fa: Vec<u64> = a.iter().map(|x| x % NTT_MODULUS).collect();
+    let mut fb: Vec<u64> = b.iter().map(|x| x % NTT_MODULUS).collect();
+    fa.resize(n, 0);
+    fb.resize(n, 0);
+
+    ntt(&mut fa, false);
+    ntt(&mut fb, false);
+    fa.iter_mut()
+        .zip(fb.iter())
+        .for_each(|(x, y)| *x = *x * y % NTT_MODULUS);
+    ntt(&mut fa, true);
+
+    fa.truncate(len);
+    fa
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn naive_multiply(a: &[u64], b: &[u64]) -> Vec<u64> {
+        let mut c = vec![0; a.len() + b.len() - 1];
+        for (i, x) in a.iter().enumerate() {
+            for (j, y) in b.iter().enumerate() {
+                c[i + j] = (c[i + j] + x * y) % NTT_MODULUS;
+            }
+        }
+        c
+    }
+
+    #[test]
+    fn pow() {
+        assert_eq!(mod_pow(2, 10, 1_000), 24);
+        assert_eq!(mod_pow(3, 0, 7), 1);
+        assert_eq!(mod_pow(5, 3, 1), 0);
+        assert_eq!(mod_pow(PRIMITIVE_ROOT, NTT_MODULUS - 1, NTT_MODULUS), 1);
+
+        // Moduli beyond 32 bits, whose residues overflow `u64` when squared.
+        assert_eq!(mod_pow(u64::MAX - 1, 2, u64::MAX), 1);
+        assert_eq!(mod_pow(2, 64, u64::MAX), 1);
+        // Fermat's little theorem for the Mersenne prime `2^61 - 1`.
+        let p = (1 << 61) - 1;
+        assert_eq!(mod_pow(3, p - 1, p), 1);
+    }
+
+    #[test]
+    fn transform_round_trip() {
+        let xs: Vec<u64> = (0..64).map(|_| fastrand::u64(..NTT_MODULUS)).collect();
+        let mut ys = xs.clone();
+        ntt(&mut ys, false);
+        ntt(&mut ys, true);
+        assert_eq!(xs, ys);
+    }
+
+    #[test]
+    fn multiply() {
+        assert_eq!(ntt_multiply(&[], &[1, 2]), Vec::<u64>::new());
+        assert_eq!(ntt_multiply(&[5], &[7]), vec![35]);
+
+        for _ in 0..20 {
+            let a: Vec<u64> = (0..fastrand::usize(1..50))
+                .map(|_| fastrand::u64(..NTT_MODULUS))
+                .collect();
+            let b: Vec<u64> = (0..fastrand::usize(1..50))
+                .map(|_| fastrand::u64(..NTT_MODULUS))
+                .collect();
+            assert_eq!(ntt_multiply(&a, &b), naive_multiply(&a, &b));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the maximum")]
+    fn transform_too_long() {
+        ntt(&mut vec![0; 2 * NTT_MAX_LEN], false);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the maximum")]
+    fn product_too_long() {
+        let a = vec![0; NTT_MAX_LEN / 2 + 1];
+        ntt_multiply(&a, &a);
+    }
+}
@@ -0,0 +1,212 @@
+//! Overflow-aware arithmetic helpers for the index computations done by the
+//! searching and sorting algorithms.
+use core::ops::Range;
+
+/// Midpoint of the closed interval `[lo, hi]`, rounded down. The computation
+/// `lo + (hi - lo) / 2` never overflows, as opposed to the usual
+/// `(lo + hi) / 2`, which does for indices near `usize::MAX / 2`. Returns
+/// `None` if the interval is empty, that is, if `hi < lo`.
+///
+/// Example:
+/// ```
+/// use algae::math::safe::checked_midpoint;
+///
+/// assert_eq!(checked_midpoint(2, 7), Some(4));
+/// assert_eq!(checked_midpoint(usize::MAX - 2, usize::MAX), Some(usize::MAX - 1));
+/// assert_eq!(checked_midpoint(3, 2), None);
+/// ```
+#[inline]
+pub fn checked_midpoint(lo: usize, hi: usize) -> Option<usize> {
+    hi.checked_sub(lo).map(|len| lo + len / 2)
+}
+
+/// Midpoint of the half-open range `start..end`, rounded down, so that both
+/// `start..mid` and `mid..end` are non-empty for ranges of length at least 2.
+/// Returns `None` if the range is empty.
+///
+/// Example:
+/// ```
+/// use algae::math::safe::range_midpoint;
+///
+/// assert_eq!(range_midpoint(&(2..8)), Some(5));
+/// assert_eq!(range_midpoint(&(3..4)), Some(3));
+/// assert_eq!(range_midpoint(&(4..4)), None);
+/// ```
+#[inline]
+pub fn range_midpoint(range: &Range<usize>) -> Option<usize> {
+    (!range.is_empty()).then(|| range.start + (range.end - range.start) / 2)
+}
+
+/// Splits the half-open range `start..end` around the index `pivot`, returning
+/// the ranges `start..pivot` and `pivot + 1..end`, which exclude the pivot.
+/// This is the recursion step shared by algorithms that settle a single index
+/// and recurse on both sides of it, such as quicksort and binary search.
+///
+/// Example:
+/// ```
+/// use algae::math::safe::split_around;
+///
+/// assert_eq!(split_around(2..8, 5), (2..5, 6..8));
+/// assert_eq!(split_around(0..1, 0), (0..0, 1..1));
+/// ```
+#[inline]
+pub fn split_around(range: Range<usize>, pivot: usize) -> (Range<usize>, Range<usize>) {
+    debug_assert!(
+        range.contains(&pivot),
+        "pivot {} outside of {:?}",
+        pivot,
+        range
+    );
+    (range.start..pivot, (pivot + 1)..range.end)
+}
+
+/// Computes `base^exp` by repeated squaring, returning `None` if any step of
+/// the computation overflows a `u64`.
+///
+/// Example:
+/// ```
+/// use algae::math::safe::checked_pow;
+///
+/// assert_eq!(checked_pow(3, 4), Some(81));
+/// assert_eq!(checked_pow(2, 63), Some(1 << 63));
+/// assert_eq!(checked_pow(2, 64), None);
+/// ```
+pub fn checked_pow(mut base: u64, mut exp: u32) -> Option<u64> {
+    let mut result: u64 = 1;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result.checked_mul(base)?;
+        }
+        exp >>= 1;
+        // Only square the base if it is going to be used again, otherwise we
+        // would report overflows that do not affect the result.
+        if exp > 0 {
+            base = base.checked_mul(base)?;
+        }
+    }
+    Some(result)
+}
+
+/// Absolute difference `|a - b|`, which never overflows: see `AbsDiff`, to
+/// which it delegates. The difference of two signed integers is given in the
+/// unsigned type of the same width.
+///
+/// Example:
+/// ```
+/// use algae::math::safe::abs_diff;
+///
+/// assert_eq!(abs_diff(3_usize, 10), 7);
+/// assert_eq!(abs_diff(10_usize, 3), 7);
+/// assert_eq!(abs_diff(i32::MIN, i32::MAX), u32::MAX);
+/// assert_eq!(abs_diff(-2.5, 1.0), 3.5);
+/// ```
+#[inline]
+pub fn abs_diff<T: AbsDiff>(a: T, b: T) -> T::Output {
+    a.abs_diff(b)
+}
+
+/// Values with a distance `|a - b|` which never overflows: the distance
+/// between integers is given in the unsigned type of the same width, which
+/// holds every difference of two values of the signed one.
+///
+/// Example:
+/// ```
+/// use algae::math::safe::AbsDiff;
+///
+/// assert_eq!(AbsDiff::abs_diff(i32::MIN, i32::MAX), u32::MAX);
+/// assert_eq!(AbsDiff::abs_diff(-2.5, 1.0), 3.5);
+/// ```
+pub trait AbsDiff: Copy {
+    /// Type of the distances, ordered as the distances are.
+    type Output: PartialOrd;
+
+    /// Distance `|self - other|`.
+    fn abs_diff(self, other: Self) -> Self::Output;
+}
+
+macro_rules! impl_abs_diff_int {
+    ($($t:ty => $u:ty),*) => {$(
+        impl AbsDiff for $t {
+            type Output = $u;
+
+            #[inline]
+            fn abs_diff(self, other: Self) -> $u {
+                <$t>::abs_diff(self, other)
+            }
+        }
+    )*};
+}
+
+impl_abs_diff_int!(
+    i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize,
+    u8 => u8, u16 => u16, u32 => u32, u64 => u64, u128 => u128, usize => usize
+);
+
+macro_rules! impl_abs_diff_float {
+    ($($t:ty),*) => {$(
+        impl AbsDiff for $t {
+            type Output = $t;
+
+            #[inline]
+            fn abs_diff(self, other: Self) -> $t {
+                (self - other).abs()
+            }
+        }
+    )*};
+}
+
+impl_abs_diff_float!(f32, f64);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn midpoint() {
+        assert_eq!(checked_midpoint(0, 0), Some(0));
+        assert_eq!(checked_midpoint(0, 1), Some(0));
+        assert_eq!(checked_midpoint(4, 6), Some(5));
+        assert_eq!(checked_midpoint(1, 0), None);
+
+        // `(lo + hi) / 2` would overflow here.
+        let lo = usize::MAX / 2 + 1;
+        assert_eq!(checked_midpoint(lo, usize::MAX), Some(lo + (lo - 1) / 2));
+    }
+
+    #[test]
+    fn ranges() {
+        assert_eq!(range_midpoint(&(0..1)), Some(0));
+        assert_eq!(range_midpoint(&(0..2)), Some(1));
+        assert_eq!(
+            range_midpoint(&(usize::MAX - 3..usize::MAX)),
+            Some(usize::MAX - 2)
+        );
+
+        let (left, right) = split_around(usize::MAX - 3..usize::MAX, usize::MAX - 1);
+        assert_eq!(left, usize::MAX - 3..usize::MAX - 1);
+        assert!(right.is_empty());
+    }
+
+    #[test]
+    fn pow() {
+        assert_eq!(checked_pow(0, 0), Some(1));
+        assert_eq!(checked_pow(7, 0), Some(1));
+        assert_eq!(checked_pow(0, 5), Some(0));
+        assert_eq!(checked_pow(10, 19), Some(10_000_000_000_000_000_000));
+        assert_eq!(checked_pow(10, 20), None);
+        assert_eq!(checked_pow(u64::MAX, 1), Some(u64::MAX));
+    }
+
+    #[test]
+    fn absolute_difference() {
+        assert_eq!(abs_diff(0_u8, 255), 255);
+        assert_eq!(abs_diff(usize::MAX, 0), usize::MAX);
+        assert_eq!(abs_diff(-3, -7), 4_u32);
+        assert_eq!(abs_diff(i32::MIN, i32::MAX), u32::MAX);
+
+        assert_eq!(AbsDiff::abs_diff(i8::MIN, i8::MAX), 255_u8);
+        assert_eq!(AbsDiff::abs_diff(i64::MAX, i64::MIN), u64::MAX);
+        assert_eq!(AbsDiff::abs_diff(isize::MIN, 0), isize::MIN.unsigned_abs());
+        assert_eq!(AbsDiff::abs_diff(3_u16, 10), 7);
+    }
+}
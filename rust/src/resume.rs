@@ -0,0 +1,18 @@
+//! Checkpointing of long-running algorithms.
+//!
+//! Algorithms working on huge inputs, such as the external merge sort of
+//! `sort::external` or the suffix array construction of
+//! `strings::SuffixArrayBuilder`, may take hours to complete. Their in-progress state can be
+//! saved with `Resumable::checkpoint` and restored with `Resumable::resume`,
+//! so that a computation survives restarts of the process.
+use std::io::{self, Read, Write};
+
+/// State of an algorithm that can be saved and later restored, possibly by a
+/// different process.
+pub trait Resumable: Sized {
+    /// Writes the current state to `output`.
+    fn checkpoint<W: Write>(&self, output: W) -> io::Result<()>;
+
+    /// Restores a state written by `checkpoint` from `input`.
+    fn resume<R: Read>(input: R) -> io::Result<Self>;
+}
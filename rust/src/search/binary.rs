@@ -1,35 +1,626 @@
-// TODO: Wrap binary search into a `BinarySearch` struct and implement the
-// `Searcher` trait.
+use crate::{
+    math::safe::{range_midpoint, split_around, AbsDiff},
+    search::{bisect, Searcher},
+};
+use num::PrimInt;
+use std::{cmp::Ordering, ops::Range};
 
 /// Binary searches for the `search`. Assumes `xs` is sorted. If the element is
-/// found at `xs[idx]`, returns `Some(idx)`, otherwise, returns `None`.
+/// found at `xs[idx]`, returns `Some(idx)`, otherwise, returns `None`. The
+/// search narrows down the half-open range `lo..hi` in a loop, so empty slices
+/// need no special care.
+///
+/// Example:
+/// ```
+/// use algae::search::binary_search;
+///
+/// assert_eq!(binary_search(&[1, 3, 5, 7], &5), Some(2));
+/// assert_eq!(binary_search(&[1, 3, 5, 7], &4), None);
+/// assert_eq!(binary_search(&[], &4), None);
+/// ```
 pub fn binary_search<T: PartialOrd>(xs: &[T], search: &T) -> Option<usize> {
-    _binary_search(xs, 0, xs.len() - 1, search)
+    binary_search_by(xs, |x| compare(x, search))
 }
 
-fn _binary_search<T: PartialOrd>(xs: &[T], low: usize, top: usize, search: &T) -> Option<usize> {
-    // If this is the last search, the element was not found.
-    if top < low {
-        return None;
+/// Binary searches the sorted `xs` with the comparator `cmp`, which returns
+/// whether an element is less than, equal to, or greater than the target, as
+/// in `slice::binary_search_by`. Returns the index of an element for which
+/// `cmp` returns `Ordering::Equal`, if any.
+///
+/// Example:
+/// ```
+/// use algae::search::binary_search_by;
+///
+/// let xs = [1.0_f64, 2.5, 4.0];
+/// assert_eq!(binary_search_by(&xs, |x| x.total_cmp(&2.5)), Some(1));
+/// assert_eq!(binary_search_by(&xs, |x| x.total_cmp(&3.0)), None);
+/// ```
+pub fn binary_search_by<T, F>(xs: &[T], mut cmp: F) -> Option<usize>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    let mut range = 0..xs.len();
+    while let Some(mid) = range_midpoint(&range) {
+        match cmp(&xs[mid]) {
+            Ordering::Equal => return Some(mid),
+            Ordering::Less => range.start = mid + 1,
+            Ordering::Greater => range.end = mid,
+        }
     }
+    None
+}
+
+/// Binary searches for `key` in `xs`, sorted by the key extracted from each
+/// element by `f`, without building a separate vector of keys.
+///
+/// Example:
+/// ```
+/// use algae::search::binary_search_by_key;
+///
+/// struct Employee {
+///     name: &'static str,
+///     age: u32,
+/// }
+///
+/// let staff = [
+///     Employee { name: "Ada", age: 28 },
+///     Employee { name: "Alan", age: 41 },
+///     Employee { name: "Grace", age: 53 },
+/// ];
+/// let found = binary_search_by_key(&staff, &41, |e| e.age);
+/// assert_eq!(found.map(|i| staff[i].name), Some("Alan"));
+/// assert_eq!(binary_search_by_key(&staff, &30, |e| e.age), None);
+/// ```
+pub fn binary_search_by_key<T, K, F>(xs: &[T], key: &K, mut f: F) -> Option<usize>
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    binary_search_by(xs, |x| compare(&f(x), key))
+}
+
+/// Binary searches for `search` in the sorted `xs`, with the same ergonomics
+/// as the `binary_search` of slices in the standard library: returns `Ok(idx)`
+/// if `xs[idx]` is equal to `search`, and otherwise `Err(idx)` with the index
+/// where `search` could be inserted keeping `xs` sorted. When `search` occurs
+/// several times, the index of its first occurrence is returned.
+///
+/// Example:
+/// ```
+/// use algae::search::binary_search_insertion;
+///
+/// let mut xs = vec![1, 3, 3, 8];
+/// assert_eq!(binary_search_insertion(&xs, &3), Ok(1));
+/// if let Err(idx) = binary_search_insertion(&xs, &5) {
+///     xs.insert(idx, 5);
+/// }
+/// assert_eq!(xs, [1, 3, 3, 5, 8]);
+/// ```
+pub fn binary_search_insertion<T: PartialOrd>(xs: &[T], search: &T) -> Result<usize, usize> {
+    binary_search_insertion_by(xs, |x| compare(x, search))
+}
+
+/// Version of `binary_search_insertion` with a comparator, as
+/// `binary_search_by`.
+pub fn binary_search_insertion_by<T, F>(xs: &[T], mut cmp: F) -> Result<usize, usize>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    let idx = lower_bound_by(xs, &mut cmp);
+    match xs.get(idx).map(cmp) {
+        Some(Ordering::Equal) => Ok(idx),
+        _ => Err(idx),
+    }
+}
 
-    let mid = (top + low) / 2;
-    let x = &xs[mid];
+/// Version of `binary_search_insertion` with a key extraction function, as
+/// `binary_search_by_key`.
+pub fn binary_search_insertion_by_key<T, K, F>(xs: &[T], key: &K, mut f: F) -> Result<usize, usize>
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    binary_search_insertion_by(xs, |x| compare(&f(x), key))
+}
+
+/// Exponential search for `search` in the sorted `xs`: probes the indices 0,
+/// 1, 3, 7, 15, ... until passing `search`, then binary searches the last
+/// gap.
+/// Takes O(log i) time when the element is found at index `i`, which beats
+/// binary search when the elements sought lie near the beginning of `xs`.
+///
+/// Example:
+/// ```
+/// use algae::search::exponential_search;
+///
+/// let xs: Vec<u32> = (0..1_000).map(|x| 2 * x).collect();
+/// assert_eq!(exponential_search(&xs, &6), Some(3));
+/// assert_eq!(exponential_search(&xs, &7), None);
+/// ```
+pub fn exponential_search<T: PartialOrd>(xs: &[T], search: &T) -> Option<usize> {
+    exponential_search_by(xs, |x| compare(x, search))
+}
+
+/// Version of `exponential_search` with a comparator, as `binary_search_by`.
+pub fn exponential_search_by<T, F>(xs: &[T], mut cmp: F) -> Option<usize>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    // Every element before `lo` is less than the target.
+    let (mut lo, mut bound) = (0, 1);
+    while bound <= xs.len() && cmp(&xs[bound - 1]) == Ordering::Less {
+        lo = bound;
+        bound = bound.saturating_mul(2);
+    }
+    let hi = bound.min(xs.len());
+    binary_search_by(&xs[lo..hi], cmp).map(|idx| lo + idx)
+}
+
+/// Version of `exponential_search` with a key extraction function, as
+/// `binary_search_by_key`.
+pub fn exponential_search_by_key<T, K, F>(xs: &[T], key: &K, mut f: F) -> Option<usize>
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    exponential_search_by(xs, |x| compare(&f(x), key))
+}
 
-    if x == search {
-        return Some(mid);
-    } else if x < search {
+/// Galloping search over the unbounded non-decreasing sequence `gen(0),
+/// gen(1), ...`, given by its index function, for the first index `i` with
+/// `gen(i) >= target`. Probes the indices 1, 2, 4, 8, ... until reaching
+/// `target`, then binary searches the last gap, taking O(log i) evaluations of
+/// `gen`. Returns `None` if no index up to `usize::MAX` reaches `target`.
+///
+/// Example:
+/// ```
+/// use algae::search::unbounded_search;
+///
+/// // The first `n` whose triangular number `n (n + 1) / 2` reaches a million.
+/// let n = unbounded_search(|n| n * (n + 1) / 2, &1_000_000).unwrap();
+/// assert_eq!(n, 1414);
+/// assert!(1413 * 1414 / 2 < 1_000_000);
+/// ```
+pub fn unbounded_search<T, F>(gen: F, target: &T) -> Option<usize>
+where
+    T: PartialOrd,
+    F: Fn(usize) -> T,
+{
+    if gen(0) >= *target {
+        return Some(0);
+    }
+    // Every index before `lo` is known to be below `target`.
+    let (mut lo, mut bound) = (1, 1);
+    while gen(bound) < *target {
+        if bound == usize::MAX {
+            return None;
+        }
+        lo = bound + 1;
+        bound = bound.saturating_mul(2);
+    }
+    // Since `gen(bound)` reaches `target`, the answer lies in `lo..=bound`.
+    Some(binary_search_answer(lo, bound, |i| gen(i) >= *target))
+}
+
+/// Recursive version of `binary_search`, recursing on the half of `xs` that
+/// may contain `search`.
+pub fn binary_search_recursive<T: PartialOrd>(xs: &[T], search: &T) -> Option<usize> {
+    binary_search_recursive_by(xs, |x| compare(x, search))
+}
+
+/// Version of `binary_search_recursive` with a comparator, as
+/// `binary_search_by`.
+pub fn binary_search_recursive_by<T, F>(xs: &[T], mut cmp: F) -> Option<usize>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    _binary_search(xs, 0..xs.len(), &mut cmp)
+}
+
+/// Version of `binary_search_recursive` with a key extraction function, as
+/// `binary_search_by_key`.
+pub fn binary_search_recursive_by_key<T, K, F>(xs: &[T], key: &K, mut f: F) -> Option<usize>
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    binary_search_recursive_by(xs, |x| compare(&f(x), key))
+}
+
+fn _binary_search<T, F>(xs: &[T], range: Range<usize>, cmp: &mut F) -> Option<usize>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    // If the range is empty, the element was not found.
+    let mid = range_midpoint(&range)?;
+    let (left, right) = split_around(range, mid);
+
+    match cmp(&xs[mid]) {
+        Ordering::Equal => Some(mid),
         // Search the right slice.
-        return _binary_search(xs, mid + 1, top, search);
+        Ordering::Less => _binary_search(xs, right, cmp),
+        // Search the left slice.
+        Ordering::Greater => _binary_search(xs, left, cmp),
+    }
+}
+
+/// Index of the first element of the sorted `xs` which is not less than `x`,
+/// or `xs.len()` if there is none. If `x` occurs in `xs`, this is its first
+/// occurrence. Same as `bisect::bisect_left`.
+///
+/// Example:
+/// ```
+/// use algae::search::lower_bound;
+///
+/// assert_eq!(lower_bound(&[1, 2, 2, 2, 5], &2), 1);
+/// assert_eq!(lower_bound(&[1, 2, 2, 2, 5], &6), 5);
+/// ```
+pub fn lower_bound<T: PartialOrd>(xs: &[T], x: &T) -> usize {
+    bisect::bisect_left(xs, x)
+}
+
+/// Index of the first element of `xs` for which `cmp` doesn't return
+/// `Ordering::Less`, or `xs.len()` if there is none, where `cmp` compares
+/// each element with the target as in `binary_search_by`.
+pub fn lower_bound_by<T, F>(xs: &[T], cmp: F) -> usize
+where
+    F: FnMut(&T) -> Ordering,
+{
+    bisect::bisect_left_by(xs, cmp)
+}
+
+/// Index of the first element of `xs`, sorted by the key extracted by `f`,
+/// whose key is not less than `key`, or `xs.len()` if there is none.
+///
+/// Example:
+/// ```
+/// use algae::search::lower_bound_by_key;
+///
+/// let events = [(1, "boot"), (4, "login"), (4, "mount"), (9, "logout")];
+/// assert_eq!(lower_bound_by_key(&events, &4, |&(t, _)| t), 1);
+/// assert_eq!(lower_bound_by_key(&events, &5, |&(t, _)| t), 3);
+/// ```
+pub fn lower_bound_by_key<T, K, F>(xs: &[T], key: &K, mut f: F) -> usize
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    lower_bound_by(xs, |x| compare(&f(x), key))
+}
+
+/// Index of the first element of the sorted `xs` which is greater than `x`,
+/// or `xs.len()` if there is none. If `x` occurs in `xs`, this is one past
+/// its last occurrence. Same as `bisect::bisect_right`.
+///
+/// Example:
+/// ```
+/// use algae::search::upper_bound;
+///
+/// assert_eq!(upper_bound(&[1, 2, 2, 2, 5], &2), 4);
+/// assert_eq!(upper_bound(&[1, 2, 2, 2, 5], &0), 0);
+/// ```
+pub fn upper_bound<T: PartialOrd>(xs: &[T], x: &T) -> usize {
+    bisect::bisect_right(xs, x)
+}
+
+/// Index of the first element of `xs` for which `cmp` returns
+/// `Ordering::Greater`, or `xs.len()` if there is none, where `cmp` compares
+/// each element with the target as in `binary_search_by`.
+pub fn upper_bound_by<T, F>(xs: &[T], cmp: F) -> usize
+where
+    F: FnMut(&T) -> Ordering,
+{
+    bisect::bisect_right_by(xs, cmp)
+}
+
+/// Index of the first element of `xs`, sorted by the key extracted by `f`,
+/// whose key is greater than `key`, or `xs.len()` if there is none.
+pub fn upper_bound_by_key<T, K, F>(xs: &[T], key: &K, mut f: F) -> usize
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    upper_bound_by(xs, |x| compare(&f(x), key))
+}
+
+/// Range of the indices of the sorted `xs` whose elements are equal to `x`.
+/// If `x` doesn't occur in `xs`, the range is empty and starts at the index
+/// where `x` would be inserted.
+///
+/// Example:
+/// ```
+/// use algae::search::equal_range;
+///
+/// assert_eq!(equal_range(&[1, 2, 2, 2, 5], &2), 1..4);
+/// assert_eq!(equal_range(&[1, 2, 2, 2, 5], &3), 4..4);
+/// ```
+pub fn equal_range<T: PartialOrd>(xs: &[T], x: &T) -> Range<usize> {
+    let start = lower_bound(xs, x);
+    // Every element before `start` is less than `x`, so the search for the
+    // upper bound can skip them.
+    start..start + upper_bound(&xs[start..], x)
+}
+
+/// Range of the indices of `xs` for which `cmp` returns `Ordering::Equal`,
+/// where `cmp` compares each element with the target as in
+/// `binary_search_by`.
+pub fn equal_range_by<T, F>(xs: &[T], mut cmp: F) -> Range<usize>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    let start = lower_bound_by(xs, &mut cmp);
+    start..start + upper_bound_by(&xs[start..], cmp)
+}
+
+/// Range of the indices of `xs`, sorted by the key extracted by `f`, whose
+/// keys are equal to `key`.
+///
+/// Example:
+/// ```
+/// use algae::search::equal_range_by_key;
+///
+/// let words = ["a", "be", "do", "go", "cat", "fish"];
+/// let two_letters = equal_range_by_key(&words, &2, |w| w.len());
+/// assert_eq!(words[two_letters], ["be", "do", "go"]);
+/// ```
+pub fn equal_range_by_key<T, K, F>(xs: &[T], key: &K, mut f: F) -> Range<usize>
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    equal_range_by(xs, |x| compare(&f(x), key))
+}
+
+/// Subslice of the sorted `xs` with the elements in the half-open interval
+/// `[lo, hi)`, found with two binary searches. The subslice is empty if `hi`
+/// is not greater than `lo`. This is the slice analogue of
+/// `OrderedSymbolTable::keys_within`.
+///
+/// Example:
+/// ```
+/// use algae::search::range_of;
+///
+/// let xs = [1, 3, 3, 4, 7, 8, 10];
+/// assert_eq!(range_of(&xs, &3, &8), [3, 3, 4, 7]);
+/// assert_eq!(range_of(&xs, &5, &7), []);
+/// assert_eq!(range_of(&xs, &8, &3), []);
+/// ```
+pub fn range_of<'a, T: PartialOrd>(xs: &'a [T], lo: &T, hi: &T) -> &'a [T] {
+    let start = lower_bound(xs, lo);
+    // Every element before `start` is less than `lo`, so the search for the
+    // end can skip them.
+    let end = start + lower_bound(&xs[start..], hi);
+    &xs[start..end]
+}
+
+/// Subslice of `xs`, sorted by the key extracted by `f`, with the elements
+/// whose keys lie in the half-open interval `[lo, hi)`, as `range_of`.
+///
+/// Example:
+/// ```
+/// use algae::search::range_of_by_key;
+///
+/// let events = [(1, "boot"), (4, "login"), (4, "mount"), (9, "logout")];
+/// let morning = range_of_by_key(&events, &2, &9, |&(t, _)| t);
+/// assert_eq!(morning, [(4, "login"), (4, "mount")]);
+/// ```
+pub fn range_of_by_key<'a, T, K, F>(xs: &'a [T], lo: &K, hi: &K, mut f: F) -> &'a [T]
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    let start = lower_bound_by_key(xs, lo, &mut f);
+    let end = start + lower_bound_by_key(&xs[start..], hi, f);
+    &xs[start..end]
+}
+
+/// Index of the element of the sorted `xs` closest to `x`, or `None` if `xs`
+/// is empty or `x` is NaN. Ties are broken in favour of the smaller element.
+/// The closest element is one of the neighbours of the insertion point of
+/// `x`, found by binary search in O(log n). The elements of `xs` must not be
+/// NaN.
+///
+/// Example:
+/// ```
+/// use algae::search::closest_index;
+///
+/// let xs = [0.5, 1.25, 3.0, 7.5];
+/// assert_eq!(closest_index(&xs, 2.0), Some(1));
+/// assert_eq!(closest_index(&xs, 100.0), Some(3));
+/// assert_eq!(closest_index(&[], 2.0), None);
+/// ```
+pub fn closest_index(xs: &[f64], x: f64) -> Option<usize> {
+    if xs.is_empty() || x.is_nan() {
+        return None;
+    }
+    let i = partition_point(xs, |&y| y < x);
+    if i == 0 {
+        return Some(0);
+    } else if i == xs.len() {
+        return Some(i - 1);
+    }
+    // Here `xs[i - 1] < x <= xs[i]`.
+    if x - xs[i - 1] <= xs[i] - x {
+        Some(i - 1)
+    } else {
+        Some(i)
+    }
+}
+
+/// The `k` elements of the sorted `xs` closest to `target`, as a subslice,
+/// or the whole of `xs` if it has at most `k` elements. Ties are broken in
+/// favour of the smaller elements. Binary searches for the insertion point
+/// of `target`, then grows a window around it towards the closer of its two
+/// neighbours, taking O(log n + k) time. Distances are compared with
+/// `AbsDiff`, so that they never overflow, even between extreme values.
+///
+/// Example:
+/// ```
+/// use algae::search::k_closest;
+///
+/// let xs = [1, 2, 3, 4, 5, 9, 10];
+/// assert_eq!(k_closest(&xs, 6, 3), [3, 4, 5]);
+/// assert_eq!(k_closest(&xs, 8, 3), [5, 9, 10]);
+/// assert_eq!(k_closest(&xs, 3, 4), [1, 2, 3, 4]);
+/// assert_eq!(k_closest(&xs, 0, 10), xs);
+/// ```
+pub fn k_closest<T>(xs: &[T], target: T, k: usize) -> &[T]
+where
+    T: PartialOrd + AbsDiff,
+{
+    k_closest_by_key(xs, target, k, |&x| x)
+}
+
+/// The `k` elements of `xs`, sorted by the key extracted by `f`, whose keys
+/// are closest to `target`, as `k_closest`.
+///
+/// Example:
+/// ```
+/// use algae::search::k_closest_by_key;
+///
+/// let stops = [("Ash", 0), ("Elm", 4), ("Oak", 9), ("Yew", 15)];
+/// assert_eq!(k_closest_by_key(&stops, 6, 2, |&(_, km)| km), [("Elm", 4), ("Oak", 9)]);
+/// ```
+pub fn k_closest_by_key<T, K, F>(xs: &[T], target: K, k: usize, mut f: F) -> &[T]
+where
+    K: PartialOrd + AbsDiff,
+    F: FnMut(&T) -> K,
+{
+    if k >= xs.len() {
+        return xs;
+    }
+    // The window `xs[lo..hi]` starts empty at the insertion point of `target`.
+    let mut lo = lower_bound_by_key(xs, &target, &mut f);
+    let mut hi = lo;
+    while hi - lo < k {
+        let take_left = lo > 0
+            && (hi == xs.len() || target.abs_diff(f(&xs[lo - 1])) <= f(&xs[hi]).abs_diff(target));
+        if take_left {
+            lo -= 1;
+        } else {
+            hi += 1;
+        }
+    }
+    &xs[lo..hi]
+}
+
+/// Binary searches the sorted `xs` for an element within `eps` of `x`,
+/// returning the index of the closest one, or `None` if every element is
+/// further away. This is the search to use on floats, where elements are
+/// rarely exactly equal to `x` due to rounding errors.
+///
+/// Example:
+/// ```
+/// use algae::search::binary_search_approx;
+///
+/// let xs = [0.1, 0.2, 0.1 + 0.2, 0.5];
+/// assert_eq!(binary_search_approx(&xs, 0.3, 1e-9), Some(2));
+/// assert_eq!(binary_search_approx(&xs, 0.4, 1e-9), None);
+/// ```
+pub fn binary_search_approx(xs: &[f64], x: f64, eps: f64) -> Option<usize> {
+    closest_index(xs, x).filter(|&i| (xs[i] - x).abs() <= eps)
+}
+
+/// Returns the first index of `xs` whose element doesn't satisfy `pred`,
+/// assuming that all elements satisfying it come before those that don't, or
+/// `xs.len()` if every element satisfies it. That is, `pred` holds on
+/// `xs[..i]` and fails on `xs[i..]`. Takes O(log n) evaluations of `pred`.
+///
+/// Example:
+/// ```
+/// use algae::search::partition_point;
+///
+/// let xs = [1, 2, 3, 3, 5, 6, 7];
+/// assert_eq!(partition_point(&xs, |&x| x < 5), 4);
+/// assert_eq!(partition_point(&xs, |&x| x < 100), 7);
+/// ```
+pub fn partition_point<T, F: FnMut(&T) -> bool>(xs: &[T], mut pred: F) -> usize {
+    let mut range = 0..xs.len();
+    while let Some(mid) = range_midpoint(&range) {
+        if pred(&xs[mid]) {
+            range.start = mid + 1;
+        } else {
+            range.end = mid;
+        }
     }
-    // Search the left slice.
-    _binary_search(xs, low, mid - 1, search)
+    range.start
 }
 
-// TODO: implement the two sum algorithm
+/// Binary searches for the answer of a problem over the integers of the
+/// range `lo..hi`: returns the smallest `x` in the range satisfying `pred`,
+/// assuming that `pred` is monotone (once it holds, it holds for every greater
+/// integer), or `hi` if no integer of the range satisfies it. Takes
+/// O(log(hi - lo)) evaluations of `pred`, and works over the whole range of
+/// any primitive integer type without overflowing.
+///
+/// Example:
+/// ```
+/// use algae::search::binary_search_answer;
+///
+/// // Integer square root: the smallest `x` with `x * x > n`, minus one.
+/// let n: u64 = 1_000_000_007;
+/// let isqrt = binary_search_answer(0, n + 1, |x| x * x > n) - 1;
+/// assert_eq!(isqrt, 31_622);
+///
+/// assert_eq!(binary_search_answer(i64::MIN, i64::MAX, |x| x >= -3), -3);
+/// assert_eq!(binary_search_answer(0, 10, |_| false), 10);
+/// ```
+pub fn binary_search_answer<T: PrimInt, F: Fn(T) -> bool>(lo: T, hi: T, pred: F) -> T {
+    let (mut lo, mut hi) = (lo, hi);
+    while lo < hi {
+        // Floor of the average of `lo` and `hi`, computed without overflow by
+        // adding the common bits to half of the differing ones. Since `lo <
+        // hi`, it lies in `lo..hi`.
+        let mid = (lo & hi) + ((lo ^ hi) >> 1);
+        if pred(mid) {
+            hi = mid;
+        } else {
+            lo = mid + T::one();
+        }
+    }
+    lo
+}
+
+/// Compares `x` with `y` for the searches above, treating incomparable
+/// elements, such as NaN, as greater.
+fn compare<T: PartialOrd>(x: &T, y: &T) -> Ordering {
+    x.partial_cmp(y).unwrap_or(Ordering::Greater)
+}
+
+/// Searcher implemented by `binary_search`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BinarySearcher;
+
+impl Searcher for BinarySearcher {
+    fn search<T: PartialEq + PartialOrd>(xs: &[T], item: T) -> Option<usize> {
+        binary_search(xs, &item)
+    }
+}
+
+/// Searcher implemented by `binary_search_recursive`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RecursiveBinarySearcher;
+
+impl Searcher for RecursiveBinarySearcher {
+    fn search<T: PartialEq + PartialOrd>(xs: &[T], item: T) -> Option<usize> {
+        binary_search_recursive(xs, &item)
+    }
+}
+
+/// Searcher implemented by `exponential_search`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExponentialSearcher;
+
+impl Searcher for ExponentialSearcher {
+    fn search<T: PartialEq + PartialOrd>(xs: &[T], item: T) -> Option<usize> {
+        exponential_search(xs, &item)
+    }
+}
 
 #[cfg(test)]
 mod tests {
+    use crate::search;
+
     #[test]
     fn binary_search() {
         let xs = vec![3];
@@ -42,5 +633,267 @@ mod tests {
         let xs = vec![3, 5, 7, 11, 35, 90];
         assert_eq!(super::binary_search(&xs, &90), Some(xs.len() - 1));
         assert_eq!(super::binary_search(&xs, &6), None);
+        assert_eq!(super::binary_search(&xs, &1), None);
+        assert_eq!(super::binary_search(&xs, &100), None);
+
+        let xs: Vec<i32> = vec![];
+        assert_eq!(super::binary_search(&xs, &1), None);
+    }
+
+    #[test]
+    fn searchers() {
+        search::check_searcher::<super::BinarySearcher>();
+        search::check_searcher::<super::RecursiveBinarySearcher>();
+        search::check_searcher::<super::ExponentialSearcher>();
+    }
+
+    #[test]
+    fn edge_cases() {
+        type Search = fn(&[i32], &i32) -> Option<usize>;
+        let searches: [Search; 2] = [super::binary_search, super::binary_search_recursive];
+        for search in searches {
+            assert_eq!(search(&[], &0), None);
+            assert_eq!(search(&[5], &5), Some(0));
+            assert_eq!(search(&[5], &4), None);
+            assert_eq!(search(&[5], &6), None);
+            assert_eq!(search(&[i32::MIN, i32::MAX], &i32::MIN), Some(0));
+            assert_eq!(search(&[i32::MIN, i32::MAX], &i32::MAX), Some(1));
+        }
+    }
+
+    #[test]
+    fn bounds() {
+        let xs: [i32; 0] = [];
+        assert_eq!(super::equal_range(&xs, &1), 0..0);
+
+        let xs = [1, 1, 3, 3, 3, 4, 7, 7];
+        for x in 0..9 {
+            let range = super::equal_range(&xs, &x);
+            assert_eq!(range.start, super::lower_bound(&xs, &x));
+            assert_eq!(range.end, super::upper_bound(&xs, &x));
+            assert_eq!(range.start, xs.iter().filter(|&&y| y < x).count());
+            assert_eq!(range.len(), xs.iter().filter(|&&y| y == x).count());
+        }
+    }
+
+    #[test]
+    fn ranges() {
+        let xs: [i32; 0] = [];
+        assert!(super::range_of(&xs, &0, &10).is_empty());
+
+        for _ in 0..50 {
+            let mut xs: Vec<i32> = (0..fastrand::usize(..30))
+                .map(|_| fastrand::i32(0..20))
+                .collect();
+            xs.sort();
+            let (lo, hi) = (fastrand::i32(-1..21), fastrand::i32(-1..21));
+            let expected: Vec<i32> = xs
+                .iter()
+                .copied()
+                .filter(|x| (lo..hi).contains(x))
+                .collect();
+            assert_eq!(super::range_of(&xs, &lo, &hi), expected);
+        }
+    }
+
+    #[test]
+    fn partitioning() {
+        let xs: [i32; 0] = [];
+        assert_eq!(super::partition_point(&xs, |_| true), 0);
+
+        let mut xs: Vec<i32> = (0..50).map(|_| fastrand::i32(0..20)).collect();
+        xs.sort();
+        for y in -1..21 {
+            assert_eq!(
+                super::partition_point(&xs, |&x| x < y),
+                xs.partition_point(|&x| x < y)
+            );
+        }
+    }
+
+    #[test]
+    fn approximate_search() {
+        let mut xs: Vec<f64> = (0..50).map(|_| fastrand::f64() * 10.0).collect();
+        xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        for _ in 0..200 {
+            let x = fastrand::f64() * 12.0 - 1.0;
+            let i = super::closest_index(&xs, x).unwrap();
+            assert!(xs.iter().all(|y| (y - x).abs() >= (xs[i] - x).abs()));
+
+            let eps = fastrand::f64() * 0.2;
+            let within = xs.iter().any(|y| (y - x).abs() <= eps);
+            assert_eq!(
+                super::binary_search_approx(&xs, x, eps),
+                within.then_some(i)
+            );
+        }
+
+        assert_eq!(super::closest_index(&[1.0, 3.0], 2.0), Some(0));
+        assert_eq!(super::closest_index(&[1.0, 3.0], f64::NAN), None);
+        assert_eq!(super::binary_search_approx(&[], 1.0, 1.0), None);
+    }
+
+    #[test]
+    fn closest_window() {
+        for _ in 0..100 {
+            let mut xs: Vec<i32> = (0..fastrand::usize(..30))
+                .map(|_| fastrand::i32(0..50))
+                .collect();
+            xs.sort();
+            let target = fastrand::i32(-5..55);
+            let k = fastrand::usize(..35);
+
+            let window = super::k_closest(&xs, target, k);
+            assert_eq!(window.len(), k.min(xs.len()));
+            // The window is as close as the `k` closest elements by brute force.
+            let mut distances: Vec<i32> = xs.iter().map(|x| (x - target).abs()).collect();
+            distances.sort();
+            let mut window_distances: Vec<i32> =
+                window.iter().map(|x| (x - target).abs()).collect();
+            window_distances.sort();
+            assert_eq!(window_distances, distances[..window.len()]);
+        }
+
+        // Distances beyond the range of the type.
+        let xs = [i32::MIN, i32::MAX];
+        assert_eq!(super::k_closest(&xs, 0, 1), [i32::MAX]);
+        assert_eq!(super::k_closest(&xs, -1, 1), [i32::MIN]);
+        assert_eq!(super::k_closest(&xs, i32::MIN, 1), [i32::MIN]);
+        let xs = [0, 1, u64::MAX];
+        assert_eq!(super::k_closest(&xs, u64::MAX - 1, 2), [1, u64::MAX]);
+        assert_eq!(super::k_closest(&[-1.5, 2.0, 2.5], 0.0, 2), [-1.5, 2.0]);
+    }
+
+    #[test]
+    fn galloping_search() {
+        let squares = |n: usize| n.saturating_mul(n);
+        for target in 0..2_000 {
+            let n = super::unbounded_search(squares, &target).unwrap();
+            assert!(squares(n) >= target && (n == 0 || squares(n - 1) < target));
+        }
+        assert_eq!(
+            super::unbounded_search(|n| n, &usize::MAX),
+            Some(usize::MAX)
+        );
+        assert_eq!(super::unbounded_search(|_| 0, &1), None);
+
+        // The number of evaluations is logarithmic in the answer.
+        let evaluations = std::cell::Cell::new(0);
+        let found = super::unbounded_search(
+            |n| {
+                evaluations.set(evaluations.get() + 1);
+                n
+            },
+            &1_000_000,
+        );
+        assert_eq!(found, Some(1_000_000));
+        assert!(evaluations.get() <= 2 * 21);
+    }
+
+    #[test]
+    fn answer_search() {
+        for (lo, hi) in [(0, 0), (3, 4), (-10, 10), (i8::MIN, i8::MAX)] {
+            for threshold in lo..=hi {
+                let expected = (lo..hi).find(|&x| x >= threshold).unwrap_or(hi);
+                assert_eq!(
+                    super::binary_search_answer(lo, hi, |x| x >= threshold),
+                    expected
+                );
+            }
+        }
+        assert_eq!(
+            super::binary_search_answer(0, u64::MAX, |x| x > u64::MAX - 2),
+            u64::MAX - 1
+        );
+        assert_eq!(super::binary_search_answer(i64::MIN, 0, |_| true), i64::MIN);
+    }
+
+    #[test]
+    fn insertion_points() {
+        assert_eq!(super::binary_search_insertion::<i32>(&[], &1), Err(0));
+        for _ in 0..50 {
+            let mut xs: Vec<i32> = (0..fastrand::usize(..30))
+                .map(|_| fastrand::i32(0..20))
+                .collect();
+            xs.sort();
+            for y in -1..21 {
+                match super::binary_search_insertion(&xs, &y) {
+                    Ok(idx) => assert!(xs[idx] == y && (idx == 0 || xs[idx - 1] < y)),
+                    Err(idx) => assert_eq!(xs.binary_search(&y), Err(idx)),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn projected_searches() {
+        #[derive(Debug)]
+        struct Record {
+            id: u32,
+            label: char,
+        }
+
+        for _ in 0..50 {
+            let mut ids: Vec<u32> = (0..fastrand::usize(..30))
+                .map(|_| fastrand::u32(..20))
+                .collect();
+            ids.sort();
+            let records: Vec<Record> = ids.iter().map(|&id| Record { id, label: 'r' }).collect();
+            let key = |r: &Record| r.id;
+
+            for y in 0..21 {
+                let cmp = |r: &Record| r.id.cmp(&y);
+                let lower = super::lower_bound(&ids, &y);
+                assert_eq!(super::lower_bound_by_key(&records, &y, key), lower);
+                assert_eq!(super::lower_bound_by(&records, cmp), lower);
+                let upper = super::upper_bound(&ids, &y);
+                assert_eq!(super::upper_bound_by_key(&records, &y, key), upper);
+                assert_eq!(super::upper_bound_by(&records, cmp), upper);
+                assert_eq!(super::equal_range_by_key(&records, &y, key), lower..upper);
+                assert_eq!(super::equal_range_by(&records, cmp), lower..upper);
+                let hi = fastrand::u32(..21);
+                let range = super::range_of(&ids, &y, &hi);
+                let projected = super::range_of_by_key(&records, &y, &hi, key);
+                assert!(projected.iter().map(key).eq(range.iter().copied()));
+                let k = fastrand::usize(..10);
+                let closest = super::k_closest(&ids, y, k);
+                let projected = super::k_closest_by_key(&records, y, k, key);
+                assert!(projected.iter().map(key).eq(closest.iter().copied()));
+                assert_eq!(
+                    super::binary_search_insertion_by_key(&records, &y, key),
+                    super::binary_search_insertion(&ids, &y)
+                );
+                assert_eq!(
+                    super::binary_search_insertion_by(&records, cmp),
+                    super::binary_search_insertion(&ids, &y)
+                );
+
+                type Search = fn(&[Record], &u32) -> Option<usize>;
+                let searches: [Search; 6] = [
+                    |xs, y| super::binary_search_by_key(xs, y, |r| r.id),
+                    |xs, y| super::binary_search_by(xs, |r| r.id.cmp(y)),
+                    |xs, y| super::binary_search_recursive_by_key(xs, y, |r| r.id),
+                    |xs, y| super::binary_search_recursive_by(xs, |r| r.id.cmp(y)),
+                    |xs, y| super::exponential_search_by_key(xs, y, |r| r.id),
+                    |xs, y| super::exponential_search_by(xs, |r| r.id.cmp(y)),
+                ];
+                for search in searches {
+                    match search(&records, &y) {
+                        Some(i) => assert!(records[i].id == y && records[i].label == 'r'),
+                        None => assert!(lower == upper),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn iterative_agrees_with_recursive() {
+        let xs: Vec<i32> = (0..100).map(|x| 3 * x).collect();
+        for y in -5..305 {
+            let found = super::binary_search(&xs, &y);
+            assert_eq!(found, super::binary_search_recursive(&xs, &y));
+            assert_eq!(found, xs.binary_search(&y).ok());
+        }
     }
 }
@@ -0,0 +1,258 @@
+//! Compatibility layer with the `bisect` module of Python's standard library,
+//! for maintaining sorted vectors without sorting them after each insertion.
+//! All functions assume the given slice to be sorted.
+use crate::search::partition_point;
+use std::cmp::Ordering;
+
+/// Returns the insertion point for `x` in `xs` that keeps `xs` sorted. If `x`
+/// is already present, the insertion point is before (to the left of) any
+/// existing entries. That is, `xs[..i]` is less than `x`, while `xs[i..]` is
+/// greater than or equal to `x`.
+///
+/// Example:
+/// ```
+/// use algae::search::bisect::bisect_left;
+///
+/// assert_eq!(bisect_left(&[1, 2, 2, 2, 5], &2), 1);
+/// assert_eq!(bisect_left(&[1, 2, 2, 2, 5], &3), 4);
+/// ```
+pub fn bisect_left<T: PartialOrd>(xs: &[T], x: &T) -> usize {
+    partition_point(xs, |y| y < x)
+}
+
+/// Version of `bisect_left` with a comparator, which returns whether an
+/// element is less than, equal to, or greater than the value to insert, as
+/// in `binary_search_by`: the insertion point is before every element for
+/// which `cmp` doesn't return `Ordering::Less`.
+pub fn bisect_left_by<T, F>(xs: &[T], mut cmp: F) -> usize
+where
+    F: FnMut(&T) -> Ordering,
+{
+    partition_point(xs, |y| cmp(y) == Ordering::Less)
+}
+
+/// Version of `bisect_left` for `xs` sorted by the key extracted from each
+/// element by `f`, as the `key` argument of Python's `bisect_left`: the
+/// insertion point is before every element whose key is not less than `key`.
+///
+/// Example:
+/// ```
+/// use algae::search::bisect::bisect_left_by_key;
+///
+/// let scores = [("Ann", 12), ("Bob", 15), ("Cid", 15), ("Dee", 20)];
+/// assert_eq!(bisect_left_by_key(&scores, &15, |&(_, s)| s), 1);
+/// assert_eq!(bisect_left_by_key(&scores, &16, |&(_, s)| s), 3);
+/// ```
+pub fn bisect_left_by_key<T, K, F>(xs: &[T], key: &K, mut f: F) -> usize
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    partition_point(xs, |y| f(y) < *key)
+}
+
+/// Returns the insertion point for `x` in `xs` that keeps `xs` sorted. If `x`
+/// is already present, the insertion point is after (to the right of) any
+/// existing entries. That is, `xs[..i]` is less than or equal to `x`, while
+/// `xs[i..]` is greater than `x`.
+///
+/// Example:
+/// ```
+/// use algae::search::bisect::bisect_right;
+///
+/// assert_eq!(bisect_right(&[1, 2, 2, 2, 5], &2), 4);
+/// assert_eq!(bisect_right(&[1, 2, 2, 2, 5], &0), 0);
+/// ```
+pub fn bisect_right<T: PartialOrd>(xs: &[T], x: &T) -> usize {
+    partition_point(xs, |y| y <= x)
+}
+
+/// Version of `bisect_right` with a comparator, as `bisect_left_by`: the
+/// insertion point is before every element for which `cmp` returns
+/// `Ordering::Greater`.
+pub fn bisect_right_by<T, F>(xs: &[T], mut cmp: F) -> usize
+where
+    F: FnMut(&T) -> Ordering,
+{
+    partition_point(xs, |y| cmp(y) != Ordering::Greater)
+}
+
+/// Version of `bisect_right` for `xs` sorted by the key extracted by `f`, as
+/// `bisect_left_by_key`: the insertion point is before every element whose
+/// key is greater than `key`.
+///
+/// Example:
+/// ```
+/// use algae::search::bisect::bisect_right_by_key;
+///
+/// let scores = [("Ann", 12), ("Bob", 15), ("Cid", 15), ("Dee", 20)];
+/// assert_eq!(bisect_right_by_key(&scores, &15, |&(_, s)| s), 3);
+/// assert_eq!(bisect_right_by_key(&scores, &11, |&(_, s)| s), 0);
+/// ```
+pub fn bisect_right_by_key<T, K, F>(xs: &[T], key: &K, mut f: F) -> usize
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    partition_point(xs, |y| f(y) <= *key)
+}
+
+/// Alias of `bisect_right`, as in Python.
+pub fn bisect<T: PartialOrd>(xs: &[T], x: &T) -> usize {
+    bisect_right(xs, x)
+}
+
+/// Inserts `x` into `xs` keeping it sorted, placing it before any existing
+/// entries equal to `x`. The search takes O(log n), but the insertion itself
+/// is O(n) due to the shifting of the vector elements.
+///
+/// Example:
+/// ```
+/// use algae::search::bisect::insort_left;
+///
+/// let mut v = vec![1, 3, 5];
+/// insort_left(&mut v, 4);
+/// assert_eq!(v, [1, 3, 4, 5]);
+/// ```
+pub fn insort_left<T: PartialOrd>(xs: &mut Vec<T>, x: T) {
+    let idx = bisect_left(xs, &x);
+    xs.insert(idx, x);
+}
+
+/// Inserts `x` into `xs` keeping it sorted, placing it after any existing
+/// entries equal to `x`. The search takes O(log n), but the insertion itself
+/// is O(n) due to the shifting of the vector elements.
+pub fn insort_right<T: PartialOrd>(xs: &mut Vec<T>, x: T) {
+    let idx = bisect_right(xs, &x);
+    xs.insert(idx, x);
+}
+
+/// Inserts `x` into `xs`, sorted by the key extracted by `f`, keeping it
+/// sorted and placing it before any existing entries with the same key. As
+/// in Python, the key of `x` is extracted by `f` too.
+///
+/// Example:
+/// ```
+/// use algae::search::bisect::insort_left_by_key;
+///
+/// let mut words = vec!["a", "be", "cat"];
+/// insort_left_by_key(&mut words, "do", |w| w.len());
+/// assert_eq!(words, ["a", "do", "be", "cat"]);
+/// ```
+pub fn insort_left_by_key<T, K, F>(xs: &mut Vec<T>, x: T, mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    let idx = bisect_left_by_key(xs, &f(&x), &mut f);
+    xs.insert(idx, x);
+}
+
+/// Inserts `x` into `xs`, sorted by the key extracted by `f`, keeping it
+/// sorted and placing it after any existing entries with the same key.
+pub fn insort_right_by_key<T, K, F>(xs: &mut Vec<T>, x: T, mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    let idx = bisect_right_by_key(xs, &f(&x), &mut f);
+    xs.insert(idx, x);
+}
+
+/// Alias of `insort_right`, as in Python.
+pub fn insort<T: PartialOrd>(xs: &mut Vec<T>, x: T) {
+    insort_right(xs, x)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bisection() {
+        let xs: [i32; 0] = [];
+        assert_eq!(bisect_left(&xs, &1), 0);
+        assert_eq!(bisect_right(&xs, &1), 0);
+
+        let xs = [1, 2, 2, 2, 5];
+        for (x, left, right) in [
+            (0, 0, 0),
+            (1, 0, 1),
+            (2, 1, 4),
+            (3, 4, 4),
+            (5, 4, 5),
+            (6, 5, 5),
+        ] {
+            assert_eq!(bisect_left(&xs, &x), left);
+            assert_eq!(bisect_right(&xs, &x), right);
+            assert_eq!(bisect(&xs, &x), right);
+        }
+    }
+
+    #[test]
+    fn grades() {
+        // The classical example from the Python documentation.
+        let breakpoints = [60, 70, 80, 90];
+        let grades = b"FDCBA";
+        let grade = |score| grades[bisect(&breakpoints, &score)] as char;
+        let scores = [33, 99, 77, 70, 89, 90, 100];
+        let result: String = scores.iter().map(|&s| grade(s)).collect();
+        assert_eq!(result, "FACCBAA");
+    }
+
+    #[test]
+    fn insortion() {
+        let mut xs = Vec::new();
+        for _ in 0..200 {
+            let x = fastrand::u8(..20);
+            if fastrand::bool() {
+                insort_left(&mut xs, x);
+            } else {
+                insort(&mut xs, x);
+            }
+            assert!(crate::sort::is_sorted(&xs));
+        }
+        assert_eq!(xs.len(), 200);
+
+        // Equal entries are inserted at the requested side: `-0.0 == 0.0`, but
+        // they can be told apart by their sign.
+        let mut ys = vec![-1.0_f64, 0.0, 1.0];
+        insort_left(&mut ys, -0.0);
+        assert!(ys[1].is_sign_negative() && ys[2].is_sign_positive());
+        insort_right(&mut ys, -0.0);
+        assert!(ys[3].is_sign_negative() && ys[2].is_sign_positive());
+    }
+
+    #[test]
+    fn projected_bisection() {
+        for _ in 0..50 {
+            let mut keys: Vec<u8> = (0..fastrand::usize(..30))
+                .map(|_| fastrand::u8(..20))
+                .collect();
+            keys.sort();
+            let pairs: Vec<(u8, usize)> = keys.iter().map(|&k| (k, fastrand::usize(..))).collect();
+            for x in 0..21 {
+                let (left, right) = (bisect_left(&keys, &x), bisect_right(&keys, &x));
+                assert_eq!(bisect_left_by_key(&pairs, &x, |p| p.0), left);
+                assert_eq!(bisect_left_by(&pairs, |p| p.0.cmp(&x)), left);
+                assert_eq!(bisect_right_by_key(&pairs, &x, |p| p.0), right);
+                assert_eq!(bisect_right_by(&pairs, |p| p.0.cmp(&x)), right);
+            }
+        }
+
+        // Entries with equal keys keep their order of insertion on the right,
+        // and the reverse one on the left.
+        let (mut left, mut right) = (Vec::new(), Vec::new());
+        let mut expected = Vec::new();
+        for i in 0..200 {
+            let x = (fastrand::u8(..10), i);
+            insort_left_by_key(&mut left, x, |p| p.0);
+            insort_right_by_key(&mut right, x, |p| p.0);
+            expected.push(x);
+        }
+        expected.sort_by_key(|p| p.0);
+        assert_eq!(right, expected);
+        expected.sort_by_key(|p| (p.0, std::cmp::Reverse(p.1)));
+        assert_eq!(left, expected);
+    }
+}
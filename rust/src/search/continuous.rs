@@ -0,0 +1,110 @@
+//! Searching over the real line, for functions that can only be evaluated.
+
+/// Inverse of the golden ratio, `(sqrt(5) - 1) / 2`.
+const INV_PHI: f64 = 0.618_033_988_749_894_8;
+
+/// Golden-section search for the minimum of `f` over `[lo, hi]`, assuming
+/// that `f` is unimodal there: strictly decreasing up to the minimum and
+/// strictly increasing after it. Returns a point within `tol` of the minimum,
+/// or as close as the precision of `f64` allows around it: the search stops
+/// once the interval no longer shrinks, which also happens for infinite
+/// bounds.
+///
+/// Each step compares `f` at two interior points splitting the interval in
+/// the golden ratio, and discards the part beyond the larger value. This
+/// shrinks the interval by a factor of about 0.618, while one of the
+/// points is reused by the next step, so that `f` is evaluated only once
+/// per step and no derivatives are needed.
+///
+/// Example:
+/// ```
+/// use algae::search::continuous::golden_section_min;
+///
+/// let x = golden_section_min(|x| (x - 2.0) * (x - 2.0) + 1.0, 0.0, 5.0, 1e-9);
+/// assert!((x - 2.0).abs() < 1e-6);
+/// ```
+pub fn golden_section_min<F: Fn(f64) -> f64>(f: F, lo: f64, hi: f64, tol: f64) -> f64 {
+    assert!(tol > 0.0, "The tolerance must be positive, got {}", tol);
+    let (mut a, mut b) = if lo <= hi { (lo, hi) } else { (hi, lo) };
+
+    let mut c = b - INV_PHI * (b - a);
+    let mut d = a + INV_PHI * (b - a);
+    let (mut fc, mut fd) = (f(c), f(d));
+    while b - a > tol {
+        let width = b - a;
+        if fc < fd {
+            // The minimum lies in `[a, d]`, where `c` becomes the right point.
+            b = d;
+            d = c;
+            fd = fc;
+            c = b - INV_PHI * (b - a);
+            fc = f(c);
+        } else {
+            // The minimum lies in `[c, b]`, where `d` becomes the left point.
+            a = c;
+            c = d;
+            fc = fd;
+            d = a + INV_PHI * (b - a);
+            fd = f(d);
+        }
+        // Below the spacing of floats around `a` and `b`, the points stop
+        // moving, and `tol` cannot be reached.
+        if b - a >= width || (b - a).is_nan() {
+            break;
+        }
+    }
+    (a + b) / 2.0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn minimizes_unimodal_functions() {
+        type Case = (fn(f64) -> f64, f64, f64, f64);
+        let cases: [Case; 4] = [
+            (|x| (x - 1.5).powi(2), -10.0, 10.0, 1.5),
+            (|x| (x + 3.0).abs(), -5.0, 0.0, -3.0),
+            (|x| x.cos(), 0.0, 6.0, std::f64::consts::PI),
+            // Minimum at the boundary of a monotone function.
+            (|x| x.exp(), -1.0, 1.0, -1.0),
+        ];
+        // Near a smooth minimum, `f` is flat up to rounding errors at a
+        // distance of about the square root of the machine epsilon, which
+        // bounds the accuracy of any comparison based search.
+        for (f, lo, hi, expected) in cases {
+            assert!((golden_section_min(f, lo, hi, 1e-10) - expected).abs() < 1e-6);
+            assert!((golden_section_min(f, hi, lo, 1e-10) - expected).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn tolerance_below_float_precision() {
+        // Floats around 1e10 are about 2e-6 apart.
+        let x = golden_section_min(|x| (x - 1e10 - 0.5).powi(2), 1e10, 1e10 + 1.0, 1e-9);
+        assert!((x - 1e10 - 0.5).abs() < 1e-5);
+        for (lo, hi) in [(0.0, f64::INFINITY), (f64::NEG_INFINITY, f64::INFINITY)] {
+            golden_section_min(|x| x * x, lo, hi, 1e-9);
+        }
+    }
+
+    #[test]
+    fn one_evaluation_per_step() {
+        let evaluations = Cell::new(0);
+        golden_section_min(
+            |x| {
+                evaluations.set(evaluations.get() + 1);
+                x * x
+            },
+            -1.0,
+            1.0,
+            1e-6,
+        );
+        // The interval shrinks from 2 to 1e-6 by a factor of 0.618 per step,
+        // in about 30 steps, plus the 2 initial evaluations.
+        let steps = ((1e-6f64 / 2.0).ln() / INV_PHI.ln()).ceil() as usize;
+        assert!(evaluations.get() <= steps + 2);
+    }
+}
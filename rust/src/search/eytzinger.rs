@@ -0,0 +1,165 @@
+use std::mem;
+
+/// Sorted array stored in *Eytzinger layout*, the breadth-first order of a
+/// complete binary search tree: the root is at position 1 and the children
+/// of position `k` are at `2k` and `2k + 1`. A search walks down the tree,
+/// and the positions visited at the next few levels lie next to each other in
+/// memory, so they are prefetched while the current comparison is being made.
+/// On arrays much larger than the cache, this makes searching typically 2 to
+/// 3 times faster than a binary search over the sorted array.
+///
+/// Searches report indices in the sorted order, so that the structure can be
+/// used in place of a sorted slice.
+///
+/// Example:
+/// ```
+/// use algae::search::Eytzinger;
+///
+/// let tree = Eytzinger::from_sorted(&[1, 3, 5, 7, 9, 11]);
+/// assert_eq!(tree.binary_search(&7), Some(3));
+/// assert_eq!(tree.binary_search(&4), None);
+/// assert_eq!(tree.lower_bound(&4), 2);
+/// assert_eq!(tree.to_sorted(), [1, 3, 5, 7, 9, 11]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Eytzinger<T> {
+    /// Elements in Eytzinger order, where position `k` is stored at `k - 1`.
+    data: Vec<T>,
+    /// Index in the sorted order of each element of `data`.
+    ranks: Vec<usize>,
+}
+
+impl<T: PartialOrd + Clone> Eytzinger<T> {
+    /// Builds the layout of the sorted slice `xs` in O(n).
+    pub fn from_sorted(xs: &[T]) -> Self {
+        debug_assert!(
+            xs.windows(2).all(|w| w[0] <= w[1]),
+            "Eytzinger layout built from an unsorted slice"
+        );
+        let mut ranks = vec![0; xs.len()];
+        let mut next_rank = 0;
+        fill_ranks(&mut ranks, 1, &mut next_rank);
+        Self {
+            data: ranks.iter().map(|&r| xs[r].clone()).collect(),
+            ranks,
+        }
+    }
+
+    /// Number of elements.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Whether there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Index in the sorted order of the first element not less than `x`, or
+    /// `len()` if there is none, as `search::lower_bound`.
+    pub fn lower_bound(&self, x: &T) -> usize {
+        match self.lower_bound_position(x) {
+            0 => self.len(),
+            k => self.ranks[k - 1],
+        }
+    }
+
+    /// Index in the sorted order of an element equal to `x`, if any. When `x`
+    /// occurs several times, this is its first occurrence.
+    pub fn binary_search(&self, x: &T) -> Option<usize> {
+        match self.lower_bound_position(x) {
+            0 => None,
+            k => (self.data[k - 1] == *x).then(|| self.ranks[k - 1]),
+        }
+    }
+
+    /// Whether some element is equal to `x`.
+    pub fn contains(&self, x: &T) -> bool {
+        self.binary_search(x).is_some()
+    }
+
+    /// The elements back in sorted order.
+    pub fn to_sorted(&self) -> Vec<T> {
+        let mut positions = vec![0; self.len()];
+        for (k, &r) in self.ranks.iter().enumerate() {
+            positions[r] = k;
+        }
+        positions.iter().map(|&k| self.data[k].clone()).collect()
+    }
+
+    /// Position of the first element not less than `x`, or 0 if there is
+    /// none.
+    fn lower_bound_position(&self, x: &T) -> usize {
+        let n = self.data.len();
+        // Number of elements in a cache line: the descendants `log2(stride)`
+        // levels below `k` start at position `stride * k`.
+        let stride = (64 / mem::size_of::<T>().max(1)).max(1);
+        let mut k = 1;
+        while k <= n {
+            prefetch(self.data.as_ptr().wrapping_add(stride * k - 1));
+            k = 2 * k + usize::from(self.data[k - 1] < *x);
+        }
+        // Going right means the element is less than `x`. The answer is the
+        // last node where the search went left, found by dropping the trailing
+        // right turns, and the left turn before them, from the path in `k`.
+        k >> (k.trailing_ones() + 1)
+    }
+}
+
+/// Assigns the ranks of the in-order traversal to the subtree rooted at the
+/// position `k`, starting at `next_rank`.
+fn fill_ranks(ranks: &mut [usize], k: usize, next_rank: &mut usize) {
+    if k <= ranks.len() {
+        fill_ranks(ranks, 2 * k, next_rank);
+        ranks[k - 1] = *next_rank;
+        *next_rank += 1;
+        fill_ranks(ranks, 2 * k + 1, next_rank);
+    }
+}
+
+/// Hints the CPU to load the cache line at `ptr`, which may be out of bounds.
+#[inline(always)]
+fn prefetch<T>(ptr: *const T) {
+    #[cfg(target_arch = "x86_64")]
+    // SAFETY: prefetching never faults, even on invalid addresses.
+    unsafe {
+        use std::arch::x86_64::{_mm_prefetch, _MM_HINT_T0};
+        _mm_prefetch::<_MM_HINT_T0>(ptr as *const i8);
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    let _ = ptr;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::search;
+
+    #[test]
+    fn agrees_with_sorted_search() {
+        for n in 0..70 {
+            let mut xs: Vec<u32> = (0..n).map(|_| fastrand::u32(..50)).collect();
+            xs.sort();
+            let tree = Eytzinger::from_sorted(&xs);
+            assert_eq!(tree.len(), xs.len());
+            assert_eq!(tree.to_sorted(), xs);
+
+            for x in 0..52 {
+                let lower = search::lower_bound(&xs, &x);
+                assert_eq!(tree.lower_bound(&x), lower);
+                let found = (lower < xs.len() && xs[lower] == x).then_some(lower);
+                assert_eq!(tree.binary_search(&x), found);
+                assert_eq!(tree.contains(&x), xs.contains(&x));
+            }
+        }
+    }
+
+    #[test]
+    fn non_copy_elements() {
+        let words = ["apple", "fig", "kiwi", "pear"].map(String::from);
+        let tree = Eytzinger::from_sorted(&words);
+        assert_eq!(tree.binary_search(&String::from("kiwi")), Some(2));
+        assert_eq!(tree.lower_bound(&String::from("b")), 1);
+        assert_eq!(tree.to_sorted(), words);
+    }
+}
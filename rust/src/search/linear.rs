@@ -0,0 +1,178 @@
+//! Searching in unsorted slices and lists, where every element may have to be
+//! compared with the item searched for.
+use crate::{ds::SingleLinkedList, search::Searcher};
+use std::mem;
+
+/// Index of the first element of `xs` equal to `x`, if any, comparing `x`
+/// with each element in turn. Takes O(n) time.
+///
+/// Example:
+/// ```
+/// use algae::search::linear_search;
+///
+/// assert_eq!(linear_search(&[5, 1, 4, 1], &1), Some(1));
+/// assert_eq!(linear_search(&[5, 1, 4, 1], &3), None);
+/// ```
+pub fn linear_search<T: PartialEq>(xs: &[T], x: &T) -> Option<usize> {
+    linear_search_by(xs, |y| y == x)
+}
+
+/// Index of the first element of `xs` for which `pred` returns `true`, if
+/// any, checking each element in turn. Takes O(n) time.
+///
+/// Example:
+/// ```
+/// use algae::search::linear_search_by;
+///
+/// assert_eq!(linear_search_by(&[5, 1, 4, 1], |&x| x % 2 == 0), Some(2));
+/// assert_eq!(linear_search_by(&[5, 1, 4, 1], |&x| x > 5), None);
+/// ```
+pub fn linear_search_by<T, F>(xs: &[T], pred: F) -> Option<usize>
+where
+    F: FnMut(&T) -> bool,
+{
+    xs.iter().position(pred)
+}
+
+/// Index of the first element of `xs` whose key, extracted by `f`, is equal
+/// to `key`, if any. Takes O(n) time.
+///
+/// Example:
+/// ```
+/// use algae::search::linear_search_by_key;
+///
+/// let pets = [("Rex", "dog"), ("Tom", "cat"), ("Kitty", "cat")];
+/// assert_eq!(linear_search_by_key(&pets, &"cat", |&(_, kind)| kind), Some(1));
+/// assert_eq!(linear_search_by_key(&pets, &"cow", |&(_, kind)| kind), None);
+/// ```
+pub fn linear_search_by_key<T, K, F>(xs: &[T], key: &K, mut f: F) -> Option<usize>
+where
+    K: PartialEq,
+    F: FnMut(&T) -> K,
+{
+    linear_search_by(xs, |x| f(x) == *key)
+}
+
+/// Index of the first element of `xs` equal to `x`, if any. A linear search
+/// checks both whether the current element equals `x` and whether the end of
+/// the slice was reached. Here `x` is temporarily placed in the last slot as a
+/// *sentinel*, so that the scan is guaranteed to stop and each step needs a
+/// single comparison. The last element is restored before returning.
+///
+/// The sentinel only stops the scan if it is equal to itself, hence the `Eq`
+/// bound: a `f64::NAN` sentinel would run past the end of the slice.
+/// ```compile_fail
+/// use algae::search::sentinel_search;
+///
+/// sentinel_search(&mut [1.0, 2.0], f64::NAN);
+/// ```
+///
+/// Example:
+/// ```
+/// use algae::search::sentinel_search;
+///
+/// let mut xs = [5, 1, 4, 1];
+/// assert_eq!(sentinel_search(&mut xs, 4), Some(2));
+/// assert_eq!(sentinel_search(&mut xs, 3), None);
+/// assert_eq!(xs, [5, 1, 4, 1]);
+/// ```
+pub fn sentinel_search<T: Eq>(xs: &mut [T], x: T) -> Option<usize> {
+    let last = xs.len().checked_sub(1)?;
+    let saved = mem::replace(&mut xs[last], x);
+    let mut i = 0;
+    while xs[i] != xs[last] {
+        i += 1;
+    }
+    let x = mem::replace(&mut xs[last], saved);
+    (i < last || xs[last] == x).then_some(i)
+}
+
+/// Searches `list` for a key equal to `x`, moving it to the front when found
+/// and returning its former position. Frequently accessed keys gather at the
+/// front of the list, so that this *self-organizing* search performs within a
+/// factor of 2 of the best fixed order for any sequence of accesses.
+///
+/// Example:
+/// ```
+/// use algae::{ds::SingleLinkedList, search::move_to_front_search};
+///
+/// let mut list = SingleLinkedList::new();
+/// for x in [3, 2, 1] {
+///     list.push(x);
+/// }
+/// assert_eq!(move_to_front_search(&mut list, &3), Some(2));
+/// assert_eq!(move_to_front_search(&mut list, &3), Some(0));
+/// assert_eq!(move_to_front_search(&mut list, &7), None);
+/// assert_eq!(list.iter().collect::<Vec<_>>(), [&3, &1, &2]);
+/// ```
+pub fn move_to_front_search<T: PartialEq>(list: &mut SingleLinkedList<T>, x: &T) -> Option<usize> {
+    list.move_to_front(|key| key == x)
+}
+
+/// Searcher comparing `item` with each element in turn, which works on
+/// unsorted slices. Takes O(n) time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LinearSearcher;
+
+impl Searcher for LinearSearcher {
+    fn search<T: PartialEq + PartialOrd>(xs: &[T], item: T) -> Option<usize> {
+        linear_search(xs, &item)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::search;
+
+    #[test]
+    fn linear_searcher() {
+        search::check_searcher::<LinearSearcher>();
+        assert_eq!(LinearSearcher::search(&[5, 1, 4, 1], 1), Some(1));
+        assert_eq!(LinearSearcher::search(&[5, 1, 4, 1], 3), None);
+    }
+
+    #[test]
+    fn sentinel_agrees_with_linear() {
+        for n in 0..20 {
+            let mut xs: Vec<u8> = (0..n).map(|_| fastrand::u8(..8)).collect();
+            let original = xs.clone();
+            for x in 0..10 {
+                assert_eq!(sentinel_search(&mut xs, x), linear_search(&xs, &x));
+                assert_eq!(xs, original);
+            }
+        }
+    }
+
+    #[test]
+    fn projected_linear_search() {
+        for n in 0..20 {
+            let pairs: Vec<(u8, usize)> = (0..n).map(|i| (fastrand::u8(..8), i)).collect();
+            let keys: Vec<u8> = pairs.iter().map(|p| p.0).collect();
+            for x in 0..10 {
+                let found = linear_search(&keys, &x);
+                assert_eq!(linear_search_by_key(&pairs, &x, |p| p.0), found);
+                assert_eq!(linear_search_by(&pairs, |p| p.0 == x), found);
+            }
+        }
+    }
+
+    #[test]
+    fn self_organizing_list() {
+        let mut list = SingleLinkedList::new();
+        for x in (0..10).rev() {
+            list.push(x);
+        }
+        for _ in 0..200 {
+            let x = fastrand::u32(..12);
+            let keys: Vec<u32> = list.iter().copied().collect();
+            let position = move_to_front_search(&mut list, &x);
+            assert_eq!(position, linear_search(&keys, &x));
+            if position.is_some() {
+                assert_eq!(list.peek(), Some(&x));
+            } else {
+                assert!(list.iter().eq(keys.iter()));
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! Searching in matrices sorted along both their rows and their columns, that
+//! is, where every row and every column is non-decreasing.
+use crate::{
+    math::{safe::range_midpoint, Matrix},
+    search::binary_search_answer,
+};
+use std::{cmp::Ordering, ops::Range};
+
+/// Saddleback search for `x` in the row and column sorted `grid`, returning
+/// the row and column of an entry equal to `x`, if any. The search starts at
+/// the top-right corner: an entry greater than `x` rules out its column, and
+/// an entry less than `x` rules out its row. Takes O(rows + cols) time.
+///
+/// Example:
+/// ```
+/// use algae::search::matrix::saddleback_search;
+///
+/// let grid = vec![
+///     vec![1, 4, 7, 11],
+///     vec![2, 5, 8, 12],
+///     vec![3, 6, 9, 16],
+/// ];
+/// assert_eq!(saddleback_search(&grid, &6), Some((2, 1)));
+/// assert_eq!(saddleback_search(&grid, &10), None);
+/// ```
+pub fn saddleback_search<T, M>(grid: &M, x: &T) -> Option<(usize, usize)>
+where
+    T: PartialOrd,
+    M: Matrix<T> + ?Sized,
+{
+    let (mut i, mut j) = (0, grid.n_cols());
+    while i < grid.n_rows() && j > 0 {
+        let y = grid.get(i, j - 1);
+        if y == x {
+            return Some((i, j - 1));
+        } else if y > x {
+            j -= 1;
+        } else {
+            i += 1;
+        }
+    }
+    None
+}
+
+/// Divide-and-conquer search for `x` in the row and column sorted `grid`,
+/// returning the row and column of an entry equal to `x`, if any. The middle
+/// row is binary searched for the first entry `grid[mid][j]` not less than
+/// `x`: entries above and to the left of it are less than `x`, and those below
+/// and to the right of it are greater, so only the top-right and bottom-left
+/// blocks remain. Takes O(rows * log(cols / rows)) time for `rows <= cols`,
+/// which beats saddleback search on matrices much wider than they are tall.
+///
+/// Example:
+/// ```
+/// use algae::search::matrix::divide_and_conquer_search;
+///
+/// let grid = vec![vec![1, 3, 5, 7, 9, 11, 13, 15], vec![2, 4, 6, 8, 10, 12, 14, 16]];
+/// assert_eq!(divide_and_conquer_search(&grid, &12), Some((1, 5)));
+/// assert_eq!(divide_and_conquer_search(&grid, &17), None);
+/// ```
+pub fn divide_and_conquer_search<T, M>(grid: &M, x: &T) -> Option<(usize, usize)>
+where
+    T: PartialOrd,
+    M: Matrix<T> + ?Sized,
+{
+    search_block(grid, x, 0..grid.n_rows(), 0..grid.n_cols())
+}
+
+/// Searches for `x` in the block of `grid` given by the `rows` and `cols`.
+fn search_block<T, M>(
+    grid: &M,
+    x: &T,
+    rows: Range<usize>,
+    cols: Range<usize>,
+) -> Option<(usize, usize)>
+where
+    T: PartialOrd,
+    M: Matrix<T> + ?Sized,
+{
+    if cols.is_empty() {
+        return None;
+    }
+    let mid = range_midpoint(&rows)?;
+
+    // Lower bound of `x` in the middle row: the partition point of the
+    // entries less than `x`, where incomparable entries count as greater.
+    let j = binary_search_answer(cols.start, cols.end, |j| {
+        grid.get(mid, j).partial_cmp(x) != Some(Ordering::Less)
+    });
+    if j < cols.end && grid.get(mid, j) == x {
+        return Some((mid, j));
+    }
+
+    search_block(grid, x, rows.start..mid, j..cols.end)
+        .or_else(|| search_block(grid, x, mid + 1..rows.end, cols.start..j))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::math::MatrixView;
+
+    /// Random `n_rows` by `n_cols` matrix sorted along rows and columns, in
+    /// row-major order.
+    fn rand_sorted_grid(n_rows: usize, n_cols: usize) -> Vec<u32> {
+        let mut entries = vec![0; n_rows * n_cols];
+        for i in 0..n_rows {
+            for j in 0..n_cols {
+                let up = if i > 0 {
+                    entries[(i - 1) * n_cols + j]
+                } else {
+                    0
+                };
+                let left = if j > 0 {
+                    entries[i * n_cols + j - 1]
+                } else {
+                    0
+                };
+                entries[i * n_cols + j] = up.max(left) + fastrand::u32(..3);
+            }
+        }
+        entries
+    }
+
+    #[test]
+    fn agrees_with_linear_scan() {
+        for _ in 0..100 {
+            let (n_rows, n_cols) = (fastrand::usize(..10), fastrand::usize(..25));
+            let entries = rand_sorted_grid(n_rows, n_cols);
+            let grid = MatrixView::new(&entries, n_rows, n_cols);
+            for x in 0..60 {
+                let found = [
+                    saddleback_search(&grid, &x),
+                    divide_and_conquer_search(&grid, &x),
+                ];
+                for found in found {
+                    match found {
+                        Some((i, j)) => assert_eq!(*grid.get(i, j), x),
+                        None => assert!(!entries.contains(&x)),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn vector_rows() {
+        let grid: Vec<Vec<i32>> = vec![];
+        assert_eq!(saddleback_search(&grid, &1), None);
+        assert_eq!(divide_and_conquer_search(&grid[..], &1), None);
+
+        let grid = vec![vec![-3], vec![0], vec![8]];
+        assert_eq!(saddleback_search(&grid, &8), Some((2, 0)));
+        assert_eq!(divide_and_conquer_search(&grid, &-3), Some((0, 0)));
+    }
+}
@@ -1,10 +1,51 @@
+pub mod bisect;
+pub mod continuous;
+pub mod matrix;
+
 mod binary;
+pub use binary::*;
+
+mod eytzinger;
+pub use eytzinger::*;
+
+mod linear;
+pub use linear::*;
 
-// TODO: Write a common test for all searchers, and write docs.
+mod sum;
+pub use sum::*;
+
+/// A search algorithm over slices, such as `BinarySearcher` for sorted slices
+/// or `LinearSearcher` for arbitrary ones. Implementors are unit structs, so
+/// that algorithms can be chosen by a type parameter and tested together by
+/// `check_searcher`.
 pub trait Searcher {
+    /// Searches for `item` in `x`, returning `Some(idx)` with `x[idx] ==
+    /// item` if found, and `None` otherwise. Searchers relying on order
+    /// assume `x` to be sorted.
     fn search<T: PartialEq + PartialOrd>(x: &[T], item: T) -> Option<usize>;
 }
 
+/// Checks that the searcher `S` finds every element of sorted slices, and
+/// only those, including empty slices and slices with duplicates.
+pub fn check_searcher<S: Searcher>() {
+    // Edge cases: empty and single element slices.
+    assert_eq!(S::search::<i64>(&[], 7), None);
+    assert_eq!(S::search(&[7], 7), Some(0));
+    assert_eq!(S::search(&[7], 6), None);
+    assert_eq!(S::search(&[7], 8), None);
+
+    for len in [2, 3, 10, 100, 1_000] {
+        let mut xs: Vec<i64> = (0..len).map(|_| fastrand::i64(0..len / 2 + 1)).collect();
+        xs.sort();
+        for item in -1..len / 2 + 2 {
+            match S::search(&xs, item) {
+                Some(idx) => assert_eq!(xs[idx], item, "Wrong index for {}", item),
+                None => assert!(!xs.contains(&item), "Missed {} in {:?}", item, xs),
+            }
+        }
+    }
+}
+
 /// A symbol table is a data structure composed of key-value pairs. The user
 /// should be able to insert or remove key-value pairs, and also be able to
 /// search for a value having a given key.
@@ -38,8 +79,21 @@ pub trait SymbolTable<K, V> {
     /// Number of key-value pairs in the table.
     fn size(&self) -> usize;
 
-    /// Returns an iterator through all keys of the table.
-    fn keys<I: Iterator>(&self) -> I;
+    /// Returns an iterator through all keys of the table. The iterator
+    /// borrows the table, which can't be modified until it is dropped.
+    fn keys(&self) -> impl Iterator<Item = K> + '_;
+
+    /// All keys of the table, collected into a vector owning them, so that
+    /// the table can be modified while going through the keys, e.g. deleting
+    /// some of them with `del`.
+    fn keys_snapshot(&self) -> Vec<K> {
+        self.keys().collect()
+    }
+
+    /// Keeps only the key-value pairs for which `pred` returns `true`,
+    /// deleting the others. Each pair in the table when the method is called
+    /// is passed to `pred` exactly once, in the order of `keys`.
+    fn retain<F: FnMut(&K, &V) -> bool>(&mut self, pred: F);
 }
 
 /// The `OrderedSymbolTable` builds out of `SymbolTable`, in that it presents
@@ -84,9 +138,10 @@ pub trait OrderedSymbolTable<K: PartialOrd, V> {
 
     /// Returns an iterator through all keys that are greater or equal to `low`
     /// and less than `high`.
-    fn keys_within<I: Iterator>(&self, low: K, high: K) -> I;
+    fn keys_within(&self, low: K, high: K) -> impl Iterator<Item = K> + '_;
 }
 
+#[allow(dead_code)]
 fn frequency_counter<ST>(_: ST)
 where
     ST: for<'a> SymbolTable<&'a str, i32> + for<'a> OrderedSymbolTable<&'a str, i32>,
@@ -0,0 +1,206 @@
+//! The two-sum and three-sum problems: finding elements of an array adding up
+//! to a given target. Two-sum is solved both by binary searching a sorted
+//! array and by hashing, which also works on unsorted arrays.
+//!
+//! Sums that overflow the integer type are handled as in exact arithmetic, so
+//! they never match the target.
+use crate::{search::lower_bound, sort};
+use num::PrimInt;
+use std::{cmp::Ordering, collections::HashMap, hash::Hash};
+
+/// Finds indices `i < j` such that `xs[i] + xs[j] == target` in the sorted
+/// `xs`, if any. For each element, binary searches for its complement among
+/// the following elements, taking O(n * log n) time and no extra space.
+///
+/// Example:
+/// ```
+/// use algae::search::two_sum_sorted;
+///
+/// assert_eq!(two_sum_sorted(&[1, 3, 4, 6, 9], 10), Some((0, 4)));
+/// assert_eq!(two_sum_sorted(&[1, 3, 4, 6, 9], 2), None);
+/// // Duplicates may pair with each other.
+/// assert_eq!(two_sum_sorted(&[2, 5, 5], 10), Some((1, 2)));
+/// ```
+pub fn two_sum_sorted<T: PrimInt>(xs: &[T], target: T) -> Option<(usize, usize)> {
+    for (i, &x) in xs.iter().enumerate() {
+        let Some(complement) = target.checked_sub(&x) else {
+            continue;
+        };
+        let rest = &xs[i + 1..];
+        let j = lower_bound(rest, &complement);
+        if rest.get(j) == Some(&complement) {
+            return Some((i, i + 1 + j));
+        }
+    }
+    None
+}
+
+/// Finds indices `i < j` such that `xs[i] + xs[j] == target`, if any, where
+/// `xs` need not be sorted. Remembers the index of each element seen so far
+/// in a hash table, looking up the complement of each new element, which
+/// takes O(n) expected time and O(n) extra space.
+///
+/// Example:
+/// ```
+/// use algae::search::two_sum_hashed;
+///
+/// assert_eq!(two_sum_hashed(&[9, 4, 1, 6, 3], 10), Some((0, 2)));
+/// assert_eq!(two_sum_hashed(&[9, 4, 1, 6, 3], 20), None);
+/// ```
+pub fn two_sum_hashed<T: PrimInt + Hash>(xs: &[T], target: T) -> Option<(usize, usize)> {
+    let mut seen: HashMap<T, usize> = HashMap::with_capacity(xs.len());
+    for (j, &x) in xs.iter().enumerate() {
+        if let Some(&i) = target.checked_sub(&x).and_then(|c| seen.get(&c)) {
+            return Some((i, j));
+        }
+        seen.entry(x).or_insert(j);
+    }
+    None
+}
+
+/// Finds all triples of indices `i < j < k` with `xs[i] + xs[j] + xs[k] ==
+/// target`, reporting a single triple of indices for each distinct triple of
+/// values, even if `xs` has duplicates. The elements are visited in sorted
+/// order, and for each of them the remaining two are found by moving two
+/// pointers towards each other, taking O(n^2) time.
+///
+/// Example:
+/// ```
+/// use algae::search::three_sum;
+///
+/// let xs = [-1, 0, 1, 2, -1, -4];
+/// let mut values: Vec<[i32; 3]> = three_sum(&xs, 0)
+///     .into_iter()
+///     .map(|(i, j, k)| {
+///         let mut v = [xs[i], xs[j], xs[k]];
+///         v.sort();
+///         v
+///     })
+///     .collect();
+/// values.sort();
+/// assert_eq!(values, [[-1, -1, 2], [-1, 0, 1]]);
+/// ```
+pub fn three_sum<T: PrimInt>(xs: &[T], target: T) -> Vec<(usize, usize, usize)> {
+    let order = sort::argsort(xs);
+    let value = |p: usize| xs[order[p]];
+    let mut triples = Vec::new();
+
+    for p in 0..order.len() {
+        // Each value is used as the smallest of a triple only once.
+        if p > 0 && value(p) == value(p - 1) {
+            continue;
+        }
+        let (mut lo, mut hi) = (p + 1, order.len().saturating_sub(1));
+        while lo < hi {
+            let (b, c) = (value(lo), value(hi));
+            match cmp_sum(value(p), b, c, target) {
+                Ordering::Equal => {
+                    let mut triple = [order[p], order[lo], order[hi]];
+                    triple.sort();
+                    triples.push((triple[0], triple[1], triple[2]));
+                    while lo < hi && value(lo) == b {
+                        lo += 1;
+                    }
+                    while lo < hi && value(hi) == c {
+                        hi -= 1;
+                    }
+                }
+                Ordering::Less => lo += 1,
+                Ordering::Greater => hi -= 1,
+            }
+        }
+    }
+    triples
+}
+
+/// Compares the exact sum `a + b + c` with `target`, given `a <= b <= c`.
+fn cmp_sum<T: PrimInt>(a: T, b: T, c: T, target: T) -> Ordering {
+    match a.checked_add(&c) {
+        // Adding `b` overflows upwards if it is positive, and downwards
+        // otherwise, in which case the exact sum is out of the range of `T`.
+        Some(ac) => match ac.checked_add(&b) {
+            Some(sum) => sum.cmp(&target),
+            None if b > T::zero() => Ordering::Greater,
+            None => Ordering::Less,
+        },
+        // The smallest and largest elements have the same sign, and so does
+        // `b`, which lies between them.
+        None if a > T::zero() => Ordering::Greater,
+        None => Ordering::Less,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn check_pair(xs: &[i32], target: i32, pair: Option<(usize, usize)>) {
+        let exists = (0..xs.len()).any(|i| (i + 1..xs.len()).any(|j| xs[i] + xs[j] == target));
+        match pair {
+            Some((i, j)) => assert!(i < j && xs[i] + xs[j] == target),
+            None => assert!(!exists),
+        }
+    }
+
+    #[test]
+    fn two_sum() {
+        for _ in 0..200 {
+            let mut xs: Vec<i32> = (0..fastrand::usize(..30))
+                .map(|_| fastrand::i32(-20..20))
+                .collect();
+            let target = fastrand::i32(-45..45);
+            check_pair(&xs, target, two_sum_hashed(&xs, target));
+            xs.sort();
+            check_pair(&xs, target, two_sum_sorted(&xs, target));
+        }
+    }
+
+    #[test]
+    fn three_sum_distinct_values() {
+        for _ in 0..100 {
+            let xs: Vec<i32> = (0..fastrand::usize(..40))
+                .map(|_| fastrand::i32(-10..10))
+                .collect();
+            let target = fastrand::i32(-15..15);
+
+            let mut expected = BTreeSet::new();
+            for i in 0..xs.len() {
+                for j in i + 1..xs.len() {
+                    for k in j + 1..xs.len() {
+                        if xs[i] + xs[j] + xs[k] == target {
+                            let mut v = [xs[i], xs[j], xs[k]];
+                            v.sort();
+                            expected.insert(v);
+                        }
+                    }
+                }
+            }
+
+            let triples = three_sum(&xs, target);
+            let found: BTreeSet<[i32; 3]> = triples
+                .iter()
+                .map(|&(i, j, k)| {
+                    assert!(i < j && j < k);
+                    let mut v = [xs[i], xs[j], xs[k]];
+                    v.sort();
+                    v
+                })
+                .collect();
+            assert_eq!(found.len(), triples.len(), "Repeated value triples");
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn overflowing_sums() {
+        assert_eq!(two_sum_sorted(&[i8::MIN, -1, 100, 127], -128), None);
+        assert_eq!(two_sum_hashed(&[100u8, 200, 56], 0), None);
+        assert_eq!(two_sum_hashed(&[100u8, 200, 56], 156), Some((0, 2)));
+        assert_eq!(three_sum(&[100i8, 100, 84], 28), vec![]);
+        assert_eq!(
+            three_sum(&[i8::MIN, i8::MIN, 127, 127, 1], 0),
+            vec![(0, 3, 4)]
+        );
+    }
+}
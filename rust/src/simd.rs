@@ -0,0 +1,348 @@
+//! Vectorized fast paths for hot primitives on `u32` slices (enabled by the
+//! `simd` feature), together with a branchless binary search.
+//!
+//! On `x86_64`, the vectorized versions use SSE intrinsics when the CPU
+//! supports the needed instruction sets, detected at runtime. On other
+//! targets, or on older CPUs, every function falls back to a portable scalar
+//! implementation with the same results.
+use std::cmp::PartialOrd;
+
+/// Index of the first element of the sorted `xs` which is not less than `x`,
+/// or `xs.len()` if there is none, as `search::lower_bound`. The loop has no
+/// data-dependent branches: each step halves the candidate range with a
+/// conditional move, which avoids branch mispredictions and lets the CPU run
+/// ahead on the memory accesses.
+///
+/// Example:
+/// ```
+/// use algae::simd::lower_bound_branchless;
+///
+/// let xs: [u64; 6] = [1, 3, 3, 5, 8, 13];
+/// assert_eq!(lower_bound_branchless(&xs, 3), 1);
+/// assert_eq!(lower_bound_branchless(&xs, 9), 5);
+/// assert_eq!(lower_bound_branchless(&xs, 20), 6);
+/// ```
+pub fn lower_bound_branchless<T: Copy + PartialOrd>(xs: &[T], x: T) -> usize {
+    if xs.is_empty() {
+        return 0;
+    }
+    // The answer lies in `base..=base + size`.
+    let mut base = 0;
+    let mut size = xs.len();
+    while size > 1 {
+        let half = size / 2;
+        let mid = base + half;
+        base = if xs[mid] < x { mid } else { base };
+        size -= half;
+    }
+    base + usize::from(xs[base] < x)
+}
+
+/// Whether `xs` is sorted in non-decreasing order, as `sort::is_sorted`,
+/// comparing four pairs of neighbours at once.
+///
+/// Example:
+/// ```
+/// use algae::simd::is_sorted_u32;
+///
+/// assert!(is_sorted_u32(&[1, 2, 2, 3, 5, 8, 13, 21, 34]));
+/// assert!(!is_sorted_u32(&[1, 2, 2, 3, 5, 8, 13, 34, 21]));
+/// ```
+pub fn is_sorted_u32(xs: &[u32]) -> bool {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("sse4.1") {
+        // SAFETY: the CPU supports SSE4.1.
+        return unsafe { x86::is_sorted_u32(xs) };
+    }
+    is_sorted_scalar(xs)
+}
+
+/// Minimum and maximum of `xs`, or `None` if it is empty, scanning four
+/// elements at once.
+///
+/// Example:
+/// ```
+/// use algae::simd::min_max_u32;
+///
+/// assert_eq!(min_max_u32(&[7, 3, 9, 1, 4, 4, 8]), Some((1, 9)));
+/// assert_eq!(min_max_u32(&[]), None);
+/// ```
+pub fn min_max_u32(xs: &[u32]) -> Option<(u32, u32)> {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("sse4.1") {
+        // SAFETY: the CPU supports SSE4.1.
+        return unsafe { x86::min_max_u32(xs) };
+    }
+    min_max_scalar(xs)
+}
+
+/// Partitions `xs` around `pivot`, as the partition step of quicksort: moves
+/// the elements less than `pivot` to the front and the others to the back,
+/// returning the number of elements less than `pivot`. The partition is
+/// stable, both sides keep the relative order of their elements, and uses
+/// O(n) extra space for the back side.
+///
+/// Example:
+/// ```
+/// use algae::simd::partition_u32;
+///
+/// let mut v = [5, 1, 8, 2, 9, 3, 7];
+/// assert_eq!(partition_u32(&mut v, 5), 3);
+/// assert_eq!(v, [1, 2, 3, 5, 8, 9, 7]);
+/// ```
+pub fn partition_u32(xs: &mut [u32], pivot: u32) -> usize {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("ssse3") {
+        // SAFETY: the CPU supports SSSE3.
+        return unsafe { x86::partition_u32(xs, pivot) };
+    }
+    partition_scalar(xs, pivot)
+}
+
+fn is_sorted_scalar(xs: &[u32]) -> bool {
+    xs.windows(2).all(|w| w[0] <= w[1])
+}
+
+fn min_max_scalar(xs: &[u32]) -> Option<(u32, u32)> {
+    let first = *xs.first()?;
+    Some(
+        xs.iter()
+            .fold((first, first), |(lo, hi), &x| (lo.min(x), hi.max(x))),
+    )
+}
+
+fn partition_scalar(xs: &mut [u32], pivot: u32) -> usize {
+    let mut back = Vec::with_capacity(xs.len());
+    let mut front = 0;
+    for i in 0..xs.len() {
+        if xs[i] < pivot {
+            xs[front] = xs[i];
+            front += 1;
+        } else {
+            back.push(xs[i]);
+        }
+    }
+    xs[front..].copy_from_slice(&back);
+    front
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use std::arch::x86_64::*;
+
+    /// Number of `u32` lanes of a 128-bit vector.
+    const LANES: usize = 4;
+
+    /// Shuffle masks for `_mm_shuffle_epi8` packing the selected lanes of a
+    /// vector to its front: bit `k` of the index marks whether the lane `k` is
+    /// selected. Bytes of unused lanes are zeroed.
+    const COMPRESS: [[u8; 16]; 16] = compress_table();
+
+    const fn compress_table() -> [[u8; 16]; 16] {
+        let mut table = [[0x80; 16]; 16];
+        let mut mask = 0;
+        while mask < 16 {
+            let mut out = 0;
+            let mut lane = 0;
+            while lane < LANES {
+                if (mask >> lane) & 1 == 1 {
+                    let mut byte = 0;
+                    while byte < 4 {
+                        table[mask][4 * out + byte] = (4 * lane + byte) as u8;
+                        byte += 1;
+                    }
+                    out += 1;
+                }
+                lane += 1;
+            }
+            mask += 1;
+        }
+        table
+    }
+
+    /// # Safety
+    ///
+    /// The CPU must support SSE4.1.
+    #[target_feature(enable = "sse4.1")]
+    pub(super) unsafe fn is_sorted_u32(xs: &[u32]) -> bool {
+        let mut i = 0;
+        // Compare `xs[i..i + 4]` with the neighbours `xs[i + 1..i + 5]`.
+        while i + LANES < xs.len() {
+            let a = _mm_loadu_si128(xs.as_ptr().add(i) as *const __m128i);
+            let b = _mm_loadu_si128(xs.as_ptr().add(i + 1) as *const __m128i);
+            // `a <= b` lane-wise if and only if `max(a, b) == b`.
+            let ordered = _mm_cmpeq_epi32(_mm_max_epu32(a, b), b);
+            if _mm_movemask_epi8(ordered) != 0xffff {
+                return false;
+            }
+            i += LANES;
+        }
+        super::is_sorted_scalar(&xs[i..])
+    }
+
+    /// # Safety
+    ///
+    /// The CPU must support SSE4.1.
+    #[target_feature(enable = "sse4.1")]
+    pub(super) unsafe fn min_max_u32(xs: &[u32]) -> Option<(u32, u32)> {
+        let chunks = xs.chunks_exact(LANES);
+        let tail = super::min_max_scalar(chunks.remainder());
+        if xs.len() < LANES {
+            return tail;
+        }
+
+        let mut lo = _mm_set1_epi32(-1);
+        let mut hi = _mm_setzero_si128();
+        for chunk in chunks {
+            let v = _mm_loadu_si128(chunk.as_ptr() as *const __m128i);
+            lo = _mm_min_epu32(lo, v);
+            hi = _mm_max_epu32(hi, v);
+        }
+        let (mut lanes_lo, mut lanes_hi) = ([0u32; LANES], [0u32; LANES]);
+        _mm_storeu_si128(lanes_lo.as_mut_ptr() as *mut __m128i, lo);
+        _mm_storeu_si128(lanes_hi.as_mut_ptr() as *mut __m128i, hi);
+
+        let (mut min, mut max) = (lanes_lo[0], lanes_hi[0]);
+        for lane in 1..LANES {
+            min = min.min(lanes_lo[lane]);
+            max = max.max(lanes_hi[lane]);
+        }
+        if let Some((tail_min, tail_max)) = tail {
+            min = min.min(tail_min);
+            max = max.max(tail_max);
+        }
+        Some((min, max))
+    }
+
+    /// # Safety
+    ///
+    /// The CPU must support SSSE3.
+    #[target_feature(enable = "ssse3")]
+    pub(super) unsafe fn partition_u32(xs: &mut [u32], pivot: u32) -> usize {
+        let n = xs.len();
+        // Every vector store writes a whole vector, of which only the first
+        // lanes are meaningful, so the back side needs room for one more.
+        let mut back = vec![0; n + LANES];
+        let (mut front, mut n_back) = (0, 0);
+
+        // Unsigned comparisons through signed ones, by flipping the sign bits.
+        let bias = _mm_set1_epi32(i32::MIN);
+        let biased_pivot = _mm_xor_si128(_mm_set1_epi32(pivot as i32), bias);
+
+        let mut i = 0;
+        while i + LANES <= n {
+            let v = _mm_loadu_si128(xs.as_ptr().add(i) as *const __m128i);
+            let less = _mm_cmplt_epi32(_mm_xor_si128(v, bias), biased_pivot);
+            let mask = _mm_movemask_ps(_mm_castsi128_ps(less)) as usize;
+
+            let to_front = _mm_shuffle_epi8(v, load_shuffle(mask));
+            let to_back = _mm_shuffle_epi8(v, load_shuffle(!mask & 0xf));
+            // The front side is written behind the reading position: the store
+            // covers at most `xs[i..i + 4]`, which was just loaded.
+            _mm_storeu_si128(xs.as_mut_ptr().add(front) as *mut __m128i, to_front);
+            _mm_storeu_si128(back.as_mut_ptr().add(n_back) as *mut __m128i, to_back);
+
+            let n_front = mask.count_ones() as usize;
+            front += n_front;
+            n_back += LANES - n_front;
+            i += LANES;
+        }
+
+        for j in i..n {
+            let x = xs[j];
+            if x < pivot {
+                xs[front] = x;
+                front += 1;
+            } else {
+                back[n_back] = x;
+                n_back += 1;
+            }
+        }
+        xs[front..].copy_from_slice(&back[..n_back]);
+        front
+    }
+
+    #[inline]
+    unsafe fn load_shuffle(mask: usize) -> __m128i {
+        _mm_loadu_si128(COMPRESS[mask].as_ptr() as *const __m128i)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::search;
+
+    fn rand_u32s(n: usize, max: u32) -> Vec<u32> {
+        (0..n).map(|_| fastrand::u32(..=max)).collect()
+    }
+
+    #[test]
+    fn branchless_search() {
+        for n in 0..40 {
+            let mut xs = rand_u32s(n, 20);
+            xs.sort();
+            for x in 0..22 {
+                assert_eq!(lower_bound_branchless(&xs, x), search::lower_bound(&xs, &x));
+            }
+        }
+        let xs: Vec<u64> = (0..1_000).map(|x| x * 7).collect();
+        for x in [0, 1, 6993, 6994, 10_000] {
+            assert_eq!(lower_bound_branchless(&xs, x), search::lower_bound(&xs, &x));
+        }
+    }
+
+    #[test]
+    fn sortedness() {
+        for n in 0..50 {
+            let mut xs = rand_u32s(n, u32::MAX);
+            xs.sort();
+            assert!(is_sorted_u32(&xs));
+            if n > 1 {
+                let (i, j) = (fastrand::usize(..n), fastrand::usize(..n));
+                xs.swap(i, j);
+                assert_eq!(is_sorted_u32(&xs), is_sorted_scalar(&xs));
+                assert_eq!(is_sorted_u32(&xs), crate::sort::is_sorted(&xs));
+            }
+        }
+    }
+
+    #[test]
+    fn extremes() {
+        for n in 0..50 {
+            let xs = rand_u32s(n, u32::MAX);
+            assert_eq!(min_max_u32(&xs), min_max_scalar(&xs));
+            assert_eq!(
+                min_max_u32(&xs),
+                xs.iter().min().copied().zip(xs.iter().max().copied())
+            );
+        }
+        assert_eq!(min_max_u32(&[u32::MAX; 9]), Some((u32::MAX, u32::MAX)));
+    }
+
+    #[test]
+    fn partitioning() {
+        for n in 0..100 {
+            let xs = rand_u32s(n, 40);
+            for pivot in [0, 1, 20, 41, u32::MAX] {
+                let (mut simd, mut scalar) = (xs.clone(), xs.clone());
+                let expected: Vec<u32> = xs
+                    .iter()
+                    .filter(|&&x| x < pivot)
+                    .chain(xs.iter().filter(|&&x| x >= pivot))
+                    .copied()
+                    .collect();
+                assert_eq!(
+                    partition_u32(&mut simd, pivot),
+                    partition_scalar(&mut scalar, pivot)
+                );
+                assert_eq!(simd, expected);
+                assert_eq!(scalar, expected);
+            }
+        }
+        // Values that differ from the pivot in the sign bit.
+        let mut xs = vec![u32::MAX, 0, 1 << 31, (1 << 31) - 1, 3];
+        assert_eq!(partition_u32(&mut xs, 1 << 31), 3);
+        assert_eq!(xs, [0, (1 << 31) - 1, 3, u32::MAX, 1 << 31]);
+    }
+}
@@ -0,0 +1,743 @@
+//! External merge sort, for inputs that do not fit into memory.
+//!
+//! The input is read as a sequence of newline separated records, which are
+//! compared as byte strings. The sort works in two phases:
+//! * The input is split into chunks of bounded size, each chunk is sorted in
+//!   memory and spilled to a temporary file, called a *run*.
+//! * The sorted runs are merged into the output with a k-way merge, keeping
+//!   only the current record of each run in memory. At most `MAX_FAN_IN` runs
+//!   are merged at once, so that the number of open files stays bounded: if
+//!   there are more runs, they are first merged in groups into longer runs.
+//!
+//! `ExternalSorter` runs the first phase one run at a time, and implements
+//! `Resumable` so that a long sort can be continued after a restart.
+//!
+//! With the `async` feature, `AsyncExternalMerger` performs the same k-way
+//! merge over sorted sources that produce their records asynchronously.
+//!
+//! The file-backed sorts are not available on `wasm32`, which has no file
+//! system, leaving only `AsyncExternalMerger`.
+#[cfg(any(feature = "async", not(target_arch = "wasm32")))]
+use crate::ds::{Heap, MaxHeap};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{resume::Resumable, sort};
+#[cfg(any(feature = "async", not(target_arch = "wasm32")))]
+use std::{
+    cmp::Reverse,
+    io::{self, BufWriter, Write},
+};
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    env, fs,
+    io::{BufRead, BufReader, Read},
+    iter,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Maximum number of runs merged at once.
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_FAN_IN: usize = 64;
+
+/// Sorts the lines of `input` into `output`, using at most around `chunk_size`
+/// bytes of memory for the records being sorted. Runs are spilled to the
+/// temporary directory of the system and removed once the sort is over. Each
+/// run holds at least one record, so that a `chunk_size` of 0 spills a run per
+/// record.
+///
+/// Every record written to `output` is terminated by a newline, even if the
+/// last line of `input` is not.
+///
+/// Example:
+/// ```
+/// use algae::sort::external::external_merge_sort;
+///
+/// let input = "pear\nfig\napple\nkiwi\n";
+/// let mut output = Vec::new();
+/// external_merge_sort(input.as_bytes(), &mut output, 8).unwrap();
+/// assert_eq!(output, b"apple\nfig\nkiwi\npear\n");
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+pub fn external_merge_sort<R: Read, W: Write>(
+    input: R,
+    output: W,
+    chunk_size: usize,
+) -> io::Result<()> {
+    external_merge_sort_in(input, output, chunk_size, &env::temp_dir())
+}
+
+/// Same as `external_merge_sort`, but the runs are spilled to the directory
+/// `dir`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn external_merge_sort_in<R: Read, W: Write>(
+    input: R,
+    output: W,
+    chunk_size: usize,
+    dir: &Path,
+) -> io::Result<()> {
+    trace_span!("external_merge_sort", chunk_size);
+    let runs = spill_runs(input, chunk_size, dir)?;
+    merge_runs(runs, output, dir)
+}
+
+/// Temporary files holding sorted runs, which are removed when dropped.
+#[cfg(not(target_arch = "wasm32"))]
+struct Runs {
+    paths: Vec<PathBuf>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for Runs {
+    fn drop(&mut self) {
+        for path in &self.paths {
+            // The file may have never been created if spilling failed midway.
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Counter giving the runs of the process distinct names.
+#[cfg(not(target_arch = "wasm32"))]
+static RUN_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Creates a new run file in `dir`. Names are unique within the process, but a
+/// run left by an earlier process with the same id, such as one held by the
+/// checkpoint of an `ExternalSorter`, may already have the name: such files
+/// are skipped rather than overwritten.
+#[cfg(not(target_arch = "wasm32"))]
+fn create_run(dir: &Path) -> io::Result<(PathBuf, fs::File)> {
+    loop {
+        let id = RUN_ID.fetch_add(1, Ordering::Relaxed);
+        let path = dir.join(format!("algae-run-{}-{}.tmp", process::id(), id));
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(file) => return Ok((path, file)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// First phase: splits `input` into chunks of about `chunk_size` bytes, sorts
+/// each chunk and writes it to a run file in `dir`.
+#[cfg(not(target_arch = "wasm32"))]
+fn spill_runs<R: Read>(input: R, chunk_size: usize, dir: &Path) -> io::Result<Runs> {
+    let mut runs = Runs { paths: Vec::new() };
+    let mut input = BufReader::new(input);
+    loop {
+        let (chunk, _) = read_chunk(&mut input, chunk_size)?;
+        if chunk.is_empty() {
+            return Ok(runs);
+        }
+        spill_chunk(&chunk, dir, &mut runs.paths)?;
+    }
+}
+
+/// Reads records from `input` until they add up to at least `chunk_size`
+/// bytes or the input is exhausted. At least one record is read, whatever
+/// `chunk_size`, so that the chunk is only empty at the end of the input.
+/// Returns the records, without their newlines, together with the number of
+/// bytes read.
+#[cfg(not(target_arch = "wasm32"))]
+fn read_chunk<R: BufRead>(input: &mut R, chunk_size: usize) -> io::Result<(Vec<Vec<u8>>, u64)> {
+    let mut chunk = Vec::new();
+    let mut chunk_bytes = 0;
+    while chunk.is_empty() || chunk_bytes < chunk_size {
+        let mut record = Vec::new();
+        let n = input.read_until(b'\n', &mut record)?;
+        if n == 0 {
+            break;
+        }
+        if record.last() == Some(&b'\n') {
+            record.pop();
+        }
+        chunk_bytes += n;
+        chunk.push(record);
+    }
+    Ok((chunk, chunk_bytes as u64))
+}
+
+/// Sorts the records of `chunk` in memory and writes them to a new run, whose
+/// path is pushed to `paths`.
+#[cfg(not(target_arch = "wasm32"))]
+fn spill_chunk(chunk: &[Vec<u8>], dir: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut records: Vec<&[u8]> = chunk.iter().map(|r| r.as_slice()).collect();
+    sort::msd_radix_sort(&mut records);
+
+    let (path, file) = create_run(dir)?;
+    paths.push(path.clone());
+    trace_event!(?path, records = records.len(), "spilling run");
+
+    let mut writer = BufWriter::new(file);
+    for record in records {
+        writer.write_all(record)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()
+}
+
+/// Second phase: merges the sorted runs into `output`, first merging groups of
+/// `MAX_FAN_IN` runs into new runs in `dir` while there are too many of them.
+#[cfg(not(target_arch = "wasm32"))]
+fn merge_runs<W: Write>(mut runs: Runs, output: W, dir: &Path) -> io::Result<()> {
+    while runs.paths.len() > MAX_FAN_IN {
+        let mut merged = Runs { paths: Vec::new() };
+        for group in runs.paths.chunks(MAX_FAN_IN) {
+            let (path, file) = create_run(dir)?;
+            merged.paths.push(path);
+            merge_files(group, file)?;
+        }
+        trace_event!(
+            from = runs.paths.len(),
+            to = merged.paths.len(),
+            "merge pass"
+        );
+        // Dropping the previous runs removes their files.
+        runs = merged;
+    }
+    merge_files(&runs.paths, output)
+}
+
+/// K-way merge of the sorted run files at `paths` into `output`. The heap holds
+/// the current record of each run together with the index of the run, which
+/// also breaks ties in favour of earlier runs.
+#[cfg(not(target_arch = "wasm32"))]
+fn merge_files<W: Write>(paths: &[PathBuf], output: W) -> io::Result<()> {
+    let mut readers = Vec::with_capacity(paths.len());
+    for path in paths {
+        readers.push(BufReader::new(fs::File::open(path)?).split(b'\n'));
+    }
+
+    let mut heap = MaxHeap::new();
+    for (run, reader) in readers.iter_mut().enumerate() {
+        if let Some(record) = reader.next() {
+            heap.push(Reverse((record?, run)));
+        }
+    }
+
+    let mut writer = BufWriter::new(output);
+    while let Some(Reverse((record, run))) = heap.pop() {
+        writer.write_all(&record)?;
+        writer.write_all(b"\n")?;
+        if let Some(next) = readers[run].next() {
+            heap.push(Reverse((next?, run)));
+        }
+    }
+    writer.flush()
+}
+
+/// External merge sort driven one run at a time, whose progress can be saved
+/// with `Resumable::checkpoint` and restored with `Resumable::resume`, so that
+/// sorting a huge input survives restarts of the process.
+///
+/// The sorter keeps the runs spilled so far and the number of bytes of the
+/// input that they hold. After resuming, the input should be fed again from
+/// that offset, given by `bytes_consumed`. Dropping a sorter leaves its runs
+/// in place so that it can be resumed later; they are removed by `finish`.
+///
+/// Example:
+/// ```
+/// use algae::{resume::Resumable, sort::external::ExternalSorter};
+/// use std::env;
+///
+/// let input = b"pear\nfig\napple\nkiwi\n";
+/// let mut sorter = ExternalSorter::new(8, &env::temp_dir());
+/// sorter.spill_run(&mut &input[..]).unwrap();
+///
+/// // Save the progress, then pick up where it was left.
+/// let mut checkpoint = Vec::new();
+/// sorter.checkpoint(&mut checkpoint).unwrap();
+/// let mut sorter = ExternalSorter::resume(checkpoint.as_slice()).unwrap();
+///
+/// let mut rest = &input[sorter.bytes_consumed() as usize..];
+/// while sorter.spill_run(&mut rest).unwrap() {}
+/// let mut output = Vec::new();
+/// sorter.finish(&mut output).unwrap();
+/// assert_eq!(output, b"apple\nfig\nkiwi\npear\n");
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+pub struct ExternalSorter {
+    chunk_size: usize,
+    dir: PathBuf,
+    runs: Vec<PathBuf>,
+    bytes_consumed: u64,
+}
+
+/// First line of the checkpoints of `ExternalSorter`, identifying the format.
+#[cfg(not(target_arch = "wasm32"))]
+const CHECKPOINT_HEADER: &str = "algae-external-sort 1";
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ExternalSorter {
+    /// Creates a sorter using around `chunk_size` bytes of memory for the
+    /// records being sorted, spilling its runs to the directory `dir`.
+    pub fn new(chunk_size: usize, dir: &Path) -> Self {
+        Self {
+            chunk_size,
+            dir: dir.to_path_buf(),
+            runs: Vec::new(),
+            bytes_consumed: 0,
+        }
+    }
+
+    /// Number of bytes of the input already spilled to runs.
+    pub fn bytes_consumed(&self) -> u64 {
+        self.bytes_consumed
+    }
+
+    /// Number of runs spilled so far.
+    pub fn n_runs(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Reads the next chunk of records from `input` and spills it to a new
+    /// run. Returns `false`, spilling nothing, if `input` is exhausted.
+    pub fn spill_run<R: BufRead>(&mut self, input: &mut R) -> io::Result<bool> {
+        let (chunk, bytes) = read_chunk(input, self.chunk_size)?;
+        if chunk.is_empty() {
+            return Ok(false);
+        }
+        spill_chunk(&chunk, &self.dir, &mut self.runs)?;
+        self.bytes_consumed += bytes;
+        Ok(true)
+    }
+
+    /// Merges the runs spilled so far into `output`, removing them.
+    pub fn finish<W: Write>(self, output: W) -> io::Result<()> {
+        let runs = Runs { paths: self.runs };
+        merge_runs(runs, output, &self.dir)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Resumable for ExternalSorter {
+    /// Writes the chunk size, the directory, the number of bytes consumed and
+    /// the paths of the runs, one per line.
+    fn checkpoint<W: Write>(&self, mut output: W) -> io::Result<()> {
+        let mut lines = vec![
+            CHECKPOINT_HEADER.to_string(),
+            self.chunk_size.to_string(),
+            self.bytes_consumed.to_string(),
+        ];
+        for path in iter::once(&self.dir).chain(&self.runs) {
+            match path.to_str() {
+                Some(p) if !p.contains('\n') => lines.push(p.to_string()),
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("Path {:?} cannot be checkpointed", path),
+                    ))
+                }
+            }
+        }
+        output.write_all(lines.join("\n").as_bytes())?;
+        output.flush()
+    }
+
+    /// Reads a checkpoint written by `checkpoint`, checking that every run
+    /// still exists.
+    fn resume<R: Read>(input: R) -> io::Result<Self> {
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+        let mut lines = BufReader::new(input).lines();
+        let mut next_line = || {
+            lines
+                .next()
+                .unwrap_or_else(|| Err(invalid("Truncated checkpoint")))
+        };
+
+        if next_line()? != CHECKPOINT_HEADER {
+            return Err(invalid("Not a checkpoint of an external sort"));
+        }
+        let chunk_size = next_line()?
+            .parse()
+            .map_err(|_| invalid("Invalid chunk size"))?;
+        let bytes_consumed = next_line()?
+            .parse()
+            .map_err(|_| invalid("Invalid offset"))?;
+        let dir = PathBuf::from(next_line()?);
+        let runs: Vec<PathBuf> = lines
+            .map(|l| l.map(PathBuf::from))
+            .collect::<io::Result<_>>()?;
+
+        if let Some(missing) = runs.iter().find(|p| !p.exists()) {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Run {:?} of the checkpoint is missing", missing),
+            ));
+        }
+        Ok(Self {
+            chunk_size,
+            dir,
+            runs,
+            bytes_consumed,
+        })
+    }
+}
+
+/// K-way merge of sorted sources whose records arrive asynchronously, such as
+/// runs read from the network or from a log-processing pipeline. Each source
+/// is an async closure returning its next record, `None` once exhausted, or
+/// an error if reading failed. The merger only relies on `std::future`, so it
+/// runs on any executor.
+///
+/// As in the merge of runs of `external_merge_sort`, only the current record
+/// of each source is held in memory, and ties are broken in favour of earlier
+/// sources, so the merge is stable.
+///
+/// Example:
+/// ```
+/// use algae::sort::external::AsyncExternalMerger;
+///
+/// async fn merge() -> Vec<u32> {
+///     let runs = [vec![1, 4, 7], vec![2, 3, 9]];
+///     let sources = runs.map(|run| {
+///         let mut records = run.into_iter().map(Ok);
+///         async move || records.next()
+///     });
+///     let mut merger = AsyncExternalMerger::new(sources);
+///     let mut merged = Vec::new();
+///     while let Some(record) = merger.next().await {
+///         merged.push(record.unwrap());
+///     }
+///     merged
+/// }
+/// # use std::future::Future;
+/// # let fut = std::pin::pin!(merge());
+/// # let std::task::Poll::Ready(merged) =
+/// #     fut.poll(&mut std::task::Context::from_waker(std::task::Waker::noop()))
+/// # else {
+/// #     unreachable!()
+/// # };
+/// # assert_eq!(merged, [1, 2, 3, 4, 7, 9]);
+/// ```
+#[cfg(feature = "async")]
+pub struct AsyncExternalMerger<T, S> {
+    sources: Vec<S>,
+    heads: MaxHeap<Reverse<(T, usize)>>,
+    /// Sources before this one already had their first record read.
+    next_to_start: usize,
+    /// Error of a source met while returning a record, returned next.
+    pending_err: Option<io::Error>,
+}
+
+#[cfg(feature = "async")]
+impl<T, S> AsyncExternalMerger<T, S>
+where
+    T: PartialOrd + Clone,
+    S: AsyncFnMut() -> Option<io::Result<T>>,
+{
+    /// Creates the merge of the sorted `sources`. No record is read until the
+    /// first call to `next`.
+    pub fn new<I: IntoIterator<Item = S>>(sources: I) -> Self {
+        Self {
+            sources: sources.into_iter().collect(),
+            heads: MaxHeap::new(),
+            next_to_start: 0,
+            pending_err: None,
+        }
+    }
+
+    /// Returns the next record of the merge, or `None` once every source is
+    /// exhausted. An error of a source is returned as is, and the source is
+    /// not read from again, while the merge of the other sources continues.
+    pub async fn next(&mut self) -> Option<io::Result<T>> {
+        if let Some(e) = self.pending_err.take() {
+            return Some(Err(e));
+        }
+        while self.next_to_start < self.sources.len() {
+            let source = self.next_to_start;
+            self.next_to_start += 1;
+            if let Err(e) = self.refill(source).await {
+                return Some(Err(e));
+            }
+        }
+
+        let Reverse((record, source)) = self.heads.pop()?;
+        if let Err(e) = self.refill(source).await {
+            // The record was already taken from its source, so return it first.
+            self.pending_err = Some(e);
+        }
+        Some(Ok(record))
+    }
+
+    /// Writes every remaining record of the merge to `output`, each terminated
+    /// by a newline.
+    pub async fn write_to<W: Write>(&mut self, output: W) -> io::Result<()>
+    where
+        T: AsRef<[u8]>,
+    {
+        let mut writer = BufWriter::new(output);
+        while let Some(record) = self.next().await {
+            writer.write_all(record?.as_ref())?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()
+    }
+
+    /// Reads the next record of `source` into the heap, if any.
+    async fn refill(&mut self, source: usize) -> io::Result<()> {
+        if let Some(record) = (self.sources[source])().await {
+            self.heads.push(Reverse((record?, source)));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+
+    fn rand_lines(n: usize) -> Vec<String> {
+        (0..n)
+            .map(|_| {
+                let len = fastrand::usize(0..12);
+                (0..len).map(|_| fastrand::alphanumeric()).collect()
+            })
+            .collect()
+    }
+
+    fn sorted_text(lines: &[String]) -> Vec<u8> {
+        let mut sorted = lines.to_vec();
+        sorted.sort();
+        sorted
+            .iter()
+            .flat_map(|l| format!("{}\n", l).into_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn sorting_many_runs() {
+        let lines = rand_lines(5_000);
+        // Terminate every line, as otherwise a trailing empty line would be
+        // indistinguishable from a final newline.
+        let input: String = lines.iter().map(|l| format!("{}\n", l)).collect();
+
+        for chunk_size in [0, 1, 100, 4_096, usize::MAX] {
+            let mut output = Vec::new();
+            external_merge_sort(input.as_bytes(), &mut output, chunk_size).unwrap();
+            assert_eq!(output, sorted_text(&lines));
+        }
+    }
+
+    #[test]
+    fn runs_are_removed() {
+        let input = rand_lines(1_000).join("\n");
+        let runs = spill_runs(input.as_bytes(), 512, &env::temp_dir()).unwrap();
+        let paths = runs.paths.clone();
+        assert!(paths.len() > 1);
+        assert!(paths.iter().all(|p| p.exists()));
+
+        drop(runs);
+        assert!(paths.iter().all(|p| !p.exists()));
+    }
+
+    /// Polls `fut` to completion, for futures that never wait on external
+    /// events, so that no waker is needed.
+    #[cfg(feature = "async")]
+    fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+        use std::task::{Context, Poll, Waker};
+
+        let mut fut = std::pin::pin!(fut);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    /// Future that is pending the first time it is polled, simulating a
+    /// source whose records take a while to arrive.
+    #[cfg(feature = "async")]
+    struct YieldOnce(bool);
+
+    #[cfg(feature = "async")]
+    impl std::future::Future for YieldOnce {
+        type Output = ();
+
+        fn poll(
+            mut self: std::pin::Pin<&mut Self>,
+            _: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<()> {
+            if std::mem::replace(&mut self.0, true) {
+                std::task::Poll::Ready(())
+            } else {
+                std::task::Poll::Pending
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn async_merge() {
+        let lines = rand_lines(2_000);
+        let mut runs: Vec<Vec<String>> = lines.chunks(300).map(|c| c.to_vec()).collect();
+        runs.iter_mut().for_each(|r| r.sort());
+
+        let sources = runs.into_iter().map(|run| {
+            let mut records = run.into_iter();
+            async move || {
+                YieldOnce(false).await;
+                records.next().map(Ok)
+            }
+        });
+        let mut output = Vec::new();
+        block_on(AsyncExternalMerger::new(sources).write_to(&mut output)).unwrap();
+        assert_eq!(output, sorted_text(&lines));
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn async_merge_errors() {
+        let failing = async || -> Option<io::Result<String>> {
+            Some(Err(io::Error::other("connection reset")))
+        };
+        let mut merger = AsyncExternalMerger::new([failing]);
+        let err = block_on(merger.next()).unwrap().unwrap_err();
+        assert_eq!(err.to_string(), "connection reset");
+
+        // Sources failing while starting and in the middle of the merge.
+        let source = |records: Vec<io::Result<&'static str>>| {
+            let mut records = records.into_iter();
+            async move || records.next()
+        };
+        let reset = || Err(io::Error::other("connection reset"));
+        let mut merger = AsyncExternalMerger::new([
+            source(vec![reset()]),
+            source(vec![Ok("a"), Ok("c"), reset()]),
+            source(vec![Ok("b"), Ok("d")]),
+        ]);
+        let mut merged = Vec::new();
+        while let Some(record) = block_on(merger.next()) {
+            merged.push(record.map_err(|e| e.to_string()));
+        }
+        let err = || Err("connection reset".to_string());
+        assert_eq!(merged, [err(), Ok("a"), Ok("b"), Ok("c"), err(), Ok("d")]);
+
+        let mut empty: AsyncExternalMerger<String, _> =
+            AsyncExternalMerger::new(Vec::<fn() -> std::future::Ready<_>>::new());
+        assert!(block_on(empty.next()).is_none());
+    }
+
+    #[test]
+    fn resuming_sort() {
+        let lines = rand_lines(3_000);
+        let input: String = lines.iter().map(|l| format!("{}\n", l)).collect();
+        let input = input.as_bytes();
+        let dir = env::temp_dir();
+
+        // Stop after every few runs, restarting from the last checkpoint.
+        let mut checkpoint = Vec::new();
+        ExternalSorter::new(1_000, &dir)
+            .checkpoint(&mut checkpoint)
+            .unwrap();
+        loop {
+            let mut sorter = ExternalSorter::resume(checkpoint.as_slice()).unwrap();
+            let mut rest = &input[sorter.bytes_consumed() as usize..];
+            let mut exhausted = false;
+            for _ in 0..fastrand::usize(1..5) {
+                if !sorter.spill_run(&mut rest).unwrap() {
+                    exhausted = true;
+                    break;
+                }
+            }
+            if exhausted {
+                let runs = sorter.runs.clone();
+                let mut output = Vec::new();
+                sorter.finish(&mut output).unwrap();
+                assert_eq!(output, sorted_text(&lines));
+                assert!(runs.iter().all(|p| !p.exists()));
+                break;
+            }
+            checkpoint.clear();
+            sorter.checkpoint(&mut checkpoint).unwrap();
+        }
+    }
+
+    #[test]
+    fn resuming_keeps_runs_of_earlier_process() {
+        // Runs checkpointed by an earlier process with the same id, named as
+        // the runs about to be spilled by this one.
+        let dir = env::temp_dir().join(format!("algae-resume-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let next = RUN_ID.load(Ordering::Relaxed);
+        let ids: Vec<usize> = iter::once(0).chain(next..next + 4).collect();
+        let mut checkpoint = format!(
+            "{}\n2\n{}\n{}",
+            CHECKPOINT_HEADER,
+            2 * ids.len(),
+            dir.display()
+        );
+        for id in &ids {
+            let path = dir.join(format!("algae-run-{}-{}.tmp", process::id(), id));
+            fs::write(&path, "x\n").unwrap();
+            checkpoint.push_str(&format!("\n{}", path.display()));
+        }
+
+        let mut sorter = ExternalSorter::resume(checkpoint.as_bytes()).unwrap();
+        let mut rest = &b"c\na\nd\nb\n"[..];
+        while sorter.spill_run(&mut rest).unwrap() {}
+        assert_eq!(sorter.n_runs(), ids.len() + 4);
+        let mut output = Vec::new();
+        sorter.finish(&mut output).unwrap();
+        assert_eq!(output, b"a\nb\nc\nd\nx\nx\nx\nx\nx\n");
+        fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn resuming_invalid_checkpoints() {
+        assert!(ExternalSorter::resume(&b""[..]).is_err());
+        assert!(ExternalSorter::resume(&b"not a checkpoint\n"[..]).is_err());
+        assert!(ExternalSorter::resume(&b"algae-external-sort 1\n16\nx\n/tmp"[..]).is_err());
+
+        let mut sorter = ExternalSorter::new(16, &env::temp_dir());
+        sorter.spill_run(&mut &b"b\na\n"[..]).unwrap();
+        let mut checkpoint = Vec::new();
+        sorter.checkpoint(&mut checkpoint).unwrap();
+        fs::remove_file(&sorter.runs[0]).unwrap();
+        let err = ExternalSorter::resume(checkpoint.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn long_identical_lines() {
+        // Sorting a chunk must not need stack space growing with the length of
+        // the lines.
+        let line = "y".repeat(300_000);
+        let mut lines = vec![line; 20];
+        lines.push("x".to_string());
+        let input: String = lines.iter().map(|l| format!("{}\n", l)).collect();
+
+        let mut output = Vec::new();
+        external_merge_sort(input.as_bytes(), &mut output, usize::MAX).unwrap();
+        assert_eq!(output, sorted_text(&lines));
+    }
+
+    #[test]
+    fn zero_chunk_size() {
+        let mut output = Vec::new();
+        external_merge_sort(&b"b\na\n"[..], &mut output, 0).unwrap();
+        assert_eq!(output, b"a\nb\n");
+
+        // Each run holds a single record.
+        let mut sorter = ExternalSorter::new(0, &env::temp_dir());
+        let mut input = &b"c\nb\na"[..];
+        while sorter.spill_run(&mut input).unwrap() {}
+        assert_eq!((sorter.n_runs(), sorter.bytes_consumed()), (3, 5));
+        let mut output = Vec::new();
+        sorter.finish(&mut output).unwrap();
+        assert_eq!(output, b"a\nb\nc\n");
+    }
+
+    #[test]
+    fn empty_input() {
+        let mut output = Vec::new();
+        external_merge_sort(io::empty(), &mut output, 16).unwrap();
+        assert!(output.is_empty());
+    }
+}
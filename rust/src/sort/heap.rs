@@ -1,7 +1,119 @@
-use crate::ds::MaxHeap;
+use std::cmp::Ordering;
 
-pub fn heap_sort<T: PartialOrd + Copy>(xs: Vec<T>) -> Vec<T> {
-    MaxHeap::heapsort(xs)
+/// Heapsort of the vector `xs`, done in place by swapping the elements of the
+/// vector, so that no element is ever copied or cloned.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// let v = vec![String::from("heap"), String::from("sort"), String::from("by")];
+/// assert_eq!(sort::heap_sort(v), ["by", "heap", "sort"]);
+/// ```
+pub fn heap_sort<T: PartialOrd>(mut xs: Vec<T>) -> Vec<T> {
+    trace_span!("heap_sort", len = xs.len());
+    heap_sort_in_place(&mut xs);
+    xs
+}
+
+/// Heapsort of the vector `xs` with the ordering given by the comparator
+/// `cmp`.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// assert_eq!(sort::heap_sort_by(vec![2, 7, 1, 8], |a, b| b.cmp(a)), [8, 7, 2, 1]);
+/// ```
+pub fn heap_sort_by<T, F>(mut xs: Vec<T>, mut cmp: F) -> Vec<T>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    trace_span!("heap_sort_by", len = xs.len());
+    heap_sort_in_place_with(&mut xs, &mut |a, b| cmp(a, b) == Ordering::Less);
+    xs
+}
+
+/// Heapsort of the vector `xs`, ordering the elements by the keys extracted
+/// with `key`.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// let v = vec!["heap", "by", "sort"];
+/// assert_eq!(sort::heap_sort_by_key(v, |s| s.chars().last()), ["heap", "sort", "by"]);
+/// ```
+pub fn heap_sort_by_key<T, K, F>(mut xs: Vec<T>, key: F) -> Vec<T>
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    trace_span!("heap_sort_by_key", len = xs.len());
+    heap_sort_in_place_with(&mut xs, &mut |a, b| key(a) < key(b));
+    xs
+}
+
+/// Heapsort working directly on the slice `xs`: the slice is first arranged
+/// into a max-heap and then the maximum is repeatedly swapped to the end of
+/// the heap, which shrinks by one. Runs in O(n * log n) with no allocation.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// let mut v = [99, 32, 58, 66, 2, 4, 0, 3928, 55, 88, 30, 44, 3, 2, 0];
+/// sort::heap_sort_in_place(&mut v);
+/// assert_eq!(v, [0, 0, 2, 2, 3, 4, 30, 32, 44, 55, 58, 66, 88, 99, 3928]);
+/// ```
+pub fn heap_sort_in_place<T: PartialOrd>(xs: &mut [T]) {
+    heap_sort_in_place_with(xs, &mut T::lt);
+}
+
+/// Same as `heap_sort_in_place`, where `is_less(a, b)` decides whether `a`
+/// should come before `b`.
+fn heap_sort_in_place_with<T, F>(xs: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let n = xs.len();
+    for root in (0..n / 2).rev() {
+        sift_down(xs, root, n, is_less);
+    }
+    for end in (1..n).rev() {
+        stats_swap!();
+        xs.swap(0, end);
+        sift_down(xs, 0, end, is_less);
+    }
+}
+
+/// Restores the max-heap property of the heap `xs[..end]` for the subtree
+/// rooted at `root`, assuming both subtrees of `root` are max-heaps.
+fn sift_down<T, F>(xs: &mut [T], mut root: usize, end: usize, is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    loop {
+        let left = 2 * root + 1;
+        if left >= end {
+            break;
+        }
+
+        let right = left + 1;
+        let largest = if right < end && is_less(&xs[left], &xs[right]) {
+            right
+        } else {
+            left
+        };
+
+        if is_less(&xs[root], &xs[largest]) {
+            stats_swap!();
+            xs.swap(root, largest);
+            root = largest;
+        } else {
+            break;
+        }
+    }
 }
 
 #[cfg(test)]
@@ -14,4 +126,18 @@ mod test {
             assert!(sort::is_sorted(&super::heap_sort(sort::rand_vec(100))));
         }
     }
+
+    #[test]
+    fn heapsort_by() {
+        for _ in 0..50 {
+            let mut xs = super::heap_sort_by(sort::rand_vec(100), |a, b| b.cmp(a));
+            xs.reverse();
+            assert!(sort::is_sorted(&xs));
+        }
+    }
+
+    #[test]
+    fn heapsort_in_place() {
+        sort::check_sort_fn(super::heap_sort_in_place);
+    }
 }
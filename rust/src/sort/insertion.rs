@@ -1,9 +1,61 @@
-use std::cmp::PartialOrd;
+use std::cmp::{Ordering, PartialOrd};
 
-pub fn insertion_sort<T: PartialOrd + Copy>(xs: &mut [T]) {
+/// Insertion sort: each element is swapped to the left until it meets an
+/// element which is not greater than it. Runs in O(n^2), but in linear time for
+/// nearly sorted inputs. Since equal elements are never swapped past each
+/// other, the sort is stable.
+pub fn insertion_sort<T: PartialOrd>(xs: &mut [T]) {
+    trace_span!("insertion_sort", len = xs.len());
+    insertion_sort_with(xs, &mut T::lt);
+}
+
+/// Insertion sort with the ordering given by the comparator `cmp`.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// let mut v = [3, 1, 4, 1, 5, 9, 2, 6];
+/// sort::insertion_sort_by(&mut v, |a, b| b.cmp(a));
+/// assert_eq!(v, [9, 6, 5, 4, 3, 2, 1, 1]);
+/// ```
+pub fn insertion_sort_by<T, F>(xs: &mut [T], mut cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    trace_span!("insertion_sort_by", len = xs.len());
+    insertion_sort_with(xs, &mut |a, b| cmp(a, b) == Ordering::Less);
+}
+
+/// Insertion sort ordering the elements by the keys extracted with `key`.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// let mut v = ["ccc", "a", "bb"];
+/// sort::insertion_sort_by_key(&mut v, |s| s.len());
+/// assert_eq!(v, ["a", "bb", "ccc"]);
+/// ```
+pub fn insertion_sort_by_key<T, K, F>(xs: &mut [T], key: F)
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    trace_span!("insertion_sort_by_key", len = xs.len());
+    insertion_sort_with(xs, &mut |a, b| key(a) < key(b));
+}
+
+/// Insertion sort where `is_less(a, b)` decides whether `a` should come
+/// before `b`. Shared by the sorts that cut off to insertion sort.
+pub(super) fn insertion_sort_with<T, F>(xs: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
     for not_sorted in 1..xs.len() {
         let mut i = not_sorted;
-        while i > 0 && xs[i - 1] > xs[i] {
+        while i > 0 && is_less(&xs[i], &xs[i - 1]) {
+            stats_swap!();
             xs.swap(i - 1, i);
             i -= 1;
         }
@@ -18,4 +70,18 @@ mod test {
     fn insertion_sort_test() {
         sort::check_sort_fn(super::insertion_sort);
     }
+
+    #[test]
+    fn stability() {
+        sort::check_stable_sort_fn(|xs| super::insertion_sort_by_key(xs, |&(k, _)| k));
+        sort::check_stable_sort_fn(|xs| super::insertion_sort_by(xs, |a, b| a.0.cmp(&b.0)));
+    }
+
+    #[test]
+    fn descending() {
+        sort::check_sort_fn(|xs| {
+            super::insertion_sort_by(xs, |a, b| b.cmp(a));
+            xs.reverse();
+        });
+    }
 }
@@ -0,0 +1,117 @@
+use crate::{
+    math::safe::split_around,
+    sort::{self, heap, quick},
+};
+use std::ops::Range;
+
+/// Introsort algorithm: quicksort that keeps track of its recursion depth and
+/// switches to heapsort once the depth exceeds `2 * log2(n)`. This keeps the
+/// O(n * log n) worst case of heapsort while retaining the speed of quicksort
+/// for typical inputs, without having to shuffle the array beforehand as
+/// `quick_sort` does. Small subarrays are still handled by insertion sort.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// let mut v = [99, 32, 58, 66, 2, 4, 0, 3928, 55, 88, 30, 44, 3, 2, 0];
+/// sort::intro_sort(&mut v);
+/// assert_eq!(v, [0, 0, 2, 2, 3, 4, 30, 32, 44, 55, 58, 66, 88, 99, 3928]);
+/// ```
+pub fn intro_sort<T: PartialOrd>(xs: &mut [T]) {
+    trace_span!("intro_sort", len = xs.len());
+    let depth_limit = 2 * xs.len().max(1).ilog2() as usize;
+    intro_sort_rec(xs, 0..xs.len(), depth_limit);
+}
+
+/// Sorts the subarray `xs[range]`, falling back to heapsort if `depth` reaches
+/// zero.
+fn intro_sort_rec<T: PartialOrd>(xs: &mut [T], range: Range<usize>, depth: usize) {
+    stats_depth!();
+    if range.len() <= quick::CUTOFF {
+        sort::insertion_sort_with(&mut xs[range], &mut T::lt);
+        return;
+    }
+    if depth == 0 {
+        trace_event!(?range, "depth limit reached, falling back to heapsort");
+        heap::heap_sort_in_place(&mut xs[range]);
+        return;
+    }
+
+    median_of_three_to_front(&mut xs[range.clone()]);
+    let pivot_idx = range.start + quick::quick_sort_partition(&mut xs[range.clone()], &mut T::lt);
+    trace_event!(?range, pivot_idx, depth, "partition chosen");
+    let (left, right) = split_around(range, pivot_idx);
+    intro_sort_rec(xs, left, depth - 1);
+    intro_sort_rec(xs, right, depth - 1);
+}
+
+/// Moves the median among the first, middle and last elements of `xs` to the
+/// front, where the partition expects the pivot. This avoids the quadratic
+/// behaviour of a first-element pivot on already sorted inputs.
+fn median_of_three_to_front<T: PartialOrd>(xs: &mut [T]) {
+    let (first, mid, last) = (0, xs.len() / 2, xs.len() - 1);
+    let is_between = |x: usize, a: usize, b: usize| {
+        (xs[a] <= xs[x] && xs[x] <= xs[b]) || (xs[b] <= xs[x] && xs[x] <= xs[a])
+    };
+    let median = if is_between(mid, first, last) {
+        mid
+    } else if is_between(first, mid, last) {
+        first
+    } else {
+        last
+    };
+    stats_swap!();
+    xs.swap(first, median);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sorting() {
+        sort::check_sort_fn(intro_sort);
+    }
+
+    #[test]
+    fn sorting_adversarial() {
+        let mut sorted: Vec<i32> = (0..1000).collect();
+        intro_sort(&mut sorted);
+        assert!(sort::is_sorted(&sorted));
+
+        let mut reversed: Vec<i32> = (0..1000).rev().collect();
+        intro_sort(&mut reversed);
+        assert!(sort::is_sorted(&reversed));
+
+        let mut equal = vec![5; 1000];
+        intro_sort(&mut equal);
+        assert_eq!(equal, vec![5; 1000]);
+
+        let mut organ_pipe: Vec<i32> = (0..500).chain((0..500).rev()).collect();
+        intro_sort(&mut organ_pipe);
+        assert!(sort::is_sorted(&organ_pipe));
+    }
+
+    #[test]
+    fn heapsort_fallback() {
+        // A depth limit of zero forces the heapsort path on the whole array.
+        let mut xs = sort::rand_vec(1000);
+        let len = xs.len();
+        intro_sort_rec(&mut xs, 0..len, 0);
+        assert!(sort::is_sorted(&xs));
+    }
+
+    #[test]
+    fn median_of_three() {
+        for (mut xs, median) in [
+            ([1, 2, 3], 2),
+            ([3, 2, 1], 2),
+            ([2, 3, 1], 2),
+            ([1, 1, 0], 1),
+        ] {
+            median_of_three_to_front(&mut xs);
+            assert_eq!(xs[0], median);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use crate::ds::{Heap, MaxHeap};
+use std::{cmp::Reverse, iter::Cloned, slice};
+
+/// Iterator adaptor lazily merging `k` sorted iterators into a single sorted
+/// sequence. A heap holds the current head of each iterator, together with the
+/// index of the iterator, so that only `k` items are held in memory at once.
+/// Each item is produced in O(log k) time.
+///
+/// Ties are broken in favour of earlier iterators, so the merge is stable: if
+/// each input is sorted stably, so is the output. The inputs are assumed to be
+/// sorted, otherwise the output is not sorted either.
+///
+/// Example:
+/// ```
+/// use algae::sort::KWayMerge;
+///
+/// let evens = (0..10).step_by(2);
+/// let odds = (1..10).step_by(2);
+/// let merged: Vec<i32> = KWayMerge::new([evens, odds]).collect();
+/// assert_eq!(merged, (0..10).collect::<Vec<_>>());
+/// ```
+pub struct KWayMerge<I: Iterator> {
+    iters: Vec<I>,
+    heads: MaxHeap<Reverse<(I::Item, usize)>>,
+}
+
+impl<I> KWayMerge<I>
+where
+    I: Iterator,
+    I::Item: PartialOrd + Clone,
+{
+    /// Creates the merge of the sorted iterators `iters`.
+    pub fn new<J: IntoIterator<Item = I>>(iters: J) -> Self {
+        let mut iters: Vec<I> = iters.into_iter().collect();
+        let mut heads = MaxHeap::new();
+        for (idx, iter) in iters.iter_mut().enumerate() {
+            if let Some(item) = iter.next() {
+                heads.push(Reverse((item, idx)));
+            }
+        }
+        Self { iters, heads }
+    }
+}
+
+impl<I> Iterator for KWayMerge<I>
+where
+    I: Iterator,
+    I::Item: PartialOrd + Clone,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((item, idx)) = self.heads.pop()?;
+        if let Some(next) = self.iters[idx].next() {
+            self.heads.push(Reverse((next, idx)));
+        }
+        Some(item)
+    }
+}
+
+/// Merges the sorted slices `slices` into a single sorted vector, using
+/// `KWayMerge`. Runs in O(n * log k) time for `n` elements in `k` slices.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// let merged = sort::merge_k_sorted(&[&[1, 4, 7][..], &[2, 5], &[], &[0, 3, 6, 9]]);
+/// assert_eq!(merged, [0, 1, 2, 3, 4, 5, 6, 7, 9]);
+/// ```
+pub fn merge_k_sorted<T: PartialOrd + Clone>(slices: &[&[T]]) -> Vec<T> {
+    trace_span!("merge_k_sorted", k = slices.len());
+    let merge: KWayMerge<Cloned<slice::Iter<T>>> =
+        KWayMerge::new(slices.iter().map(|s| s.iter().cloned()));
+    merge.collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sort;
+
+    #[test]
+    fn merging_slices() {
+        for _ in 0..20 {
+            let k = fastrand::usize(..10);
+            let runs: Vec<Vec<i64>> = (0..k)
+                .map(|_| {
+                    let mut run = sort::rand_vec(fastrand::usize(..50));
+                    run.sort();
+                    run
+                })
+                .collect();
+            let slices: Vec<&[i64]> = runs.iter().map(|r| r.as_slice()).collect();
+
+            let mut expected: Vec<i64> = runs.concat();
+            expected.sort();
+            assert_eq!(merge_k_sorted(&slices), expected);
+        }
+        assert!(merge_k_sorted::<i64>(&[]).is_empty());
+    }
+
+    #[test]
+    fn merge_is_stable() {
+        /// Item ordered by its key only.
+        #[derive(Debug, Clone, PartialEq)]
+        struct Keyed(u8, char);
+
+        impl PartialOrd for Keyed {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                self.0.partial_cmp(&other.0)
+            }
+        }
+
+        let a = [Keyed(1, 'z'), Keyed(2, 'z')];
+        let b = [Keyed(1, 'a'), Keyed(2, 'a')];
+        let merged: Vec<Keyed> = KWayMerge::new([a.iter().cloned(), b.iter().cloned()]).collect();
+        let tags: Vec<char> = merged.iter().map(|k| k.1).collect();
+        assert_eq!(tags, ['z', 'a', 'z', 'a']);
+    }
+
+    #[test]
+    fn lazy_infinite_iterators() {
+        let multiples = |m: u64| (1..).map(move |i| i * m);
+        let merged: Vec<u64> = KWayMerge::new([multiples(3), multiples(5)])
+            .take(7)
+            .collect();
+        assert_eq!(merged, [3, 5, 6, 9, 10, 12, 15]);
+    }
+}
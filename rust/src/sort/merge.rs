@@ -1,52 +1,304 @@
-/// Merge sort algorithm.
-pub fn merge_sort<T: PartialOrd + Copy>(xs: &mut [T]) {
-    merge_sort_rec(xs, 0, xs.len() - 1);
+use crate::math::safe::range_midpoint;
+use std::{cmp::Ordering, ops::Range};
+
+/// Merge sort algorithm: sorts both halves of the array recursively and then
+/// merges them. Runs in O(n * log n) with O(n) extra space. The merge takes
+/// from the left half on ties, so the sort is stable.
+pub fn merge_sort<T: PartialOrd + Clone>(xs: &mut [T]) {
+    trace_span!("merge_sort", len = xs.len());
+    merge_sort_rec(xs, 0..xs.len(), &mut Vec::new(), &mut T::lt);
+}
+
+/// Same as `merge_sort`, but every merge goes through the auxiliary buffer
+/// `buf` provided by the caller, which grows to hold half of `xs` if needed.
+/// Reusing the same buffer across calls, as when sorting many arrays in a
+/// loop, avoids allocating at all once the buffer is large enough. The
+/// contents of `buf` are unspecified after the call.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// let mut buf = Vec::new();
+/// for mut v in [vec![3, 1, 2], vec![9, 7, 8, 6]] {
+///     sort::merge_sort_with_buf(&mut v, &mut buf);
+///     assert!(sort::is_sorted(&v));
+/// }
+/// ```
+pub fn merge_sort_with_buf<T: PartialOrd + Clone>(xs: &mut [T], buf: &mut Vec<T>) {
+    trace_span!("merge_sort_with_buf", len = xs.len());
+    merge_sort_rec(xs, 0..xs.len(), buf, &mut T::lt);
+}
+
+/// Merge sort with the ordering given by the comparator `cmp`.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// let mut v = ["Merge", "sort", "By", "comparator"];
+/// sort::merge_sort_by(&mut v, |a, b| a.to_lowercase().cmp(&b.to_lowercase()));
+/// assert_eq!(v, ["By", "comparator", "Merge", "sort"]);
+/// ```
+pub fn merge_sort_by<T, F>(xs: &mut [T], mut cmp: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    trace_span!("merge_sort_by", len = xs.len());
+    merge_sort_rec(xs, 0..xs.len(), &mut Vec::new(), &mut |a, b| {
+        cmp(a, b) == Ordering::Less
+    });
+}
+
+/// Merge sort ordering the elements by the keys extracted with `key`.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// let mut v = [(3, "c"), (1, "a"), (2, "b")];
+/// sort::merge_sort_by_key(&mut v, |&(k, _)| k);
+/// assert_eq!(v, [(1, "a"), (2, "b"), (3, "c")]);
+/// ```
+pub fn merge_sort_by_key<T, K, F>(xs: &mut [T], key: F)
+where
+    T: Clone,
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    trace_span!("merge_sort_by_key", len = xs.len());
+    merge_sort_rec(xs, 0..xs.len(), &mut Vec::new(), &mut |a, b| {
+        key(a) < key(b)
+    });
+}
+
+/// Recursive counterpart of merge-sort, sorting the subarray `xs[range]`,
+/// where `is_less(a, b)` decides whether `a` should come before `b`. All the
+/// merges share the auxiliary buffer `buf`.
+fn merge_sort_rec<T, F>(xs: &mut [T], range: Range<usize>, buf: &mut Vec<T>, is_less: &mut F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> bool,
+{
+    stats_depth!();
+    // Subarrays with less than two elements are already sorted.
+    if let Some(mid) = range_midpoint(&range).filter(|_| range.len() > 1) {
+        merge_sort_rec(xs, range.start..mid, buf, is_less);
+        merge_sort_rec(xs, mid..range.end, buf, is_less);
+        trace_event!(?range, mid, "merging halves");
+        merge_with(xs, range.start, mid, range.end, buf, is_less);
+    }
 }
 
-/// Recursive counterpart of merge-sort.
-fn merge_sort_rec<T: PartialOrd + Copy>(xs: &mut [T], low: usize, top: usize) {
-    if low < top {
-        let mid = (low + top) / 2;
-        merge_sort_rec(xs, low, mid);
-        merge_sort_rec(xs, mid + 1, top);
-        merge(xs, low, mid, top);
+/// Bottom-up merge sort: instead of recursively splitting the array, merges
+/// consecutive subarrays of width 1, then 2, 4, and so on, until the whole
+/// array is merged. Since it is a plain loop, there is no recursion stack,
+/// while the number of comparisons is the same as the top-down `merge_sort`.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// let mut v = [99, 32, 58, 66, 2, 4, 0, 3928, 55, 88, 30, 44, 3, 2, 0];
+/// sort::merge_sort_bottom_up(&mut v);
+/// assert_eq!(v, [0, 0, 2, 2, 3, 4, 30, 32, 44, 55, 58, 66, 88, 99, 3928]);
+/// ```
+pub fn merge_sort_bottom_up<T: PartialOrd + Clone>(xs: &mut [T]) {
+    trace_span!("merge_sort_bottom_up", len = xs.len());
+    let n = xs.len();
+    let mut buf = Vec::new();
+    let mut width = 1;
+    while width < n {
+        // Merge the pairs `xs[low..mid]` and `xs[mid..high]` of width `width`,
+        // where the last right subarray may be shorter.
+        let mut low = 0;
+        while width < n - low {
+            let mid = low + width;
+            let high = mid + width.min(n - mid);
+            merge(xs, low, mid, high, &mut buf);
+            low = high;
+        }
+        width = width.saturating_mul(2);
     }
 }
 
 /// Merges two sorted arrays into the original array `xs`.
-/// * `low`: index of the first element.
-/// * `mid`: index of middle element.
-/// * `top`: index of the last element.
-///
-/// Takes time `O(n)` where `n = top - low + 1`.
-fn merge<T: PartialOrd + Copy>(xs: &mut [T], low: usize, mid: usize, top: usize) {
-    let left = xs[low..=mid].to_vec();
-    let right = xs[(mid + 1)..=top].to_vec();
-
-    // Merge `left` and `right` into `xs`
-    xs[low..=top].iter_mut().fold((0, 0), |(i, j), x| {
-        let in_left = i < left.len();
-        let in_right = j < right.len();
-
-        if in_left && in_right {
-            // Check for the minimum element
-            if left[i] <= right[j] {
-                *x = left[i];
-                (i + 1, j)
-            } else {
-                *x = right[j];
-                (i, j + 1)
-            }
-        } else if in_left {
-            // Dump the remainder of the left array
-            *x = left[i];
-            (i + 1, j)
+/// * `low`: index of the first element of the left array.
+/// * `mid`: index of the first element of the right array.
+/// * `high`: index one past the last element of the right array.
+///
+/// That is, the sorted subarrays `xs[low..mid]` and `xs[mid..high]` are merged
+/// into `xs[low..high]`, using `buf` as auxiliary space for the left array.
+/// Takes time `O(n)` where `n = high - low`.
+fn merge<T: PartialOrd + Clone>(
+    xs: &mut [T],
+    low: usize,
+    mid: usize,
+    high: usize,
+    buf: &mut Vec<T>,
+) {
+    merge_with(xs, low, mid, high, buf, &mut T::lt);
+}
+
+/// Same as `merge`, where `is_less(a, b)` decides whether `a` should come
+/// before `b`. Returns the number of inversions between both arrays, that is,
+/// the number of pairs of a left element and a right element where the right
+/// one is strictly less than the left one.
+fn merge_with<T, F>(
+    xs: &mut [T],
+    low: usize,
+    mid: usize,
+    high: usize,
+    buf: &mut Vec<T>,
+    is_less: &mut F,
+) -> u64
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> bool,
+{
+    // Only the left array is cloned into the buffer. The next position to
+    // write, `k`, never reaches the next right element `j` while the buffer
+    // is non-empty, and the positions in `k..j` hold stale elements, so that
+    // right elements can simply be swapped into place.
+    buf.clear();
+    buf.extend_from_slice(&xs[low..mid]);
+    let mut left = buf.drain(..).peekable();
+    let (mut k, mut j) = (low, mid);
+
+    // Take from the left array on ties so that the merge is stable. Whenever
+    // a right element is taken, it is less than every element remaining in the
+    // left array. Once the left array is exhausted, the remaining right
+    // elements are already in place.
+    let mut inversions = 0;
+    while let Some(l) = left.peek() {
+        if j < high && is_less(&xs[j], l) {
+            inversions += left.len() as u64;
+            xs.swap(k, j);
+            j += 1;
         } else {
-            // Dump the remainder of the right array
-            *x = right[j];
-            (i, j + 1)
+            xs[k] = left.next().expect("Left array should be non-empty");
         }
-    });
+        k += 1;
+    }
+    inversions
+}
+
+/// Counts the inversions of `xs`, that is, the pairs of indices `i < j` with
+/// `xs[j] < xs[i]`, which measures how far `xs` is from being sorted: sorted
+/// arrays have no inversions, while reversed arrays of distinct elements have
+/// all the `n * (n - 1) / 2` possible ones. This is also the number of swaps
+/// done by insertion sort.
+///
+/// A copy of `xs` is merge sorted, summing the inversions found across the
+/// halves by each merge, which takes O(n * log n) time.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// assert_eq!(sort::count_inversions(&[2, 4, 1, 3, 5]), 3);
+/// assert_eq!(sort::count_inversions(&[1, 1, 1]), 0);
+/// ```
+pub fn count_inversions<T: PartialOrd + Clone>(xs: &[T]) -> u64 {
+    trace_span!("count_inversions", len = xs.len());
+    let mut ys = xs.to_vec();
+    let len = ys.len();
+    count_inversions_rec(&mut ys, 0..len, &mut Vec::new())
+}
+
+/// Merge sorts `xs[range]`, returning its number of inversions.
+fn count_inversions_rec<T: PartialOrd + Clone>(
+    xs: &mut [T],
+    range: Range<usize>,
+    buf: &mut Vec<T>,
+) -> u64 {
+    stats_depth!();
+    match range_midpoint(&range).filter(|_| range.len() > 1) {
+        Some(mid) => {
+            count_inversions_rec(xs, range.start..mid, buf)
+                + count_inversions_rec(xs, mid..range.end, buf)
+                + merge_with(xs, range.start, mid, range.end, buf, &mut T::lt)
+        }
+        None => 0,
+    }
+}
+
+/// Merge sort using `merge_in_place`, so that no auxiliary array is needed:
+/// apart from the recursion stack, only O(1) extra space is used. The price is
+/// an extra logarithmic factor in the running time, which is O(n * log^2 n).
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// let mut v = vec![String::from("merge"), String::from("in"), String::from("place")];
+/// sort::in_place_merge_sort(&mut v);
+/// assert_eq!(v, ["in", "merge", "place"]);
+/// ```
+pub fn in_place_merge_sort<T: PartialOrd>(xs: &mut [T]) {
+    trace_span!("in_place_merge_sort", len = xs.len());
+    in_place_merge_sort_rec(xs);
+}
+
+fn in_place_merge_sort_rec<T: PartialOrd>(xs: &mut [T]) {
+    stats_depth!();
+    if xs.len() > 1 {
+        let mid = xs.len() / 2;
+        let (left, right) = xs.split_at_mut(mid);
+        in_place_merge_sort_rec(left);
+        in_place_merge_sort_rec(right);
+        merge_in_place(xs, mid);
+    }
+}
+
+/// Merges the sorted subarrays `xs[..mid]` and `xs[mid..]` without allocating
+/// any auxiliary array, as opposed to `merge`.
+///
+/// The middle element of the longer run is used as a pivot, and its position
+/// in the other run is found by binary search. Rotating the block between the
+/// two positions moves every element to the correct side of the pivot, leaving
+/// two smaller merge problems, which are solved recursively. The merge is
+/// stable and takes time O(n * log n) where `n = xs.len()`.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// let mut v = [1, 4, 6, 9, 2, 3, 7];
+/// sort::merge_in_place(&mut v, 4);
+/// assert_eq!(v, [1, 2, 3, 4, 6, 7, 9]);
+/// ```
+pub fn merge_in_place<T: PartialOrd>(xs: &mut [T], mid: usize) {
+    stats_depth!();
+    let n = xs.len();
+    if mid == 0 || mid >= n {
+        return;
+    }
+    if n == 2 {
+        if xs[1] < xs[0] {
+            stats_swap!();
+            xs.swap(0, 1);
+        }
+        return;
+    }
+
+    // Elements of the right run only jump over strictly greater elements of
+    // the left run, which keeps the merge stable.
+    let (cut_left, cut_right) = if mid >= n - mid {
+        let cut_left = mid / 2;
+        let pivot = &xs[cut_left];
+        (cut_left, mid + xs[mid..].partition_point(|x| x < pivot))
+    } else {
+        let cut_right = mid + (n - mid) / 2;
+        let pivot = &xs[cut_right];
+        (xs[..mid].partition_point(|x| x <= pivot), cut_right)
+    };
+
+    xs[cut_left..cut_right].rotate_left(mid - cut_left);
+    let new_mid = cut_left + (cut_right - mid);
+    merge_in_place(&mut xs[..new_mid], cut_left);
+    merge_in_place(&mut xs[new_mid..], mid - cut_left);
 }
 
 #[cfg(test)]
@@ -58,14 +310,127 @@ mod test {
         sort::check_sort_fn(super::merge_sort);
     }
 
+    #[test]
+    fn sorting_with_buf() {
+        sort::check_sort_fn(|xs| super::merge_sort_with_buf(xs, &mut Vec::new()));
+
+        // The same buffer reused across sorts of different lengths.
+        let mut buf = Vec::new();
+        for len in [100, 7, 0, 250, 1] {
+            let mut xs = sort::rand_vec(len);
+            super::merge_sort_with_buf(&mut xs, &mut buf);
+            assert!(sort::is_sorted(&xs));
+        }
+    }
+
+    #[test]
+    fn stability() {
+        sort::check_stable_sort_fn(|xs| super::merge_sort_by_key(xs, |&(k, _)| k));
+        sort::check_stable_sort_fn(|xs| super::merge_sort_by(xs, |a, b| a.0.cmp(&b.0)));
+    }
+
+    #[test]
+    fn sorting_bottom_up() {
+        sort::check_sort_fn(super::merge_sort_bottom_up);
+
+        // Lengths that are not a power of two leave unpaired subarrays.
+        for n in [2, 3, 5, 17, 100, 129] {
+            let mut xs = sort::rand_vec(n);
+            super::merge_sort_bottom_up(&mut xs);
+            assert!(sort::is_sorted(&xs));
+        }
+    }
+
+    #[test]
+    fn sorting_in_place() {
+        sort::check_sort_fn(super::in_place_merge_sort);
+
+        let mut xs: Vec<String> = ["pear", "fig", "apple", "kiwi"].map(String::from).into();
+        super::in_place_merge_sort(&mut xs);
+        assert_eq!(xs, ["apple", "fig", "kiwi", "pear"]);
+    }
+
+    #[test]
+    fn merge_in_place_agrees_with_merge() {
+        for _ in 0..200 {
+            let n = fastrand::usize(0..60);
+            let mid = fastrand::usize(0..=n);
+            let mut xs: Vec<u8> = (0..n).map(|_| fastrand::u8(..10)).collect();
+            xs[..mid].sort();
+            xs[mid..].sort();
+
+            let mut ys = xs.clone();
+            super::merge(&mut xs, 0, mid, n, &mut Vec::new());
+            super::merge_in_place(&mut ys, mid);
+            assert_eq!(xs, ys);
+        }
+    }
+
+    #[test]
+    fn merge_in_place_is_stable() {
+        // Pairs compared by their first entry only, tagged by their position.
+        #[derive(Debug, Clone, Copy)]
+        struct Tagged(u8, usize);
+        impl PartialEq for Tagged {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+        impl PartialOrd for Tagged {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                self.0.partial_cmp(&other.0)
+            }
+        }
+
+        for _ in 0..100 {
+            let mut keys: Vec<u8> = (0..50).map(|_| fastrand::u8(..5)).collect();
+            keys[..20].sort();
+            keys[20..].sort();
+            let mut xs: Vec<Tagged> = keys
+                .iter()
+                .enumerate()
+                .map(|(i, &k)| Tagged(k, i))
+                .collect();
+
+            super::merge_in_place(&mut xs, 20);
+            assert!(xs
+                .windows(2)
+                .all(|w| w[0].0 < w[1].0 || (w[0].0 == w[1].0 && w[0].1 < w[1].1)));
+        }
+    }
+
+    #[test]
+    fn inversions() {
+        let brute_force = |xs: &[u8]| {
+            let mut count = 0;
+            for i in 0..xs.len() {
+                for j in i + 1..xs.len() {
+                    count += (xs[j] < xs[i]) as u64;
+                }
+            }
+            count
+        };
+
+        for _ in 0..50 {
+            let xs: Vec<u8> = (0..fastrand::usize(..200))
+                .map(|_| fastrand::u8(..20))
+                .collect();
+            assert_eq!(super::count_inversions(&xs), brute_force(&xs));
+        }
+
+        let reversed: Vec<u32> = (0..1_000).rev().collect();
+        assert_eq!(super::count_inversions(&reversed), 1_000 * 999 / 2);
+        assert_eq!(super::count_inversions::<u32>(&[]), 0);
+    }
+
     #[test]
     fn merge_arrays() {
         let mut xs = vec![5, 6, 7, 8, 1, 2, 3, 4];
-        super::merge(&mut xs, 0, 3, 7);
+        super::merge(&mut xs, 0, 4, 8, &mut Vec::new());
         assert_eq!(xs, vec![1, 2, 3, 4, 5, 6, 7, 8]);
 
         let mut xs = vec![13, 11, 5, 6, 7, 8, 1, 2, 3, 4, 90, 21];
-        super::merge(&mut xs, 2, 5, 9);
+        super::merge(&mut xs, 2, 6, 10, &mut Vec::new());
         assert_eq!(xs, vec![13, 11, 1, 2, 3, 4, 5, 6, 7, 8, 90, 21]);
     }
 }
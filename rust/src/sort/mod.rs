@@ -1,27 +1,57 @@
+//! Sorting algorithms.
+//!
+//! A sort is *stable* if elements that compare equal keep their relative
+//! order, which matters when sorting records by one of their fields. The
+//! stable sorts of this module are `insertion_sort`, `merge_sort`,
+//! `merge_sort_bottom_up`, `in_place_merge_sort` and `msd_radix_sort`, as well
+//! as their `_by` and `_by_key` variants, and `external::external_merge_sort`.
+//! The quicksorts, heapsort and introsort move elements across long distances
+//! and are not stable. Stable sorts are checked by `check_stable_sort_fn`.
+pub mod external;
+pub mod network;
+#[cfg(feature = "stats")]
+pub mod stats;
+
 mod insertion;
 pub use insertion::*;
 
+mod intro;
+pub use intro::*;
+
+mod kway;
+pub use kway::*;
+
 mod merge;
 pub use merge::*;
 
+#[cfg(feature = "parallel")]
+mod parallel;
+#[cfg(feature = "parallel")]
+pub use parallel::*;
+
 mod heap;
 pub use heap::*;
 
+mod partial;
+pub use partial::*;
+
+mod permutation;
+pub use permutation::*;
+
 mod quick;
 pub use quick::*;
 
+mod radix;
+pub use radix::*;
+
+mod select;
+pub use select::*;
+
 use fastrand;
 use std::{cmp::PartialOrd, iter};
 
 pub fn is_sorted(xs: &[impl PartialOrd]) -> bool {
-    let mut last = &xs[0];
-    for next in xs {
-        if last > next {
-            return false;
-        }
-        last = next;
-    }
-    true
+    xs.windows(2).all(|w| w[0] <= w[1])
 }
 
 pub fn rand_vec(vec_size: usize) -> Vec<i64> {
@@ -31,6 +61,12 @@ pub fn rand_vec(vec_size: usize) -> Vec<i64> {
 }
 
 pub fn check_sort_fn<F: Fn(&mut [i64])>(sort_fn: F) {
+    // Edge cases: empty and single element arrays.
+    sort_fn(&mut []);
+    let mut xs = [7];
+    sort_fn(&mut xs);
+    assert_eq!(xs, [7]);
+
     for _ in 0..50 {
         let mut xs = rand_vec(100);
         sort_fn(&mut xs);
@@ -38,6 +74,22 @@ pub fn check_sort_fn<F: Fn(&mut [i64])>(sort_fn: F) {
     }
 }
 
+/// Checks that `sort_fn` is a stable sort when ordering the pairs `(key, i)`
+/// by their key only: the pairs are built with increasing indices `i` and few
+/// distinct keys, so that ties must keep the indices increasing.
+pub fn check_stable_sort_fn<F: Fn(&mut [(i64, usize)])>(sort_fn: F) {
+    for n_keys in [1, 5, 50] {
+        let mut xs: Vec<(i64, usize)> = (0..200).map(|i| (fastrand::i64(0..n_keys), i)).collect();
+        sort_fn(&mut xs);
+        assert!(
+            xs.windows(2)
+                .all(|w| w[0].0 < w[1].0 || (w[0].0 == w[1].0 && w[0].1 < w[1].1)),
+            "Sort is either incorrect or unstable: {:?}",
+            xs
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -49,5 +101,40 @@ mod tests {
 
         let v2 = vec![0, 3, 9, 8, 10];
         assert!(!is_sorted(&v2));
+
+        let v3: Vec<i32> = vec![];
+        assert!(is_sorted(&v3));
+    }
+
+    #[test]
+    fn sorting_non_copy() {
+        let sorts: [fn(&mut [String]); 7] = [
+            insertion_sort,
+            merge_sort,
+            merge_sort_bottom_up,
+            quick_sort,
+            quick_three_way_sort,
+            intro_sort,
+            heap_sort_in_place,
+        ];
+        for sort_fn in sorts {
+            let mut xs: Vec<String> = rand_vec(200).iter().map(|x| x.to_string()).collect();
+            let mut expected = xs.clone();
+            expected.sort();
+            sort_fn(&mut xs);
+            assert_eq!(xs, expected);
+        }
+
+        let xs: Vec<String> = rand_vec(200).iter().map(|x| x.to_string()).collect();
+        assert!(is_sorted(&heap_sort(xs)));
+    }
+
+    #[test]
+    #[should_panic(expected = "unstable")]
+    fn detects_unstable_sort() {
+        check_stable_sort_fn(|xs| {
+            let sorted = heap_sort_by_key(xs.to_vec(), |&(k, _)| k);
+            xs.copy_from_slice(&sorted);
+        });
     }
 }
@@ -0,0 +1,359 @@
+//! Sorting networks for tiny slices.
+//!
+//! A sorting network is a fixed sequence of *comparators* `(i, j)` with
+//! `i < j`, each of which swaps `xs[i]` and `xs[j]` if they are out of order.
+//! Since the sequence does not depend on the input, and the swaps are done
+//! with conditional moves, a network has no data-dependent control flow,
+//! which suits tiny inputs better than the unpredictable branches of insertion
+//! sort. The networks below have the least known number of comparators for
+//! each size, which has been proven optimal for sizes up to 12.
+//!
+//! By the 0-1 principle, a network sorts every input if and only if it sorts
+//! every sequence of zeros and ones, which is how the networks are verified.
+
+use std::{mem::ManuallyDrop, ptr};
+
+/// Largest size for which a sorting network is available.
+pub const MAX_LEN: usize = 16;
+
+/// Layers of comparators of the network sorting `n` elements, indexed by `n`.
+/// The comparators of a layer touch disjoint positions, so that they are
+/// independent of each other.
+#[rustfmt::skip]
+const NETWORKS: [&[&[(u8, u8)]]; MAX_LEN + 1] = [
+    &[],
+    &[],
+    // 2 elements: 1 comparator in 1 layer.
+    &[
+        &[(0, 1)],
+    ],
+    // 3 elements: 3 comparators in 3 layers.
+    &[
+        &[(0, 2)],
+        &[(0, 1)],
+        &[(1, 2)],
+    ],
+    // 4 elements: 5 comparators in 3 layers.
+    &[
+        &[(0, 2), (1, 3)],
+        &[(0, 1), (2, 3)],
+        &[(1, 2)],
+    ],
+    // 5 elements: 9 comparators in 5 layers.
+    &[
+        &[(0, 3), (1, 4)],
+        &[(0, 2), (1, 3)],
+        &[(0, 1), (2, 4)],
+        &[(1, 2), (3, 4)],
+        &[(2, 3)],
+    ],
+    // 6 elements: 12 comparators in 5 layers.
+    &[
+        &[(0, 5), (1, 3), (2, 4)],
+        &[(1, 2), (3, 4)],
+        &[(0, 3), (2, 5)],
+        &[(0, 1), (2, 3), (4, 5)],
+        &[(1, 2), (3, 4)],
+    ],
+    // 7 elements: 16 comparators in 6 layers.
+    &[
+        &[(0, 6), (2, 3), (4, 5)],
+        &[(0, 2), (1, 4), (3, 6)],
+        &[(0, 1), (2, 5), (3, 4)],
+        &[(1, 2), (4, 6)],
+        &[(2, 3), (4, 5)],
+        &[(1, 2), (3, 4), (5, 6)],
+    ],
+    // 8 elements: 19 comparators in 6 layers.
+    &[
+        &[(0, 2), (1, 3), (4, 6), (5, 7)],
+        &[(0, 4), (1, 5), (2, 6), (3, 7)],
+        &[(0, 1), (2, 3), (4, 5), (6, 7)],
+        &[(2, 4), (3, 5)],
+        &[(1, 4), (3, 6)],
+        &[(1, 2), (3, 4), (5, 6)],
+    ],
+    // 9 elements: 25 comparators in 7 layers.
+    &[
+        &[(0, 3), (1, 7), (2, 5), (4, 8)],
+        &[(0, 7), (2, 4), (3, 8), (5, 6)],
+        &[(0, 2), (1, 3), (4, 5), (7, 8)],
+        &[(1, 4), (3, 6), (5, 7)],
+        &[(0, 1), (2, 4), (3, 5), (6, 8)],
+        &[(2, 3), (4, 5), (6, 7)],
+        &[(1, 2), (3, 4), (5, 6)],
+    ],
+    // 10 elements: 29 comparators in 8 layers.
+    &[
+        &[(0, 8), (1, 9), (2, 7), (3, 5), (4, 6)],
+        &[(0, 2), (1, 4), (5, 8), (7, 9)],
+        &[(0, 3), (2, 4), (5, 7), (6, 9)],
+        &[(0, 1), (3, 6), (8, 9)],
+        &[(1, 5), (2, 3), (4, 8), (6, 7)],
+        &[(1, 2), (3, 5), (4, 6), (7, 8)],
+        &[(2, 3), (4, 5), (6, 7)],
+        &[(3, 4), (5, 6)],
+    ],
+    // 11 elements: 35 comparators in 8 layers.
+    &[
+        &[(0, 9), (1, 6), (2, 4), (3, 7), (5, 8)],
+        &[(0, 1), (3, 5), (4, 10), (6, 9), (7, 8)],
+        &[(1, 3), (2, 5), (4, 7), (8, 10)],
+        &[(0, 4), (1, 2), (3, 7), (5, 9), (6, 8)],
+        &[(0, 1), (2, 6), (4, 5), (7, 8), (9, 10)],
+        &[(2, 4), (3, 6), (5, 7), (8, 9)],
+        &[(1, 2), (3, 4), (5, 6), (7, 8)],
+        &[(2, 3), (4, 5), (6, 7)],
+    ],
+    // 12 elements: 39 comparators in 9 layers.
+    &[
+        &[(0, 8), (1, 7), (2, 6), (3, 11), (4, 10), (5, 9)],
+        &[(0, 1), (2, 5), (3, 4), (6, 9), (7, 8), (10, 11)],
+        &[(0, 2), (1, 6), (5, 10), (9, 11)],
+        &[(0, 3), (1, 2), (4, 6), (5, 7), (8, 11), (9, 10)],
+        &[(1, 4), (3, 5), (6, 8), (7, 10)],
+        &[(1, 3), (2, 5), (6, 9), (8, 10)],
+        &[(2, 3), (4, 5), (6, 7), (8, 9)],
+        &[(4, 6), (5, 7)],
+        &[(3, 4), (5, 6), (7, 8)],
+    ],
+    // 13 elements: 45 comparators in 10 layers.
+    &[
+        &[(0, 12), (1, 10), (2, 9), (3, 7), (5, 11), (6, 8)],
+        &[(1, 6), (2, 3), (4, 11), (7, 9), (8, 10)],
+        &[(0, 4), (1, 2), (3, 6), (7, 8), (9, 10), (11, 12)],
+        &[(4, 6), (5, 9), (8, 11), (10, 12)],
+        &[(0, 5), (3, 8), (4, 7), (6, 11), (9, 10)],
+        &[(0, 1), (2, 5), (6, 9), (7, 8), (10, 11)],
+        &[(1, 3), (2, 4), (5, 6), (9, 10)],
+        &[(1, 2), (3, 4), (5, 7), (6, 8)],
+        &[(2, 3), (4, 5), (6, 7), (8, 9)],
+        &[(3, 4), (5, 6)],
+    ],
+    // 14 elements: 51 comparators in 10 layers.
+    &[
+        &[(0, 1), (2, 3), (4, 5), (6, 7), (8, 9), (10, 11), (12, 13)],
+        &[(0, 2), (1, 3), (4, 8), (5, 9), (10, 12), (11, 13)],
+        &[(0, 4), (1, 2), (3, 7), (5, 8), (6, 10), (9, 13), (11, 12)],
+        &[(0, 6), (1, 5), (3, 9), (4, 10), (7, 13), (8, 12)],
+        &[(2, 10), (3, 11), (4, 6), (7, 9)],
+        &[(1, 3), (2, 8), (5, 11), (6, 7), (10, 12)],
+        &[(1, 4), (2, 6), (3, 5), (7, 11), (8, 10), (9, 12)],
+        &[(2, 4), (3, 6), (5, 8), (7, 10), (9, 11)],
+        &[(3, 4), (5, 6), (7, 8), (9, 10)],
+        &[(6, 7)],
+    ],
+    // 15 elements: 56 comparators in 10 layers.
+    &[
+        &[(0, 13), (1, 12), (3, 14), (4, 8), (5, 6), (7, 11), (9, 10)],
+        &[(0, 5), (1, 7), (2, 9), (3, 4), (6, 13), (8, 14), (11, 12)],
+        &[(0, 1), (2, 3), (4, 5), (6, 8), (7, 9), (10, 11), (12, 13)],
+        &[(0, 2), (1, 3), (4, 10), (5, 11), (6, 7), (8, 9), (12, 14)],
+        &[(1, 2), (3, 12), (4, 6), (5, 7), (8, 10), (9, 11), (13, 14)],
+        &[(1, 4), (2, 6), (5, 8), (7, 10), (9, 13), (11, 14)],
+        &[(2, 4), (3, 6), (9, 12), (11, 13)],
+        &[(3, 5), (6, 8), (7, 9), (10, 12)],
+        &[(3, 4), (5, 6), (7, 8), (9, 10), (11, 12)],
+        &[(6, 7), (8, 9)],
+    ],
+    // 16 elements: 60 comparators in 10 layers.
+    &[
+        &[(0, 13), (1, 12), (2, 15), (3, 14), (4, 8), (5, 6), (7, 11), (9, 10)],
+        &[(0, 5), (1, 7), (2, 9), (3, 4), (6, 13), (8, 14), (10, 15), (11, 12)],
+        &[(0, 1), (2, 3), (4, 5), (6, 8), (7, 9), (10, 11), (12, 13), (14, 15)],
+        &[(0, 2), (1, 3), (4, 10), (5, 11), (6, 7), (8, 9), (12, 14), (13, 15)],
+        &[(1, 2), (3, 12), (4, 6), (5, 7), (8, 10), (9, 11), (13, 14)],
+        &[(1, 4), (2, 6), (5, 8), (7, 10), (9, 13), (11, 14)],
+        &[(2, 4), (3, 6), (9, 12), (11, 13)],
+        &[(3, 5), (6, 8), (7, 9), (10, 12)],
+        &[(3, 4), (5, 6), (7, 8), (9, 10), (11, 12)],
+        &[(6, 7), (8, 9)],
+    ],
+];
+
+/// Sorts the array `xs` with the optimal sorting network for its size, which is
+/// selected at compile time. Arrays with more than `MAX_LEN` elements are
+/// rejected at compile time as well.
+///
+/// Example:
+/// ```
+/// use algae::sort::network::sort_network;
+///
+/// let mut v = [5, 3, 9, 1, 7, 2];
+/// sort_network(&mut v);
+/// assert_eq!(v, [1, 2, 3, 5, 7, 9]);
+/// ```
+///
+/// ```compile_fail
+/// use algae::sort::network::sort_network;
+///
+/// sort_network(&mut [0; 17]);
+/// ```
+pub fn sort_network<T: PartialOrd, const N: usize>(xs: &mut [T; N]) {
+    const {
+        assert!(N <= MAX_LEN, "No sorting network for arrays this large.");
+    }
+    sort_small_with(xs, &mut T::lt);
+}
+
+/// Sorts the slice `xs` with the sorting network for its length. Panics if
+/// `xs` has more than `MAX_LEN` elements.
+///
+/// Example:
+/// ```
+/// use algae::sort::network::sort_small;
+///
+/// let mut v = vec!["network", "sorting", "a"];
+/// sort_small(&mut v);
+/// assert_eq!(v, ["a", "network", "sorting"]);
+/// ```
+pub fn sort_small<T: PartialOrd>(xs: &mut [T]) {
+    sort_small_with(xs, &mut T::lt);
+}
+
+/// Same as `sort_small`, where `is_less(a, b)` decides whether `a` should come
+/// before `b`.
+pub(super) fn sort_small_with<T, F>(xs: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    assert!(
+        xs.len() <= MAX_LEN,
+        "No sorting network for slices of length {}.",
+        xs.len()
+    );
+    // Dispatching on the length makes it a constant in `sort_len`, whose
+    // loops over the comparators are then unrolled.
+    match xs.len() {
+        0 | 1 => {}
+        2 => sort_len::<T, F, 2>(xs, is_less),
+        3 => sort_len::<T, F, 3>(xs, is_less),
+        4 => sort_len::<T, F, 4>(xs, is_less),
+        5 => sort_len::<T, F, 5>(xs, is_less),
+        6 => sort_len::<T, F, 6>(xs, is_less),
+        7 => sort_len::<T, F, 7>(xs, is_less),
+        8 => sort_len::<T, F, 8>(xs, is_less),
+        9 => sort_len::<T, F, 9>(xs, is_less),
+        10 => sort_len::<T, F, 10>(xs, is_less),
+        11 => sort_len::<T, F, 11>(xs, is_less),
+        12 => sort_len::<T, F, 12>(xs, is_less),
+        13 => sort_len::<T, F, 13>(xs, is_less),
+        14 => sort_len::<T, F, 14>(xs, is_less),
+        15 => sort_len::<T, F, 15>(xs, is_less),
+        _ => sort_len::<T, F, 16>(xs, is_less),
+    }
+}
+
+/// Applies the network for `N` elements to `xs`, which has length `N`.
+#[inline(always)]
+fn sort_len<T, F, const N: usize>(xs: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let xs: &mut [T; N] = xs.try_into().expect("Slice of the network length");
+    for &(i, j) in NETWORKS[N].iter().copied().flatten() {
+        compare_exchange(xs, i as usize, j as usize, is_less);
+    }
+}
+
+/// Orders `xs[i]` and `xs[j]`, for `i < j`, without branching on the outcome
+/// of the comparison: the two elements are always moved, from positions
+/// picked by conditional moves, which the branch predictor never sees.
+#[inline(always)]
+fn compare_exchange<T, F>(xs: &mut [T], i: usize, j: usize, is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    assert!(i < j && j < xs.len());
+    let swap = is_less(&xs[j], &xs[i]);
+    if cfg!(feature = "stats") && swap {
+        stats_swap!();
+    }
+    // SAFETY: `i` and `j` are distinct indices within `xs`, so that both
+    // pointers are valid and do not alias. The element read into `tmp` is
+    // written back before returning, and nothing in between can panic, as
+    // `is_less` was called before, so that every element ends up in `xs`
+    // exactly once.
+    unsafe {
+        let base = xs.as_mut_ptr();
+        let (a, b) = (base.add(i), base.add(j));
+        let (from_a, from_b) = if swap { (b, a) } else { (a, b) };
+        let tmp = ManuallyDrop::new(ptr::read(from_b));
+        ptr::copy(from_a, a, 1);
+        ptr::copy_nonoverlapping(&*tmp, b, 1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sort;
+
+    #[test]
+    fn zero_one_principle() {
+        for n in 0..=MAX_LEN {
+            for bits in 0..(1_u32 << n) {
+                let mut xs: Vec<u32> = (0..n).map(|i| (bits >> i) & 1).collect();
+                sort_small(&mut xs);
+                assert!(sort::is_sorted(&xs), "Network for {} fails on {:?}", n, xs);
+            }
+        }
+    }
+
+    #[test]
+    fn layers_are_valid() {
+        // Least known number of comparators and layers for each size.
+        let comparators = [0, 0, 1, 3, 5, 9, 12, 16, 19, 25, 29, 35, 39, 45, 51, 56, 60];
+        let depths = [0, 0, 1, 3, 3, 5, 5, 6, 6, 7, 8, 8, 9, 10, 10, 10, 10];
+        for (n, layers) in NETWORKS.iter().enumerate() {
+            assert_eq!(layers.len(), depths[n]);
+            assert_eq!(
+                layers.iter().map(|l| l.len()).sum::<usize>(),
+                comparators[n]
+            );
+            for layer in layers.iter() {
+                let mut touched = vec![false; n];
+                for &(i, j) in layer.iter() {
+                    assert!(i < j && (j as usize) < n);
+                    for k in [i, j] {
+                        assert!(!touched[k as usize], "Layer {:?} is not disjoint", layer);
+                        touched[k as usize] = true;
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn owned_elements() {
+        let mut xs: Vec<String> = (0..16).map(|_| fastrand::u16(..).to_string()).collect();
+        let mut expected = xs.clone();
+        expected.sort();
+        sort_small(&mut xs);
+        assert_eq!(xs, expected);
+
+        // A comparison panicking midway leaves every element in the slice.
+        fastrand::shuffle(&mut xs);
+        let mut calls = 0;
+        let sorting = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            sort_small_with(&mut xs, &mut |a: &String, b: &String| {
+                calls += 1;
+                assert!(calls < 30, "Comparison failed");
+                a < b
+            })
+        }));
+        assert!(sorting.is_err());
+        xs.sort();
+        assert_eq!(xs, expected);
+    }
+
+    #[test]
+    fn arrays() {
+        let mut xs: [i64; 16] = sort::rand_vec(16).try_into().unwrap();
+        sort_network(&mut xs);
+        assert!(sort::is_sorted(&xs));
+
+        let mut empty: [i64; 0] = [];
+        sort_network(&mut empty);
+    }
+}
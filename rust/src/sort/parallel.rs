@@ -0,0 +1,67 @@
+use crate::sort::quick;
+use fastrand;
+
+/// Slices with at most this many elements are sorted sequentially, since the
+/// cost of spawning parallel tasks outweighs the gain for small inputs.
+const PARALLEL_CUTOFF: usize = 1 << 13;
+
+/// Parallel quicksort (enabled by the `parallel` feature). Each partition step
+/// is done sequentially, but the two resulting halves are sorted in parallel
+/// with `rayon::join`, which balances the work over the threads of the rayon
+/// pool. Once the slices become small enough, the sequential `quick_sort` is
+/// used instead.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// let mut v = [99, 32, 58, 66, 2, 4, 0, 3928, 55, 88, 30, 44, 3, 2, 0];
+/// sort::par_quick_sort(&mut v);
+/// assert_eq!(v, [0, 0, 2, 2, 3, 4, 30, 32, 44, 55, 58, 66, 88, 99, 3928]);
+/// ```
+pub fn par_quick_sort<T: PartialOrd + Send>(xs: &mut [T]) {
+    trace_span!("par_quick_sort", len = xs.len());
+    fastrand::shuffle(xs);
+    par_quick_sort_rec(xs);
+}
+
+fn par_quick_sort_rec<T: PartialOrd + Send>(xs: &mut [T]) {
+    stats_depth!();
+    if xs.len() <= PARALLEL_CUTOFF {
+        let len = xs.len();
+        quick::quick_sort_rec(xs, 0..len, &mut T::lt);
+        return;
+    }
+
+    let pivot_idx = quick::quick_sort_partition(xs, &mut T::lt);
+    trace_event!(len = xs.len(), pivot_idx, "partition chosen");
+    let (left, right) = xs.split_at_mut(pivot_idx);
+    rayon::join(
+        || par_quick_sort_rec(left),
+        || par_quick_sort_rec(&mut right[1..]),
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sort;
+
+    #[test]
+    fn sorting() {
+        sort::check_sort_fn(par_quick_sort);
+    }
+
+    #[test]
+    fn sorting_large() {
+        for n in [1 << 20, 3_000_000] {
+            let mut xs = sort::rand_vec(n);
+            par_quick_sort(&mut xs);
+            assert!(sort::is_sorted(&xs));
+        }
+
+        let mut dups: Vec<u8> = (0..2_000_000).map(|_| fastrand::u8(..4)).collect();
+        par_quick_sort(&mut dups);
+        assert!(sort::is_sorted(&dups));
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{
+    ds::{Heap, MaxHeap},
+    sort::{self, heap},
+};
+
+/// Partial sort: rearranges `xs` so that `xs[..k]` holds its `k` smallest
+/// elements in sorted order, while the order of the remaining elements is
+/// unspecified. The `k` smallest elements are first gathered with
+/// `quick_select` and then sorted with heapsort, which takes O(n + k * log k)
+/// time instead of the O(n * log n) of a full sort. Values of `k` greater than
+/// `xs.len()` sort the whole array.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// let mut v = [9, 4, 7, 1, 8, 2, 6];
+/// sort::partial_sort(&mut v, 3);
+/// assert_eq!(v[..3], [1, 2, 4]);
+/// ```
+pub fn partial_sort<T: PartialOrd>(xs: &mut [T], k: usize) {
+    trace_span!("partial_sort", len = xs.len(), k);
+    let k = k.min(xs.len());
+    if k == 0 {
+        return;
+    }
+    if k < xs.len() {
+        sort::quick_select(xs, k - 1);
+    }
+    heap::heap_sort_in_place(&mut xs[..k]);
+}
+
+/// Returns the `k` smallest elements of `xs` in sorted order, or all of them
+/// if `k >= xs.len()`. The elements are streamed through a max-heap holding
+/// the `k` smallest elements seen so far, whose root is evicted whenever a
+/// smaller element arrives. Runs in O(n * log k) time with O(k) extra space,
+/// and does not modify `xs`.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// assert_eq!(sort::top_k(&[9, 4, 7, 1, 8, 2, 6], 3), vec![1, 2, 4]);
+/// ```
+pub fn top_k<T: PartialOrd + Clone>(xs: &[T], k: usize) -> Vec<T> {
+    trace_span!("top_k", len = xs.len(), k);
+    let mut heap = MaxHeap::new();
+    for x in xs {
+        if heap.size() < k {
+            heap.push(x.clone());
+        } else if heap.peek().is_some_and(|max| x < max) {
+            heap.pop();
+            heap.push(x.clone());
+        }
+    }
+
+    let mut smallest: Vec<T> = std::iter::from_fn(|| heap.pop()).collect();
+    smallest.reverse();
+    smallest
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn partial_sorting() {
+        for _ in 0..50 {
+            let mut xs = sort::rand_vec(fastrand::usize(..200));
+            let mut sorted = xs.clone();
+            sorted.sort();
+
+            let k = fastrand::usize(..=xs.len() + 1);
+            partial_sort(&mut xs, k);
+            let k = k.min(xs.len());
+            assert_eq!(xs[..k], sorted[..k]);
+
+            xs.sort();
+            assert_eq!(xs, sorted);
+        }
+    }
+
+    #[test]
+    fn smallest_k() {
+        for _ in 0..50 {
+            let xs: Vec<u8> = (0..fastrand::usize(..200))
+                .map(|_| fastrand::u8(..))
+                .collect();
+            let mut sorted = xs.clone();
+            sorted.sort();
+
+            let k = fastrand::usize(..=xs.len() + 1);
+            assert_eq!(top_k(&xs, k), sorted[..k.min(xs.len())]);
+        }
+        assert!(top_k(&[3, 1, 2], 0).is_empty());
+    }
+}
@@ -0,0 +1,128 @@
+use crate::sort;
+
+/// Returns the permutation of indices that sorts `xs`, that is, the indices
+/// `perm` such that `xs[perm[0]], xs[perm[1]], ...` is sorted, without moving
+/// the elements of `xs`. Indices of equal elements keep their relative order,
+/// since the indices are sorted with the stable `merge_sort_by`.
+///
+/// Combined with `apply_permutation`, this reorders several parallel arrays
+/// according to the order of one of them.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// let ages = [31, 25, 47, 25];
+/// let mut names = ["ana", "bia", "caio", "davi"];
+/// let perm = sort::argsort(&ages);
+/// assert_eq!(perm, [1, 3, 0, 2]);
+///
+/// sort::apply_permutation(&mut names, &perm);
+/// assert_eq!(names, ["bia", "davi", "ana", "caio"]);
+/// ```
+pub fn argsort<T: PartialOrd>(xs: &[T]) -> Vec<usize> {
+    trace_span!("argsort", len = xs.len());
+    let mut perm: Vec<usize> = (0..xs.len()).collect();
+    sort::merge_sort_by(&mut perm, |&i, &j| {
+        xs[i].partial_cmp(&xs[j]).expect("Unable to compare values")
+    });
+    perm
+}
+
+/// Rearranges `xs` in place so that `xs[i]` becomes the element previously at
+/// `xs[perm[i]]`. Each cycle of the permutation is followed with swaps, so
+/// that no element is cloned and only a bitmap of visited positions is
+/// allocated. Panics if `perm` is not a permutation of `0..xs.len()`.
+pub fn apply_permutation<T>(xs: &mut [T], perm: &[usize]) {
+    assert_permutation(perm, xs.len());
+    let mut visited = vec![false; xs.len()];
+    for start in 0..xs.len() {
+        // Invariant: `xs[j]` holds the element originally at `start`, which
+        // only belongs at the last position of the cycle.
+        let mut j = start;
+        while !visited[j] {
+            visited[j] = true;
+            let k = perm[j];
+            if k == start {
+                break;
+            }
+            xs.swap(j, k);
+            j = k;
+        }
+    }
+}
+
+/// Returns the inverse of the permutation `perm`, that is, the permutation
+/// `inv` with `inv[perm[i]] = i` for every `i`. The inverse of an `argsort`
+/// gives the rank of each element. Panics if `perm` is not a permutation of
+/// `0..perm.len()`.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// assert_eq!(sort::invert_permutation(&[2, 0, 1]), [1, 2, 0]);
+/// ```
+pub fn invert_permutation(perm: &[usize]) -> Vec<usize> {
+    assert_permutation(perm, perm.len());
+    let mut inv = vec![0; perm.len()];
+    for (i, &p) in perm.iter().enumerate() {
+        inv[p] = i;
+    }
+    inv
+}
+
+/// Panics unless `perm` contains every index of `0..n` exactly once.
+fn assert_permutation(perm: &[usize], n: usize) {
+    assert_eq!(perm.len(), n, "Permutation has the wrong length.");
+    let mut seen = vec![false; n];
+    for &p in perm {
+        assert!(p < n && !seen[p], "{:?} is not a permutation.", perm);
+        seen[p] = true;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn argsort_sorts() {
+        for _ in 0..50 {
+            let xs: Vec<u8> = (0..fastrand::usize(..100))
+                .map(|_| fastrand::u8(..10))
+                .collect();
+            let perm = argsort(&xs);
+            assert!(perm
+                .windows(2)
+                .all(|w| xs[w[0]] < xs[w[1]] || (xs[w[0]] == xs[w[1]] && w[0] < w[1])));
+
+            let mut ys = xs.clone();
+            apply_permutation(&mut ys, &perm);
+            assert!(sort::is_sorted(&ys));
+        }
+    }
+
+    #[test]
+    fn inverse() {
+        for _ in 0..50 {
+            let mut perm: Vec<usize> = (0..fastrand::usize(..100)).collect();
+            fastrand::shuffle(&mut perm);
+            let inv = invert_permutation(&perm);
+            assert!((0..perm.len()).all(|i| inv[perm[i]] == i && perm[inv[i]] == i));
+
+            // Applying a permutation and then its inverse is the identity.
+            let mut xs: Vec<String> = (0..perm.len()).map(|i| i.to_string()).collect();
+            let original = xs.clone();
+            apply_permutation(&mut xs, &perm);
+            apply_permutation(&mut xs, &inv);
+            assert_eq!(xs, original);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn not_a_permutation() {
+        apply_permutation(&mut [1, 2, 3], &[0, 0, 1]);
+    }
+}
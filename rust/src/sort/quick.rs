@@ -1,19 +1,22 @@
-use crate::sort;
+use crate::{
+    math::safe::split_around,
+    sort::{self, network},
+};
 use fastrand;
-use std::cmp::Ordering;
+use std::{cmp::Ordering, ops::Range};
 
-/// Cutoff to insertion sort: since quicksort is a recursive algorithm, its
+/// Cutoff to a simpler sort: since quicksort is a recursive algorithm, its
 /// performance is worse than insertion sort for tiny subarrays. This leads way
-/// to defining a cutoff constant to be used. If `high` and `low` are the
-/// highest and lowest index of a subarray, then for `high <= low + CUTOFF` we
-/// jump from quicksort to insertion sort.
-const CUTOFF: usize = 10;
+/// to defining a cutoff constant to be used: subarrays with at most `CUTOFF`
+/// elements are sorted with insertion sort (or a sorting network, which must
+/// exist for this size) instead of quicksort.
+pub(super) const CUTOFF: usize = 10;
 
 /// Quicksort algorithm. The implementation also uses the cutoff method for
 /// small arrays in order to reduce the number of calls in the stack generated
 /// by the recursive nature of the quicksort algorithm. Instead of applying
-/// quicksort, for these small arrays we apply the straight forward
-/// insertion sort.
+/// quicksort, these small arrays are sorted by the optimal sorting network for
+/// their size, whose comparisons do not depend on the input.
 ///
 /// Example:
 /// ```
@@ -23,46 +26,112 @@ const CUTOFF: usize = 10;
 /// sort::quick_sort(&mut v);
 /// assert_eq!(v, [0, 0, 2, 2, 3, 4, 30, 32, 44, 55, 58, 66, 88, 99, 3928]);
 /// ```
-pub fn quick_sort<T: PartialOrd + Copy>(xs: &mut [T]) {
+pub fn quick_sort<T: PartialOrd>(xs: &mut [T]) {
+    trace_span!("quick_sort", len = xs.len());
     fastrand::shuffle(xs);
-    quick_sort_rec(xs, 0, xs.len().saturating_sub(1));
+    quick_sort_rec(xs, 0..xs.len(), &mut T::lt);
 }
 
-fn quick_sort_rec<T: PartialOrd + Copy>(xs: &mut [T], low: usize, high: usize) {
-    if high <= low + CUTOFF {
-        sort::insertion_sort(xs);
+/// Quicksort with the ordering given by the comparator `cmp`.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// let mut v = [99, 32, 58, 66, 2, 4, 0, 3928, 55, 88, 30, 44, 3, 2, 0];
+/// sort::quick_sort_by(&mut v, |a, b| b.cmp(a));
+/// assert_eq!(v, [3928, 99, 88, 66, 58, 55, 44, 32, 30, 4, 3, 2, 2, 0, 0]);
+/// ```
+pub fn quick_sort_by<T, F>(xs: &mut [T], mut cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    trace_span!("quick_sort_by", len = xs.len());
+    fastrand::shuffle(xs);
+    quick_sort_rec(xs, 0..xs.len(), &mut |a, b| cmp(a, b) == Ordering::Less);
+}
+
+/// Quicksort ordering the elements by the keys extracted with `key`.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// let mut v = [(1, 'c'), (2, 'a'), (3, 'b')];
+/// sort::quick_sort_by_key(&mut v, |&(_, c)| c);
+/// assert_eq!(v, [(2, 'a'), (3, 'b'), (1, 'c')]);
+/// ```
+pub fn quick_sort_by_key<T, K, F>(xs: &mut [T], key: F)
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    trace_span!("quick_sort_by_key", len = xs.len());
+    fastrand::shuffle(xs);
+    quick_sort_rec(xs, 0..xs.len(), &mut |a, b| key(a) < key(b));
+}
+
+/// Sorts the subarray `xs[range]`, where `is_less(a, b)` decides whether `a`
+/// should come before `b`.
+pub(super) fn quick_sort_rec<T, F>(xs: &mut [T], range: Range<usize>, is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    stats_depth!();
+    if range.len() <= CUTOFF {
+        network::sort_small_with(&mut xs[range], is_less);
         return;
     }
-    let pivot_idx = quick_sort_partition(xs, low, high);
-    quick_sort_rec(xs, low, pivot_idx.saturating_sub(1));
-    quick_sort_rec(xs, pivot_idx + 1, high);
+    let pivot_idx = range.start + quick_sort_partition(&mut xs[range.clone()], is_less);
+    trace_event!(?range, pivot_idx, "partition chosen");
+    let (left, right) = split_around(range, pivot_idx);
+    quick_sort_rec(xs, left, is_less);
+    quick_sort_rec(xs, right, is_less);
 }
 
-fn quick_sort_partition<T: PartialOrd + Copy>(xs: &mut [T], low: usize, high: usize) -> usize {
-    let pivot = xs[low];
-    let mut lscan = low + 1;
+/// Partitions the non-empty slice `xs` around its first element, returning the
+/// index where the pivot ends up: every element to its left is less than or
+/// equal to the pivot, while every element to its right is greater than or
+/// equal to it.
+///
+/// Both scans stop at elements equal to the pivot, which are then swapped. This
+/// may look wasteful, but it splits runs of equal keys evenly between both
+/// sides: skipping over them instead makes the partition degenerate (and the
+/// recursion linearly deep) on arrays with many duplicates.
+pub(super) fn quick_sort_partition<T, F>(xs: &mut [T], is_less: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    // The pivot stays at index `0` until the final swap, so it is compared in
+    // place rather than copied out.
+    let high = xs.len() - 1;
+    let mut lscan = 1;
     let mut rscan = high;
     loop {
-        while xs[lscan] <= pivot {
+        while is_less(&xs[lscan], &xs[0]) {
             if lscan == high {
                 break;
             }
             lscan += 1;
         }
 
-        while pivot <= xs[rscan] {
-            if rscan == low {
+        while is_less(&xs[0], &xs[rscan]) {
+            if rscan == 0 {
                 break;
             }
-            rscan = rscan.saturating_sub(1);
+            rscan -= 1;
         }
 
         if rscan <= lscan {
             break;
         }
+        stats_swap!();
         xs.swap(lscan, rscan);
+        lscan += 1;
+        rscan -= 1;
     }
-    xs.swap(low, rscan);
+    stats_swap!();
+    xs.swap(0, rscan);
     rscan
 }
 
@@ -90,40 +159,49 @@ fn quick_sort_partition<T: PartialOrd + Copy>(xs: &mut [T], low: usize, high: us
 /// sort::quick_three_way_sort(&mut v);
 /// assert_eq!("aceeiklmopqrstux", &String::from_utf8(v).unwrap());
 /// ```
-pub fn quick_three_way_sort<T: PartialOrd + Copy>(xs: &mut [T]) {
+pub fn quick_three_way_sort<T: PartialOrd>(xs: &mut [T]) {
+    trace_span!("quick_three_way_sort", len = xs.len());
     fastrand::shuffle(xs);
-    quick_three_way_sort_rec(xs, 0, xs.len().saturating_sub(1));
+    quick_three_way_sort_rec(xs, 0..xs.len());
 }
 
-fn quick_three_way_sort_rec<T: PartialOrd + Copy>(xs: &mut [T], low: usize, high: usize) {
-    if high <= low + CUTOFF {
-        sort::insertion_sort(xs);
+/// Sorts the subarray `xs[range]`.
+fn quick_three_way_sort_rec<T: PartialOrd>(xs: &mut [T], range: Range<usize>) {
+    stats_depth!();
+    if range.len() <= CUTOFF {
+        sort::insertion_sort(&mut xs[range]);
         return;
     }
 
-    let mut lt = low;
-    let mut gt = high;
-    let mut scan = low + 1;
+    // Invariant: `xs[start..lt]` is less than the pivot, `xs[lt..scan]` is
+    // equal to the pivot and `xs[gt..end]` is greater than the pivot. Since
+    // `xs[lt..scan]` is never empty, `xs[lt]` always holds an element equal to it.
+    let mut lt = range.start;
+    let mut gt = range.end;
+    let mut scan = range.start + 1;
 
-    while scan <= gt {
+    while scan < gt {
         match xs[scan]
-            .partial_cmp(&xs[low])
+            .partial_cmp(&xs[lt])
             .expect("Unable to compare values")
         {
             Ordering::Less => {
+                stats_swap!();
                 xs.swap(lt, scan);
                 lt += 1;
                 scan += 1;
             }
             Ordering::Greater => {
+                gt -= 1;
+                stats_swap!();
                 xs.swap(scan, gt);
-                gt = gt.saturating_sub(1);
             }
             Ordering::Equal => scan += 1,
         }
     }
-    quick_three_way_sort_rec(xs, low, lt.saturating_sub(1));
-    quick_three_way_sort_rec(xs, gt + 1, high);
+    trace_event!(?range, lt, gt, "three-way partition chosen");
+    quick_three_way_sort_rec(xs, range.start..lt);
+    quick_three_way_sort_rec(xs, gt..range.end);
 }
 
 #[cfg(test)]
@@ -135,8 +213,67 @@ mod test {
         sort::check_sort_fn(super::quick_sort);
     }
 
+    #[test]
+    fn sorting_by() {
+        sort::check_sort_fn(|xs| {
+            super::quick_sort_by(xs, |a, b| b.cmp(a));
+            xs.reverse();
+        });
+        sort::check_sort_fn(|xs| {
+            super::quick_sort_by_key(xs, |&x| std::cmp::Reverse(x));
+            xs.reverse();
+        });
+    }
+
     #[test]
     fn sorting_quick3waysort() {
         sort::check_sort_fn(super::quick_three_way_sort);
     }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn traces_partitions() {
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+        use tracing::{span, Event, Metadata, Subscriber};
+
+        /// Subscriber counting the events it receives.
+        struct EventCounter(Arc<AtomicUsize>);
+
+        impl Subscriber for EventCounter {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+                span::Id::from_u64(1)
+            }
+            fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+            fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+            fn event(&self, _: &Event<'_>) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+            fn enter(&self, _: &span::Id) {}
+            fn exit(&self, _: &span::Id) {}
+        }
+
+        let count = Arc::new(AtomicUsize::new(0));
+        tracing::subscriber::with_default(EventCounter(count.clone()), || {
+            super::quick_sort(&mut sort::rand_vec(1000));
+        });
+        assert!(count.load(Ordering::Relaxed) > 0);
+    }
+
+    #[test]
+    fn sorting_duplicates() {
+        for _ in 0..50 {
+            let mut xs: Vec<u8> = (0..1000).map(|_| fastrand::u8(..5)).collect();
+            let mut ys = xs.clone();
+            super::quick_sort(&mut xs);
+            super::quick_three_way_sort(&mut ys);
+            assert!(sort::is_sorted(&xs));
+            assert!(sort::is_sorted(&ys));
+        }
+    }
 }
@@ -0,0 +1,289 @@
+use std::mem;
+
+/// Number of distinct values a single byte of a key can assume.
+const RADIX: usize = 256;
+
+/// Cutoff to insertion sort: the key-indexed counting done at each level of
+/// the MSD radix sort allocates and scans `RADIX + 2` counters, which is
+/// wasteful for tiny buckets. Buckets of at most `CUTOFF` keys are sorted with
+/// insertion sort instead, comparing only the bytes past the common prefix.
+const CUTOFF: usize = 15;
+
+/// Most-significant-digit (MSD) radix sort for keys that can be seen as a
+/// sequence of bytes, such as `&str` and `&[u8]`. Keys are distributed into
+/// buckets according to their `d`-th byte (where keys that end before `d` come
+/// first) and each bucket is then sorted in the same way on the byte `d + 1`.
+///
+/// Since UTF-8 preserves the code point order, sorting `&str` keys by their
+/// bytes yields the same ordering as `str::cmp`.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// let mut v = ["she", "sells", "seashells", "by", "the", "sea", "shore"];
+/// sort::msd_radix_sort(&mut v);
+/// assert_eq!(v, ["by", "sea", "seashells", "sells", "she", "shore", "the"]);
+/// ```
+pub fn msd_radix_sort<T: AsRef<[u8]> + Copy>(xs: &mut [T]) {
+    trace_span!("msd_radix_sort", len = xs.len());
+    let mut aux = xs.to_vec();
+    msd_radix_sort_buckets(xs, &mut aux);
+}
+
+/// Three-way string quicksort for keys that can be seen as a sequence of bytes,
+/// such as `&str` and `&[u8]`. The keys are partitioned into those whose `d`-th
+/// byte is less than, equal to, or greater than the `d`-th byte of a pivot
+/// key. The outer parts are sorted recursively on the same byte, while the
+/// middle part, whose keys share their first `d + 1` bytes, moves on to the
+/// byte `d + 1`.
+///
+/// As opposed to `msd_radix_sort`, there are no buckets to allocate, and each
+/// byte of a common prefix is examined only about once per key rather than once
+/// per comparison. This only pays off while the prefixes are short, though:
+/// comparison sorts compare whole prefixes at once with `memcmp`, and the
+/// `sorting` benchmark shows `quick_sort` ahead on keys behind a shared
+/// 80-byte prefix, while this sort is faster on random words. The keys are
+/// shuffled beforehand, as in `quick_sort`.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// let mut v = ["she", "sells", "seashells", "by", "the", "sea", "shore"];
+/// sort::quick_string_sort(&mut v);
+/// assert_eq!(v, ["by", "sea", "seashells", "sells", "she", "shore", "the"]);
+/// ```
+pub fn quick_string_sort<T: AsRef<[u8]>>(xs: &mut [T]) {
+    trace_span!("quick_string_sort", len = xs.len());
+    fastrand::shuffle(xs);
+    quick_string_sort_rec(xs, 0);
+}
+
+/// Sorts `xs` assuming all of its keys share the same first `d` bytes. The
+/// middle part moves on to the next byte by iterating rather than recursing,
+/// so that the recursion depth does not grow with the length of the common
+/// prefixes.
+fn quick_string_sort_rec<T: AsRef<[u8]>>(mut xs: &mut [T], mut d: usize) {
+    stats_depth!();
+    loop {
+        if xs.len() <= CUTOFF {
+            insertion_sort_from(xs, d);
+            return;
+        }
+
+        // Invariant: the keys of `xs[..lt]` have a `d`-th digit less than the
+        // pivot digit, those of `xs[lt..scan]` equal to it and those of
+        // `xs[gt..]` greater than it.
+        let pivot = digit_at(&xs[0], d);
+        let (mut lt, mut gt, mut scan) = (0, xs.len(), 1);
+        while scan < gt {
+            let digit = digit_at(&xs[scan], d);
+            if digit < pivot {
+                stats_swap!();
+                xs.swap(lt, scan);
+                lt += 1;
+                scan += 1;
+            } else if digit > pivot {
+                gt -= 1;
+                stats_swap!();
+                xs.swap(scan, gt);
+            } else {
+                scan += 1;
+            }
+        }
+        trace_event!(len = xs.len(), d, lt, gt, "three-way partition on digit");
+
+        let (less, rest) = mem::take(&mut xs).split_at_mut(lt);
+        let (equal, greater) = rest.split_at_mut(gt - lt);
+        quick_string_sort_rec(less, d);
+        quick_string_sort_rec(greater, d);
+        // Keys whose digit is `0` have ended, and are therefore all equal.
+        if pivot == 0 {
+            return;
+        }
+        xs = equal;
+        d += 1;
+    }
+}
+
+/// Returns the digit of `key` at position `d`. Positions past the end of the
+/// key map to `0`, so that shorter keys come before their extensions.
+#[inline]
+fn digit_at<T: AsRef<[u8]>>(key: &T, d: usize) -> usize {
+    key.as_ref().get(d).map_or(0, |&b| b as usize + 1)
+}
+
+/// Sorts `xs` bucket by bucket. The auxiliary slice `aux` must have the same
+/// length as `xs`.
+///
+/// Equal keys all land in the same bucket, so the number of levels is the
+/// length of the longest common prefix rather than a function of the number of
+/// keys. The buckets left to sort are therefore kept on an explicit stack of
+/// ranges instead of the call stack. Since pending ranges are disjoint and hold
+/// at least two keys each, the stack never holds more than `xs.len() / 2` of
+/// them.
+fn msd_radix_sort_buckets<T: AsRef<[u8]> + Copy>(xs: &mut [T], aux: &mut [T]) {
+    // Ranges `lo..hi` of keys sharing their first `d` bytes.
+    let mut pending = vec![(0, xs.len(), 0)];
+    while let Some((lo, hi, d)) = pending.pop() {
+        let (xs, aux) = (&mut xs[lo..hi], &mut aux[lo..hi]);
+        if xs.len() <= CUTOFF {
+            insertion_sort_from(xs, d);
+            continue;
+        }
+
+        // Key-indexed counting: after the prefix sums, `count[r]` holds the
+        // index where the bucket of the digit `r` starts.
+        let mut count = [0; RADIX + 2];
+        for x in xs.iter() {
+            count[digit_at(x, d) + 1] += 1;
+        }
+        for r in 0..=RADIX {
+            count[r + 1] += count[r];
+        }
+
+        // Distribute the keys into `aux` and copy them back. At the end of
+        // the loop, `count[r]` holds the index where the bucket of `r` ends.
+        for x in xs.iter() {
+            let r = digit_at(x, d);
+            aux[count[r]] = *x;
+            count[r] += 1;
+        }
+        xs.copy_from_slice(aux);
+        trace_event!(len = xs.len(), d, "keys distributed into buckets");
+
+        // Keys in the bucket `0` have ended and are therefore already sorted.
+        let mut start = count[0];
+        for &end in &count[1..=RADIX] {
+            if end - start > 1 {
+                pending.push((lo + start, lo + end, d + 1));
+            }
+            start = end;
+        }
+    }
+}
+
+/// Insertion sort of keys sharing the same first `d` bytes, where only the
+/// remaining bytes are compared.
+fn insertion_sort_from<T: AsRef<[u8]>>(xs: &mut [T], d: usize) {
+    for not_sorted in 1..xs.len() {
+        let mut i = not_sorted;
+        while i > 0 && xs[i - 1].as_ref()[d..] > xs[i].as_ref()[d..] {
+            stats_swap!();
+            xs.swap(i - 1, i);
+            i -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::iter;
+
+    fn rand_words(n: usize, alphabet: &[u8], max_len: usize) -> Vec<Vec<u8>> {
+        iter::repeat_with(|| {
+            let len = fastrand::usize(..=max_len);
+            iter::repeat_with(|| alphabet[fastrand::usize(..alphabet.len())])
+                .take(len)
+                .collect()
+        })
+        .take(n)
+        .collect()
+    }
+
+    #[test]
+    fn sorting_strs() {
+        let text = "it was the best of times it was the worst of times \
+                    it was the age of wisdom it was the age of foolishness";
+        let mut words: Vec<&str> = text.split_whitespace().collect();
+        let mut expected = words.clone();
+        expected.sort();
+
+        msd_radix_sort(&mut words);
+        assert_eq!(words, expected);
+    }
+
+    #[test]
+    fn sorting_bytes() {
+        for _ in 0..50 {
+            // A small alphabet yields long common prefixes and many duplicates.
+            let words = rand_words(500, b"abc", 12);
+            let mut xs: Vec<&[u8]> = words.iter().map(|w| w.as_slice()).collect();
+            let mut expected = xs.clone();
+            expected.sort();
+
+            msd_radix_sort(&mut xs);
+            assert_eq!(xs, expected);
+        }
+
+        let words = rand_words(1000, &(0..=255).collect::<Vec<u8>>(), 8);
+        let mut xs: Vec<&[u8]> = words.iter().map(|w| w.as_slice()).collect();
+        let mut expected = xs.clone();
+        expected.sort();
+        msd_radix_sort(&mut xs);
+        assert_eq!(xs, expected);
+    }
+
+    #[test]
+    fn sorting_edge_cases() {
+        let mut empty: [&str; 0] = [];
+        msd_radix_sort(&mut empty);
+        quick_string_sort(&mut empty);
+
+        let mut xs = ["", "a", "", "aa", "a"];
+        msd_radix_sort(&mut xs);
+        assert_eq!(xs, ["", "", "a", "a", "aa"]);
+
+        let mut ys = ["", "a", "", "aa", "a"];
+        quick_string_sort(&mut ys);
+        assert_eq!(ys, ["", "", "a", "a", "aa"]);
+    }
+
+    #[test]
+    fn string_quicksort() {
+        let text = "it was the best of times it was the worst of times \
+                    it was the age of wisdom it was the age of foolishness";
+        let mut words: Vec<&str> = text.split_whitespace().collect();
+        let mut expected = words.clone();
+        expected.sort();
+        quick_string_sort(&mut words);
+        assert_eq!(words, expected);
+
+        for _ in 0..50 {
+            // Long common prefixes and many duplicates.
+            let words = rand_words(500, b"ab", 20);
+            let mut xs: Vec<&[u8]> = words.iter().map(|w| w.as_slice()).collect();
+            let mut expected = xs.clone();
+            expected.sort();
+            quick_string_sort(&mut xs);
+            assert_eq!(xs, expected);
+        }
+
+        // Non-`Copy` keys are sorted as well.
+        let mut owned: Vec<String> = ["shore", "sea", "by", "she"].map(String::from).to_vec();
+        quick_string_sort(&mut owned);
+        assert_eq!(owned, ["by", "sea", "she", "shore"]);
+    }
+
+    #[test]
+    fn long_common_prefixes() {
+        // Each level of either sort examines one more byte of the common
+        // prefix, which must not cost a stack frame.
+        let prefix = "x".repeat(200_000);
+        let words: Vec<String> = (0..40)
+            .map(|i| format!("{}{}", prefix, ["b", "a", ""][i % 3]))
+            .collect();
+        let mut expected: Vec<&str> = words.iter().map(|w| w.as_str()).collect();
+        expected.sort();
+
+        let mut xs: Vec<&str> = words.iter().map(|w| w.as_str()).collect();
+        msd_radix_sort(&mut xs);
+        assert_eq!(xs, expected);
+
+        let mut ys: Vec<&str> = words.iter().map(|w| w.as_str()).collect();
+        quick_string_sort(&mut ys);
+        assert_eq!(ys, expected);
+    }
+}
@@ -0,0 +1,210 @@
+use crate::sort::{self, quick};
+use fastrand;
+use std::cmp::Ordering;
+
+/// Quickselect: rearranges `xs` so that its `k`-th smallest element (counting
+/// from zero) ends up at index `k`, with every element to its left less than
+/// or equal to it and every element to its right greater than or equal to it.
+/// Returns a reference to that element.
+///
+/// As in quicksort, the array is partitioned around a pivot, but only the side
+/// containing the index `k` is partitioned further, which brings the expected
+/// running time down to O(n). The array is shuffled beforehand, so that the
+/// quadratic worst case is unlikely for any input. Panics if `k >= xs.len()`.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// let mut v = [7, 1, 9, 3, 5];
+/// assert_eq!(*sort::quick_select(&mut v, 1), 3);
+/// assert_eq!(v[1], 3);
+/// ```
+pub fn quick_select<T: PartialOrd>(xs: &mut [T], k: usize) -> &T {
+    assert!(
+        k < xs.len(),
+        "Index {} out of range for a slice of length {}.",
+        k,
+        xs.len()
+    );
+    trace_span!("quick_select", len = xs.len(), k);
+    fastrand::shuffle(xs);
+
+    let (mut low, mut high) = (0, xs.len());
+    while high - low > 1 {
+        let pivot_idx = low + quick::quick_sort_partition(&mut xs[low..high], &mut T::lt);
+        trace_event!(low, high, pivot_idx, "partition chosen");
+        match pivot_idx.cmp(&k) {
+            Ordering::Less => low = pivot_idx + 1,
+            Ordering::Greater => high = pivot_idx,
+            Ordering::Equal => break,
+        }
+    }
+    &xs[k]
+}
+
+/// Size of the groups whose medians are used to choose the pivot in
+/// `select_median_of_medians`. Slices with at most this many elements are
+/// simply sorted.
+const GROUP_SIZE: usize = 5;
+
+/// Deterministic selection (Blum, Floyd, Pratt, Rivest and Tarjan): same as
+/// `quick_select`, but with a guaranteed O(n) worst case instead of an
+/// expected one, and without shuffling.
+///
+/// The pivot is chosen as the median of the medians of groups of 5 elements,
+/// found recursively. At least 3/10 of the elements are then guaranteed to lie
+/// on each side of the pivot, so that each partition discards a constant
+/// fraction of the array. The constant factors are larger than those of
+/// `quick_select`, which is usually faster in practice. Panics if
+/// `k >= xs.len()`.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// let mut v = [7, 1, 9, 3, 5];
+/// assert_eq!(*sort::select_median_of_medians(&mut v, 3), 7);
+/// assert_eq!(v[3], 7);
+/// ```
+pub fn select_median_of_medians<T: PartialOrd>(xs: &mut [T], k: usize) -> &T {
+    assert!(
+        k < xs.len(),
+        "Index {} out of range for a slice of length {}.",
+        k,
+        xs.len()
+    );
+    trace_span!("select_median_of_medians", len = xs.len(), k);
+    median_of_medians_select(xs, k);
+    &xs[k]
+}
+
+/// Moves the `k`-th smallest element of `xs` to the index `k`, partitioning
+/// the remaining elements around it.
+fn median_of_medians_select<T: PartialOrd>(xs: &mut [T], k: usize) {
+    stats_depth!();
+    let (mut low, mut high) = (0, xs.len());
+    loop {
+        let range = &mut xs[low..high];
+        if range.len() <= GROUP_SIZE {
+            sort::insertion_sort(range);
+            return;
+        }
+
+        // Move the median of each group to the front of the range, then move
+        // the median of these medians to the first position, where the
+        // partition expects the pivot.
+        let n_groups = range.len().div_ceil(GROUP_SIZE);
+        let len = range.len();
+        for g in 0..n_groups {
+            let group = g * GROUP_SIZE..((g + 1) * GROUP_SIZE).min(len);
+            let median = group.start + (group.len() - 1) / 2;
+            sort::insertion_sort(&mut range[group]);
+            stats_swap!();
+            range.swap(g, median);
+        }
+        median_of_medians_select(&mut range[..n_groups], (n_groups - 1) / 2);
+        stats_swap!();
+        range.swap(0, (n_groups - 1) / 2);
+
+        let pivot_idx = low + quick::quick_sort_partition(range, &mut T::lt);
+        trace_event!(low, high, pivot_idx, "partition chosen");
+        match pivot_idx.cmp(&k) {
+            Ordering::Less => low = pivot_idx + 1,
+            Ordering::Greater => high = pivot_idx,
+            Ordering::Equal => return,
+        }
+    }
+}
+
+/// Lower median of `xs`, that is, its element of rank `(n - 1) / 2` where
+/// `n = xs.len()`, found with `quick_select`, which rearranges `xs`. Returns
+/// `None` if `xs` is empty.
+///
+/// Example:
+/// ```
+/// use algae::sort;
+///
+/// assert_eq!(sort::median(&mut [4, 1, 3, 2]), Some(&2));
+/// assert_eq!(sort::median(&mut [5, 9, 1]), Some(&5));
+/// assert_eq!(sort::median::<i32>(&mut []), None);
+/// ```
+pub fn median<T: PartialOrd>(xs: &mut [T]) -> Option<&T> {
+    if xs.is_empty() {
+        return None;
+    }
+    let k = (xs.len() - 1) / 2;
+    Some(quick_select(xs, k))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sort;
+
+    #[test]
+    fn agrees_with_sorting() {
+        for _ in 0..50 {
+            let mut xs = sort::rand_vec(fastrand::usize(1..200));
+            let mut sorted = xs.clone();
+            sorted.sort();
+
+            let k = fastrand::usize(..xs.len());
+            let kth = *quick_select(&mut xs, k);
+            assert_eq!(kth, sorted[k]);
+            assert!(xs[..k].iter().all(|&x| x <= kth));
+            assert!(xs[k + 1..].iter().all(|&x| x >= kth));
+        }
+    }
+
+    #[test]
+    fn median_of_medians_adversarial() {
+        let n = 1000;
+        let inputs: Vec<Vec<i64>> = vec![
+            (0..n).collect(),
+            (0..n).rev().collect(),
+            vec![42; n as usize],
+            (0..n / 2).chain((0..n / 2).rev()).collect(),
+            (0..n).map(|_| fastrand::i64(..4)).collect(),
+            sort::rand_vec(n as usize),
+        ];
+        for xs in inputs {
+            let mut sorted = xs.clone();
+            sorted.sort();
+            for k in [0, 1, xs.len() / 3, xs.len() / 2, xs.len() - 1] {
+                let mut ys = xs.clone();
+                let kth = *select_median_of_medians(&mut ys, k);
+                assert_eq!(kth, sorted[k]);
+                assert!(ys[..k].iter().all(|&y| y <= kth));
+                assert!(ys[k + 1..].iter().all(|&y| y >= kth));
+
+                let mut zs = xs.clone();
+                assert_eq!(*quick_select(&mut zs, k), sorted[k]);
+            }
+        }
+    }
+
+    #[test]
+    fn duplicates() {
+        let mut xs: Vec<u8> = (0..1000).map(|_| fastrand::u8(..3)).collect();
+        let mut sorted = xs.clone();
+        sorted.sort();
+        for k in [0, 333, 500, 999] {
+            assert_eq!(*quick_select(&mut xs, k), sorted[k]);
+        }
+    }
+
+    #[test]
+    fn medians() {
+        assert_eq!(median(&mut [1]), Some(&1));
+        assert_eq!(median(&mut [2, 1]), Some(&1));
+        let mut words = ["kiwi", "fig", "apple", "pear", "banana"];
+        assert_eq!(median(&mut words), Some(&"fig"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_range() {
+        quick_select(&mut [1, 2, 3], 3);
+    }
+}
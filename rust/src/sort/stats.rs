@@ -0,0 +1,157 @@
+//! Instrumented sorting (enabled by the `stats` feature): counts the
+//! comparisons, swaps and recursion depth of any of the sorts of the crate, so
+//! that their running time can be analysed empirically.
+//!
+//! Comparisons are counted by sorting the elements through the `Counted`
+//! wrapper, whose `PartialOrd` implementation records every call, while the
+//! sorts themselves record their swaps and recursive calls. Swaps count the
+//! exchanges of two elements: merge sorts, which copy elements into auxiliary
+//! arrays or rotate blocks instead, report few or no swaps.
+//!
+//! The counters are thread-local: the work done by other threads, such as the
+//! rayon workers of `par_quick_sort`, is not accounted for.
+use std::{cell::Cell, cmp::Ordering};
+
+/// Statistics of a sorting run, collected by `sort_with_stats`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SortStats {
+    /// Number of comparisons between elements.
+    pub comparisons: usize,
+    /// Number of swaps of two elements.
+    pub swaps: usize,
+    /// Maximum number of nested recursive calls, or zero for sorts that do not
+    /// recurse.
+    pub max_depth: usize,
+}
+
+thread_local! {
+    static STATS: Cell<SortStats> = const {
+        Cell::new(SortStats {
+            comparisons: 0,
+            swaps: 0,
+            max_depth: 0,
+        })
+    };
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+fn update_stats<F: FnOnce(&mut SortStats)>(f: F) {
+    STATS.with(|stats| {
+        let mut current = stats.get();
+        f(&mut current);
+        stats.set(current);
+    });
+}
+
+/// Records a swap done by a sort.
+pub(crate) fn record_swap() {
+    update_stats(|stats| stats.swaps += 1);
+}
+
+/// Guard recording one level of recursion for as long as it is alive.
+pub(crate) struct DepthGuard;
+
+impl DepthGuard {
+    pub(crate) fn enter() -> Self {
+        let depth = DEPTH.with(|d| {
+            d.set(d.get() + 1);
+            d.get()
+        });
+        update_stats(|stats| stats.max_depth = stats.max_depth.max(depth));
+        DepthGuard
+    }
+}
+
+impl Drop for DepthGuard {
+    fn drop(&mut self) {
+        DEPTH.with(|d| d.set(d.get() - 1));
+    }
+}
+
+/// Wrapper counting the comparisons between the elements it wraps. Since it is
+/// a transparent wrapper, a slice of `T` can be sorted as a slice of
+/// `Counted<T>` in place, which is what `sort_with_stats` does.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Counted<T>(pub T);
+
+impl<T: PartialOrd> PartialOrd for Counted<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        update_stats(|stats| stats.comparisons += 1);
+        self.0.partial_cmp(&other.0)
+    }
+}
+
+/// Sorts `xs` with `sort_fn` and returns the statistics of the run. The sort
+/// sees the elements through the `Counted` wrapper, so that any sort of the
+/// crate can be passed directly. Runs of `sort_with_stats` should not be
+/// nested, since they share the counters of the thread.
+///
+/// Example:
+/// ```
+/// use algae::sort::{self, stats::sort_with_stats};
+///
+/// let mut v = [5, 4, 3, 2, 1];
+/// let stats = sort_with_stats(&mut v, sort::insertion_sort);
+/// assert_eq!(v, [1, 2, 3, 4, 5]);
+/// // Insertion sort compares and swaps every pair of a reversed array.
+/// assert_eq!(stats.comparisons, 10);
+/// assert_eq!(stats.swaps, 10);
+/// ```
+pub fn sort_with_stats<T, F>(xs: &mut [T], sort_fn: F) -> SortStats
+where
+    T: PartialOrd,
+    F: FnOnce(&mut [Counted<T>]),
+{
+    STATS.with(|stats| stats.set(SortStats::default()));
+    // SAFETY: `Counted<T>` is a `repr(transparent)` wrapper of `T`, so both
+    // slices have the same layout.
+    let counted = unsafe { &mut *(xs as *mut [T] as *mut [Counted<T>]) };
+    sort_fn(counted);
+    STATS.with(Cell::get)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sort;
+
+    #[test]
+    fn counts() {
+        let mut sorted: Vec<i64> = (0..100).collect();
+        let stats = sort_with_stats(&mut sorted, sort::insertion_sort);
+        assert_eq!(
+            stats,
+            SortStats {
+                comparisons: 99,
+                swaps: 0,
+                max_depth: 0
+            }
+        );
+
+        let mut xs = sort::rand_vec(1_000);
+        let stats = sort_with_stats(&mut xs, sort::merge_sort);
+        assert!(sort::is_sorted(&xs));
+        // Merge sort splits the array in halves: ceil(log2(1000)) + 1 levels.
+        assert_eq!(stats.max_depth, 11);
+        assert!(stats.comparisons <= 1_000 * 10);
+    }
+
+    #[test]
+    fn heapsort_never_recurses() {
+        let mut xs = sort::rand_vec(1_000);
+        let stats = sort_with_stats(&mut xs, sort::heap_sort_in_place);
+        assert!(sort::is_sorted(&xs));
+        assert_eq!(stats.max_depth, 0);
+        assert!(stats.swaps > 0);
+    }
+
+    #[test]
+    fn quicksort_depth_is_logarithmic() {
+        let mut xs: Vec<i64> = (0..10_000).collect();
+        let stats = sort_with_stats(&mut xs, sort::intro_sort);
+        assert!(sort::is_sorted(&xs));
+        assert!(stats.max_depth <= 2 * 14 + 1);
+        assert!(stats.comparisons > 0);
+    }
+}
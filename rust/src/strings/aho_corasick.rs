@@ -0,0 +1,246 @@
+//! The Aho-Corasick automaton, which finds the occurrences of a whole set of
+//! patterns in a single pass over the text, in time linear in the length of
+//! the text plus the number of matches.
+use crate::ds::Queue;
+use std::collections::BTreeMap;
+
+/// Index of the root of the trie, which corresponds to the empty string.
+const ROOT: usize = 0;
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    children: BTreeMap<u8, usize>,
+    /// Node of the longest proper suffix of this node's string which is also
+    /// in the trie.
+    fail: usize,
+    /// Nearest node along the failure links, excluding this one, where some
+    /// pattern ends.
+    dict: Option<usize>,
+    /// Indices of the patterns ending at this node.
+    outputs: Vec<usize>,
+}
+
+/// Automaton matching a set of byte patterns. The patterns are stored in a
+/// trie, where each node also gets a *failure link* to the node of the longest
+/// proper suffix of its string present in the trie. Reading the text moves
+/// down the trie, following failure links on mismatches, so that the current
+/// node is always the longest suffix of the text read so far that is a prefix
+/// of some pattern. The failure links are computed level by level, in a
+/// breadth-first traversal of the trie.
+///
+/// Example:
+/// ```
+/// use algae::strings::AhoCorasick;
+///
+/// let ac = AhoCorasick::new(&["he", "she", "his", "hers"]);
+/// let matches: Vec<(usize, usize)> = ac.find_iter("ushers").collect();
+/// // "she" and "he" end at the same position, then "hers" follows.
+/// assert_eq!(matches, [(1, 1), (0, 2), (3, 2)]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AhoCorasick {
+    nodes: Vec<Node>,
+    pattern_lens: Vec<usize>,
+}
+
+impl AhoCorasick {
+    /// Builds the automaton of `patterns` in time linear in their total
+    /// length. Patterns may repeat, and each copy is reported separately.
+    pub fn new<P: AsRef<[u8]>>(patterns: &[P]) -> Self {
+        let mut nodes = vec![Node::default()];
+        for (k, pattern) in patterns.iter().enumerate() {
+            let mut u = ROOT;
+            for &b in pattern.as_ref() {
+                u = match nodes[u].children.get(&b) {
+                    Some(&v) => v,
+                    None => {
+                        nodes.push(Node::default());
+                        let v = nodes.len() - 1;
+                        nodes[u].children.insert(b, v);
+                        v
+                    }
+                };
+            }
+            nodes[u].outputs.push(k);
+        }
+
+        let mut ac = Self {
+            nodes,
+            pattern_lens: patterns.iter().map(|p| p.as_ref().len()).collect(),
+        };
+        ac.link_failures();
+        ac
+    }
+
+    /// Computes the failure and dictionary links in breadth-first order, so
+    /// that the links of shallower nodes are known when a node is reached.
+    fn link_failures(&mut self) {
+        let mut queue = Queue::new();
+        queue.push(ROOT);
+        while let Some(u) = queue.pop() {
+            let children: Vec<(u8, usize)> = self.nodes[u]
+                .children
+                .iter()
+                .map(|(&b, &v)| (b, v))
+                .collect();
+            for (b, v) in children {
+                let fail = if u == ROOT {
+                    ROOT
+                } else {
+                    self.step(self.nodes[u].fail, b)
+                };
+                let dict = if self.nodes[fail].outputs.is_empty() {
+                    self.nodes[fail].dict
+                } else {
+                    Some(fail)
+                };
+                self.nodes[v].fail = fail;
+                self.nodes[v].dict = dict;
+                queue.push(v);
+            }
+        }
+    }
+
+    /// Node reached from `u` by reading `b`.
+    fn step(&self, mut u: usize, b: u8) -> usize {
+        loop {
+            if let Some(&v) = self.nodes[u].children.get(&b) {
+                return v;
+            } else if u == ROOT {
+                return ROOT;
+            }
+            u = self.nodes[u].fail;
+        }
+    }
+
+    /// Number of patterns.
+    pub fn len(&self) -> usize {
+        self.pattern_lens.len()
+    }
+
+    /// Whether there are no patterns.
+    pub fn is_empty(&self) -> bool {
+        self.pattern_lens.is_empty()
+    }
+
+    /// Iterator over the occurrences of the patterns in `text`, as pairs
+    /// `(pattern_index, position)` where `position` is the index of the
+    /// first byte of the match. Overlapping matches are all reported, ordered
+    /// by their end, and matches ending at the same byte are ordered from the
+    /// longest to the shortest.
+    pub fn find_iter<'a, 't, T: AsRef<[u8]> + ?Sized>(&'a self, text: &'t T) -> FindIter<'a, 't> {
+        FindIter {
+            automaton: self,
+            text: text.as_ref(),
+            pos: 0,
+            state: ROOT,
+            // Empty patterns match before the first byte is read.
+            outputs: Some((ROOT, 0)),
+        }
+    }
+
+    /// Whether some pattern occurs in `text`.
+    pub fn is_match<T: AsRef<[u8]> + ?Sized>(&self, text: &T) -> bool {
+        self.find_iter(text).next().is_some()
+    }
+}
+
+/// Iterator over the matches of an `AhoCorasick` automaton in a text.
+#[derive(Debug, Clone)]
+pub struct FindIter<'a, 't> {
+    automaton: &'a AhoCorasick,
+    text: &'t [u8],
+    /// Number of bytes of the text read so far.
+    pos: usize,
+    /// Node of the automaton after reading `text[..pos]`.
+    state: usize,
+    /// Node along the dictionary links of `state` whose outputs are being
+    /// reported, and the next output to report.
+    outputs: Option<(usize, usize)>,
+}
+
+impl Iterator for FindIter<'_, '_> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ac = self.automaton;
+        loop {
+            if let Some((u, k)) = self.outputs {
+                if let Some(&pattern) = ac.nodes[u].outputs.get(k) {
+                    self.outputs = Some((u, k + 1));
+                    return Some((pattern, self.pos - ac.pattern_lens[pattern]));
+                }
+                self.outputs = ac.nodes[u].dict.map(|v| (v, 0));
+                continue;
+            }
+            let &b = self.text.get(self.pos)?;
+            self.state = ac.step(self.state, b);
+            self.pos += 1;
+            self.outputs = Some((self.state, 0));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strings::kmp_search_all;
+
+    fn naive_matches(patterns: &[Vec<u8>], text: &[u8]) -> Vec<(usize, usize)> {
+        let mut matches: Vec<(usize, usize)> = patterns
+            .iter()
+            .enumerate()
+            .flat_map(|(k, p)| kmp_search_all(text, p).into_iter().map(move |pos| (k, pos)))
+            .collect();
+        // By end position, then from the longest to the shortest.
+        matches
+            .sort_by_key(|&(k, pos)| (pos + patterns[k].len(), usize::MAX - patterns[k].len(), k));
+        matches
+    }
+
+    #[test]
+    fn agrees_with_naive_matching() {
+        for _ in 0..200 {
+            let patterns: Vec<Vec<u8>> = (0..fastrand::usize(..8))
+                .map(|_| {
+                    (0..fastrand::usize(1..5))
+                        .map(|_| fastrand::u8(b'a'..b'd'))
+                        .collect()
+                })
+                .collect();
+            let text: Vec<u8> = (0..fastrand::usize(..60))
+                .map(|_| fastrand::u8(b'a'..b'd'))
+                .collect();
+            let ac = AhoCorasick::new(&patterns);
+            assert_eq!(ac.len(), patterns.len());
+            let matches: Vec<(usize, usize)> = ac.find_iter(&text).collect();
+            assert_eq!(matches, naive_matches(&patterns, &text));
+            assert_eq!(ac.is_match(&text), !matches.is_empty());
+        }
+    }
+
+    #[test]
+    fn repeated_and_empty_patterns() {
+        let ac = AhoCorasick::new(&["ab", "", "ab"]);
+        let matches: Vec<(usize, usize)> = ac.find_iter("xab").collect();
+        assert_eq!(matches, [(1, 0), (1, 1), (1, 2), (0, 1), (2, 1), (1, 3)]);
+
+        let ac = AhoCorasick::new::<&str>(&[]);
+        assert!(ac.is_empty());
+        assert!(!ac.is_match("anything"));
+    }
+
+    #[test]
+    fn unicode_patterns() {
+        let text = "naïve café, naïveté";
+        let ac = AhoCorasick::new(&["naïve", "café", "é"]);
+        for (k, pos) in ac.find_iter(text) {
+            assert!(text.is_char_boundary(pos));
+            assert_eq!(
+                &text[pos..pos + ac.pattern_lens[k]],
+                ["naïve", "café", "é"][k]
+            );
+        }
+        assert_eq!(ac.find_iter(text).count(), 5);
+    }
+}
@@ -0,0 +1,267 @@
+//! The Boyer-Moore algorithm and its Horspool simplification, which compare
+//! the pattern with each window of the text from right to left, and use the
+//! mismatched symbol to skip windows that cannot match. On large alphabets,
+//! most windows are skipped after a single comparison, making them sublinear
+//! on average.
+use crate::strings::Finder;
+
+/// Index of the first occurrence of `pattern` in `text`, if any. An empty
+/// pattern occurs at index 0. Runs in O(n + m) time, and O(n / m) on average
+/// for random texts.
+///
+/// Example:
+/// ```
+/// use algae::strings::boyer_moore_search;
+///
+/// assert_eq!(boyer_moore_search(b"here is a simple example", b"example"), Some(17));
+/// assert_eq!(boyer_moore_search(b"here is a simple example", b"sample"), None);
+/// ```
+pub fn boyer_moore_search(text: &[u8], pattern: &[u8]) -> Option<usize> {
+    BoyerMooreMatches::new(text, pattern).next()
+}
+
+/// Indices of all occurrences of `pattern` in `text`, including overlapping
+/// ones, in increasing order. An empty pattern occurs at every index from 0
+/// to `text.len()`.
+///
+/// Example:
+/// ```
+/// use algae::strings::boyer_moore_search_all;
+///
+/// assert_eq!(boyer_moore_search_all(b"abababa", b"aba"), [0, 2, 4]);
+/// ```
+pub fn boyer_moore_search_all(text: &[u8], pattern: &[u8]) -> Vec<usize> {
+    BoyerMooreMatches::new(text, pattern).collect()
+}
+
+/// Index of the first occurrence of `pattern` in `text` with the Horspool
+/// algorithm, if any. An empty pattern occurs at index 0. Takes O(n * m) time
+/// in the worst case, but is often faster than Boyer-Moore for short patterns
+/// since it only keeps the bad character table.
+///
+/// Example:
+/// ```
+/// use algae::strings::horspool_search;
+///
+/// assert_eq!(horspool_search(b"abracadabra", b"cad"), Some(4));
+/// assert_eq!(horspool_search(b"abracadabra", b"bad"), None);
+/// ```
+pub fn horspool_search(text: &[u8], pattern: &[u8]) -> Option<usize> {
+    HorspoolMatches::new(text, pattern).next()
+}
+
+/// Indices of all occurrences of `pattern` in `text` with the Horspool
+/// algorithm, including overlapping ones, in increasing order.
+///
+/// Example:
+/// ```
+/// use algae::strings::horspool_search_all;
+///
+/// assert_eq!(horspool_search_all(b"aaaa", b"aa"), [0, 1, 2]);
+/// ```
+pub fn horspool_search_all(text: &[u8], pattern: &[u8]) -> Vec<usize> {
+    HorspoolMatches::new(text, pattern).collect()
+}
+
+/// Good suffix table of `pattern`: after a mismatch at `pattern[j - 1]`, the
+/// suffix `pattern[j..]` has matched, and the entry `j` is the smallest shift
+/// aligning it with another occurrence in the pattern not preceded by the
+/// same symbol, or else the longest prefix of the pattern that is a suffix of
+/// the match. The entry 0 is the shift after a full match.
+fn good_suffix_table(pattern: &[u8]) -> Vec<usize> {
+    let m = pattern.len();
+    let mut shift = vec![0; m + 1];
+    // `border[i]` is the start of the widest border of `pattern[i..]`.
+    let mut border = vec![0; m + 1];
+    let (mut i, mut j) = (m, m + 1);
+    border[i] = j;
+    while i > 0 {
+        while j <= m && pattern[i - 1] != pattern[j - 1] {
+            if shift[j] == 0 {
+                shift[j] = j - i;
+            }
+            j = border[j];
+        }
+        i -= 1;
+        j -= 1;
+        border[i] = j;
+    }
+
+    // Suffixes without another occurrence shift up to the widest border of
+    // the whole pattern.
+    j = border[0];
+    for (i, s) in shift.iter_mut().enumerate() {
+        if *s == 0 {
+            *s = j;
+        }
+        if i == j {
+            j = border[j];
+        }
+    }
+    shift
+}
+
+/// For each byte, one plus the index of its last occurrence in `pattern`, or
+/// 0 if it does not occur.
+fn last_occurrences(pattern: &[u8]) -> [usize; 256] {
+    let mut last = [0; 256];
+    for (i, &c) in pattern.iter().enumerate() {
+        last[c as usize] = i + 1;
+    }
+    last
+}
+
+/// Iterator over the occurrences of a pattern in a text with the bad
+/// character and good suffix rules.
+struct BoyerMooreMatches<'a> {
+    text: &'a [u8],
+    pattern: &'a [u8],
+    last: [usize; 256],
+    good_suffix: Vec<usize>,
+    /// Start of the next window of the text to be compared.
+    pos: usize,
+}
+
+impl<'a> BoyerMooreMatches<'a> {
+    fn new(text: &'a [u8], pattern: &'a [u8]) -> Self {
+        Self {
+            text,
+            pattern,
+            last: last_occurrences(pattern),
+            good_suffix: good_suffix_table(pattern),
+            pos: 0,
+        }
+    }
+}
+
+impl Iterator for BoyerMooreMatches<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let m = self.pattern.len();
+        while self.pos + m <= self.text.len() {
+            let s = self.pos;
+            let window = &self.text[s..s + m];
+            // Length of the prefix of the pattern left to match.
+            let mut j = m;
+            while j > 0 && self.pattern[j - 1] == window[j - 1] {
+                j -= 1;
+            }
+            if j == 0 {
+                self.pos += self.good_suffix[0];
+                return Some(s);
+            }
+            // Align the mismatched byte with its last occurrence in the
+            // pattern, unless the good suffix rule shifts further.
+            let bad_char = j.saturating_sub(self.last[window[j - 1] as usize]);
+            self.pos += self.good_suffix[j].max(bad_char);
+        }
+        None
+    }
+}
+
+/// Iterator over the occurrences of a pattern in a text, shifting each window
+/// by the last occurrence in the pattern of the last byte of the window.
+struct HorspoolMatches<'a> {
+    text: &'a [u8],
+    pattern: &'a [u8],
+    skip: [usize; 256],
+    /// Start of the next window of the text to be compared.
+    pos: usize,
+}
+
+impl<'a> HorspoolMatches<'a> {
+    fn new(text: &'a [u8], pattern: &'a [u8]) -> Self {
+        let m = pattern.len();
+        let mut skip = [m.max(1); 256];
+        // The last byte of the pattern is excluded, so that shifts are never
+        // zero.
+        for (i, &c) in pattern.iter().enumerate().take(m.saturating_sub(1)) {
+            skip[c as usize] = m - 1 - i;
+        }
+        Self {
+            text,
+            pattern,
+            skip,
+            pos: 0,
+        }
+    }
+}
+
+impl Iterator for HorspoolMatches<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let m = self.pattern.len();
+        while self.pos + m <= self.text.len() {
+            let s = self.pos;
+            let window = &self.text[s..s + m];
+            self.pos += match window.last() {
+                Some(&c) => self.skip[c as usize],
+                None => 1,
+            };
+            if window.iter().rev().eq(self.pattern.iter().rev()) {
+                return Some(s);
+            }
+        }
+        None
+    }
+}
+
+/// Finder implemented by the Boyer-Moore algorithm, whose matches are found
+/// lazily.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BoyerMooreFinder;
+
+impl Finder for BoyerMooreFinder {
+    fn find_iter<'a>(text: &'a [u8], pattern: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        BoyerMooreMatches::new(text, pattern)
+    }
+}
+
+/// Finder implemented by the Horspool algorithm, whose matches are found
+/// lazily.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HorspoolFinder;
+
+impl Finder for HorspoolFinder {
+    fn find_iter<'a>(text: &'a [u8], pattern: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        HorspoolMatches::new(text, pattern)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strings;
+
+    #[test]
+    fn boyer_moore_finder() {
+        strings::check_finder::<BoyerMooreFinder>();
+    }
+
+    #[test]
+    fn horspool_finder() {
+        strings::check_finder::<HorspoolFinder>();
+    }
+
+    #[test]
+    fn good_suffix_shifts() {
+        // A full match of "abab" shifts to its border "ab".
+        assert_eq!(good_suffix_table(b"abab")[0], 2);
+        assert_eq!(good_suffix_table(b"abc")[0], 3);
+        assert_eq!(good_suffix_table(b""), [1]);
+    }
+
+    #[test]
+    fn long_patterns() {
+        let text: Vec<u8> = (0..5_000).map(|_| fastrand::u8(b'a'..b'c')).collect();
+        for _ in 0..20 {
+            let start = fastrand::usize(..4_900);
+            let pattern = &text[start..start + fastrand::usize(1..100)];
+            let expected = strings::naive_search_all(&text, pattern);
+            assert_eq!(boyer_moore_search_all(&text, pattern), expected);
+            assert_eq!(horspool_search_all(&text, pattern), expected);
+        }
+    }
+}
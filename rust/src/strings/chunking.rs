@@ -0,0 +1,141 @@
+//! Content-defined chunking, which splits data into chunks at positions
+//! determined by the data itself rather than by fixed offsets. An insertion
+//! or deletion then only changes the chunks around it, while the following
+//! chunks are unaffected, so that duplicated content is found by comparing
+//! chunks, as in deduplicating storage and backup systems.
+use crate::strings::RollingHash;
+use std::ops::Range;
+
+/// Number of bytes whose fingerprint decides whether a chunk ends.
+const WINDOW: usize = 48;
+
+/// Fixed base of the fingerprints, so that the same data is always split at
+/// the same positions.
+const BASE: u64 = 0x003D_A335_8B4D_C173;
+
+/// Splits `data` into consecutive chunks at content-defined boundaries,
+/// returning their ranges. A chunk ends after a byte when the Rabin
+/// fingerprint of the last 48 bytes is congruent to `avg - 1` modulo `avg`,
+/// which happens once every `avg` bytes on average, as long as the chunk has
+/// at least `min` bytes. Chunks are also cut at `max` bytes. Only the last
+/// chunk may be shorter than `min`. The fingerprints are updated with the
+/// rolling hash in O(1) per byte.
+///
+/// Panics unless `0 < min <= avg <= max`.
+///
+/// Example:
+/// ```
+/// use algae::strings::chunking::rabin_chunks;
+/// use std::collections::HashSet;
+///
+/// let mut rng = fastrand::Rng::with_seed(7);
+/// let data: Vec<u8> = (0..100_000).map(|_| rng.u8(..)).collect();
+/// let chunks = rabin_chunks(&data, 512, 2048, 8192);
+/// assert_eq!(chunks.first().unwrap().start, 0);
+/// assert_eq!(chunks.last().unwrap().end, data.len());
+/// assert!(chunks.windows(2).all(|w| w[0].end == w[1].start));
+///
+/// // Changing the start of the data leaves the later boundaries in place.
+/// let header = b"a short header";
+/// let edited = [&header[..], &data].concat();
+/// let shifted: HashSet<usize> = rabin_chunks(&edited, 512, 2048, 8192)
+///     .iter()
+///     .filter_map(|c| c.end.checked_sub(header.len()))
+///     .collect();
+/// assert!(chunks[5..].iter().all(|c| shifted.contains(&c.end)));
+/// ```
+pub fn rabin_chunks(data: &[u8], min: usize, avg: usize, max: usize) -> Vec<Range<usize>> {
+    assert!(
+        0 < min && min <= avg && avg <= max,
+        "Chunk sizes must satisfy 0 < min <= avg <= max"
+    );
+    let hasher = RollingHash::with_base(WINDOW, BASE);
+    let avg = avg as u64;
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut fingerprint = 0;
+    for i in 0..data.len() {
+        // Fingerprint of the window ending at `i`.
+        if i + 1 == WINDOW {
+            fingerprint = hasher.hash(&data[..WINDOW]);
+        } else if i >= WINDOW {
+            fingerprint = hasher.roll(fingerprint, data[i - WINDOW], data[i]);
+        }
+        let len = i + 1 - start;
+        let boundary = i + 1 >= WINDOW && fingerprint % avg == avg - 1;
+        if (len >= min && boundary) || len == max {
+            chunks.push(start..i + 1);
+            start = i + 1;
+        }
+    }
+    if start < data.len() {
+        chunks.push(start..data.len());
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Seeded data, so that the statistical assertions always hold.
+    fn random_data(rng: &mut fastrand::Rng, len: usize) -> Vec<u8> {
+        (0..len).map(|_| rng.u8(..)).collect()
+    }
+
+    #[test]
+    fn chunk_sizes() {
+        assert!(rabin_chunks(&[], 1, 2, 3).is_empty());
+        let data = random_data(&mut fastrand::Rng::with_seed(1), 200_000);
+        let (min, avg, max) = (256, 1024, 4096);
+        let chunks = rabin_chunks(&data, min, avg, max);
+        let mut next = 0;
+        for (k, chunk) in chunks.iter().enumerate() {
+            assert_eq!(chunk.start, next);
+            assert!(chunk.len() <= max);
+            assert!(chunk.len() >= min || k == chunks.len() - 1);
+            next = chunk.end;
+        }
+        assert_eq!(next, data.len());
+
+        // The expected chunk size is about `min + avg`.
+        let mean = data.len() / chunks.len();
+        assert!(
+            (avg / 2..3 * avg).contains(&mean),
+            "Mean chunk size {}",
+            mean
+        );
+    }
+
+    #[test]
+    fn constant_data_is_cut_at_max() {
+        let chunks = rabin_chunks(&[0; 10_000], 10, 100, 1000);
+        assert!(chunks.iter().all(|c| c.len() == 1000 || c.len() < 10));
+    }
+
+    #[test]
+    fn edits_change_few_chunks() {
+        let mut rng = fastrand::Rng::with_seed(2);
+        let data = random_data(&mut rng, 100_000);
+        let mut edited = data.clone();
+        edited.splice(50_000..50_010, random_data(&mut rng, 25));
+
+        let chunk_set = |data: &[u8]| -> HashSet<Vec<u8>> {
+            rabin_chunks(data, 256, 1024, 4096)
+                .into_iter()
+                .map(|c| data[c].to_vec())
+                .collect()
+        };
+        let (original, changed) = (chunk_set(&data), chunk_set(&edited));
+        let lost = original.difference(&changed).count();
+        assert!(lost <= 3, "{} chunks changed", lost);
+        assert_eq!(chunk_set(&data), original);
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_sizes() {
+        rabin_chunks(b"data", 10, 5, 20);
+    }
+}
@@ -0,0 +1,129 @@
+use crate::strings::suffix_array;
+
+/// Burrows-Wheeler transform of `input`: the last column of the sorted
+/// rotations of `input` followed by an end marker smaller than every byte.
+/// The end marker itself is left out of the returned bytes, and its position
+/// in the last column is returned instead, which is all `inverse_bwt` needs
+/// to recover `input`. The rotations are sorted as the suffixes of `input`,
+/// with the suffix array, in O(n log n).
+///
+/// Bytes followed by the same context end up next to each other, so the
+/// transform of text tends to have long runs of equal bytes.
+///
+/// Example:
+/// ```
+/// use algae::strings::compress::{bwt, inverse_bwt};
+///
+/// let (transformed, end) = bwt(b"banana");
+/// assert_eq!((transformed.as_slice(), end), (&b"annbaa"[..], 4));
+/// assert_eq!(inverse_bwt(&transformed, end), b"banana");
+/// ```
+pub fn bwt(input: &[u8]) -> (Vec<u8>, usize) {
+    let Some(&last) = input.last() else {
+        return (Vec::new(), 0);
+    };
+    // The first rotation starts with the end marker, and is preceded by the
+    // last byte. The others start at the suffixes, in sorted order.
+    let mut transformed = Vec::with_capacity(input.len());
+    transformed.push(last);
+    let mut end = 0;
+    for (row, &i) in suffix_array(input).iter().enumerate() {
+        match i {
+            0 => end = row + 1,
+            _ => transformed.push(input[i - 1]),
+        }
+    }
+    (transformed, end)
+}
+
+/// Inverts `bwt`, given the transformed bytes and the position of the end
+/// marker. The byte of each row in the last column is the one preceding the
+/// byte of the same occurrence in the first column, which is the sorted last
+/// column. Walking from a row to the row of its preceding byte recovers the
+/// input backwards, in O(n) time.
+///
+/// Panics if `end` is greater than `transformed.len()`.
+pub fn inverse_bwt(transformed: &[u8], end: usize) -> Vec<u8> {
+    assert!(end <= transformed.len(), "End marker out of bounds");
+    let n = transformed.len();
+    // Last column with the end marker, as `None`, put back.
+    let column = |row: usize| match row.cmp(&end) {
+        std::cmp::Ordering::Less => Some(transformed[row]),
+        std::cmp::Ordering::Equal => None,
+        std::cmp::Ordering::Greater => Some(transformed[row - 1]),
+    };
+
+    // First row of each byte in the first column, after the end marker.
+    let mut first = [0; 257];
+    for &b in transformed {
+        first[b as usize + 1] += 1;
+    }
+    first[0] = 1;
+    for b in 1..257 {
+        first[b] += first[b - 1];
+    }
+
+    // Row of the first column holding the same occurrence as each row of the
+    // last column.
+    let mut seen = [0; 256];
+    let mut preceding = vec![0; n + 1];
+    for (row, preceding) in preceding.iter_mut().enumerate() {
+        if let Some(b) = column(row) {
+            *preceding = first[b as usize] + seen[b as usize];
+            seen[b as usize] += 1;
+        }
+    }
+
+    let mut output = vec![0; n];
+    // The row starting with the end marker ends with the last byte.
+    let mut row = 0;
+    for i in (0..n).rev() {
+        output[i] = column(row).expect("Invalid Burrows-Wheeler transform");
+        row = preceding[row];
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Transform by explicitly sorting the rotations with the end marker.
+    fn naive_bwt(input: &[u8]) -> (Vec<u8>, usize) {
+        let marked: Vec<Option<u8>> = input.iter().map(|&b| Some(b)).chain([None]).collect();
+        let n = marked.len();
+        let mut rotations: Vec<Vec<Option<u8>>> = (0..n)
+            .map(|i| marked[i..].iter().chain(&marked[..i]).copied().collect())
+            .collect();
+        rotations.sort();
+        let last: Vec<Option<u8>> = rotations.iter().map(|r| r[n - 1]).collect();
+        let end = last.iter().position(|b| b.is_none()).unwrap();
+        (last.into_iter().flatten().collect(), end)
+    }
+
+    #[test]
+    fn agrees_with_sorting_rotations() {
+        assert_eq!(bwt(b""), (vec![], 0));
+        assert_eq!(inverse_bwt(b"", 0), b"");
+        for _ in 0..200 {
+            let input: Vec<u8> = (0..fastrand::usize(..40))
+                .map(|_| fastrand::u8(b'a'..b'e'))
+                .collect();
+            let (transformed, end) = bwt(&input);
+            assert_eq!((transformed.clone(), end), naive_bwt(&input));
+            assert_eq!(inverse_bwt(&transformed, end), input);
+        }
+    }
+
+    #[test]
+    fn arbitrary_bytes() {
+        let input: Vec<u8> = (0..1_000).map(|_| fastrand::u8(..)).collect();
+        let (transformed, end) = bwt(&input);
+        assert_eq!(inverse_bwt(&transformed, end), input);
+
+        let text = "the rain in spain falls mainly on the plain ".repeat(10);
+        let (transformed, _) = bwt(text.as_bytes());
+        let runs = 1 + transformed.windows(2).filter(|w| w[0] != w[1]).count();
+        assert!(runs < text.len() / 3, "Too many runs: {}", runs);
+    }
+}
@@ -0,0 +1,199 @@
+//! The LZ77 compressor, which replaces repeated byte sequences with
+//! references to an earlier occurrence within a sliding window.
+use std::collections::HashMap;
+
+/// Shortest match worth encoding as a reference, and length of the prefixes
+/// indexed by the hash chains.
+const MIN_MATCH: usize = 3;
+
+/// Marks the end of a hash chain.
+const NONE: usize = usize::MAX;
+
+/// Output unit of LZ77: copy `length` bytes starting `offset` bytes back in
+/// the output, then append `literal`, which is only missing when the copy
+/// reaches the end of the input. A triple without a copy has `offset` and
+/// `length` 0. The copied bytes may overlap the ones being produced, so that
+/// a run is encoded by a reference to its first byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Triple {
+    pub offset: usize,
+    pub length: usize,
+    pub literal: Option<u8>,
+}
+
+/// LZ77 codec with a sliding window of `window` bytes, where references may
+/// point to, and a lookahead of `lookahead` bytes, the maximum length of a
+/// copy.
+///
+/// Matches are found with *hash chains*: for each position, the most recent
+/// earlier position starting with the same three bytes is remembered, so that
+/// only the positions sharing a prefix with the current one are compared, up
+/// to `max_chain` of them, the closest first.
+///
+/// Example:
+/// ```
+/// use algae::strings::compress::lz77::{self, Lz77};
+///
+/// let input = b"abcabcabcabcx";
+/// let triples = Lz77::new(16, 16).compress(input);
+/// assert_eq!(triples.len(), 4);
+/// assert_eq!(lz77::decompress(&triples), input);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lz77 {
+    window: usize,
+    lookahead: usize,
+    max_chain: usize,
+}
+
+impl Lz77 {
+    /// Codec with the given window and lookahead sizes, following hash chains
+    /// for up to 64 positions.
+    pub fn new(window: usize, lookahead: usize) -> Self {
+        Self {
+            window,
+            lookahead,
+            max_chain: 64,
+        }
+    }
+
+    /// Sets the maximum number of earlier positions compared when looking for
+    /// a match. Longer chains may find longer matches, at the cost of time.
+    pub fn max_chain(mut self, max_chain: usize) -> Self {
+        self.max_chain = max_chain;
+        self
+    }
+
+    /// Encodes `input` as a sequence of triples, taking the longest match
+    /// found at each position.
+    pub fn compress(&self, input: &[u8]) -> Vec<Triple> {
+        let n = input.len();
+        let prefix = |i: usize| [input[i], input[i + 1], input[i + 2]];
+        let mut head: HashMap<[u8; MIN_MATCH], usize> = HashMap::new();
+        let mut prev = vec![NONE; n];
+
+        let mut triples = Vec::new();
+        let mut pos = 0;
+        while pos < n {
+            let max_len = self.lookahead.min(n - pos);
+            let (mut offset, mut length) = (0, 0);
+            if max_len >= MIN_MATCH {
+                let mut candidate = head.get(&prefix(pos)).copied().unwrap_or(NONE);
+                let mut steps = 0;
+                while candidate != NONE && pos - candidate <= self.window && steps < self.max_chain
+                {
+                    let len = (0..max_len)
+                        .take_while(|&k| input[candidate + k] == input[pos + k])
+                        .count();
+                    if len > length {
+                        (offset, length) = (pos - candidate, len);
+                    }
+                    candidate = prev[candidate];
+                    steps += 1;
+                }
+            }
+            if length < MIN_MATCH {
+                (offset, length) = (0, 0);
+            }
+
+            let next = (pos + length + 1).min(n);
+            // Index the positions passed over, which have a full prefix.
+            let end = next.min(n.saturating_sub(MIN_MATCH - 1));
+            for (i, link) in prev.iter_mut().enumerate().take(end).skip(pos) {
+                *link = head.insert(prefix(i), i).unwrap_or(NONE);
+            }
+            triples.push(Triple {
+                offset,
+                length,
+                literal: input.get(pos + length).copied(),
+            });
+            pos = next;
+        }
+        triples
+    }
+}
+
+impl Default for Lz77 {
+    /// Window of 32 KiB and lookahead of 258 bytes, as in DEFLATE.
+    fn default() -> Self {
+        Self::new(1 << 15, 258)
+    }
+}
+
+/// Decodes the output of `Lz77::compress`, whatever the codec parameters.
+///
+/// Panics if a triple refers to bytes before the start of the output.
+pub fn decompress(triples: &[Triple]) -> Vec<u8> {
+    let mut output = Vec::new();
+    for t in triples {
+        assert!(t.offset <= output.len(), "LZ77 reference out of bounds");
+        let start = output.len() - t.offset;
+        // Byte by byte, since the copy may overlap its own output.
+        for k in 0..t.length {
+            output.push(output[start + k]);
+        }
+        output.extend(t.literal);
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn check_round_trip(codec: &Lz77, input: &[u8]) -> Vec<Triple> {
+        let triples = codec.compress(input);
+        for t in &triples {
+            assert!(t.offset <= codec.window && t.length <= codec.lookahead);
+            assert!(t.length == 0 || t.length >= MIN_MATCH);
+        }
+        assert_eq!(decompress(&triples), input);
+        triples
+    }
+
+    #[test]
+    fn round_trip() {
+        for _ in 0..200 {
+            let codec = Lz77::new(fastrand::usize(1..40), fastrand::usize(1..20))
+                .max_chain(fastrand::usize(1..10));
+            let input: Vec<u8> = (0..fastrand::usize(..200))
+                .map(|_| fastrand::u8(b'a'..b'c'))
+                .collect();
+            check_round_trip(&codec, &input);
+        }
+        assert!(Lz77::default().compress(b"").is_empty());
+    }
+
+    #[test]
+    fn overlapping_copies() {
+        let triples = check_round_trip(&Lz77::default(), &[b'z'; 100]);
+        assert_eq!(
+            triples,
+            [
+                Triple {
+                    offset: 0,
+                    length: 0,
+                    literal: Some(b'z')
+                },
+                Triple {
+                    offset: 1,
+                    length: 99,
+                    literal: None
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn repetitive_text_compresses() {
+        let text = "to be or not to be, that is the question. ".repeat(50);
+        let triples = check_round_trip(&Lz77::default(), text.as_bytes());
+        assert!(triples.len() < 60, "{} triples", triples.len());
+
+        // Matches further back than the window can't be used.
+        let mut input: Vec<u8> = (0..=255).collect();
+        input.extend(0..=255);
+        assert_eq!(check_round_trip(&Lz77::new(100, 300), &input).len(), 512);
+        assert_eq!(check_round_trip(&Lz77::new(256, 300), &input).len(), 257);
+    }
+}
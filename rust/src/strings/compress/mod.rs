@@ -0,0 +1,14 @@
+//! Lossless compression of byte strings. The classic pipeline of the bzip2
+//! compressor applies the Burrows-Wheeler transform, which groups equal bytes
+//! together, then move-to-front coding, which turns these groups into runs of
+//! small numbers, easy to compress by the following stages.
+
+mod bwt;
+pub use bwt::*;
+
+mod mtf;
+pub use mtf::*;
+
+pub mod lz77;
+
+pub mod rle;
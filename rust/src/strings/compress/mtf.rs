@@ -0,0 +1,68 @@
+/// Move-to-front coding: each byte is replaced by its position in a list of
+/// all byte values, and then moved to the front of the list. Recently seen
+/// bytes get small codes, so the runs of equal bytes left by `bwt` become runs
+/// of zeros.
+///
+/// Example:
+/// ```
+/// use algae::strings::compress::{mtf_decode, mtf_encode};
+///
+/// let codes = mtf_encode(b"aaabbbaaa");
+/// assert_eq!(codes, [97, 0, 0, 98, 0, 0, 1, 0, 0]);
+/// assert_eq!(mtf_decode(&codes), b"aaabbbaaa");
+/// ```
+pub fn mtf_encode(input: &[u8]) -> Vec<u8> {
+    let mut list = initial_list();
+    input
+        .iter()
+        .map(|&b| {
+            let code = list.iter().position(|&x| x == b).unwrap();
+            list[..=code].rotate_right(1);
+            code as u8
+        })
+        .collect()
+}
+
+/// Inverts `mtf_encode`.
+pub fn mtf_decode(codes: &[u8]) -> Vec<u8> {
+    let mut list = initial_list();
+    codes
+        .iter()
+        .map(|&code| {
+            let code = code as usize;
+            list[..=code].rotate_right(1);
+            list[0]
+        })
+        .collect()
+}
+
+/// All byte values in increasing order.
+fn initial_list() -> [u8; 256] {
+    std::array::from_fn(|b| b as u8)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strings::compress::bwt;
+
+    #[test]
+    fn round_trip() {
+        assert!(mtf_encode(b"").is_empty());
+        for _ in 0..100 {
+            let input: Vec<u8> = (0..fastrand::usize(..200))
+                .map(|_| fastrand::u8(..))
+                .collect();
+            assert_eq!(mtf_decode(&mtf_encode(&input)), input);
+        }
+    }
+
+    #[test]
+    fn runs_become_zeros() {
+        let text = "abracadabra ".repeat(20);
+        let (transformed, _) = bwt(text.as_bytes());
+        let codes = mtf_encode(&transformed);
+        let zeros = codes.iter().filter(|&&c| c == 0).count();
+        assert!(zeros > codes.len() / 2, "Only {} zeros", zeros);
+    }
+}
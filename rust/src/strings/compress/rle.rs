@@ -0,0 +1,216 @@
+//! Run-length encoding, which replaces each run of equal symbols by the
+//! symbol and the length of the run.
+
+/// Longest run stored in a single pair of `encode`.
+const MAX_RUN: usize = u8::MAX as usize;
+
+/// Binary run-length encoding of `input` as pairs of bytes `(length,
+/// symbol)`, for runs of 1 to 255 bytes. Longer runs are split into several
+/// pairs.
+///
+/// Example:
+/// ```
+/// use algae::strings::compress::rle;
+///
+/// let encoded = rle::encode(b"aaaabccc");
+/// assert_eq!(encoded, [4, b'a', 1, b'b', 3, b'c']);
+/// assert_eq!(rle::decode(&encoded), b"aaaabccc");
+/// ```
+pub fn encode(input: &[u8]) -> Vec<u8> {
+    let mut encoded = Vec::new();
+    for (b, len) in runs(input.iter().copied()) {
+        for chunk in (0..len).step_by(MAX_RUN) {
+            encoded.push((len - chunk).min(MAX_RUN) as u8);
+            encoded.push(b);
+        }
+    }
+    encoded
+}
+
+/// Inverts `encode`.
+///
+/// Panics if `encoded` has an odd length.
+pub fn decode(encoded: &[u8]) -> Vec<u8> {
+    assert!(
+        encoded.len().is_multiple_of(2),
+        "Truncated run-length encoding"
+    );
+    let mut decoded = Vec::new();
+    for pair in encoded.chunks_exact(2) {
+        decoded.extend(std::iter::repeat_n(pair[1], pair[0] as usize));
+    }
+    decoded
+}
+
+/// Text run-length encoding of the characters of `s`, as `(character,
+/// length)` pairs.
+///
+/// Example:
+/// ```
+/// use algae::strings::compress::rle;
+///
+/// let runs = rle::encode_text("ññññaa!");
+/// assert_eq!(runs, [('ñ', 4), ('a', 2), ('!', 1)]);
+/// assert_eq!(rle::decode_text(&runs), "ññññaa!");
+/// ```
+pub fn encode_text(s: &str) -> Vec<(char, usize)> {
+    runs(s.chars()).collect()
+}
+
+/// Inverts `encode_text`.
+pub fn decode_text(runs: &[(char, usize)]) -> String {
+    runs.iter()
+        .flat_map(|&(c, len)| std::iter::repeat_n(c, len))
+        .collect()
+}
+
+/// Symbol of `encode_zero_runs` for a 1 bit in the length of a run of zeros.
+pub const RUN_A: u16 = 0;
+/// Symbol of `encode_zero_runs` for a 2 bit in the length of a run of zeros.
+pub const RUN_B: u16 = 1;
+
+/// Bit-level run-length encoding of the runs of zeros of `codes`, as in the
+/// bzip2 compressor, meant for the output of move-to-front coding after the
+/// Burrows-Wheeler transform, which is mostly zeros. Each run length is
+/// written in bijective base 2, one bit per symbol, with the digits 1 and 2
+/// given by `RUN_A` and `RUN_B`, least significant first. Other codes are
+/// shifted up by one to make room for these symbols.
+///
+/// A run of `k` zeros takes about `log2(k)` symbols.
+///
+/// Example:
+/// ```
+/// use algae::strings::compress::rle::{self, RUN_A, RUN_B};
+///
+/// // Runs of 1, 2, 3 and 4 zeros, separated by ones.
+/// let codes = [0, 1, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 0];
+/// let symbols = rle::encode_zero_runs(&codes);
+/// assert_eq!(symbols, [RUN_A, 2, RUN_B, 2, RUN_A, RUN_A, 2, RUN_B, RUN_A]);
+/// assert_eq!(rle::decode_zero_runs(&symbols), codes);
+/// ```
+pub fn encode_zero_runs(codes: &[u8]) -> Vec<u16> {
+    let mut symbols = Vec::new();
+    for (c, len) in runs(codes.iter().copied()) {
+        if c != 0 {
+            symbols.extend(std::iter::repeat_n(c as u16 + 1, len));
+            continue;
+        }
+        // Bijective base 2: the digits are 1 and 2, so there is no zero digit
+        // and every positive length has a unique representation.
+        let mut len = len;
+        while len > 0 {
+            if len % 2 == 1 {
+                symbols.push(RUN_A);
+                len = (len - 1) / 2;
+            } else {
+                symbols.push(RUN_B);
+                len = (len - 2) / 2;
+            }
+        }
+    }
+    symbols
+}
+
+/// Inverts `encode_zero_runs`.
+///
+/// Panics if a symbol is greater than 256.
+pub fn decode_zero_runs(symbols: &[u16]) -> Vec<u8> {
+    let mut codes = Vec::new();
+    let (mut run, mut weight) = (0, 1);
+    for &s in symbols {
+        match s {
+            RUN_A | RUN_B => {
+                run += weight * (s as usize + 1);
+                weight *= 2;
+            }
+            _ => {
+                codes.extend(std::iter::repeat_n(0, run));
+                (run, weight) = (0, 1);
+                codes.push(u8::try_from(s - 1).expect("Invalid zero-run symbol"));
+            }
+        }
+    }
+    codes.extend(std::iter::repeat_n(0, run));
+    codes
+}
+
+/// Maximal runs of equal symbols of `xs`, as `(symbol, length)` pairs.
+fn runs<T: PartialEq + Copy>(xs: impl Iterator<Item = T>) -> impl Iterator<Item = (T, usize)> {
+    let mut xs = xs.peekable();
+    std::iter::from_fn(move || {
+        let x = xs.next()?;
+        let mut len = 1;
+        while xs.next_if_eq(&x).is_some() {
+            len += 1;
+        }
+        Some((x, len))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strings::compress::{bwt, inverse_bwt, mtf_decode, mtf_encode};
+
+    fn random_runs(n: usize) -> Vec<u8> {
+        let mut xs = Vec::new();
+        for _ in 0..n {
+            let len = fastrand::usize(1..600);
+            xs.extend(std::iter::repeat_n(fastrand::u8(..4), len));
+        }
+        xs
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        assert!(encode(b"").is_empty());
+        for _ in 0..50 {
+            let input = random_runs(fastrand::usize(..10));
+            let encoded = encode(&input);
+            assert_eq!(decode(&encoded), input);
+            assert!(encoded.chunks(2).all(|pair| pair[0] > 0));
+        }
+        assert_eq!(encode(&[7; 300]), [255, 7, 45, 7]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn truncated_binary() {
+        decode(&[3, b'a', 2]);
+    }
+
+    #[test]
+    fn text_round_trip() {
+        assert!(encode_text("").is_empty());
+        let s = "wwwwaaadexxxxxx日日本";
+        let runs = encode_text(s);
+        assert_eq!(runs.len(), 7);
+        assert_eq!(decode_text(&runs), s);
+    }
+
+    #[test]
+    fn zero_runs_round_trip() {
+        for _ in 0..50 {
+            let codes = random_runs(fastrand::usize(..10));
+            let symbols = encode_zero_runs(&codes);
+            assert_eq!(decode_zero_runs(&symbols), codes);
+        }
+        for len in 1..100usize {
+            let symbols = encode_zero_runs(&vec![0; len]);
+            assert_eq!(symbols.len(), (len + 1).ilog2() as usize);
+        }
+        assert_eq!(encode_zero_runs(&[255]), [256]);
+    }
+
+    #[test]
+    fn pipeline_shrinks_text() {
+        let text = "she sells sea shells by the sea shore ".repeat(30);
+        let (transformed, end) = bwt(text.as_bytes());
+        let symbols = encode_zero_runs(&mtf_encode(&transformed));
+        assert!(symbols.len() < text.len() / 3, "{} symbols", symbols.len());
+
+        let codes = decode_zero_runs(&symbols);
+        let recovered = inverse_bwt(&mtf_decode(&codes), end);
+        assert_eq!(recovered, text.as_bytes());
+    }
+}
@@ -0,0 +1,291 @@
+//! Distances and similarities between strings, for fuzzy matching. The edit
+//! distances share the shape `fn(&[T], &[T]) -> usize` of `Distance`, and
+//! are at most the length of the longer string, so `Normalized` turns any of
+//! them into a similarity in `[0, 1]`. The Jaro similarities have the shape
+//! `fn(&[T], &[T]) -> f64` of `Similarity`.
+//!
+//! Example:
+//! ```
+//! use algae::strings::distance::{self, Distance, Normalized};
+//!
+//! let metrics: [Distance<u8>; 3] = [
+//!     distance::hamming,
+//!     distance::levenshtein,
+//!     distance::damerau_levenshtein,
+//! ];
+//! let distances = metrics.map(|d| d(b"abcd", b"acbd"));
+//! assert_eq!(distances, [2, 2, 1]);
+//!
+//! let similarity = Normalized(distance::damerau_levenshtein).similarity(b"abcd", b"acbd");
+//! assert_eq!(similarity, 0.75);
+//! ```
+use crate::strings::edit_distance;
+use std::{collections::HashMap, hash::Hash};
+
+/// Distance between two strings, as the number of some single-symbol edits.
+pub type Distance<T> = fn(&[T], &[T]) -> usize;
+
+/// Similarity between two strings, from 0 for unrelated strings to 1 for
+/// equal ones.
+pub type Similarity<T> = fn(&[T], &[T]) -> f64;
+
+/// Hamming distance: the number of positions where `a` and `b` differ. For
+/// strings of different lengths, the extra symbols of the longer one count as
+/// differences.
+///
+/// Example:
+/// ```
+/// use algae::strings::distance::hamming;
+///
+/// assert_eq!(hamming(b"karolin", b"kathrin"), 3);
+/// assert_eq!(hamming(b"abc", b"abcde"), 2);
+/// ```
+pub fn hamming<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    let mismatches = a.iter().zip(b).filter(|(x, y)| x != y).count();
+    mismatches + a.len().abs_diff(b.len())
+}
+
+/// Levenshtein distance, counting insertions, deletions and substitutions.
+/// Same as `strings::edit_distance`.
+pub fn levenshtein<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    edit_distance(a, b)
+}
+
+/// Damerau-Levenshtein distance, which also counts the transposition of two
+/// adjacent symbols as a single edit, and allows further edits of the
+/// transposed symbols. Uses the algorithm of Lowrance and Wagner, which looks
+/// up the last occurrence of each symbol to find the transposition to
+/// consider at each entry of the table, in O(n * m) time and space.
+///
+/// Example:
+/// ```
+/// use algae::strings::distance::damerau_levenshtein;
+///
+/// assert_eq!(damerau_levenshtein(b"teh", b"the"), 1);
+/// // Transposing "ca" into "ac", then inserting "b" in between.
+/// assert_eq!(damerau_levenshtein(b"ca", b"abc"), 2);
+/// ```
+pub fn damerau_levenshtein<T: Eq + Hash>(a: &[T], b: &[T]) -> usize {
+    let (n, m) = (a.len(), b.len());
+    // The table is shifted by one, with a border larger than any distance
+    // making transpositions from before the strings start unattractive.
+    let infinity = n + m;
+    let mut d = vec![vec![0; m + 2]; n + 2];
+    d[0].fill(infinity);
+    for (i, row) in d.iter_mut().enumerate().skip(1) {
+        row[0] = infinity;
+        row[1] = i - 1;
+    }
+    for (j, entry) in d[1].iter_mut().enumerate().skip(1) {
+        *entry = j - 1;
+    }
+
+    // Last row of `a` where each symbol occurs.
+    let mut last_row: HashMap<&T, usize> = HashMap::new();
+    for i in 1..=n {
+        // Last column of `b` matching `a[i - 1]` in this row.
+        let mut last_col = 0;
+        for j in 1..=m {
+            let i1 = last_row.get(&b[j - 1]).copied().unwrap_or(0);
+            let j1 = last_col;
+            let cost = if a[i - 1] == b[j - 1] {
+                last_col = j;
+                0
+            } else {
+                1
+            };
+            d[i + 1][j + 1] = (d[i][j] + cost)
+                .min(d[i + 1][j] + 1)
+                .min(d[i][j + 1] + 1)
+                .min(d[i1][j1] + (i - i1 - 1) + 1 + (j - j1 - 1));
+        }
+        last_row.insert(&a[i - 1], i);
+    }
+    d[n + 1][m + 1]
+}
+
+/// Jaro similarity: symbols of `a` and `b` match if they are equal and not
+/// further apart than half the length of the longer string, and the
+/// similarity averages the fractions of matched symbols of each string with
+/// the fraction of matches that are in the same order.
+///
+/// Example:
+/// ```
+/// use algae::strings::distance::jaro;
+///
+/// assert!((jaro(b"MARTHA", b"MARHTA") - 0.944).abs() < 1e-3);
+/// assert_eq!(jaro(b"abc", b"xyz"), 0.0);
+/// ```
+pub fn jaro<T: PartialEq>(a: &[T], b: &[T]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut b_matched = vec![false; b.len()];
+    let mut a_matches = Vec::new();
+    for (i, x) in a.iter().enumerate() {
+        let lo = i.saturating_sub(window);
+        let hi = (i + window + 1).min(b.len());
+        if let Some(j) = (lo..hi).find(|&j| !b_matched[j] && b[j] == *x) {
+            b_matched[j] = true;
+            a_matches.push(x);
+        }
+    }
+    let matches = a_matches.len();
+    if matches == 0 {
+        return 0.0;
+    }
+
+    // Half the number of matched symbols out of order.
+    let b_matches = b
+        .iter()
+        .zip(&b_matched)
+        .filter(|(_, &matched)| matched)
+        .map(|(y, _)| y);
+    let transpositions = a_matches
+        .iter()
+        .zip(b_matches)
+        .filter(|(&x, y)| x != *y)
+        .count()
+        / 2;
+    let m = matches as f64;
+    (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64) / m) / 3.0
+}
+
+/// Jaro-Winkler similarity: the Jaro similarity raised in favour of strings
+/// sharing a prefix, of up to 4 symbols, as typos are less common at the
+/// start of words.
+///
+/// Example:
+/// ```
+/// use algae::strings::distance::jaro_winkler;
+///
+/// assert!((jaro_winkler(b"MARTHA", b"MARHTA") - 0.961).abs() < 1e-3);
+/// assert!(jaro_winkler(b"DIXON", b"DICKSONX") > 0.81);
+/// ```
+pub fn jaro_winkler<T: PartialEq>(a: &[T], b: &[T]) -> f64 {
+    const SCALE: f64 = 0.1;
+    let sim = jaro(a, b);
+    let prefix = a.iter().zip(b).take(4).take_while(|(x, y)| x == y).count();
+    sim + prefix as f64 * SCALE * (1.0 - sim)
+}
+
+/// Similarity derived from a distance bounded by the length of the longer
+/// string, such as the edit distances of this module: `1 - d / max(n, m)`,
+/// which is 1 for equal strings.
+#[derive(Debug, Clone, Copy)]
+pub struct Normalized<F>(pub F);
+
+impl<F> Normalized<F> {
+    /// Similarity between `a` and `b`, in `[0, 1]`.
+    pub fn similarity<T>(&self, a: &[T], b: &[T]) -> f64
+    where
+        F: Fn(&[T], &[T]) -> usize,
+    {
+        let longest = a.len().max(b.len());
+        if longest == 0 {
+            return 1.0;
+        }
+        1.0 - (self.0)(a, b) as f64 / longest as f64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::{HashMap, VecDeque};
+
+    fn random_string() -> Vec<u8> {
+        (0..fastrand::usize(..6))
+            .map(|_| fastrand::u8(b'a'..b'd'))
+            .collect()
+    }
+
+    /// Damerau-Levenshtein distance by breadth-first search over the edits,
+    /// of strings over the alphabet "abc".
+    fn edits_bfs(a: &[u8], b: &[u8]) -> usize {
+        let mut dist = HashMap::from([(a.to_vec(), 0)]);
+        let mut queue = VecDeque::from([a.to_vec()]);
+        while let Some(s) = queue.pop_front() {
+            let d = dist[&s];
+            if s == b {
+                return d;
+            }
+            let mut next = Vec::new();
+            for i in 0..=s.len() {
+                for c in b'a'..b'd' {
+                    let mut t = s.clone();
+                    t.insert(i, c);
+                    next.push(t);
+                    if i < s.len() {
+                        let mut t = s.clone();
+                        t[i] = c;
+                        next.push(t);
+                    }
+                }
+                if i < s.len() {
+                    let mut t = s.clone();
+                    t.remove(i);
+                    next.push(t);
+                }
+                if i + 1 < s.len() {
+                    let mut t = s.clone();
+                    t.swap(i, i + 1);
+                    next.push(t);
+                }
+            }
+            for t in next {
+                if t.len() <= a.len().max(b.len()) + 1 && !dist.contains_key(&t) {
+                    dist.insert(t.clone(), d + 1);
+                    queue.push_back(t);
+                }
+            }
+        }
+        unreachable!()
+    }
+
+    #[test]
+    fn damerau_levenshtein_brute_force() {
+        for _ in 0..100 {
+            let (a, b) = (random_string(), random_string());
+            let d = damerau_levenshtein(&a, &b);
+            assert_eq!(d, edits_bfs(&a, &b), "{:?} {:?}", a, b);
+            assert!(d <= levenshtein(&a, &b));
+            assert_eq!(d, damerau_levenshtein(&b, &a));
+        }
+    }
+
+    #[test]
+    fn bounded_by_longest() {
+        let metrics: [Distance<u8>; 3] = [hamming, levenshtein, damerau_levenshtein];
+        let similarities: [Similarity<u8>; 2] = [jaro, jaro_winkler];
+        for _ in 0..200 {
+            let (a, b) = (random_string(), random_string());
+            for d in metrics {
+                let similarity = Normalized(d).similarity(&a, &b);
+                assert!((0.0..=1.0).contains(&similarity));
+                assert_eq!(similarity == 1.0, a == b);
+            }
+            for sim in similarities {
+                let s = sim(&a, &b);
+                assert!((0.0..=1.0).contains(&s));
+                assert!((s - sim(&b, &a)).abs() < 1e-12);
+            }
+            assert!(jaro_winkler(&a, &b) >= jaro(&a, &b));
+        }
+    }
+
+    #[test]
+    fn known_values() {
+        assert_eq!(hamming(b"1011101", b"1001001"), 2);
+        assert_eq!(damerau_levenshtein(b"", b"abc"), 3);
+        assert_eq!(damerau_levenshtein(b"abcdef", b"badcfe"), 3);
+        assert!((jaro(b"DWAYNE", b"DUANE") - 0.822).abs() < 1e-3);
+        assert!((jaro_winkler(b"DWAYNE", b"DUANE") - 0.84).abs() < 1e-3);
+        assert_eq!(jaro(b"", b""), 1.0);
+        assert_eq!(jaro(b"a", b""), 0.0);
+        let word: Vec<char> = "naïve".chars().collect();
+        let other: Vec<char> = "naive".chars().collect();
+        assert_eq!(Normalized(levenshtein).similarity(&word, &other), 0.8);
+    }
+}
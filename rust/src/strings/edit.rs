@@ -0,0 +1,219 @@
+//! Levenshtein edit distance, the minimum number of insertions, deletions and
+//! substitutions of single symbols turning one string into another, computed
+//! by dynamic programming.
+
+/// Single-symbol edit of a string. Positions are indices of the original
+/// string: insertions go before the symbol at `pos`, or at the end if `pos`
+/// is the length of the string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditOp<T> {
+    Insert { pos: usize, symbol: T },
+    Delete { pos: usize },
+    Substitute { pos: usize, symbol: T },
+}
+
+impl<T> EditOp<T> {
+    /// Position in the original string affected by the edit.
+    pub fn pos(&self) -> usize {
+        match *self {
+            EditOp::Insert { pos, .. }
+            | EditOp::Delete { pos }
+            | EditOp::Substitute { pos, .. } => pos,
+        }
+    }
+}
+
+/// Levenshtein distance between `a` and `b`. The classical table has an entry
+/// for each pair of prefixes of `a` and `b`, but each row only depends on the
+/// previous one, so only a single row over the shorter string is kept,
+/// taking O(n * m) time and O(min(n, m)) space.
+///
+/// Example:
+/// ```
+/// use algae::strings::edit_distance;
+///
+/// assert_eq!(edit_distance(b"kitten", b"sitting"), 3);
+/// assert_eq!(edit_distance(b"flaw", b"lawn"), 2);
+/// assert_eq!(edit_distance(b"", b"abc"), 3);
+/// ```
+pub fn edit_distance<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    let (long, short) = if a.len() < b.len() { (b, a) } else { (a, b) };
+    last_row(long, short)[short.len()]
+}
+
+/// Shortest sequence of edits turning `a` into `b`, ordered by position,
+/// which has `edit_distance(a, b)` edits. Uses Hirschberg's divide and conquer
+/// to recover the edits while keeping the space linear: the middle symbol of
+/// `a` is aligned with the position of `b` minimizing the sum of the distances
+/// of the prefixes before it and the suffixes after it, each computed with a
+/// single row, and both halves are solved recursively. This takes O(n * m)
+/// time and O(n + m) space.
+///
+/// Example:
+/// ```
+/// use algae::strings::{apply_edit_script, edit_script, EditOp};
+///
+/// let script = edit_script(b"kitten", b"sitting");
+/// assert_eq!(
+///     script,
+///     [
+///         EditOp::Substitute { pos: 0, symbol: b's' },
+///         EditOp::Substitute { pos: 4, symbol: b'i' },
+///         EditOp::Insert { pos: 6, symbol: b'g' },
+///     ]
+/// );
+/// assert_eq!(apply_edit_script(b"kitten", &script), b"sitting");
+/// ```
+pub fn edit_script<T: PartialEq + Clone>(a: &[T], b: &[T]) -> Vec<EditOp<T>> {
+    let mut ops = Vec::new();
+    hirschberg(a, b, 0, &mut ops);
+    ops
+}
+
+/// Applies the edits of `ops`, ordered by position as given by `edit_script`,
+/// to `a`.
+pub fn apply_edit_script<T: Clone>(a: &[T], ops: &[EditOp<T>]) -> Vec<T> {
+    let mut edited = Vec::with_capacity(a.len());
+    let mut ops = ops.iter().peekable();
+    for i in 0..=a.len() {
+        let mut kept = i < a.len();
+        while let Some(op) = ops.next_if(|op| op.pos() == i) {
+            match op {
+                EditOp::Insert { symbol, .. } => edited.push(symbol.clone()),
+                EditOp::Delete { .. } => kept = false,
+                EditOp::Substitute { symbol, .. } => {
+                    edited.push(symbol.clone());
+                    kept = false;
+                }
+            }
+        }
+        if kept {
+            edited.push(a[i].clone());
+        }
+    }
+    edited
+}
+
+/// Last row of the edit distance table of `a` and `b`: the entry `j` is the
+/// distance between `a` and `b[..j]`.
+fn last_row<T: PartialEq>(a: &[T], b: &[T]) -> Vec<usize> {
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, x) in a.iter().enumerate() {
+        // Entry `j - 1` of the previous row, overwritten in the last step.
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, y) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(x != y);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row
+}
+
+/// Pushes to `ops` the edits turning `a` into `b`, where `a` starts at the
+/// position `offset` of the original string.
+fn hirschberg<T: PartialEq + Clone>(a: &[T], b: &[T], offset: usize, ops: &mut Vec<EditOp<T>>) {
+    let insert_all = |ys: &[T], pos: usize, ops: &mut Vec<EditOp<T>>| {
+        ops.extend(ys.iter().map(|y| EditOp::Insert {
+            pos,
+            symbol: y.clone(),
+        }));
+    };
+
+    match a {
+        [] => insert_all(b, offset, ops),
+        [x] => match (b.iter().position(|y| y == x), b.split_first()) {
+            (Some(j), _) => {
+                insert_all(&b[..j], offset, ops);
+                insert_all(&b[j + 1..], offset + 1, ops);
+            }
+            (None, Some((y, rest))) => {
+                ops.push(EditOp::Substitute {
+                    pos: offset,
+                    symbol: y.clone(),
+                });
+                insert_all(rest, offset + 1, ops);
+            }
+            (None, None) => ops.push(EditOp::Delete { pos: offset }),
+        },
+        _ => {
+            let mid = a.len() / 2;
+            let forward = last_row(&a[..mid], b);
+            let a_rev: Vec<&T> = a[mid..].iter().rev().collect();
+            let b_rev: Vec<&T> = b.iter().rev().collect();
+            // The entry `m - j` is the distance between `a[mid..]` and `b[j..]`.
+            let backward = last_row(&a_rev, &b_rev);
+            let split = (0..=b.len())
+                .min_by_key(|&j| forward[j] + backward[b.len() - j])
+                .unwrap();
+            hirschberg(&a[..mid], &b[..split], offset, ops);
+            hirschberg(&a[mid..], &b[split..], offset + mid, ops);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Edit distance from the full table.
+    fn table_distance(a: &[u8], b: &[u8]) -> usize {
+        let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+        for i in 0..=a.len() {
+            for j in 0..=b.len() {
+                d[i][j] = if i == 0 || j == 0 {
+                    i + j
+                } else {
+                    (d[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]))
+                        .min(d[i - 1][j] + 1)
+                        .min(d[i][j - 1] + 1)
+                };
+            }
+        }
+        d[a.len()][b.len()]
+    }
+
+    fn random_string() -> Vec<u8> {
+        (0..fastrand::usize(..15))
+            .map(|_| fastrand::u8(b'a'..b'd'))
+            .collect()
+    }
+
+    #[test]
+    fn distance_agrees_with_table() {
+        for _ in 0..300 {
+            let (a, b) = (random_string(), random_string());
+            let d = edit_distance(&a, &b);
+            assert_eq!(d, table_distance(&a, &b));
+            assert_eq!(d, edit_distance(&b, &a));
+        }
+    }
+
+    #[test]
+    fn scripts_are_optimal() {
+        for _ in 0..300 {
+            let (a, b) = (random_string(), random_string());
+            let script = edit_script(&a, &b);
+            assert_eq!(script.len(), edit_distance(&a, &b));
+            assert!(script.windows(2).all(|w| w[0].pos() <= w[1].pos()));
+            assert_eq!(apply_edit_script(&a, &script), b);
+        }
+    }
+
+    #[test]
+    fn edge_cases() {
+        assert_eq!(edit_script::<u8>(b"", b""), []);
+        assert_eq!(
+            edit_script(b"ab", b""),
+            [EditOp::Delete { pos: 0 }, EditOp::Delete { pos: 1 }]
+        );
+        let words: Vec<&str> = "the quick brown fox".split(' ').collect();
+        let other: Vec<&str> = "the brown fox jumps".split(' ').collect();
+        assert_eq!(edit_distance(&words, &other), 2);
+        assert_eq!(
+            apply_edit_script(&words, &edit_script(&words, &other)),
+            other
+        );
+    }
+}
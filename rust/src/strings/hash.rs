@@ -0,0 +1,140 @@
+//! Polynomial rolling hash, for fingerprinting all windows of a fixed length
+//! of a sequence in O(1) time per window.
+
+/// The Mersenne prime `2^61 - 1`, modulus of the hashes.
+pub const HASH_MODULUS: u64 = (1 << 61) - 1;
+
+/// Polynomial hash of windows of `window` symbols: the window `x_1, ...,
+/// x_m` hashes to `x_1 * b^(m - 1) + ... + x_m` modulo `HASH_MODULUS`, for
+/// a base `b`. Moving the window one symbol forward only has to remove the
+/// term of the outgoing symbol and append the incoming one, which is what
+/// `roll` does in O(1) time.
+///
+/// For a random base, two distinct windows collide with probability at most
+/// `m / HASH_MODULUS`, so equal hashes should still be confirmed by comparing
+/// the windows when exactness matters.
+///
+/// Example:
+/// ```
+/// use algae::strings::RollingHash;
+///
+/// let text = b"abcabc";
+/// let hasher = RollingHash::new(3);
+/// let mut h = hasher.hash(&text[..3]);
+/// for i in 1..=3 {
+///     h = hasher.roll(h, text[i - 1], text[i + 2]);
+///     assert_eq!(h, hasher.hash(&text[i..i + 3]));
+/// }
+/// assert_eq!(hasher.hash(b"abc"), hasher.hash(&text[3..]));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RollingHash {
+    base: u64,
+    window: usize,
+    /// Weight `base^(window - 1)` of the oldest symbol of a window.
+    high: u64,
+}
+
+impl RollingHash {
+    /// Hasher of windows of `window` symbols with a random base.
+    pub fn new(window: usize) -> Self {
+        Self::with_base(window, fastrand::u64(256..HASH_MODULUS - 1))
+    }
+
+    /// Hasher of windows of `window` symbols with the given base, reduced
+    /// modulo `HASH_MODULUS`. A fixed base gives reproducible hashes, but an
+    /// adversary knowing it may craft colliding inputs.
+    pub fn with_base(window: usize, base: u64) -> Self {
+        let base = base % HASH_MODULUS;
+        let mut high = 1;
+        for _ in 1..window {
+            high = mul_mod(high, base);
+        }
+        Self { base, window, high }
+    }
+
+    /// Number of symbols in a window.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Polynomial hash of `xs`, which may have any length. For slices of
+    /// `window()` symbols, this is the hash updated by `roll`.
+    pub fn hash<T: Copy + Into<u64>>(&self, xs: &[T]) -> u64 {
+        xs.iter()
+            .fold(0, |h, &x| add_mod(mul_mod(h, self.base), symbol(x)))
+    }
+
+    /// Hash of the window following the one hashing to `hash`, which drops
+    /// the symbol `outgoing` at its start and appends `incoming` to its end.
+    pub fn roll<T: Copy + Into<u64>>(&self, hash: u64, outgoing: T, incoming: T) -> u64 {
+        let dropped = HASH_MODULUS - mul_mod(symbol(outgoing), self.high);
+        add_mod(mul_mod(add_mod(hash, dropped), self.base), symbol(incoming))
+    }
+}
+
+/// Value of the symbol `x` modulo `HASH_MODULUS`.
+fn symbol<T: Into<u64>>(x: T) -> u64 {
+    x.into() % HASH_MODULUS
+}
+
+fn add_mod(a: u64, b: u64) -> u64 {
+    let sum = a + b;
+    if sum >= HASH_MODULUS {
+        sum - HASH_MODULUS
+    } else {
+        sum
+    }
+}
+
+/// Product modulo the Mersenne prime `2^61 - 1`, where reducing amounts to
+/// adding the high bits of the product to its low 61 bits.
+fn mul_mod(a: u64, b: u64) -> u64 {
+    let product = a as u128 * b as u128;
+    let folded = (product >> 61) as u64 + (product as u64 & HASH_MODULUS);
+    if folded >= HASH_MODULUS {
+        folded - HASH_MODULUS
+    } else {
+        folded
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn modular_arithmetic() {
+        for _ in 0..1_000 {
+            let a = fastrand::u64(..HASH_MODULUS);
+            let b = fastrand::u64(..HASH_MODULUS);
+            let m = HASH_MODULUS as u128;
+            assert_eq!(mul_mod(a, b) as u128, a as u128 * b as u128 % m);
+            assert_eq!(add_mod(a, b) as u128, (a as u128 + b as u128) % m);
+        }
+        assert_eq!(mul_mod(HASH_MODULUS - 1, HASH_MODULUS - 1), 1);
+    }
+
+    #[test]
+    fn rolling_agrees_with_hashing() {
+        for window in 1..10 {
+            let hasher = RollingHash::new(window);
+            assert_eq!(hasher.window(), window);
+            let xs: Vec<u32> = (0..50).map(|_| fastrand::u32(..)).collect();
+            let mut h = hasher.hash(&xs[..window]);
+            for i in 1..=xs.len() - window {
+                h = hasher.roll(h, xs[i - 1], xs[i + window - 1]);
+                assert_eq!(h, hasher.hash(&xs[i..i + window]));
+            }
+        }
+    }
+
+    #[test]
+    fn fixed_base() {
+        let hasher = RollingHash::with_base(2, 10);
+        assert_eq!(hasher.hash(&[1u8, 2, 3]), 123);
+        assert_eq!(hasher.roll(12, 1u8, 3), 23);
+        assert_eq!(hasher.hash::<u8>(&[]), 0);
+        assert_eq!(hasher.hash(&['a']), 'a' as u64);
+    }
+}
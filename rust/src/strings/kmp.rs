@@ -0,0 +1,211 @@
+//! The Knuth-Morris-Pratt algorithm, which finds the occurrences of a pattern
+//! in O(n + m) time by never moving backwards in the text.
+use crate::strings::Finder;
+
+/// Failure function of `pattern`: the entry `i` is the length of the longest
+/// proper prefix of `pattern[..=i]` which is also a suffix of it (a *border*).
+/// After a mismatch following a partial match of length `i + 1`, the search
+/// can resume as if only the border of length `failure[i]` had matched.
+/// Computed in O(m).
+///
+/// Example:
+/// ```
+/// use algae::strings::failure_function;
+///
+/// assert_eq!(failure_function(b"abacaba"), [0, 0, 1, 0, 1, 2, 3]);
+/// assert_eq!(failure_function(b"aaaa"), [0, 1, 2, 3]);
+/// ```
+pub fn failure_function<T: PartialEq>(pattern: &[T]) -> Vec<usize> {
+    let mut failure = vec![0; pattern.len()];
+    let mut k = 0;
+    for i in 1..pattern.len() {
+        // Shrink the border of `pattern[..i]` until it can be extended.
+        while k > 0 && pattern[i] != pattern[k] {
+            k = failure[k - 1];
+        }
+        if pattern[i] == pattern[k] {
+            k += 1;
+        }
+        failure[i] = k;
+    }
+    failure
+}
+
+/// Index of the first occurrence of `pattern` in `text`, if any. An empty
+/// pattern occurs at index 0.
+///
+/// Example:
+/// ```
+/// use algae::strings::kmp_search;
+///
+/// assert_eq!(kmp_search(b"abracadabra", b"cad"), Some(4));
+/// assert_eq!(kmp_search(b"abracadabra", b"dab"), Some(6));
+/// assert_eq!(kmp_search(b"abracadabra", b"bad"), None);
+/// ```
+pub fn kmp_search<T: PartialEq>(text: &[T], pattern: &[T]) -> Option<usize> {
+    KmpMatches::new(text, pattern).next()
+}
+
+/// Indices of all occurrences of `pattern` in `text`, including overlapping
+/// ones, in increasing order. An empty pattern occurs at every index from 0
+/// to `text.len()`.
+///
+/// Example:
+/// ```
+/// use algae::strings::kmp_search_all;
+///
+/// assert_eq!(kmp_search_all(b"aaaa", b"aa"), [0, 1, 2]);
+/// // Byte positions in a UTF-8 string.
+/// let text = "¿qué tal? ¿qué?";
+/// assert_eq!(kmp_search_all(text.as_bytes(), "qué".as_bytes()), [2, 14]);
+/// assert_eq!(&text[14..], "qué?");
+/// ```
+pub fn kmp_search_all<T: PartialEq>(text: &[T], pattern: &[T]) -> Vec<usize> {
+    KmpMatches::new(text, pattern).collect()
+}
+
+/// Iterator over the occurrences of a pattern in a text.
+struct KmpMatches<'a, T> {
+    text: &'a [T],
+    pattern: &'a [T],
+    failure: Vec<usize>,
+    /// Next index of the text to be read.
+    i: usize,
+    /// Length of the prefix of the pattern matched by the text before `i`.
+    matched: usize,
+    /// Whether an empty pattern still has matches to report.
+    empty_pending: bool,
+}
+
+impl<'a, T: PartialEq> KmpMatches<'a, T> {
+    fn new(text: &'a [T], pattern: &'a [T]) -> Self {
+        Self {
+            text,
+            pattern,
+            failure: failure_function(pattern),
+            i: 0,
+            matched: 0,
+            empty_pending: true,
+        }
+    }
+}
+
+impl<T: PartialEq> Iterator for KmpMatches<'_, T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let m = self.pattern.len();
+        if m == 0 {
+            // Every position, including the end of the text, is a match.
+            if !self.empty_pending {
+                return None;
+            }
+            let pos = self.i;
+            self.i += 1;
+            self.empty_pending = pos < self.text.len();
+            return Some(pos);
+        }
+
+        while self.i < self.text.len() {
+            let x = &self.text[self.i];
+            while self.matched > 0 && *x != self.pattern[self.matched] {
+                self.matched = self.failure[self.matched - 1];
+            }
+            if *x == self.pattern[self.matched] {
+                self.matched += 1;
+            }
+            self.i += 1;
+            if self.matched == m {
+                // Continue from the longest border to find overlapping matches.
+                self.matched = self.failure[m - 1];
+                return Some(self.i - m);
+            }
+        }
+        None
+    }
+}
+
+/// Finder implemented by the Knuth-Morris-Pratt algorithm, whose matches are
+/// found lazily.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KmpFinder;
+
+impl Finder for KmpFinder {
+    fn find_iter<'a>(text: &'a [u8], pattern: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        KmpMatches::new(text, pattern)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strings;
+
+    #[test]
+    fn kmp_finder() {
+        strings::check_finder::<KmpFinder>();
+    }
+
+    #[test]
+    fn failure_borders() {
+        assert!(failure_function::<u8>(&[]).is_empty());
+        for _ in 0..100 {
+            let pattern: Vec<u8> = (0..fastrand::usize(..20))
+                .map(|_| fastrand::u8(b'a'..b'c'))
+                .collect();
+            for (i, &k) in failure_function(&pattern).iter().enumerate() {
+                let s = &pattern[..=i];
+                let longest = (0..=i).rev().find(|&b| s.ends_with(&s[..b])).unwrap();
+                assert_eq!(k, longest);
+            }
+        }
+    }
+
+    #[test]
+    fn overlapping_matches() {
+        assert_eq!(kmp_search_all(b"abababa", b"aba"), [0, 2, 4]);
+        assert_eq!(kmp_search_all(b"aaaaa", b"aaa"), [0, 1, 2]);
+        assert_eq!(kmp_search_all(b"abc", b""), [0, 1, 2, 3]);
+        assert_eq!(kmp_search_all(b"", b""), [0]);
+        assert_eq!(kmp_search_all(b"ab", b"abc"), []);
+        assert_eq!(kmp_search(b"", b"a"), None);
+        assert_eq!(kmp_search(b"a", b""), Some(0));
+    }
+
+    #[test]
+    fn agrees_with_naive_search() {
+        for _ in 0..300 {
+            let text: Vec<u8> = (0..fastrand::usize(..60))
+                .map(|_| fastrand::u8(b'a'..b'd'))
+                .collect();
+            let pattern: Vec<u8> = (0..fastrand::usize(..5))
+                .map(|_| fastrand::u8(b'a'..b'd'))
+                .collect();
+            let expected = strings::naive_search_all(&text, &pattern);
+            assert_eq!(kmp_search_all(&text, &pattern), expected);
+            assert_eq!(kmp_search(&text, &pattern), expected.first().copied());
+        }
+    }
+
+    #[test]
+    fn unicode_text() {
+        let text = "日本語のテキスト、日本語の文字";
+        let pattern = "日本語";
+        let matches = kmp_search_all(text.as_bytes(), pattern.as_bytes());
+        assert_eq!(
+            matches,
+            text.match_indices(pattern)
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>()
+        );
+        for i in matches {
+            assert!(text.is_char_boundary(i));
+            assert_eq!(&text[i..i + pattern.len()], pattern);
+        }
+
+        // Searching characters rather than bytes gives character positions.
+        let chars: Vec<char> = text.chars().collect();
+        let pattern: Vec<char> = pattern.chars().collect();
+        assert_eq!(kmp_search_all(&chars, &pattern), [0, 9]);
+    }
+}
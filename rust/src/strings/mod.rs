@@ -0,0 +1,103 @@
+//! Algorithms on strings. Texts and patterns are slices of symbols, usually
+//! the bytes of a `str`, and match positions are indices into the slice.
+//! Since UTF-8 is self-synchronizing, a match of a valid UTF-8 pattern in a
+//! valid UTF-8 text always starts at a character boundary, so searching the
+//! bytes of strings is Unicode-safe.
+
+pub mod compress;
+
+mod naive;
+pub use naive::*;
+
+mod kmp;
+pub use kmp::*;
+
+mod boyer_moore;
+pub use boyer_moore::*;
+
+mod hash;
+pub use hash::*;
+
+mod rabin_karp;
+pub use rabin_karp::*;
+
+mod aho_corasick;
+pub use aho_corasick::*;
+
+mod z;
+pub use z::*;
+
+mod edit;
+pub use edit::*;
+
+mod trie;
+pub use trie::*;
+
+mod suffix;
+pub use suffix::*;
+
+pub mod regex;
+
+pub mod distance;
+
+pub mod chunking;
+
+/// A single-pattern substring search algorithm over bytes, such as
+/// `KmpFinder` or `BoyerMooreFinder`. Implementors are unit structs, so that
+/// algorithms can be chosen by a type parameter and tested together by
+/// `check_finder`.
+pub trait Finder {
+    /// Iterator over the indices of all occurrences of `pattern` in `text`,
+    /// including overlapping ones, in increasing order. An empty pattern
+    /// occurs at every index from 0 to `text.len()`.
+    fn find_iter<'a>(text: &'a [u8], pattern: &'a [u8]) -> impl Iterator<Item = usize> + 'a;
+
+    /// Index of the first occurrence of `pattern` in `text`, if any.
+    fn find(text: &[u8], pattern: &[u8]) -> Option<usize> {
+        Self::find_iter(text, pattern).next()
+    }
+
+    /// Indices of all occurrences of `pattern` in `text`, in increasing order.
+    fn find_all(text: &[u8], pattern: &[u8]) -> Vec<usize> {
+        Self::find_iter(text, pattern).collect()
+    }
+}
+
+/// Checks that the finder `F` reports exactly the occurrences found by
+/// comparing `pattern` with every window of the text, on edge cases and on
+/// random texts over small alphabets, where matches overlap often.
+pub fn check_finder<F: Finder>() {
+    // Edge cases: empty texts and patterns, and patterns longer than the text.
+    assert_eq!(F::find_all(b"", b""), [0]);
+    assert_eq!(F::find_all(b"abc", b""), [0, 1, 2, 3]);
+    assert_eq!(F::find(b"", b"a"), None);
+    assert_eq!(F::find(b"ab", b"abc"), None);
+    assert_eq!(F::find_all(b"aaaaa", b"aaa"), [0, 1, 2]);
+    assert_eq!(F::find_all(b"abababa", b"aba"), [0, 2, 4]);
+
+    for alphabet in [2, 4, 26] {
+        for _ in 0..200 {
+            let random =
+                |len| -> Vec<u8> { (0..len).map(|_| b'a' + fastrand::u8(..alphabet)).collect() };
+            let text = random(fastrand::usize(..80));
+            let pattern = if text.is_empty() || fastrand::bool() {
+                random(fastrand::usize(..6))
+            } else {
+                // A substring of the text, so that there is at least one match.
+                let start = fastrand::usize(..text.len());
+                text[start..fastrand::usize(start..=text.len())].to_vec()
+            };
+            let expected: Vec<usize> = (0..=text.len())
+                .filter(|&i| text[i..].starts_with(&pattern))
+                .collect();
+            assert_eq!(
+                F::find_all(&text, &pattern),
+                expected,
+                "{:?} in {:?}",
+                pattern,
+                text
+            );
+            assert_eq!(F::find(&text, &pattern), expected.first().copied());
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Naive substring search, comparing the pattern with every window of the
+//! text. Takes O(n * m) time in the worst case, but is fast in practice for
+//! short patterns, and serves as the reference for the other algorithms.
+use crate::strings::Finder;
+
+/// Index of the first occurrence of `pattern` in `text`, if any. An empty
+/// pattern occurs at index 0.
+///
+/// Example:
+/// ```
+/// use algae::strings::naive_search;
+///
+/// assert_eq!(naive_search(b"abracadabra", b"cad"), Some(4));
+/// assert_eq!(naive_search(b"abracadabra", b"bad"), None);
+/// ```
+pub fn naive_search<T: PartialEq>(text: &[T], pattern: &[T]) -> Option<usize> {
+    naive_matches(text, pattern).next()
+}
+
+/// Indices of all occurrences of `pattern` in `text`, including overlapping
+/// ones, in increasing order. An empty pattern occurs at every index from 0
+/// to `text.len()`.
+///
+/// Example:
+/// ```
+/// use algae::strings::naive_search_all;
+///
+/// assert_eq!(naive_search_all(b"abababa", b"aba"), [0, 2, 4]);
+/// ```
+pub fn naive_search_all<T: PartialEq>(text: &[T], pattern: &[T]) -> Vec<usize> {
+    naive_matches(text, pattern).collect()
+}
+
+fn naive_matches<'a, T: PartialEq>(
+    text: &'a [T],
+    pattern: &'a [T],
+) -> impl Iterator<Item = usize> + 'a {
+    let windows = (text.len() + 1).saturating_sub(pattern.len());
+    (0..windows).filter(move |&i| text[i..i + pattern.len()] == *pattern)
+}
+
+/// Finder implemented by the naive search.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NaiveFinder;
+
+impl Finder for NaiveFinder {
+    fn find_iter<'a>(text: &'a [u8], pattern: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        naive_matches(text, pattern)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strings;
+
+    #[test]
+    fn naive_finder() {
+        strings::check_finder::<NaiveFinder>();
+        let chars: Vec<char> = "ñandú ñu".chars().collect();
+        assert_eq!(naive_search_all(&chars, &['ñ']), [0, 6]);
+    }
+}
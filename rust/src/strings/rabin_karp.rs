@@ -0,0 +1,212 @@
+//! The Rabin-Karp algorithm, which compares the rolling hash of each window
+//! of the text with the hashes of the patterns, and only compares the symbols
+//! of windows whose hash matches.
+use crate::strings::{Finder, RollingHash};
+use std::collections::HashMap;
+
+/// Index of the first occurrence of `pattern` in `text`, if any. An empty
+/// pattern occurs at index 0. Takes O(n + m) expected time.
+///
+/// Example:
+/// ```
+/// use algae::strings::rabin_karp_search;
+///
+/// assert_eq!(rabin_karp_search(b"abracadabra", b"cad"), Some(4));
+/// assert_eq!(rabin_karp_search(b"abracadabra", b"bad"), None);
+/// ```
+pub fn rabin_karp_search<T: Copy + Into<u64> + PartialEq>(
+    text: &[T],
+    pattern: &[T],
+) -> Option<usize> {
+    let hasher = RollingHash::new(pattern.len());
+    let mut matches = None;
+    rabin_karp_with(&hasher, text, &[pattern], |_, pos| {
+        matches = Some(pos);
+        false
+    });
+    matches
+}
+
+/// Indices of all occurrences of `pattern` in `text`, including overlapping
+/// ones, in increasing order. An empty pattern occurs at every index from 0
+/// to `text.len()`.
+///
+/// Example:
+/// ```
+/// use algae::strings::rabin_karp_search_all;
+///
+/// assert_eq!(rabin_karp_search_all(b"abababa", b"aba"), [0, 2, 4]);
+/// ```
+pub fn rabin_karp_search_all<T: Copy + Into<u64> + PartialEq>(
+    text: &[T],
+    pattern: &[T],
+) -> Vec<usize> {
+    let hasher = RollingHash::new(pattern.len());
+    let mut matches = Vec::new();
+    rabin_karp_with(&hasher, text, &[pattern], |_, pos| {
+        matches.push(pos);
+        true
+    });
+    matches
+}
+
+/// Finds the occurrences of every pattern of `patterns` in `text` in a single
+/// pass, returning the pairs `(pattern_index, position)` ordered by position,
+/// then by pattern index. The hashes of the patterns are kept in a hash table,
+/// so that each window of the text is checked against all patterns in O(1)
+/// expected time. Repeated patterns are all reported.
+///
+/// Panics if the patterns don't all have the same length.
+///
+/// Example:
+/// ```
+/// use algae::strings::rabin_karp_multi;
+///
+/// let text = b"the cat sat on the mat";
+/// let patterns: [&[u8]; 3] = [b"cat", b"mat", b"dog"];
+/// assert_eq!(rabin_karp_multi(text, &patterns), [(0, 4), (1, 19)]);
+/// ```
+pub fn rabin_karp_multi<T: Copy + Into<u64> + PartialEq>(
+    text: &[T],
+    patterns: &[&[T]],
+) -> Vec<(usize, usize)> {
+    let Some(first) = patterns.first() else {
+        return Vec::new();
+    };
+    assert!(
+        patterns.iter().all(|p| p.len() == first.len()),
+        "Rabin-Karp patterns must have the same length"
+    );
+    let hasher = RollingHash::new(first.len());
+    let mut matches = Vec::new();
+    rabin_karp_with(&hasher, text, patterns, |k, pos| {
+        matches.push((k, pos));
+        true
+    });
+    matches
+}
+
+/// Reports to `on_match` each occurrence of the patterns, all of length
+/// `hasher.window()`, as `(pattern_index, position)`, stopping when it
+/// returns `false`. Windows whose hash equals that of a pattern are compared
+/// with it symbol by symbol, so hash collisions are never reported.
+fn rabin_karp_with<T, F>(hasher: &RollingHash, text: &[T], patterns: &[&[T]], mut on_match: F)
+where
+    T: Copy + Into<u64> + PartialEq,
+    F: FnMut(usize, usize) -> bool,
+{
+    let m = hasher.window();
+    if m > text.len() {
+        return;
+    }
+    let mut by_hash: HashMap<u64, Vec<usize>> = HashMap::with_capacity(patterns.len());
+    for (k, p) in patterns.iter().enumerate() {
+        by_hash.entry(hasher.hash(p)).or_default().push(k);
+    }
+
+    let mut h = hasher.hash(&text[..m]);
+    for pos in 0..=text.len() - m {
+        if pos > 0 && m > 0 {
+            h = hasher.roll(h, text[pos - 1], text[pos + m - 1]);
+        }
+        let window = &text[pos..pos + m];
+        for &k in by_hash.get(&h).into_iter().flatten() {
+            if patterns[k] == window && !on_match(k, pos) {
+                return;
+            }
+        }
+    }
+}
+
+/// Finder implemented by the Rabin-Karp algorithm. The matches are all found
+/// by `find_iter` before being iterated.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RabinKarpFinder;
+
+impl Finder for RabinKarpFinder {
+    fn find_iter<'a>(text: &'a [u8], pattern: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        rabin_karp_search_all(text, pattern).into_iter()
+    }
+
+    fn find(text: &[u8], pattern: &[u8]) -> Option<usize> {
+        rabin_karp_search(text, pattern)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strings;
+    use crate::strings::kmp_search_all;
+
+    fn random_text(len: usize) -> Vec<u8> {
+        (0..len).map(|_| fastrand::u8(b'a'..b'd')).collect()
+    }
+
+    #[test]
+    fn rabin_karp_finder() {
+        strings::check_finder::<RabinKarpFinder>();
+    }
+
+    #[test]
+    fn agrees_with_kmp() {
+        for _ in 0..300 {
+            let text = random_text(fastrand::usize(..60));
+            let pattern = random_text(fastrand::usize(..5));
+            let expected = kmp_search_all(&text, &pattern);
+            assert_eq!(rabin_karp_search_all(&text, &pattern), expected);
+            assert_eq!(
+                rabin_karp_search(&text, &pattern),
+                expected.first().copied()
+            );
+        }
+        assert_eq!(rabin_karp_search_all(b"ab", b""), [0, 1, 2]);
+    }
+
+    #[test]
+    fn multiple_patterns() {
+        for _ in 0..100 {
+            let text = random_text(fastrand::usize(..80));
+            let m = fastrand::usize(1..4);
+            let patterns: Vec<Vec<u8>> =
+                (0..fastrand::usize(..6)).map(|_| random_text(m)).collect();
+            let slices: Vec<&[u8]> = patterns.iter().map(|p| p.as_slice()).collect();
+
+            let mut expected: Vec<(usize, usize)> = slices
+                .iter()
+                .enumerate()
+                .flat_map(|(k, p)| {
+                    kmp_search_all(&text, p)
+                        .into_iter()
+                        .map(move |pos| (k, pos))
+                })
+                .collect();
+            expected.sort_by_key(|&(k, pos)| (pos, k));
+            assert_eq!(rabin_karp_multi(&text, &slices), expected);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn patterns_of_different_lengths() {
+        let patterns: [&[u8]; 2] = [b"ab", b"abc"];
+        rabin_karp_multi(b"abcabc", &patterns);
+    }
+
+    #[test]
+    fn collisions_are_verified() {
+        // With base 1 the hash is the sum of the symbols, so every anagram of
+        // a pattern collides with it.
+        let hasher = RollingHash::with_base(3, 1);
+        assert_eq!(hasher.hash(b"abc"), hasher.hash(b"cba"));
+
+        let text = b"cbaabcbcacab";
+        let patterns: [&[u8]; 2] = [b"abc", b"cab"];
+        let mut matches = Vec::new();
+        rabin_karp_with(&hasher, text, &patterns, |k, pos| {
+            matches.push((k, pos));
+            true
+        });
+        assert_eq!(matches, [(0, 3), (1, 9)]);
+    }
+}
@@ -0,0 +1,520 @@
+//! Regular expressions compiled to a nondeterministic finite automaton (NFA)
+//! with Thompson's construction, and matched by simulating all the states the
+//! automaton may be in at once. As opposed to backtracking engines, matching
+//! takes O(n * m) time for a text of length `n` and a pattern of length `m`,
+//! whatever the pattern.
+//!
+//! The supported syntax is:
+//! * Characters, matching themselves, and `.`, matching any character.
+//! * Character classes such as `[abc]`, `[a-z0-9_]` and `[^aeiou]`.
+//! * The postfix operators `*` (zero or more), `+` (one or more) and `?`
+//!   (zero or one).
+//! * Concatenation, alternation with `|`, and grouping with parentheses.
+//! * Escapes with `\` of the characters with a special meaning.
+use std::{fmt, ops::Range};
+
+/// Error in the syntax of a regular expression, at the byte offset `pos` of
+/// the pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub pos: usize,
+    pub message: &'static str,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.pos)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Set of characters matched by a single step of the automaton.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CharSet {
+    Char(char),
+    Any,
+    Class {
+        ranges: Vec<(char, char)>,
+        negated: bool,
+    },
+}
+
+impl CharSet {
+    fn contains(&self, c: char) -> bool {
+        match self {
+            CharSet::Char(x) => *x == c,
+            CharSet::Any => true,
+            CharSet::Class { ranges, negated } => {
+                ranges.iter().any(|&(lo, hi)| (lo..=hi).contains(&c)) != *negated
+            }
+        }
+    }
+}
+
+/// Abstract syntax tree of a regular expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Ast {
+    Empty,
+    Set(CharSet),
+    Concat(Vec<Ast>),
+    Alt(Box<Ast>, Box<Ast>),
+    Star(Box<Ast>),
+    Plus(Box<Ast>),
+    Quest(Box<Ast>),
+}
+
+/// Recursive descent parser of the grammar
+/// ```text
+/// alt    := concat ('|' concat)*
+/// concat := repeat*
+/// repeat := atom ('*' | '+' | '?')*
+/// atom   := '(' alt ')' | '[' class ']' | '.' | '\' char | char
+/// ```
+struct Parser<'p> {
+    pattern: &'p str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.pattern[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn error(&self, message: &'static str) -> ParseError {
+        ParseError {
+            pos: self.pos,
+            message,
+        }
+    }
+
+    fn alt(&mut self) -> Result<Ast, ParseError> {
+        let mut ast = self.concat()?;
+        while self.peek() == Some('|') {
+            self.bump();
+            ast = Ast::Alt(Box::new(ast), Box::new(self.concat()?));
+        }
+        Ok(ast)
+    }
+
+    fn concat(&mut self) -> Result<Ast, ParseError> {
+        let mut items = Vec::new();
+        while !matches!(self.peek(), None | Some('|') | Some(')')) {
+            items.push(self.repeat()?);
+        }
+        Ok(match items.len() {
+            0 => Ast::Empty,
+            1 => items.pop().unwrap(),
+            _ => Ast::Concat(items),
+        })
+    }
+
+    fn repeat(&mut self) -> Result<Ast, ParseError> {
+        let mut ast = self.atom()?;
+        loop {
+            ast = match self.peek() {
+                Some('*') => Ast::Star(Box::new(ast)),
+                Some('+') => Ast::Plus(Box::new(ast)),
+                Some('?') => Ast::Quest(Box::new(ast)),
+                _ => return Ok(ast),
+            };
+            self.bump();
+        }
+    }
+
+    fn atom(&mut self) -> Result<Ast, ParseError> {
+        let start = self.pos;
+        match self.bump() {
+            Some('(') => {
+                let ast = self.alt()?;
+                if self.bump() != Some(')') {
+                    return Err(ParseError {
+                        pos: start,
+                        message: "unclosed group",
+                    });
+                }
+                Ok(ast)
+            }
+            Some('[') => self.class(start),
+            Some('.') => Ok(Ast::Set(CharSet::Any)),
+            Some('\\') => self.escaped().map(|c| Ast::Set(CharSet::Char(c))),
+            Some('*' | '+' | '?') => Err(ParseError {
+                pos: start,
+                message: "nothing to repeat",
+            }),
+            Some(c) => Ok(Ast::Set(CharSet::Char(c))),
+            None => Err(self.error("unexpected end of pattern")),
+        }
+    }
+
+    fn escaped(&mut self) -> Result<char, ParseError> {
+        self.bump().ok_or_else(|| self.error("trailing backslash"))
+    }
+
+    /// Parses a class after its opening bracket, which is at `start`.
+    fn class(&mut self, start: usize) -> Result<Ast, ParseError> {
+        let unclosed = ParseError {
+            pos: start,
+            message: "unclosed character class",
+        };
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.bump();
+        }
+        let mut ranges = Vec::new();
+        loop {
+            let lo = match self.bump() {
+                None => return Err(unclosed),
+                Some(']') if ranges.is_empty() => return Err(self.error("empty character class")),
+                Some(']') => break,
+                Some('\\') => self.escaped()?,
+                Some(c) => c,
+            };
+            // A `-` right before the closing bracket is a literal.
+            let mut lookahead = self.pattern[self.pos..].chars();
+            if lookahead.next() == Some('-') && !matches!(lookahead.next(), None | Some(']')) {
+                self.bump();
+                let hi = match self.bump() {
+                    Some('\\') => self.escaped()?,
+                    Some(c) => c,
+                    None => return Err(unclosed),
+                };
+                if hi < lo {
+                    return Err(self.error("invalid class range"));
+                }
+                ranges.push((lo, hi));
+            } else {
+                ranges.push((lo, lo));
+            }
+        }
+        Ok(Ast::Set(CharSet::Class { ranges, negated }))
+    }
+}
+
+/// State of the automaton.
+#[derive(Debug, Clone)]
+enum State {
+    /// Reads a character of the set and moves to the next state.
+    Step(CharSet, usize),
+    /// Moves to both states without reading anything.
+    Split(usize, usize),
+    Match,
+}
+
+/// Regular expression compiled to an NFA.
+///
+/// Example:
+/// ```
+/// use algae::strings::regex::Regex;
+///
+/// let re = Regex::new("(a|b)*abb").unwrap();
+/// assert!(re.is_full_match("babaabb"));
+/// assert!(!re.is_full_match("babaab"));
+///
+/// let date = Regex::new("[0-9]+-[0-9][0-9]?").unwrap();
+/// assert_eq!(date.find("due 2024-7 or 2024-12"), Some(4..10));
+/// assert!(Regex::new("a(b").is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Regex {
+    states: Vec<State>,
+    start: usize,
+}
+
+impl Regex {
+    /// Parses `pattern` and compiles it into an automaton with O(m) states.
+    pub fn new(pattern: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser { pattern, pos: 0 };
+        let ast = parser.alt()?;
+        if parser.pos < pattern.len() {
+            return Err(parser.error("unmatched closing parenthesis"));
+        }
+        let mut states = vec![State::Match];
+        let start = compile(&ast, 0, &mut states);
+        Ok(Self { states, start })
+    }
+
+    /// Whether the whole of `text` matches.
+    pub fn is_full_match(&self, text: &str) -> bool {
+        self.run(text, true).is_some_and(|m| m.end == text.len())
+    }
+
+    /// Whether some substring of `text` matches.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.find(text).is_some()
+    }
+
+    /// Byte range of the leftmost match in `text`, taking the longest one
+    /// among those starting at the same position.
+    pub fn find(&self, text: &str) -> Option<Range<usize>> {
+        self.run(text, false)
+    }
+
+    /// Simulates the automaton on `text`, keeping the set of states it may be
+    /// in, each paired with the position where its match started. States
+    /// reached from an earlier start take priority, so that the leftmost
+    /// match wins, and the simulation goes on while the threads of that
+    /// match are alive to find its longest extension. If `anchored`, matches
+    /// must start at the beginning of the text.
+    fn run(&self, text: &str, anchored: bool) -> Option<Range<usize>> {
+        let mut marks = vec![usize::MAX; self.states.len()];
+        let mut generation = 0;
+        let mut current: Vec<(usize, usize)> = Vec::new();
+        let mut next = Vec::new();
+        let mut best: Option<Range<usize>> = None;
+
+        let positions = text.char_indices().map(|(i, c)| (i, Some(c)));
+        for (i, c) in positions.chain([(text.len(), None)]) {
+            if best.is_none() && (!anchored || i == 0) {
+                self.add(&mut current, &mut marks, generation, self.start, i);
+            }
+            // The first matching thread has the earliest start.
+            if let Some(&(_, start)) = current
+                .iter()
+                .find(|&&(s, _)| matches!(self.states[s], State::Match))
+            {
+                if best.as_ref().is_none_or(|m| start <= m.start) {
+                    best = Some(start..i);
+                }
+            }
+
+            let Some(c) = c else { break };
+            generation += 1;
+            next.clear();
+            for &(s, start) in &current {
+                if best.as_ref().is_some_and(|m| start > m.start) {
+                    continue;
+                }
+                if let State::Step(set, to) = &self.states[s] {
+                    if set.contains(c) {
+                        self.add(&mut next, &mut marks, generation, *to, start);
+                    }
+                }
+            }
+            std::mem::swap(&mut current, &mut next);
+            if current.is_empty() && (best.is_some() || anchored) {
+                break;
+            }
+        }
+        best
+    }
+
+    /// Adds to `list` the states reachable from `s` without reading any
+    /// character, skipping those already marked with `generation`.
+    fn add(
+        &self,
+        list: &mut Vec<(usize, usize)>,
+        marks: &mut [usize],
+        generation: usize,
+        s: usize,
+        start: usize,
+    ) {
+        let mut stack = vec![s];
+        while let Some(s) = stack.pop() {
+            if marks[s] == generation {
+                continue;
+            }
+            marks[s] = generation;
+            match self.states[s] {
+                State::Split(a, b) => {
+                    stack.push(b);
+                    stack.push(a);
+                }
+                _ => list.push((s, start)),
+            }
+        }
+    }
+}
+
+/// Thompson's construction: pushes the states of `ast` to `states`, leading
+/// to the state `next` once `ast` is matched, and returns the state where
+/// matching `ast` starts.
+fn compile(ast: &Ast, next: usize, states: &mut Vec<State>) -> usize {
+    let push = |state: State, states: &mut Vec<State>| {
+        states.push(state);
+        states.len() - 1
+    };
+    match ast {
+        Ast::Empty => next,
+        Ast::Set(set) => push(State::Step(set.clone(), next), states),
+        Ast::Concat(items) => items
+            .iter()
+            .rev()
+            .fold(next, |next, item| compile(item, next, states)),
+        Ast::Alt(a, b) => {
+            let a = compile(a, next, states);
+            let b = compile(b, next, states);
+            push(State::Split(a, b), states)
+        }
+        Ast::Quest(inner) => {
+            let body = compile(inner, next, states);
+            push(State::Split(body, next), states)
+        }
+        Ast::Star(inner) | Ast::Plus(inner) => {
+            // The loop state goes back to the body, whose end leads to it.
+            let split = push(State::Split(next, next), states);
+            let body = compile(inner, split, states);
+            states[split] = State::Split(body, next);
+            if matches!(ast, Ast::Star(_)) {
+                split
+            } else {
+                body
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn full_matches() {
+        let cases: [(&str, &[&str], &[&str]); 8] = [
+            ("abc", &["abc"], &["", "ab", "abcd"]),
+            ("a|b|", &["a", "b", ""], &["ab"]),
+            ("(ab)*", &["", "ab", "abab"], &["a", "aba"]),
+            ("a+b?", &["a", "aab"], &["", "b", "abb"]),
+            ("[a-c]x[^0-9]", &["axz", "cx!"], &["dxz", "ax5"]),
+            ("h.llo", &["hello", "hållo"], &["hllo"]),
+            (r"\(\*\)|\[", &["(*)", "["], &["*"]),
+            ("[-a]-[b-]", &["--b", "a--"], &["b-b"]),
+        ];
+        for (pattern, accepted, rejected) in cases {
+            let re = Regex::new(pattern).unwrap();
+            for text in accepted {
+                assert!(
+                    re.is_full_match(text),
+                    "{} should match {:?}",
+                    pattern,
+                    text
+                );
+            }
+            for text in rejected {
+                assert!(
+                    !re.is_full_match(text),
+                    "{} shouldn't match {:?}",
+                    pattern,
+                    text
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn leftmost_longest() {
+        let re = Regex::new("a+|b").unwrap();
+        assert_eq!(re.find("xxbaaay"), Some(2..3));
+        assert_eq!(re.find("xxaaab"), Some(2..5));
+        assert_eq!(re.find("xyz"), None);
+        assert_eq!(Regex::new("").unwrap().find("abc"), Some(0..0));
+        assert_eq!(Regex::new("x*").unwrap().find("abc"), Some(0..0));
+        assert_eq!(Regex::new("ü+").unwrap().find("naüüve"), Some(2..6));
+        assert!(Regex::new("[0-9]").unwrap().is_match("abc1"));
+    }
+
+    #[test]
+    fn no_exponential_blowup() {
+        // Takes exponential time with backtracking.
+        let n = 30;
+        let pattern = format!("{}{}", "a?".repeat(n), "a".repeat(n));
+        let re = Regex::new(&pattern).unwrap();
+        assert!(re.is_full_match(&"a".repeat(n)));
+        assert!(!re.is_full_match(&"a".repeat(n - 1)));
+
+        // Nested stars create loops of empty moves.
+        let re = Regex::new("(a*)*b").unwrap();
+        assert!(!re.is_full_match(&"a".repeat(1_000)));
+        assert!(re.is_full_match(&format!("{}b", "a".repeat(1_000))));
+    }
+
+    #[test]
+    fn syntax_errors() {
+        let error = |pattern: &str| Regex::new(pattern).unwrap_err();
+        assert_eq!(error("a(b").pos, 1);
+        assert_eq!(error("ab)").message, "unmatched closing parenthesis");
+        assert_eq!(error("*a").message, "nothing to repeat");
+        assert_eq!(error("a|+").message, "nothing to repeat");
+        assert_eq!(error("[ab").message, "unclosed character class");
+        assert_eq!(error("[]").message, "empty character class");
+        assert_eq!(error("[z-a]").message, "invalid class range");
+        assert_eq!(
+            error("ab\\").to_string(),
+            "trailing backslash at position 3"
+        );
+    }
+
+    /// Whole-string matching by brute force over the syntax tree.
+    fn naive_full_match(ast: &Ast, text: &[char]) -> bool {
+        fn ends(ast: &Ast, text: &[char], from: usize) -> Vec<usize> {
+            let mut result: Vec<usize> = match ast {
+                Ast::Empty => vec![from],
+                Ast::Set(set) => match text.get(from) {
+                    Some(&c) if set.contains(c) => vec![from + 1],
+                    _ => vec![],
+                },
+                Ast::Concat(items) => items.iter().fold(vec![from], |starts, item| {
+                    starts.iter().flat_map(|&s| ends(item, text, s)).collect()
+                }),
+                Ast::Alt(a, b) => [ends(a, text, from), ends(b, text, from)].concat(),
+                Ast::Quest(inner) => [vec![from], ends(inner, text, from)].concat(),
+                Ast::Star(inner) | Ast::Plus(inner) => {
+                    let mut reached = if matches!(ast, Ast::Star(_)) {
+                        vec![from]
+                    } else {
+                        vec![]
+                    };
+                    let mut frontier = ends(inner, text, from);
+                    while let Some(e) = frontier.pop() {
+                        if !reached.contains(&e) {
+                            reached.push(e);
+                            frontier.extend(ends(inner, text, e));
+                        }
+                    }
+                    reached
+                }
+            };
+            result.sort();
+            result.dedup();
+            result
+        }
+        ends(ast, text, 0).contains(&text.len())
+    }
+
+    #[test]
+    fn agrees_with_brute_force() {
+        let atoms = ["a", "b", ".", "[ab]", "(a|b)", "(ab)", "(a|)"];
+        for _ in 0..200 {
+            let mut pattern = String::new();
+            for _ in 0..fastrand::usize(1..5) {
+                pattern.push_str(atoms[fastrand::usize(..atoms.len())]);
+                pattern.push_str(["", "*", "+", "?"][fastrand::usize(..4)]);
+            }
+            let re = Regex::new(&pattern).unwrap();
+            let mut parser = Parser {
+                pattern: &pattern,
+                pos: 0,
+            };
+            let ast = parser.alt().unwrap();
+            for _ in 0..20 {
+                let text: String = (0..fastrand::usize(..7))
+                    .map(|_| fastrand::char('a'..'d'))
+                    .collect();
+                let chars: Vec<char> = text.chars().collect();
+                assert_eq!(
+                    re.is_full_match(&text),
+                    naive_full_match(&ast, &chars),
+                    "{} on {:?}",
+                    pattern,
+                    text
+                );
+            }
+        }
+    }
+}
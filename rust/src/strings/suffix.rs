@@ -0,0 +1,378 @@
+//! Suffix array of a string: its suffixes in lexicographic order, which
+//! turns many questions about substrings into questions about neighbouring
+//! suffixes.
+use crate::resume::Resumable;
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+/// Starting indices of the suffixes of `s` in lexicographic order, where a
+/// proper prefix comes before the longer string. Built by prefix doubling:
+/// after the round for `k`, the suffixes are sorted by their first `2k`
+/// symbols, which is the pair of the ranks of their first `k` symbols and of
+/// the `k` symbols after them. Each round sorts these pairs with two stable
+/// counting sorts in O(n), for O(n log n) time overall.
+///
+/// Example:
+/// ```
+/// use algae::strings::suffix_array;
+///
+/// // The suffixes "a", "ana", "anana", "banana", "na", "nana".
+/// assert_eq!(suffix_array(b"banana"), [5, 3, 1, 0, 4, 2]);
+/// ```
+pub fn suffix_array<T: Ord>(s: &[T]) -> Vec<usize> {
+    SuffixArrayBuilder::new(s).finish()
+}
+
+/// Suffix array construction of `suffix_array`, one doubling round at a time.
+/// The rounds only need the ranks of the suffixes by their first `k` symbols,
+/// not the string itself, so that a construction on a huge input can be
+/// saved between rounds with `Resumable::checkpoint`, and carried on by
+/// another process after `Resumable::resume`.
+///
+/// Example:
+/// ```
+/// use algae::{resume::Resumable, strings::SuffixArrayBuilder};
+///
+/// let mut builder = SuffixArrayBuilder::new(b"mississippi");
+/// builder.step();
+/// assert_eq!(builder.doubling_step(), 2);
+///
+/// let mut checkpoint = Vec::new();
+/// builder.checkpoint(&mut checkpoint).unwrap();
+/// let builder = SuffixArrayBuilder::resume(checkpoint.as_slice()).unwrap();
+/// assert_eq!(builder.finish(), [10, 7, 4, 1, 0, 9, 8, 6, 3, 5, 2]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuffixArrayBuilder {
+    /// Suffixes sorted by their first `k` symbols.
+    sa: Vec<usize>,
+    /// Rank of each suffix by its first `k` symbols, equal suffixes sharing
+    /// the same rank.
+    rank: Vec<usize>,
+    k: usize,
+}
+
+/// First line of the checkpoints of `SuffixArrayBuilder`, identifying the
+/// format.
+const CHECKPOINT_HEADER: &str = "algae-suffix-array 1";
+
+impl SuffixArrayBuilder {
+    /// Ranks the suffixes of `s` by their first symbol, before the first
+    /// doubling round.
+    pub fn new<T: Ord>(s: &[T]) -> Self {
+        let n = s.len();
+        let mut sa: Vec<usize> = (0..n).collect();
+        sa.sort_by(|&i, &j| s[i].cmp(&s[j]));
+        let mut rank = vec![0; n];
+        for w in 1..n {
+            rank[sa[w]] = rank[sa[w - 1]] + usize::from(s[sa[w]] != s[sa[w - 1]]);
+        }
+        Self { sa, rank, k: 1 }
+    }
+
+    /// Number of leading symbols by which the suffixes are sorted so far.
+    pub fn doubling_step(&self) -> usize {
+        self.k
+    }
+
+    /// Whether the suffixes are sorted, which is once all ranks are distinct.
+    pub fn is_done(&self) -> bool {
+        let n = self.sa.len();
+        self.k >= n || self.rank[self.sa[n - 1]] == n - 1
+    }
+
+    /// Runs one doubling round, unless the suffixes are already sorted,
+    /// returning whether it did.
+    pub fn step(&mut self) -> bool {
+        if self.is_done() {
+            return false;
+        }
+        let (n, k) = (self.sa.len(), self.k);
+        let (sa, rank) = (&mut self.sa, &self.rank);
+        // Order by the rank of the second half: suffixes too short to have
+        // one come first, then those whose second half starts at `j` in the
+        // current order.
+        let mut by_second = Vec::with_capacity(n);
+        by_second.extend(n - k..n);
+        by_second.extend(sa.iter().filter(|&&j| j >= k).map(|&j| j - k));
+
+        // Stable counting sort by the rank of the first half.
+        let mut count = vec![0; n + 1];
+        for &i in &by_second {
+            count[rank[i] + 1] += 1;
+        }
+        for r in 1..=n {
+            count[r] += count[r - 1];
+        }
+        for &i in &by_second {
+            sa[count[rank[i]]] = i;
+            count[rank[i]] += 1;
+        }
+
+        let second = |i: usize| (i + k < n).then(|| rank[i + k]);
+        let mut next_rank = vec![0; n];
+        for w in 1..n {
+            let (i, j) = (sa[w - 1], sa[w]);
+            let differ = rank[i] != rank[j] || second(i) != second(j);
+            next_rank[j] = next_rank[i] + usize::from(differ);
+        }
+        self.rank = next_rank;
+        self.k *= 2;
+        true
+    }
+
+    /// Runs the remaining rounds and returns the suffix array.
+    pub fn finish(mut self) -> Vec<usize> {
+        while self.step() {}
+        self.sa
+    }
+}
+
+impl Resumable for SuffixArrayBuilder {
+    /// Writes the doubling step and then the rank of each suffix, one per
+    /// line. The order of the suffixes is rebuilt from the ranks.
+    fn checkpoint<W: Write>(&self, mut output: W) -> io::Result<()> {
+        let mut lines = vec![CHECKPOINT_HEADER.to_string(), self.k.to_string()];
+        lines.extend(self.rank.iter().map(usize::to_string));
+        output.write_all(lines.join("\n").as_bytes())?;
+        output.flush()
+    }
+
+    /// Reads a checkpoint written by `checkpoint`, checking that the ranks
+    /// are those of suffixes of a string of their length.
+    fn resume<R: Read>(input: R) -> io::Result<Self> {
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+        let mut lines = BufReader::new(input).lines();
+        if lines.next().transpose()?.as_deref() != Some(CHECKPOINT_HEADER) {
+            return Err(invalid("Not a checkpoint of a suffix array construction"));
+        }
+        let k: usize = lines
+            .next()
+            .transpose()?
+            .and_then(|l| l.parse().ok())
+            .filter(|&k| k > 0)
+            .ok_or_else(|| invalid("Invalid doubling step"))?;
+        let rank: Vec<usize> = lines
+            .map(|l| l?.parse().map_err(|_| invalid("Invalid rank")))
+            .collect::<io::Result<_>>()?;
+
+        // Counting sort of the suffixes by rank, whose values must be the
+        // ranks 0 to the largest one, each taken at least once.
+        let n = rank.len();
+        let mut count = vec![0; n + 1];
+        for &r in &rank {
+            if r >= n {
+                return Err(invalid("Rank out of range"));
+            }
+            count[r + 1] += 1;
+        }
+        let used = count[1..].iter().take_while(|&&c| c > 0).count();
+        if count[used + 1..].iter().any(|&c| c > 0) {
+            return Err(invalid("Ranks are not consecutive"));
+        }
+        for r in 1..=n {
+            count[r] += count[r - 1];
+        }
+        let mut sa = vec![0; n];
+        for (i, &r) in rank.iter().enumerate() {
+            sa[count[r]] = i;
+            count[r] += 1;
+        }
+        Ok(Self { sa, rank, k })
+    }
+}
+
+/// Longest common prefix array of `s`, given its suffix array `sa`: the entry
+/// `i > 0` is the length of the longest common prefix of the suffixes at
+/// `sa[i - 1]` and `sa[i]`, and the entry 0 is 0. Computed in O(n) with
+/// Kasai's algorithm: going from the suffix at `i` to the one at `i + 1`
+/// drops a single symbol, so the common prefix with its predecessor in `sa`
+/// shrinks by at most one.
+///
+/// Example:
+/// ```
+/// use algae::strings::{lcp_array, suffix_array};
+///
+/// let s = b"banana";
+/// assert_eq!(lcp_array(s, &suffix_array(s)), [0, 1, 3, 0, 0, 2]);
+/// ```
+pub fn lcp_array<T: PartialEq>(s: &[T], sa: &[usize]) -> Vec<usize> {
+    let n = s.len();
+    let mut rank = vec![0; n];
+    for (r, &i) in sa.iter().enumerate() {
+        rank[i] = r;
+    }
+    let mut lcp = vec![0; n];
+    let mut h: usize = 0;
+    for i in 0..n {
+        if rank[i] == 0 {
+            h = 0;
+            continue;
+        }
+        let j = sa[rank[i] - 1];
+        while i + h < n && j + h < n && s[i + h] == s[j + h] {
+            h += 1;
+        }
+        lcp[rank[i]] = h;
+        h = h.saturating_sub(1);
+    }
+    lcp
+}
+
+/// Longest substring occurring at least twice in `s`, where occurrences may
+/// overlap, or an empty slice if no symbol repeats. Among several longest
+/// ones, the lexicographically smallest is returned. Repeated substrings are
+/// common prefixes of two suffixes, and the longest are found between
+/// neighbours in the suffix array, so this is the maximum of the LCP array.
+///
+/// Example:
+/// ```
+/// use algae::strings::longest_repeated_substring;
+///
+/// assert_eq!(longest_repeated_substring(b"banana"), b"ana");
+/// assert_eq!(longest_repeated_substring(b"abc"), b"");
+/// ```
+pub fn longest_repeated_substring<T: Ord>(s: &[T]) -> &[T] {
+    let sa = suffix_array(s);
+    let lcp = lcp_array(s, &sa);
+    match (0..lcp.len()).rev().max_by_key(|&r| lcp[r]) {
+        Some(r) => &s[sa[r]..sa[r] + lcp[r]],
+        None => &s[..0],
+    }
+}
+
+/// Number of distinct non-empty substrings of `s`. Every substring is a
+/// prefix of some suffix, and the prefixes of a suffix not shared with its
+/// predecessor in the suffix array are new, so the count is the total number
+/// of prefixes minus the sum of the LCP array.
+///
+/// Example:
+/// ```
+/// use algae::strings::count_distinct_substrings;
+///
+/// // "a", "b", "n", "an", "ba", "na", "ana", "ban", "nan", ...
+/// assert_eq!(count_distinct_substrings(b"banana"), 15);
+/// ```
+pub fn count_distinct_substrings<T: Ord>(s: &[T]) -> usize {
+    let sa = suffix_array(s);
+    let n = s.len();
+    n * (n + 1) / 2 - lcp_array(s, &sa).iter().sum::<usize>()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn agrees_with_sorting_suffixes() {
+        assert!(suffix_array::<u8>(&[]).is_empty());
+        assert_eq!(suffix_array(b"a"), [0]);
+        for _ in 0..200 {
+            let s: Vec<u8> = (0..fastrand::usize(..50))
+                .map(|_| fastrand::u8(b'a'..b'a' + fastrand::u8(1..4)))
+                .collect();
+            let mut expected: Vec<usize> = (0..s.len()).collect();
+            expected.sort_by_key(|&i| &s[i..]);
+            assert_eq!(suffix_array(&s), expected);
+        }
+    }
+
+    #[test]
+    fn periodic_strings() {
+        let s = vec![7u32; 100];
+        assert_eq!(suffix_array(&s), (0..100).rev().collect::<Vec<_>>());
+        let s: Vec<char> = "abababab".chars().collect();
+        assert_eq!(suffix_array(&s), [6, 4, 2, 0, 7, 5, 3, 1]);
+    }
+
+    #[test]
+    fn resumed_builds() {
+        for _ in 0..100 {
+            let s: Vec<u8> = (0..fastrand::usize(..60))
+                .map(|_| fastrand::u8(b'a'..b'a' + fastrand::u8(1..4)))
+                .collect();
+            let expected = suffix_array(&s);
+            // Checkpoint and resume after every round.
+            let mut builder = SuffixArrayBuilder::new(&s);
+            loop {
+                let mut checkpoint = Vec::new();
+                builder.checkpoint(&mut checkpoint).unwrap();
+                let resumed = SuffixArrayBuilder::resume(checkpoint.as_slice()).unwrap();
+                assert_eq!(resumed.doubling_step(), builder.doubling_step());
+                assert_eq!(resumed.is_done(), builder.is_done());
+                assert_eq!(resumed.clone().finish(), expected);
+                builder = resumed;
+                if !builder.step() {
+                    break;
+                }
+            }
+            assert_eq!(builder.finish(), expected);
+        }
+    }
+
+    #[test]
+    fn resuming_invalid_checkpoints() {
+        for checkpoint in [
+            "",
+            "algae-external-sort 1\n1",
+            "algae-suffix-array 1",
+            "algae-suffix-array 1\n0\n0",
+            "algae-suffix-array 1\n1\n0\nx",
+            "algae-suffix-array 1\n1\n0\n2",
+            "algae-suffix-array 1\n1\n0\n2\n2",
+        ] {
+            let err = SuffixArrayBuilder::resume(checkpoint.as_bytes()).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+        let builder = SuffixArrayBuilder::resume("algae-suffix-array 1\n1\n1\n0\n1".as_bytes());
+        // The ranks of "bab" by first symbol.
+        assert_eq!(builder.unwrap().finish(), suffix_array(b"bab"));
+    }
+
+    fn random_string(max_len: usize) -> Vec<u8> {
+        (0..fastrand::usize(..max_len))
+            .map(|_| fastrand::u8(b'a'..b'd'))
+            .collect()
+    }
+
+    #[test]
+    fn lcp_brute_force() {
+        assert!(lcp_array::<u8>(&[], &[]).is_empty());
+        for _ in 0..200 {
+            let s = random_string(40);
+            let sa = suffix_array(&s);
+            let lcp = lcp_array(&s, &sa);
+            for r in 1..s.len() {
+                let common = s[sa[r - 1]..]
+                    .iter()
+                    .zip(&s[sa[r]..])
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                assert_eq!(lcp[r], common);
+            }
+        }
+    }
+
+    #[test]
+    fn repeated_and_distinct_substrings() {
+        for _ in 0..200 {
+            let s = random_string(25);
+            let substrings: HashSet<&[u8]> = (0..s.len())
+                .flat_map(|i| (i + 1..=s.len()).map(move |j| (i, j)))
+                .map(|(i, j)| &s[i..j])
+                .collect();
+            assert_eq!(count_distinct_substrings(&s), substrings.len());
+
+            let repeated = |t: &[u8]| (0..s.len()).filter(|&i| s[i..].starts_with(t)).count() >= 2;
+            let expected = substrings
+                .iter()
+                .filter(|t| repeated(t))
+                .max_by(|a, b| a.len().cmp(&b.len()).then(b.cmp(a)))
+                .copied()
+                .unwrap_or(&[]);
+            assert_eq!(longest_repeated_substring(&s), expected);
+        }
+        assert_eq!(longest_repeated_substring(b"aaaa"), b"aaa");
+        assert_eq!(count_distinct_substrings::<u8>(b""), 0);
+    }
+}
@@ -0,0 +1,705 @@
+//! Trie (prefix tree) of strings, which answers prefix queries in time
+//! proportional to the length of the prefix rather than the number of keys.
+use crate::{
+    ds::{Heap, MaxHeap},
+    search::SymbolTable,
+    sort,
+};
+use std::{cmp::Ordering, collections::BTreeMap, rc::Rc, str::Chars};
+
+#[derive(Debug, Clone)]
+struct Node<V> {
+    value: Option<V>,
+    /// Weight of the key ending at this node, if any.
+    weight: u64,
+    /// Maximum weight of the keys in the subtree of this node.
+    best: u64,
+    children: BTreeMap<char, Node<V>>,
+}
+
+impl<V> Node<V> {
+    fn new() -> Self {
+        Self {
+            value: None,
+            weight: 0,
+            best: 0,
+            children: BTreeMap::new(),
+        }
+    }
+
+    /// Recomputes `best` from the node's own weight and its children.
+    fn refresh(&mut self) {
+        let own = self.value.as_ref().map_or(0, |_| self.weight);
+        self.best = self.children.values().map(|c| c.best).fold(own, u64::max);
+    }
+}
+
+/// Symbol table of string keys stored as a tree of characters, where each
+/// key is the path from the root to the node holding its value. Keys sharing
+/// a prefix share the nodes of that prefix, so that all keys with a given
+/// prefix are found below a single node. The children of each node are kept
+/// in order, so keys are listed in lexicographic order.
+///
+/// Example:
+/// ```
+/// use algae::strings::Trie;
+///
+/// let mut trie = Trie::new();
+/// for (i, word) in ["she", "sells", "sea", "shells", "by", "the", "shore"].iter().enumerate() {
+///     trie.put(word, i);
+/// }
+/// assert_eq!(trie.get("sea"), Some(&2));
+/// assert_eq!(trie.keys_with_prefix("sh"), ["she", "shells", "shore"]);
+/// assert_eq!(trie.longest_prefix_of("shellsort"), Some("shells"));
+/// assert_eq!(trie.keys_that_match(".he"), ["she", "the"]);
+/// ```
+///
+/// Keys may also be given a weight, such as their frequency, to suggest the
+/// heaviest completions of a prefix:
+/// ```
+/// use algae::strings::Trie;
+///
+/// let mut trie = Trie::new();
+/// for (word, frequency) in [("tree", 50), ("trie", 20), ("try", 80), ("trip", 35), ("true", 60)] {
+///     trie.put_weighted(word, (), frequency);
+/// }
+/// assert_eq!(trie.suggest("tr", 3), ["try", "true", "tree"]);
+/// assert_eq!(trie.suggest("tri", 5), ["trip", "trie"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Trie<V> {
+    root: Node<V>,
+    len: usize,
+}
+
+impl<V> Trie<V> {
+    /// Creates an empty trie.
+    pub fn new() -> Self {
+        Self {
+            root: Node::new(),
+            len: 0,
+        }
+    }
+
+    /// Number of keys.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether there are no keys.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Associates `val` with `key`, returning the value previously associated
+    /// with it, if any. A key already present keeps its weight, and a new key
+    /// gets weight 0.
+    pub fn put(&mut self, key: &str, val: V) -> Option<V> {
+        self.put_entry(key, val, None)
+    }
+
+    /// Associates `val` with `key`, giving the key the weight used by
+    /// `suggest`, and returns the value previously associated with it, if any.
+    pub fn put_weighted(&mut self, key: &str, val: V, weight: u64) -> Option<V> {
+        self.put_entry(key, val, Some(weight))
+    }
+
+    fn put_entry(&mut self, key: &str, val: V, weight: Option<u64>) -> Option<V> {
+        let old = insert(&mut self.root, key.chars(), val, weight);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    /// Weight of `key`, if present.
+    pub fn weight(&self, key: &str) -> Option<u64> {
+        let node = self.find(key)?;
+        node.value.as_ref().map(|_| node.weight)
+    }
+
+    /// Value associated with `key`, if any.
+    pub fn get(&self, key: &str) -> Option<&V> {
+        self.find(key)?.value.as_ref()
+    }
+
+    /// Mutable reference to the value associated with `key`, if any.
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut V> {
+        let mut node = &mut self.root;
+        for c in key.chars() {
+            node = node.children.get_mut(&c)?;
+        }
+        node.value.as_mut()
+    }
+
+    /// Whether `key` is in the trie.
+    pub fn contains(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Removes `key` from the trie, returning its value if it was present.
+    /// Nodes left without keys below them are removed as well.
+    pub fn del(&mut self, key: &str) -> Option<V> {
+        let removed = remove(&mut self.root, key.chars());
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// Keeps only the keys for which `pred` returns `true`, given each key
+    /// with its value in lexicographic order. Nodes left without keys below
+    /// them are removed, in a single traversal of the trie.
+    pub fn retain<F: FnMut(&str, &V) -> bool>(&mut self, mut pred: F) {
+        let removed = retain(&mut self.root, &mut String::new(), &mut |k, v| pred(k, v));
+        self.len -= removed;
+    }
+
+    /// All keys in lexicographic order. The keys are collected into a vector,
+    /// which is a snapshot: it doesn't borrow the trie.
+    pub fn keys(&self) -> Vec<String> {
+        self.keys_with_prefix("")
+    }
+
+    /// Keys starting with `prefix`, in lexicographic order.
+    pub fn keys_with_prefix(&self, prefix: &str) -> Vec<String> {
+        let mut keys = Vec::new();
+        if let Some(node) = self.find(prefix) {
+            collect(node, &mut prefix.to_string(), &mut keys);
+        }
+        keys
+    }
+
+    /// Longest key which is a prefix of `query`, if any.
+    pub fn longest_prefix_of<'q>(&self, query: &'q str) -> Option<&'q str> {
+        let mut node = &self.root;
+        let mut longest = node.value.as_ref().map(|_| 0);
+        for (i, c) in query.char_indices() {
+            match node.children.get(&c) {
+                Some(child) => node = child,
+                None => break,
+            }
+            if node.value.is_some() {
+                longest = Some(i + c.len_utf8());
+            }
+        }
+        longest.map(|end| &query[..end])
+    }
+
+    /// Keys matching `pattern`, in lexicographic order, where the character
+    /// `.` of the pattern matches any single character.
+    pub fn keys_that_match(&self, pattern: &str) -> Vec<String> {
+        let pattern: Vec<char> = pattern.chars().collect();
+        let mut keys = Vec::new();
+        collect_matching(&self.root, &pattern, &mut String::new(), &mut keys);
+        keys
+    }
+
+    /// The `k` keys starting with `prefix` with the highest weights, from the
+    /// heaviest to the lightest, breaking ties in lexicographic order.
+    ///
+    /// Each node knows the maximum weight below it, which bounds the weights
+    /// of its completions. The search is best-first: a max-heap holds the
+    /// nodes to explore and the keys found, ordered by their weight or bound,
+    /// and a key popped from the heap is heavier than anything left to
+    /// explore. The search stops after `k` keys, visiting only the paths to
+    /// the heaviest completions and their siblings.
+    ///
+    /// The heap is bounded: the entries of the heap lead to distinct keys,
+    /// each reaching the weight of its entry, so that when `r` more keys are
+    /// needed, an entry ranked below the `r` best ones cannot lead to any of
+    /// them. Once the heap holds more than `2 * r` entries, it is cut down to
+    /// the `r` best ones with `quick_select`, keeping it within `2 * k`
+    /// entries, plus the children of a node, for an amortized O(1) time per
+    /// entry.
+    pub fn suggest(&self, prefix: &str, k: usize) -> Vec<String> {
+        let mut suggestions = Vec::with_capacity(k.min(self.len()));
+        let Some(start) = self.find(prefix) else {
+            return suggestions;
+        };
+        let mut heap = MaxHeap::new();
+        heap.push(Candidate {
+            weight: start.best,
+            key: Rc::from(prefix),
+            node: Some(start),
+        });
+        while suggestions.len() < k {
+            let Some(candidate) = heap.pop() else {
+                break;
+            };
+            let Some(node) = candidate.node else {
+                suggestions.push(candidate.key.to_string());
+                continue;
+            };
+            if node.value.is_some() {
+                heap.push(Candidate {
+                    weight: node.weight,
+                    key: Rc::clone(&candidate.key),
+                    node: None,
+                });
+            }
+            for (&c, child) in &node.children {
+                let mut key = candidate.key.to_string();
+                key.push(c);
+                heap.push(Candidate {
+                    weight: child.best,
+                    key: Rc::from(key),
+                    node: Some(child),
+                });
+            }
+
+            let needed = k - suggestions.len();
+            if heap.size() > needed.saturating_mul(2) {
+                let mut entries = heap.drain_filter(|_| true);
+                let cut = entries.len() - needed;
+                sort::quick_select(&mut entries, cut);
+                heap = MaxHeap::from_vec(entries.split_off(cut));
+            }
+        }
+        suggestions
+    }
+
+    /// Node at the end of the path of `key`, if any.
+    fn find(&self, key: &str) -> Option<&Node<V>> {
+        let mut node = &self.root;
+        for c in key.chars() {
+            node = node.children.get(&c)?;
+        }
+        Some(node)
+    }
+}
+
+impl<V> Default for Trie<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: Clone> SymbolTable<String, V> for Trie<V> {
+    fn new() -> Self {
+        Trie::new()
+    }
+
+    fn put(&mut self, key: String, val: V) {
+        Trie::put(self, &key, val);
+    }
+
+    fn get(&self, key: String) -> Option<V> {
+        Trie::get(self, &key).cloned()
+    }
+
+    fn del(&mut self, key: String) {
+        Trie::del(self, &key);
+    }
+
+    fn contains(&self, key: String) -> bool {
+        Trie::contains(self, &key)
+    }
+
+    fn is_empty(&self) -> bool {
+        Trie::is_empty(self)
+    }
+
+    fn size(&self) -> usize {
+        self.len
+    }
+
+    fn keys(&self) -> impl Iterator<Item = String> + '_ {
+        Trie::keys(self).into_iter()
+    }
+
+    fn keys_snapshot(&self) -> Vec<String> {
+        Trie::keys(self)
+    }
+
+    fn retain<F: FnMut(&String, &V) -> bool>(&mut self, mut pred: F) {
+        let removed = retain(&mut self.root, &mut String::new(), &mut pred);
+        self.len -= removed;
+    }
+}
+
+/// Entry of the heap of `Trie::suggest`: either a node to explore, whose
+/// weight bounds those of the keys below it, or a key found.
+struct Candidate<'a, V> {
+    weight: u64,
+    key: Rc<str>,
+    node: Option<&'a Node<V>>,
+}
+
+impl<V> Clone for Candidate<'_, V> {
+    fn clone(&self) -> Self {
+        Self {
+            weight: self.weight,
+            key: Rc::clone(&self.key),
+            node: self.node,
+        }
+    }
+}
+
+impl<V> PartialEq for Candidate<'_, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl<V> PartialOrd for Candidate<'_, V> {
+    /// Heavier entries first, then smaller keys, then keys found before the
+    /// nodes with the same key. The keys below a node are not smaller than its
+    /// own, so keys of equal weight come out in lexicographic order.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(
+            self.weight
+                .cmp(&other.weight)
+                .then_with(|| other.key.cmp(&self.key))
+                .then_with(|| other.node.is_some().cmp(&self.node.is_some())),
+        )
+    }
+}
+
+/// Puts the key spelled by `chars` below `node`, updating the maximum weights
+/// along its path. Without a `weight`, the key keeps its weight if present,
+/// and gets weight 0 otherwise.
+fn insert<V>(node: &mut Node<V>, mut chars: Chars, val: V, weight: Option<u64>) -> Option<V> {
+    let old = match chars.next() {
+        None => {
+            match weight {
+                Some(weight) => node.weight = weight,
+                // The weight of a deleted key may still be around.
+                None if node.value.is_none() => node.weight = 0,
+                None => {}
+            }
+            node.value.replace(val)
+        }
+        Some(c) => insert(
+            node.children.entry(c).or_insert_with(Node::new),
+            chars,
+            val,
+            weight,
+        ),
+    };
+    node.refresh();
+    old
+}
+
+/// Removes the key spelled by `chars` below `node`, pruning the nodes left
+/// without keys.
+fn remove<V>(node: &mut Node<V>, mut chars: Chars) -> Option<V> {
+    let removed = match chars.next() {
+        None => node.value.take(),
+        Some(c) => {
+            let child = node.children.get_mut(&c)?;
+            let removed = remove(child, chars);
+            if child.value.is_none() && child.children.is_empty() {
+                node.children.remove(&c);
+            }
+            removed
+        }
+    };
+    node.refresh();
+    removed
+}
+
+/// Removes the keys below `node`, whose path spells `prefix`, for which
+/// `pred` returns `false`, pruning the nodes left without keys. Returns the
+/// number of keys removed.
+fn retain<V, F: FnMut(&String, &V) -> bool>(
+    node: &mut Node<V>,
+    prefix: &mut String,
+    pred: &mut F,
+) -> usize {
+    let mut removed = 0;
+    if node.value.as_ref().is_some_and(|v| !pred(prefix, v)) {
+        node.value = None;
+        removed += 1;
+    }
+    // Children are visited in increasing order of their character.
+    node.children.retain(|&c, child| {
+        prefix.push(c);
+        removed += retain(child, prefix, pred);
+        prefix.pop();
+        child.value.is_some() || !child.children.is_empty()
+    });
+    node.refresh();
+    removed
+}
+
+/// Pushes to `keys` the keys below `node`, whose path spells `prefix`.
+fn collect<V>(node: &Node<V>, prefix: &mut String, keys: &mut Vec<String>) {
+    if node.value.is_some() {
+        keys.push(prefix.clone());
+    }
+    for (&c, child) in &node.children {
+        prefix.push(c);
+        collect(child, prefix, keys);
+        prefix.pop();
+    }
+}
+
+/// Pushes to `keys` the keys below `node` whose remaining characters match
+/// `pattern`.
+fn collect_matching<V>(
+    node: &Node<V>,
+    pattern: &[char],
+    prefix: &mut String,
+    keys: &mut Vec<String>,
+) {
+    let Some((&p, rest)) = pattern.split_first() else {
+        if node.value.is_some() {
+            keys.push(prefix.clone());
+        }
+        return;
+    };
+    let mut visit = |c: char, child: &Node<V>| {
+        prefix.push(c);
+        collect_matching(child, rest, prefix, keys);
+        prefix.pop();
+    };
+    if p == '.' {
+        for (&c, child) in &node.children {
+            visit(c, child);
+        }
+    } else if let Some(child) = node.children.get(&p) {
+        visit(p, child);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn random_word() -> String {
+        (0..fastrand::usize(..5))
+            .map(|_| fastrand::char('a'..'d'))
+            .collect()
+    }
+
+    #[test]
+    fn agrees_with_btree_map() {
+        let mut trie = Trie::new();
+        let mut map = BTreeMap::new();
+        for i in 0..500 {
+            let word = random_word();
+            if fastrand::bool() {
+                assert_eq!(trie.put(&word, i), map.insert(word.clone(), i));
+            } else {
+                assert_eq!(trie.del(&word), map.remove(&word));
+            }
+            assert_eq!(trie.len(), map.len());
+            assert_eq!(trie.get(&word), map.get(&word));
+        }
+        assert_eq!(trie.keys(), map.keys().cloned().collect::<Vec<_>>());
+
+        for _ in 0..50 {
+            let prefix = random_word();
+            let expected: Vec<String> = map
+                .keys()
+                .filter(|k| k.starts_with(&prefix))
+                .cloned()
+                .collect();
+            assert_eq!(trie.keys_with_prefix(&prefix), expected);
+
+            let query = random_word();
+            let longest = map
+                .keys()
+                .filter(|k| query.starts_with(k.as_str()))
+                .max_by_key(|k| k.len());
+            assert_eq!(trie.longest_prefix_of(&query), longest.map(|k| k.as_str()));
+
+            let pattern: String = query
+                .chars()
+                .map(|c| if fastrand::bool() { '.' } else { c })
+                .collect();
+            let expected: Vec<String> = map
+                .keys()
+                .filter(|k| {
+                    k.chars().count() == pattern.chars().count()
+                        && k.chars()
+                            .zip(pattern.chars())
+                            .all(|(c, p)| p == '.' || c == p)
+                })
+                .cloned()
+                .collect();
+            assert_eq!(trie.keys_that_match(&pattern), expected);
+        }
+    }
+
+    #[test]
+    fn deletion_prunes_nodes() {
+        let mut trie = Trie::new();
+        trie.put("car", 1);
+        trie.put("cart", 2);
+        assert_eq!(trie.del("cart"), Some(2));
+        assert_eq!(trie.del("cart"), None);
+        assert!(trie.keys_with_prefix("cart").is_empty());
+        assert_eq!(trie.del("car"), Some(1));
+        assert!(trie.is_empty() && trie.root.children.is_empty());
+    }
+
+    #[test]
+    fn unicode_keys() {
+        let mut trie = Trie::new();
+        trie.put("", 0);
+        trie.put("über", 1);
+        trie.put("übel", 2);
+        trie.put("日本", 3);
+        assert_eq!(trie.longest_prefix_of("übermensch"), Some("über"));
+        assert_eq!(trie.longest_prefix_of("x"), Some(""));
+        assert_eq!(trie.keys_that_match("..."), Vec::<String>::new());
+        assert_eq!(trie.keys_that_match("übe."), ["übel", "über"]);
+        assert_eq!(trie.keys_that_match(".本"), ["日本"]);
+        if let Some(v) = trie.get_mut("日本") {
+            *v = 4;
+        }
+        assert_eq!(trie.get("日本"), Some(&4));
+    }
+
+    #[test]
+    fn suggestions() {
+        let mut trie = Trie::new();
+        let mut weights = BTreeMap::new();
+        for _ in 0..300 {
+            let word = random_word();
+            if fastrand::u8(..4) == 0 {
+                trie.del(&word);
+                weights.remove(&word);
+            } else {
+                let weight = fastrand::u64(..20);
+                trie.put_weighted(&word, (), weight);
+                weights.insert(word, weight);
+            }
+        }
+
+        for _ in 0..50 {
+            let prefix = random_word();
+            let k = fastrand::usize(..10);
+            let mut expected: Vec<(&String, &u64)> = weights
+                .iter()
+                .filter(|(w, _)| w.starts_with(&prefix))
+                .collect();
+            expected.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+            let expected: Vec<String> = expected
+                .into_iter()
+                .take(k)
+                .map(|(w, _)| w.clone())
+                .collect();
+            assert_eq!(trie.suggest(&prefix, k), expected);
+        }
+        for (word, &weight) in &weights {
+            assert_eq!(trie.weight(word), Some(weight));
+        }
+        assert_eq!(trie.weight("no such word"), None);
+    }
+
+    #[test]
+    fn suggestions_among_ties() {
+        // Few distinct weights, so that the heap is cut down between ties.
+        let mut trie = Trie::new();
+        let mut weights = BTreeMap::new();
+        for _ in 0..2_000 {
+            let word = random_word();
+            let weight = fastrand::u64(..3);
+            trie.put_weighted(&word, (), weight);
+            weights.insert(word, weight);
+        }
+        for k in [1, 2, 5, 40, 500] {
+            let mut expected: Vec<(&String, &u64)> = weights.iter().collect();
+            expected.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+            let expected: Vec<&String> = expected.into_iter().take(k).map(|(w, _)| w).collect();
+            assert_eq!(trie.suggest("", k).iter().collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn reweighting() {
+        let mut trie = Trie::new();
+        trie.put_weighted("ab", 1, 10);
+        trie.put_weighted("ac", 2, 5);
+        assert_eq!(trie.suggest("a", 1), ["ab"]);
+        // Updating the value of a key keeps its weight.
+        assert_eq!(trie.put("ab", 3), Some(1));
+        assert_eq!(trie.weight("ab"), Some(10));
+        assert_eq!(trie.suggest("a", 1), ["ab"]);
+        trie.put_weighted("ab", 1, 1);
+        assert_eq!(trie.suggest("a", 1), ["ac"]);
+        trie.del("ac");
+        assert_eq!(trie.suggest("", 5), ["ab"]);
+        // A deleted key put back has no weight left.
+        trie.put("ac", 2);
+        assert_eq!(trie.weight("ac"), Some(0));
+        trie.del("ac");
+        assert!(trie.suggest("b", 5).is_empty());
+        assert_eq!(trie.suggest("", usize::MAX), ["ab"]);
+    }
+
+    #[test]
+    fn symbol_table() {
+        fn count_words<ST: SymbolTable<String, usize>>(text: &str) -> ST {
+            let mut table = ST::new();
+            for w in text.split_whitespace() {
+                let count = table.get(w.to_string()).unwrap_or(0);
+                table.put(w.to_string(), count + 1);
+            }
+            table
+        }
+
+        let table: Trie<usize> = count_words("it was the best of times it was the worst of times");
+        assert_eq!(SymbolTable::size(&table), 7);
+        assert!(SymbolTable::contains(&table, "times".to_string()));
+        assert_eq!(SymbolTable::get(&table, "was".to_string()), Some(2));
+        let keys: Vec<String> = SymbolTable::keys(&table).collect();
+        assert_eq!(keys, ["best", "it", "of", "the", "times", "was", "worst"]);
+
+        // Deleting while going through the keys needs a snapshot of them.
+        let mut table = table;
+        for key in SymbolTable::keys_snapshot(&table) {
+            if key.starts_with('w') {
+                SymbolTable::del(&mut table, key);
+            }
+        }
+        assert_eq!(SymbolTable::size(&table), 5);
+        SymbolTable::retain(&mut table, |_, &count| count > 1);
+        assert_eq!(
+            SymbolTable::keys_snapshot(&table),
+            ["it", "of", "the", "times"]
+        );
+    }
+
+    #[test]
+    fn retain_agrees_with_btree_map() {
+        for _ in 0..50 {
+            let mut trie = Trie::new();
+            let mut map = BTreeMap::new();
+            for i in 0..50 {
+                let word = random_word();
+                let weight = fastrand::u64(..20);
+                trie.put_weighted(&word, i, weight);
+                map.insert(word, (i, weight));
+            }
+            let threshold = fastrand::usize(..50);
+            let mut seen = Vec::new();
+            trie.retain(|k, &v| {
+                seen.push(k.to_string());
+                v < threshold
+            });
+            assert_eq!(seen, map.keys().cloned().collect::<Vec<_>>());
+            map.retain(|_, (i, _)| *i < threshold);
+            assert_eq!(trie.len(), map.len());
+            assert_eq!(trie.keys(), map.keys().cloned().collect::<Vec<_>>());
+            // Pruned nodes no longer bound the weights of the suggestions.
+            let mut expected: Vec<(&String, &u64)> = map.iter().map(|(k, (_, w))| (k, w)).collect();
+            expected.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+            let expected: Vec<String> = expected
+                .into_iter()
+                .take(3)
+                .map(|(k, _)| k.clone())
+                .collect();
+            assert_eq!(trie.suggest("", 3), expected);
+        }
+        let mut trie = Trie::new();
+        trie.put("a", 1);
+        trie.put("ab", 2);
+        trie.retain(|_, _| false);
+        assert!(trie.is_empty() && trie.root.children.is_empty());
+    }
+}
@@ -0,0 +1,95 @@
+//! The Z-algorithm, computing for each position of a string the length of
+//! the longest common prefix of the string and its suffix at that position.
+
+/// Z-array of `s`: the entry `i` is the length of the longest common prefix
+/// of `s` and `s[i..]`, so that the entry 0 is `s.len()`. Computed in O(n)
+/// by reusing the rightmost match `s[l..r]` of a prefix found so far: inside
+/// it, `s[i..r]` equals `s[i - l..r - l]`, whose entry is already known.
+///
+/// Example:
+/// ```
+/// use algae::strings::z_array;
+///
+/// assert_eq!(z_array(b"aabxaab"), [7, 1, 0, 0, 3, 1, 0]);
+/// assert_eq!(z_array(b"aaaa"), [4, 3, 2, 1]);
+/// ```
+pub fn z_array<T: PartialEq>(s: &[T]) -> Vec<usize> {
+    let n = s.len();
+    let mut z = vec![0; n];
+    if n == 0 {
+        return z;
+    }
+    z[0] = n;
+    let (mut l, mut r) = (0, 0);
+    for i in 1..n {
+        if i < r {
+            z[i] = z[i - l].min(r - i);
+        }
+        while i + z[i] < n && s[z[i]] == s[i + z[i]] {
+            z[i] += 1;
+        }
+        if i + z[i] > r {
+            (l, r) = (i, i + z[i]);
+        }
+    }
+    z
+}
+
+/// Indices of all occurrences of `pattern` in `text`, including overlapping
+/// ones, in increasing order. These are the positions of the text where the
+/// Z-array of the pattern followed by the text reaches the length of the
+/// pattern. An empty pattern occurs at every index from 0 to `text.len()`.
+///
+/// Example:
+/// ```
+/// use algae::strings::z_search;
+///
+/// assert_eq!(z_search(b"abababa", b"aba"), [0, 2, 4]);
+/// assert_eq!(z_search(b"abababa", b"abc"), []);
+/// ```
+pub fn z_search<T: PartialEq>(text: &[T], pattern: &[T]) -> Vec<usize> {
+    let m = pattern.len();
+    if m == 0 {
+        return (0..=text.len()).collect();
+    }
+    let joined: Vec<&T> = pattern.iter().chain(text).collect();
+    z_array(&joined)[m..]
+        .iter()
+        .enumerate()
+        .filter(|&(_, &len)| len >= m)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strings::kmp_search_all;
+
+    fn random_string(len: usize) -> Vec<u8> {
+        (0..len).map(|_| fastrand::u8(b'a'..b'c')).collect()
+    }
+
+    #[test]
+    fn z_array_brute_force() {
+        assert!(z_array::<u8>(&[]).is_empty());
+        for _ in 0..200 {
+            let s = random_string(fastrand::usize(1..40));
+            let expected: Vec<usize> = (0..s.len())
+                .map(|i| s[i..].iter().zip(&s).take_while(|(a, b)| a == b).count())
+                .collect();
+            assert_eq!(z_array(&s), expected);
+        }
+    }
+
+    #[test]
+    fn agrees_with_kmp() {
+        for _ in 0..300 {
+            let text = random_string(fastrand::usize(..60));
+            let pattern = random_string(fastrand::usize(..6));
+            assert_eq!(z_search(&text, &pattern), kmp_search_all(&text, &pattern));
+        }
+        assert_eq!(z_search(b"", b""), [0]);
+        assert_eq!(z_search(b"ab", b"abc"), []);
+    }
+}
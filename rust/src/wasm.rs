@@ -0,0 +1,170 @@
+//! Flattened entry points for WebAssembly hosts (enabled by the `wasm`
+//! feature). The functions only exchange numbers and pointers into the linear
+//! memory of the module, so that they can be called from JavaScript without
+//! any binding generator:
+//!
+//! ```js
+//! const { algae_alloc_f64, algae_sort_f64, algae_free_f64, memory } = instance.exports;
+//! const ptr = algae_alloc_f64(values.length);
+//! new Float64Array(memory.buffer, ptr, values.length).set(values);
+//! algae_sort_f64(ptr, values.length);
+//! ```
+use crate::{
+    graph::{self, Graph},
+    sort,
+};
+use std::{mem, ptr, slice};
+
+/// Allocates a zeroed buffer of `len` doubles inside the module memory and
+/// returns a pointer to it. The buffer should be released with
+/// `algae_free_f64`.
+#[no_mangle]
+pub extern "C" fn algae_alloc_f64(len: usize) -> *mut f64 {
+    let mut buf = vec![0.0; len].into_boxed_slice();
+    let ptr = buf.as_mut_ptr();
+    mem::forget(buf);
+    ptr
+}
+
+/// Releases a buffer obtained from `algae_alloc_f64`.
+///
+/// # Safety
+///
+/// `ptr` and `len` must come from a single call to `algae_alloc_f64`, and the
+/// buffer must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn algae_free_f64(ptr: *mut f64, len: usize) {
+    if !ptr.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(ptr, len)));
+    }
+}
+
+/// Sorts in place the `len` doubles starting at `ptr` using introsort.
+///
+/// # Safety
+///
+/// `ptr` must be valid for reads and writes of `len` consecutive `f64` values.
+#[no_mangle]
+pub unsafe extern "C" fn algae_sort_f64(ptr: *mut f64, len: usize) {
+    if ptr.is_null() || len == 0 {
+        return;
+    }
+    sort::intro_sort(slice::from_raw_parts_mut(ptr, len));
+}
+
+/// Allocates a zeroed buffer of `len` 32-bit integers inside the module
+/// memory and returns a pointer to it. The buffer should be released with
+/// `algae_free_u32`.
+#[no_mangle]
+pub extern "C" fn algae_alloc_u32(len: usize) -> *mut u32 {
+    let mut buf = vec![0; len].into_boxed_slice();
+    let ptr = buf.as_mut_ptr();
+    mem::forget(buf);
+    ptr
+}
+
+/// Releases a buffer obtained from `algae_alloc_u32`.
+///
+/// # Safety
+///
+/// `ptr` and `len` must come from a single call to `algae_alloc_u32`, and the
+/// buffer must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn algae_free_u32(ptr: *mut u32, len: usize) {
+    if !ptr.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(ptr, len)));
+    }
+}
+
+/// Breadth-first search from `source` in the undirected graph with `n`
+/// vertices whose edges are given by the `len` integers starting at `ptr`,
+/// read in pairs `v, w`. Writes to the `n` integers starting at `out` the
+/// distance of each vertex from `source` in number of edges, or `u32::MAX`
+/// (`0xFFFFFFFF`) if it is unreachable.
+///
+/// Returns `0` on success, or `-1`, leaving `out` untouched, if the
+/// arguments are invalid: null pointers, an odd `len`, or vertices out of
+/// range.
+///
+/// # Safety
+///
+/// `ptr` must be valid for reads of `len` consecutive `u32` values, and `out`
+/// for writes of `n` values.
+#[no_mangle]
+pub unsafe extern "C" fn algae_bfs_edge_list(
+    ptr: *const u32,
+    len: usize,
+    n: usize,
+    source: usize,
+    out: *mut u32,
+) -> i32 {
+    if (ptr.is_null() && len > 0) || out.is_null() || !len.is_multiple_of(2) || source >= n {
+        return -1;
+    }
+    let edges = if len == 0 {
+        &[][..]
+    } else {
+        slice::from_raw_parts(ptr, len)
+    };
+    if edges.iter().any(|&v| v as usize >= n) {
+        return -1;
+    }
+
+    let graph = Graph::from_edges(
+        n,
+        edges
+            .chunks_exact(2)
+            .map(|e| (e[0] as usize, e[1] as usize)),
+    );
+    let result = graph::bfs(&graph, source);
+    let out = slice::from_raw_parts_mut(out, n);
+    for (v, d) in out.iter_mut().enumerate() {
+        *d = result.dist_to(v).map_or(u32::MAX, |d| d as u32);
+    }
+    0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sort_through_module_memory() {
+        let values = [3.5, -1.0, 2.25, 0.0, 10.0, -7.5];
+        let buf = algae_alloc_f64(values.len());
+        unsafe {
+            slice::from_raw_parts_mut(buf, values.len()).copy_from_slice(&values);
+            algae_sort_f64(buf, values.len());
+            assert_eq!(
+                slice::from_raw_parts(buf, values.len()),
+                &[-7.5, -1.0, 0.0, 2.25, 3.5, 10.0]
+            );
+            algae_free_f64(buf, values.len());
+
+            // Null and empty buffers are ignored.
+            algae_sort_f64(ptr::null_mut(), 3);
+            algae_free_f64(ptr::null_mut(), 0);
+        }
+    }
+
+    #[test]
+    fn bfs_through_module_memory() {
+        // A path 0-1-2-3, the edge 1-3, and an isolated vertex 4.
+        let edges = [0, 1, 1, 2, 2, 3, 3, 1];
+        let (buf, out) = (algae_alloc_u32(edges.len()), algae_alloc_u32(5));
+        unsafe {
+            slice::from_raw_parts_mut(buf, edges.len()).copy_from_slice(&edges);
+            assert_eq!(algae_bfs_edge_list(buf, edges.len(), 5, 0, out), 0);
+            assert_eq!(slice::from_raw_parts(out, 5), &[0, 1, 2, 2, u32::MAX]);
+
+            assert_eq!(algae_bfs_edge_list(buf, 3, 5, 0, out), -1);
+            assert_eq!(algae_bfs_edge_list(buf, edges.len(), 3, 0, out), -1);
+            assert_eq!(algae_bfs_edge_list(buf, edges.len(), 5, 5, out), -1);
+            assert_eq!(algae_bfs_edge_list(ptr::null(), 0, 1, 0, out), 0);
+            assert_eq!(*out, 0);
+
+            algae_free_u32(buf, edges.len());
+            algae_free_u32(out, 5);
+        }
+    }
+}